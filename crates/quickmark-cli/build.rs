@@ -0,0 +1,35 @@
+//! Embeds the rule documentation of `docs/rules` for `qmark explain`.
+
+use std::path::PathBuf;
+use std::{env, fs};
+
+fn main() {
+    let manifest_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+    let docs_dir = manifest_dir.join("../../docs/rules");
+    println!("cargo:rerun-if-changed={}", docs_dir.display());
+
+    // Docs are missing from packaged crates; explanations then fall back to descriptions
+    let mut docs: Vec<(String, PathBuf)> = fs::read_dir(&docs_dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "md"))
+        .filter_map(|path| {
+            let id = path.file_stem()?.to_string_lossy().to_uppercase();
+            Some((id, fs::canonicalize(&path).ok()?))
+        })
+        .collect();
+    docs.sort();
+
+    let mut out = String::from("pub static RULE_DOCS: &[(&str, &str)] = &[\n");
+    for (id, path) in docs {
+        out.push_str(&format!(
+            "    ({id:?}, include_str!({:?})),\n",
+            path.to_string_lossy()
+        ));
+    }
+    out.push_str("];\n");
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+    fs::write(out_dir.join("rule_docs.rs"), out).unwrap();
+}
@@ -0,0 +1,462 @@
+//! Persistent lint cache, enabled with `--cache`.
+//!
+//! Every linted file gets an entry in the cache directory. Entries are keyed by the file
+//! path, its content, the effective configuration and the quickmark version, so they
+//! are only reused for exactly the same input and never need to be invalidated.
+//!
+//! Rules that follow links, such as `QM002`, make the result of a file depend on the
+//! files it links to. An entry records a fingerprint of every relative link target and
+//! is only reused while those are unchanged. Files the configuration refers to, such as
+//! plugin libraries and the JSON schema of `QM001`, are recorded by the hash of their
+//! content the same way.
+//! Workspace rules are not cached; entries keep the summary the workspace rules run on
+//! instead.
+
+use anyhow::{bail, Context};
+use clap::Subcommand;
+use quickmark_core::config::{QuickmarkConfig, RuleSeverity};
+use quickmark_core::linter::{Edit, Range, RuleViolation};
+use quickmark_core::project_index::is_markdown_path;
+use quickmark_core::rules::find_rule_for_config;
+use quickmark_core::workspace::DocumentSummary;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Cache directory used when `--cache-location` is not given
+pub const DEFAULT_CACHE_LOCATION: &str = ".quickmark_cache";
+
+/// Marks the directory as a cache, see <https://bford.info/cachedir/>. `qmark cache
+/// clean` refuses to delete directories without it.
+const CACHEDIR_TAG: &str = "CACHEDIR.TAG";
+const CACHEDIR_TAG_CONTENT: &str = "Signature: 8a477f597d28d172789f06886806bc55\n\
+# This file is a cache directory tag created by qmark.\n";
+
+#[derive(Subcommand, Debug)]
+pub enum CacheCommand {
+    /// Delete the lint cache
+    Clean {
+        /// Cache directory to delete
+        #[arg(long, value_name = "DIR", default_value = DEFAULT_CACHE_LOCATION)]
+        cache_location: PathBuf,
+    },
+}
+
+/// Run a `qmark cache` subcommand and return the process exit code
+pub fn run(command: CacheCommand) -> anyhow::Result<i32> {
+    match command {
+        CacheCommand::Clean { cache_location } => {
+            if LintCache::clean(&cache_location)? {
+                println!("Removed {}", cache_location.to_string_lossy());
+            } else {
+                println!("No cache found at {}", cache_location.to_string_lossy());
+            }
+            Ok(0)
+        }
+    }
+}
+
+/// State of a file a cached result depends on
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+enum Fingerprint {
+    Missing,
+    /// A file or directory whose content doesn't matter, only that it exists
+    Exists,
+    /// Hash of the content of a Markdown document
+    Markdown(String),
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedViolation {
+    rule: String,
+    message: String,
+    range: Range,
+    severity: RuleSeverity,
+    fix: Vec<Edit>,
+}
+
+#[derive(Serialize)]
+struct EntryRef<'a> {
+    violations: Vec<CachedViolation>,
+    summary: Option<&'a DocumentSummary>,
+    dependencies: Vec<(PathBuf, Fingerprint)>,
+    config_files: Vec<(PathBuf, Option<String>)>,
+}
+
+#[derive(Deserialize)]
+struct Entry {
+    violations: Vec<CachedViolation>,
+    summary: Option<DocumentSummary>,
+    dependencies: Vec<(PathBuf, Fingerprint)>,
+    /// Files the configuration refers to, with the hash of their content
+    config_files: Vec<(PathBuf, Option<String>)>,
+}
+
+/// Result of a file read back from the cache
+pub struct CachedReport {
+    pub violations: Vec<RuleViolation>,
+    pub summary: Option<DocumentSummary>,
+}
+
+/// Key of a cache entry, see [`LintCache::key`]
+pub struct CacheKey {
+    hash: String,
+    /// Files the configuration refers to, whose content the result depends on
+    config_files: Vec<PathBuf>,
+}
+
+/// Cache of lint results in a directory, shared by all threads of a run
+pub struct LintCache {
+    dir: PathBuf,
+    /// Fingerprints of link targets computed during this run
+    fingerprints: Mutex<HashMap<PathBuf, Fingerprint>>,
+    /// Content hashes of configuration files computed during this run
+    content_hashes: Mutex<HashMap<PathBuf, Option<String>>>,
+}
+
+impl LintCache {
+    /// Use `dir` as cache directory, creating it when needed
+    pub fn open(dir: &Path) -> anyhow::Result<Self> {
+        fs::create_dir_all(dir)
+            .with_context(|| format!("Can't create cache directory {}", dir.to_string_lossy()))?;
+        let tag = dir.join(CACHEDIR_TAG);
+        if !tag.exists() {
+            fs::write(&tag, CACHEDIR_TAG_CONTENT)?;
+            // Keep the cache out of version control when it lives inside a repository
+            fs::write(dir.join(".gitignore"), "*\n")?;
+        }
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            fingerprints: Mutex::default(),
+            content_hashes: Mutex::default(),
+        })
+    }
+
+    /// Delete the cache directory. Returns whether there was a cache to delete.
+    pub fn clean(dir: &Path) -> anyhow::Result<bool> {
+        if !dir.exists() {
+            return Ok(false);
+        }
+        if !dir.join(CACHEDIR_TAG).is_file() {
+            bail!(
+                "{} is not a quickmark cache directory, not deleting it",
+                dir.to_string_lossy()
+            );
+        }
+        fs::remove_dir_all(dir)
+            .with_context(|| format!("Can't delete {}", dir.to_string_lossy()))?;
+        Ok(true)
+    }
+
+    /// Key of the entry for linting `content` at `file_path` with `config`
+    pub fn key(&self, file_path: &Path, config: &QuickmarkConfig, content: &str) -> CacheKey {
+        let mut hasher = blake3::Hasher::new();
+        for part in [
+            env!("CARGO_PKG_VERSION").as_bytes(),
+            file_path.to_string_lossy().as_bytes(),
+            config_hash(config).as_bytes(),
+            content.as_bytes(),
+        ] {
+            // Length prefixes keep the boundaries between the parts unambiguous
+            hasher.update(&(part.len() as u64).to_le_bytes());
+            hasher.update(part);
+        }
+        CacheKey {
+            hash: hasher.finalize().to_hex().to_string(),
+            config_files: config_files(config),
+        }
+    }
+
+    fn entry_path(&self, key: &CacheKey) -> PathBuf {
+        self.dir
+            .join(&key.hash[..2])
+            .join(format!("{}.json", key.hash))
+    }
+
+    /// Cached result of a file, if its entry exists and every file it depends on is
+    /// unchanged. `config` must be the one the key was computed with.
+    pub fn get(
+        &self,
+        key: &CacheKey,
+        file_path: &Path,
+        config: &QuickmarkConfig,
+    ) -> Option<CachedReport> {
+        let content = fs::read_to_string(self.entry_path(key)).ok()?;
+        let entry: Entry = serde_json::from_str(&content).ok()?;
+        let unchanged = entry
+            .dependencies
+            .iter()
+            .all(|(path, fingerprint)| self.fingerprint(path) == *fingerprint)
+            && entry
+                .config_files
+                .iter()
+                .all(|(path, hash)| self.content_hash(path) == *hash);
+        if !unchanged {
+            return None;
+        }
+
+        let violations = entry
+            .violations
+            .into_iter()
+            .map(|cached| {
+                // Entries referring to rules that no longer exist are stale
+                let rule = find_rule_for_config(&cached.rule, config)?;
+                Some(
+                    RuleViolation::new(rule, cached.message, file_path.to_path_buf(), cached.range)
+                        .with_fix(cached.fix)
+                        .with_severity(cached.severity),
+                )
+            })
+            .collect::<Option<Vec<_>>>()?;
+        Some(CachedReport {
+            violations,
+            summary: entry
+                .summary
+                .map(|summary| summary.with_config(config.clone())),
+        })
+    }
+
+    /// Store the result of a file. Failing to write the cache doesn't fail the run,
+    /// the file is linted again next time.
+    pub fn put(
+        &self,
+        key: &CacheKey,
+        violations: &[RuleViolation],
+        summary: Option<&DocumentSummary>,
+    ) {
+        let targets: BTreeSet<&Path> = summary
+            .iter()
+            .flat_map(|summary| summary.links.iter())
+            .map(|link| link.target.as_path())
+            .collect();
+        let entry = EntryRef {
+            violations: violations
+                .iter()
+                .map(|violation| CachedViolation {
+                    rule: violation.rule().alias.to_string(),
+                    message: violation.message().to_string(),
+                    range: violation.location().range.clone(),
+                    severity: violation.severity().clone(),
+                    fix: violation.fix().to_vec(),
+                })
+                .collect(),
+            summary,
+            dependencies: targets
+                .into_iter()
+                .map(|path| (path.to_path_buf(), self.fingerprint(path)))
+                .collect(),
+            config_files: key
+                .config_files
+                .iter()
+                .map(|path| (path.clone(), self.content_hash(path)))
+                .collect(),
+        };
+
+        let path = self.entry_path(key);
+        let Ok(content) = serde_json::to_string(&entry) else {
+            return;
+        };
+        // Write to a temporary file first so that concurrent runs never read half an entry
+        let temp_path = path.with_extension(format!("{}.tmp", std::process::id()));
+        let written = path
+            .parent()
+            .is_some_and(|parent| fs::create_dir_all(parent).is_ok())
+            && fs::write(&temp_path, content).is_ok()
+            && fs::rename(&temp_path, &path).is_ok();
+        if !written {
+            let _ = fs::remove_file(&temp_path);
+        }
+    }
+
+    fn fingerprint(&self, path: &Path) -> Fingerprint {
+        if let Some(fingerprint) = self.fingerprints.lock().unwrap().get(path) {
+            return fingerprint.clone();
+        }
+        let fingerprint = if !path.exists() {
+            Fingerprint::Missing
+        } else if path.is_file() && is_markdown_path(path) {
+            match fs::read(path) {
+                Ok(content) => Fingerprint::Markdown(blake3::hash(&content).to_hex().to_string()),
+                Err(_) => Fingerprint::Missing,
+            }
+        } else {
+            Fingerprint::Exists
+        };
+        self.fingerprints
+            .lock()
+            .unwrap()
+            .insert(path.to_path_buf(), fingerprint.clone());
+        fingerprint
+    }
+
+    /// Hash of the content of a file, `None` when it can't be read
+    fn content_hash(&self, path: &Path) -> Option<String> {
+        if let Some(hash) = self.content_hashes.lock().unwrap().get(path) {
+            return hash.clone();
+        }
+        let hash = fs::read(path)
+            .ok()
+            .map(|content| blake3::hash(&content).to_hex().to_string());
+        self.content_hashes
+            .lock()
+            .unwrap()
+            .insert(path.to_path_buf(), hash.clone());
+        hash
+    }
+}
+
+/// Plugin libraries of `config` and files referred to by the settings of its active rules
+fn config_files(config: &QuickmarkConfig) -> Vec<PathBuf> {
+    let mut files = config.plugins.clone();
+    let schema = &config.linters.settings.front_matter_schema.schema;
+    let schema_active = config
+        .linters
+        .severity
+        .get("front-matter-schema")
+        .is_some_and(|severity| *severity != RuleSeverity::Off);
+    if schema_active && !schema.is_empty() {
+        files.push(PathBuf::from(schema));
+    }
+    files
+}
+
+/// Hash of a configuration that doesn't depend on the iteration order of its maps
+fn config_hash(config: &QuickmarkConfig) -> String {
+    let mut value = serde_json::to_value(config).unwrap_or(Value::Null);
+    sort_keys(&mut value);
+    blake3::hash(value.to_string().as_bytes())
+        .to_hex()
+        .to_string()
+}
+
+fn sort_keys(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            let mut entries: Vec<(String, Value)> = std::mem::take(map).into_iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+            for (key, mut value) in entries {
+                sort_keys(&mut value);
+                map.insert(key, value);
+            }
+        }
+        Value::Array(values) => values.iter_mut().for_each(sort_keys),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quickmark_core::linter::MultiRuleLinter;
+    use quickmark_core::test_utils::test_helpers::test_config_with_rules;
+
+    fn lint(path: &Path, config: &QuickmarkConfig, content: &str) -> Vec<RuleViolation> {
+        MultiRuleLinter::new_for_document(path.to_path_buf(), config.clone(), content).analyze()
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let cache = LintCache::open(dir.path()).unwrap();
+        let config = test_config_with_rules(vec![
+            ("heading-increment", RuleSeverity::Warning),
+            ("no-trailing-spaces", RuleSeverity::Error),
+        ]);
+        let path = Path::new("doc.md");
+        let content = "# Title \n\n### Skipped\n";
+        let key = cache.key(path, &config, content);
+        assert!(cache.get(&key, path, &config).is_none());
+
+        let violations = lint(path, &config, content);
+        cache.put(&key, &violations, None);
+        let cached = cache.get(&key, path, &config).unwrap();
+        assert_eq!(
+            violations.iter().map(|v| v.to_string()).collect::<Vec<_>>(),
+            cached
+                .violations
+                .iter()
+                .map(|v| v.to_string())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(violations[0].severity(), cached.violations[0].severity());
+        assert_eq!(violations[1].fix(), cached.violations[1].fix());
+    }
+
+    #[test]
+    fn test_key_depends_on_content_and_config() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let cache = LintCache::open(dir.path()).unwrap();
+        let config = test_config_with_rules(vec![("line-length", RuleSeverity::Error)]);
+        let other = test_config_with_rules(vec![("line-length", RuleSeverity::Warning)]);
+        let path = Path::new("doc.md");
+
+        let key = |config, content| cache.key(path, config, content).hash;
+        let same = config.clone();
+        assert_eq!(key(&config, "# A\n"), key(&same, "# A\n"));
+        assert_ne!(key(&config, "# A\n"), key(&config, "# B\n"));
+        assert_ne!(key(&config, "# A\n"), key(&other, "# A\n"));
+        assert_ne!(
+            key(&config, "# A\n"),
+            cache.key(Path::new("b.md"), &config, "# A\n").hash
+        );
+    }
+
+    #[test]
+    fn test_clean_requires_cache_directory() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        assert!(!LintCache::clean(&dir.path().join("missing")).unwrap());
+        assert!(LintCache::clean(dir.path()).is_err());
+
+        let cache_dir = dir.path().join("cache");
+        LintCache::open(&cache_dir).unwrap();
+        assert!(LintCache::clean(&cache_dir).unwrap());
+        assert!(!cache_dir.exists());
+    }
+
+    #[test]
+    fn test_schema_changes_invalidate_entries() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let cache = LintCache::open(&dir.path().join("cache")).unwrap();
+        let schema = dir.path().join("schema.json");
+        fs::write(&schema, r#"{"required": ["title"]}"#).unwrap();
+        let mut config = test_config_with_rules(vec![("front-matter-schema", RuleSeverity::Error)]);
+        config.linters.settings.front_matter_schema.schema = schema.to_string_lossy().to_string();
+
+        let path = Path::new("doc.md");
+        let content = "---\ntitle: x\n---\n# Title\n";
+        let key = cache.key(path, &config, content);
+        cache.put(&key, &lint(path, &config, content), None);
+        assert!(cache.get(&key, path, &config).is_some());
+
+        // A later run sees the schema as changed
+        fs::write(&schema, r#"{"required": ["title", "author"]}"#).unwrap();
+        let cache = LintCache::open(&dir.path().join("cache")).unwrap();
+        assert!(cache.get(&key, path, &config).is_none());
+    }
+
+    #[test]
+    fn test_plugin_changes_invalidate_entries() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let cache = LintCache::open(&dir.path().join("cache")).unwrap();
+        let plugin = dir.path().join("libplugin.so");
+        fs::write(&plugin, "first build").unwrap();
+        let mut config = test_config_with_rules(vec![("line-length", RuleSeverity::Error)]);
+        config.plugins = vec![plugin.clone()];
+        let path = Path::new("doc.md");
+        // Files are linted with the configuration resolved for them
+        let config = config.for_file(path).unwrap();
+
+        let content = "# Title\n";
+        let key = cache.key(path, &config, content);
+        cache.put(&key, &lint(path, &config, content), None);
+        assert!(cache.get(&key, path, &config).is_some());
+
+        // A later run sees the rebuilt plugin as changed
+        fs::write(&plugin, "second build").unwrap();
+        let cache = LintCache::open(&dir.path().join("cache")).unwrap();
+        assert!(cache.get(&key, path, &config).is_none());
+    }
+}
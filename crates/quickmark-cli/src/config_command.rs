@@ -0,0 +1,200 @@
+//! `qmark config` subcommands.
+
+use anyhow::{bail, Context};
+use clap::Subcommand;
+use quickmark_core::config::{
+    config_from_env_path_or_default, import_markdownlint_config, load_config_file,
+    to_quickmark_toml, validate_config_file, ConfigDiscovery, ConfigSearchResult, QuickmarkConfig,
+    SearchStop, MARKDOWNLINT_CONFIG_FILES,
+};
+use quickmark_core::project_index::normalize_path;
+use std::path::{Path, PathBuf};
+use std::{env, fs};
+
+#[derive(Subcommand, Debug)]
+pub enum ConfigCommand {
+    /// Convert a markdownlint configuration file to quickmark.toml
+    Convert {
+        /// markdownlint or markdownlint-cli2 configuration file [default: the first one found in the current directory]
+        input: Option<PathBuf>,
+
+        /// Write the converted configuration to a file instead of stdout
+        #[arg(long, short, value_name = "FILE")]
+        output: Option<PathBuf>,
+    },
+    /// Report unknown rules, unknown settings and invalid values in a configuration file
+    Check {
+        /// Configuration file to check [default: the configuration used for the current directory]
+        file: Option<PathBuf>,
+    },
+    /// Print the configuration used for a file as TOML, and where it was found
+    Print {
+        /// File or directory to print the configuration of
+        #[arg(default_value = ".")]
+        file: PathBuf,
+    },
+}
+
+/// Run a `qmark config` subcommand and return the process exit code
+pub fn run(command: ConfigCommand) -> anyhow::Result<i32> {
+    match command {
+        ConfigCommand::Convert { input, output } => convert(input, output.as_deref()),
+        ConfigCommand::Check { file } => check(file),
+        ConfigCommand::Print { file } => print(&file),
+    }
+}
+
+fn print(file: &Path) -> anyhow::Result<i32> {
+    // Discovery walks up from an absolute path, so it can reach the current directory
+    let file = normalize_path(file);
+    let mut notes = vec![format!("Configuration for {}", file.to_string_lossy())];
+
+    let config = if let Ok(env_config_path) = env::var("QUICKMARK_CONFIG") {
+        notes.push(format!(
+            "QUICKMARK_CONFIG is set to {env_config_path}, so no configuration files are searched"
+        ));
+        config_from_env_path_or_default(&env::current_dir()?)?.for_file(&file)?
+    } else {
+        let trace = ConfigDiscovery::new().trace_config(&file);
+        let config = match trace.result {
+            ConfigSearchResult::Found { path, config } => {
+                notes.push(format!("Config file: {}", path.to_string_lossy()));
+                *config
+            }
+            ConfigSearchResult::NotFound { .. } => {
+                notes.push("Config file: none, the default configuration is used".to_string());
+                QuickmarkConfig::default_with_normalized_severities()
+            }
+            ConfigSearchResult::Error { path, error } => {
+                bail!("Can't load {}: {error}", path.to_string_lossy())
+            }
+        };
+        notes.push("Searched:".to_string());
+        notes.extend(
+            trace
+                .searched_paths
+                .iter()
+                .map(|path| format!("  {}", path.to_string_lossy())),
+        );
+        notes.push(format!("Search stopped {}", describe_stop(&trace.stop)));
+        config
+    };
+
+    // Notes are comments, so the output is a valid configuration file
+    for note in notes {
+        println!("# {note}");
+    }
+    println!();
+    print!(
+        "{}",
+        toml::to_string_pretty(&toml::Value::try_from(&config)?)?
+    );
+    Ok(0)
+}
+
+fn describe_stop(stop: &SearchStop) -> String {
+    match stop {
+        SearchStop::ConfigFile(path) => format!(
+            "at {}, which doesn't set merge_with_parent = true",
+            path.to_string_lossy()
+        ),
+        SearchStop::WorkspaceRoot(dir) => {
+            format!("at the workspace root {}", dir.to_string_lossy())
+        }
+        SearchStop::WorkingDirectory(dir) => {
+            format!("at the current directory {}", dir.to_string_lossy())
+        }
+        SearchStop::GitRoot(dir) => {
+            format!(
+                "at the root of the git repository {}",
+                dir.to_string_lossy()
+            )
+        }
+        SearchStop::FileSystemRoot => "at the root of the file system".to_string(),
+    }
+}
+
+fn check(file: Option<PathBuf>) -> anyhow::Result<i32> {
+    let file = match file {
+        Some(file) => file,
+        None => match ConfigDiscovery::new().find_config(&env::current_dir()?) {
+            ConfigSearchResult::Found { path, .. } | ConfigSearchResult::Error { path, .. } => path,
+            ConfigSearchResult::NotFound { .. } => {
+                println!("No configuration file found, the default configuration is used.");
+                return Ok(0);
+            }
+        },
+    };
+
+    let problems = validate_config_file(&file)
+        .with_context(|| format!("Can't read file {}", file.to_string_lossy()))?;
+    for problem in &problems {
+        eprintln!(
+            "{}:{}:{}: {}",
+            file.to_string_lossy(),
+            problem.range.start.line + 1,
+            problem.range.start.character + 1,
+            problem.message
+        );
+    }
+
+    if problems.is_empty() {
+        println!("{} is valid.", file.to_string_lossy());
+        Ok(0)
+    } else {
+        println!(
+            "Found {} problem{} in {}.",
+            problems.len(),
+            if problems.len() == 1 { "" } else { "s" },
+            file.to_string_lossy()
+        );
+        Ok(1)
+    }
+}
+
+fn convert(input: Option<PathBuf>, output: Option<&Path>) -> anyhow::Result<i32> {
+    let input = match input {
+        Some(input) => input,
+        None => match MARKDOWNLINT_CONFIG_FILES
+            .iter()
+            .map(PathBuf::from)
+            .find(|path| path.is_file())
+        {
+            Some(input) => input,
+            None => bail!(
+                "No markdownlint configuration file found in the current directory, looked for: {}",
+                MARKDOWNLINT_CONFIG_FILES.join(", ")
+            ),
+        },
+    };
+
+    let content = fs::read_to_string(&input)
+        .with_context(|| format!("Can't read file {}", input.to_string_lossy()))?;
+    let import = import_markdownlint_config(&content, &input)?;
+    // Make sure the converted settings are understood before writing anything
+    load_config_file(&input)
+        .with_context(|| format!("Can't convert {}", input.to_string_lossy()))?;
+
+    for warning in &import.warnings {
+        eprintln!("warning: {warning}");
+    }
+
+    let toml = format!(
+        "# Converted from {} with `qmark config convert`\n\n{}",
+        input.to_string_lossy(),
+        to_quickmark_toml(&import)?
+    );
+    match output {
+        Some(output) => {
+            fs::write(output, toml)
+                .with_context(|| format!("Can't write file {}", output.to_string_lossy()))?;
+            eprintln!(
+                "Converted {} to {}",
+                input.to_string_lossy(),
+                output.to_string_lossy()
+            );
+        }
+        None => print!("{toml}"),
+    }
+    Ok(0)
+}
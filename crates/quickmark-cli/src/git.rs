@@ -0,0 +1,216 @@
+//! Files and lines changed in the local git repository, for `--since` and `--staged`.
+//!
+//! Changes are read with the `git` executable, which only looks at the local repository.
+
+use anyhow::{bail, Context};
+use quickmark_core::linter::RuleViolation;
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// What the working tree is compared to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChangeBase {
+    /// Committed and uncommitted changes since a revision, plus untracked files
+    Since(String),
+    /// Changes staged in the index
+    Staged,
+}
+
+/// Lines of a file that changed, as zero-based inclusive ranges
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ChangedLines {
+    /// New and untracked files
+    All,
+    Ranges(Vec<(usize, usize)>),
+}
+
+/// Files changed relative to a [`ChangeBase`], keyed by canonical path
+#[derive(Debug, Default)]
+pub struct GitChanges {
+    files: HashMap<PathBuf, ChangedLines>,
+}
+
+impl GitChanges {
+    /// Read the changed files of the repository containing the current directory.
+    /// With `with_lines`, also read which lines of each file changed.
+    pub fn read(base: &ChangeBase, with_lines: bool) -> anyhow::Result<Self> {
+        let root = git(["rev-parse", "--show-toplevel"])
+            .context("--since and --staged need to be run inside a git repository")?;
+        let root = PathBuf::from(root.trim_end_matches(['\n', '\r']));
+
+        let mut diff_args: Vec<&str> = vec!["diff", "--no-ext-diff", "--no-color"];
+        match base {
+            ChangeBase::Since(rev) => {
+                if git([
+                    "rev-parse",
+                    "--verify",
+                    "--quiet",
+                    &format!("{rev}^{{commit}}"),
+                ])
+                .is_err()
+                {
+                    bail!("Unknown git revision '{rev}'");
+                }
+                diff_args.push(rev);
+            }
+            ChangeBase::Staged => diff_args.push("--cached"),
+        }
+
+        let mut files = HashMap::new();
+        let mut add_file = |path: &str, lines: ChangedLines| {
+            if let Ok(path) = fs::canonicalize(root.join(path)) {
+                files.insert(path, lines);
+            }
+        };
+
+        if with_lines {
+            let diff = git(diff_args
+                .iter()
+                .chain(&["-U0", "--no-prefix", "--diff-filter=d"]))?;
+            for (path, ranges) in parse_hunks(&diff) {
+                add_file(&path, ChangedLines::Ranges(ranges));
+            }
+        } else {
+            let names = git(diff_args
+                .iter()
+                .chain(&["--name-only", "-z", "--diff-filter=d"]))?;
+            for path in names.split('\0').filter(|path| !path.is_empty()) {
+                add_file(path, ChangedLines::All);
+            }
+        }
+
+        if let ChangeBase::Since(_) = base {
+            // --full-name makes the paths relative to the root like the ones of git diff
+            let untracked = git([
+                "ls-files",
+                "-z",
+                "--others",
+                "--exclude-standard",
+                "--full-name",
+            ])?;
+            for path in untracked.split('\0').filter(|path| !path.is_empty()) {
+                add_file(path, ChangedLines::All);
+            }
+        }
+
+        Ok(Self { files })
+    }
+
+    /// Whether a file changed
+    pub fn contains(&self, path: &Path) -> bool {
+        fs::canonicalize(path).is_ok_and(|path| self.files.contains_key(&path))
+    }
+
+    /// Whether a violation is reported on a changed line
+    pub fn intersects(&self, violation: &RuleViolation) -> bool {
+        let location = violation.location();
+        let Ok(path) = fs::canonicalize(&location.file_path) else {
+            return false;
+        };
+        let (start, end) = (location.range.start.line, location.range.end.line);
+        match self.files.get(&path) {
+            Some(ChangedLines::All) => true,
+            Some(ChangedLines::Ranges(ranges)) => ranges
+                .iter()
+                .any(|&(first, last)| start <= last && first <= end),
+            None => false,
+        }
+    }
+}
+
+/// Run git in the current directory and return its standard output
+fn git<I, S>(args: I) -> anyhow::Result<String>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<OsStr>,
+{
+    let output = Command::new("git")
+        .args(["-c", "core.quotepath=off"])
+        .args(args)
+        .output()
+        .context("Can't run git")?;
+    if !output.status.success() {
+        bail!(
+            "git failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Changed line ranges per file of a `git diff -U0 --no-prefix` output
+fn parse_hunks(diff: &str) -> Vec<(String, Vec<(usize, usize)>)> {
+    let mut files: Vec<(String, Vec<(usize, usize)>)> = Vec::new();
+    // Hunk lines may look like headers, e.g. an added line starting with "++ "
+    let mut in_header = false;
+    for line in diff.lines() {
+        if line.starts_with("diff ") {
+            in_header = true;
+        } else if let (true, Some(path)) = (in_header, line.strip_prefix("+++ ")) {
+            let path = path.trim_end_matches('\t');
+            let path = path
+                .strip_prefix('"')
+                .and_then(|path| path.strip_suffix('"'))
+                .unwrap_or(path);
+            files.push((path.to_string(), Vec::new()));
+        } else if let Some(header) = line.strip_prefix("@@ ") {
+            in_header = false;
+            // @@ -old_start[,old_count] +new_start[,new_count] @@
+            let Some(new) = header.split(' ').find_map(|part| part.strip_prefix('+')) else {
+                continue;
+            };
+            let (start, count) = match new.split_once(',') {
+                Some((start, count)) => (start.parse::<usize>(), count.parse::<usize>()),
+                None => (new.parse::<usize>(), Ok(1)),
+            };
+            // Pure deletions don't leave any changed line behind
+            if let (Ok(start), Ok(count @ 1..), Some((_, ranges))) =
+                (start, count, files.last_mut())
+            {
+                ranges.push((start - 1, start + count - 2));
+            }
+        }
+    }
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_hunks;
+
+    #[test]
+    fn test_parse_hunks() {
+        let diff = "diff --git docs/a.md docs/a.md
+index 1111111..2222222 100644
+--- docs/a.md
++++ docs/a.md
+@@ -3 +3 @@ Title
+-old
++new
+@@ -10,2 +9,0 @@
+-gone
+-gone
+@@ -20,0 +19,3 @@
++added
++++ added
++added
+diff --git new.md new.md
+new file mode 100644
+--- /dev/null
++++ new.md
+@@ -0,0 +1,2 @@
++# New
++
+";
+        assert_eq!(
+            vec![
+                ("docs/a.md".to_string(), vec![(2, 2), (18, 20)]),
+                ("new.md".to_string(), vec![(0, 1)]),
+            ],
+            parse_hunks(diff)
+        );
+    }
+}
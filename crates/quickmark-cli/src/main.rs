@@ -1,16 +1,22 @@
-use anyhow::Context;
-use clap::Parser;
+use anyhow::{bail, Context};
+use clap::{ArgGroup, Parser, Subcommand};
 use glob::glob;
 use ignore::{
     types::TypesBuilder, ParallelVisitor, ParallelVisitorBuilder, WalkBuilder, WalkState,
 };
+use quickmark_core::baseline::Baseline;
 use quickmark_core::config::{
     config_from_env_path_or_default, discover_config_or_default, QuickmarkConfig, RuleSeverity,
 };
 use quickmark_core::linter::{MultiRuleLinter, RuleViolation};
+use quickmark_core::plugin;
+use quickmark_core::project_index::ProjectIndex;
+use quickmark_core::workspace::{check_workspace, DocumentSummary};
 use rayon::prelude::*;
 use std::cmp::min;
+use std::collections::HashMap;
 use std::env;
+use std::io::{self, BufWriter, Read, Write};
 use std::path::{Path, PathBuf};
 use std::{
     fs,
@@ -18,12 +24,168 @@ use std::{
     sync::{Arc, Mutex},
 };
 
+mod cache;
+mod config_command;
+mod git;
+mod output;
+mod rules_command;
+mod watch;
+
+use cache::{CacheCommand, LintCache, DEFAULT_CACHE_LOCATION};
+use config_command::ConfigCommand;
+use git::{ChangeBase, GitChanges};
+use output::{format_text_line, write_report, OutputFormat, Report};
+
 #[derive(Parser, Debug)]
-#[command(version, about = "Quickmark: An extremely fast CommonMark linter")]
+#[command(
+    version,
+    about = "Quickmark: An extremely fast CommonMark linter",
+    args_conflicts_with_subcommands = true,
+    group(ArgGroup::new("changes").args(["since", "staged"]))
+)]
 struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// Files, directories, or glob patterns to check
-    #[arg(help = "Files, directories, or glob patterns to check [default: .]")]
+    #[arg(help = "Files, directories, or glob patterns to check, or - for stdin [default: .]")]
     files: Vec<PathBuf>,
+
+    /// Check the document read from stdin, same as passing - as the only file.
+    /// With --fix, the fixed document is written to stdout
+    #[arg(long)]
+    stdin: bool,
+
+    /// Path the document read from stdin is reported under and configured for
+    #[arg(long, value_name = "PATH")]
+    stdin_filename: Option<PathBuf>,
+
+    /// Automatically fix problems where possible and write the changes back to the files
+    #[arg(long, conflicts_with = "fix_dry_run")]
+    fix: bool,
+
+    /// Compute automatic fixes and report the remaining problems without writing any files
+    #[arg(long)]
+    fix_dry_run: bool,
+
+    /// Report format. Machine-readable formats are printed to stdout unless --output is given
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    format: OutputFormat,
+
+    /// Write the report to a file and keep the human-readable summary on the terminal
+    #[arg(long, value_name = "FILE")]
+    output: Option<PathBuf>,
+
+    /// Reuse the results of unchanged files from previous runs
+    #[arg(long)]
+    cache: bool,
+
+    /// Directory of the cache used with --cache
+    #[arg(long, value_name = "DIR", requires = "cache", default_value = DEFAULT_CACHE_LOCATION)]
+    cache_location: PathBuf,
+
+    /// Only lint files changed since a git revision, including uncommitted and untracked files
+    #[arg(long, value_name = "REV")]
+    since: Option<String>,
+
+    /// Only lint files with changes staged for commit
+    #[arg(long)]
+    staged: bool,
+
+    /// With --since or --staged, only report problems on changed lines
+    #[arg(long, requires = "changes")]
+    changed_lines_only: bool,
+
+    /// Don't report problems recorded in a baseline file
+    #[arg(long, value_name = "FILE")]
+    baseline: Option<PathBuf>,
+
+    /// Record all current problems in a baseline file instead of reporting them
+    #[arg(
+        long,
+        value_name = "FILE",
+        conflicts_with_all = ["baseline", "fix", "fix_dry_run"]
+    )]
+    write_baseline: Option<PathBuf>,
+
+    /// Keep running and lint files again as they change
+    #[arg(
+        long,
+        conflicts_with_all = [
+            "stdin", "fix", "fix_dry_run", "format", "output", "cache", "changes",
+            "baseline", "write_baseline",
+        ]
+    )]
+    watch: bool,
+
+    /// Load the plugin libraries listed in configuration files, which runs their code
+    #[arg(long, global = true)]
+    allow_plugins: bool,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Work with configuration files
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+    /// Manage the lint cache
+    Cache {
+        #[command(subcommand)]
+        command: CacheCommand,
+    },
+    /// List the rules with their tags and severities
+    Rules {
+        /// File or directory whose configuration gives the effective severities
+        #[arg(default_value = ".")]
+        path: PathBuf,
+    },
+    /// Show the documentation of a rule and its settings
+    Explain {
+        /// Rule ID or alias, e.g. MD013 or line-length
+        rule: String,
+
+        /// File or directory whose configuration gives the current settings
+        #[arg(default_value = ".")]
+        path: PathBuf,
+    },
+}
+
+/// How automatic fixes are handled during a run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FixMode {
+    /// Report problems only
+    Off,
+    /// Apply fixes and write the fixed content back to disk
+    Apply,
+    /// Apply fixes in memory only
+    DryRun,
+}
+
+impl FixMode {
+    fn from_cli(cli: &Cli) -> Self {
+        if cli.fix {
+            FixMode::Apply
+        } else if cli.fix_dry_run {
+            FixMode::DryRun
+        } else {
+            FixMode::Off
+        }
+    }
+}
+
+/// Lint outcome for a single file
+#[derive(Debug, Default)]
+struct FileReport {
+    violations: Vec<RuleViolation>,
+    /// Number of problems fixed automatically
+    fixed: usize,
+    /// Input of the workspace rules, which run once all files are linted
+    summary: Option<DocumentSummary>,
+    /// Content the violations refer to when it differs from the file on disk: the fixed
+    /// content of a dry run, or the document read from stdin
+    content: Option<String>,
 }
 
 struct FileCollector {
@@ -126,47 +288,73 @@ fn is_markdown_file(path: &Path) -> bool {
     }
 }
 
-/// Print linting errors with 1-based line numbering for CLI display
-fn print_cli_errors(results: &[RuleViolation]) -> (i32, i32) {
-    let res = results.iter().fold((0, 0), |(errs, warns), v| {
-        let severity = v.severity();
-        let prefix;
-        let mut new_err = errs;
-        let mut new_warns = warns;
-        match severity {
-            RuleSeverity::Error => {
-                prefix = "ERR";
-                new_err += 1;
-            }
-            _ => {
-                prefix = "WARN";
-                new_warns += 1;
-            }
-        };
-        // Convert 0-based line and character numbers to 1-based for CLI display
-        eprintln!(
-            "{}: {}:{}:{} {}/{} {}",
-            prefix,
-            v.location().file_path.to_string_lossy(),
-            v.location().range.start.line + 1,
-            v.location().range.start.character + 1,
-            v.rule().id,
-            v.rule().alias,
-            v.message()
-        );
-        (new_err, new_warns)
-    });
+/// Print linting errors with 1-based line numbering for CLI display, and their counts
+/// to `summary`
+fn print_cli_errors(results: &[RuleViolation], summary: &mut impl Write) -> io::Result<(i32, i32)> {
+    for v in results {
+        eprintln!("{}", format_text_line(v));
+    }
+
+    let res = count_by_severity(results);
+    writeln!(summary, "\nErrors: {}", res.0)?;
+    writeln!(summary, "Warnings: {}", res.1)?;
+    Ok(res)
+}
+
+/// Count errors and warnings among the violations
+fn count_by_severity(results: &[RuleViolation]) -> (i32, i32) {
+    results
+        .iter()
+        .fold((0, 0), |(errs, warns), v| match v.severity() {
+            RuleSeverity::Error => (errs + 1, warns),
+            _ => (errs, warns + 1),
+        })
+}
+
+/// Write the report for `--format`/`--output`
+fn write_report_to(
+    format: OutputFormat,
+    report: &Report,
+    output: Option<&Path>,
+) -> anyhow::Result<()> {
+    match output {
+        Some(path) => {
+            let file = fs::File::create(path).context(format!(
+                "Can't create report file {}",
+                path.to_string_lossy()
+            ))?;
+            let mut writer = BufWriter::new(file);
+            write_report(format, report, &mut writer)?;
+            writer.flush()?;
+        }
+        None => {
+            let mut stdout = io::stdout().lock();
+            write_report(format, report, &mut stdout)?;
+            stdout.flush()?;
+        }
+    }
+    Ok(())
+}
 
-    println!("\nErrors: {}", res.0);
-    println!("Warnings: {}", res.1);
-    res
+/// Print the number of automatically fixed problems
+fn print_fix_summary(fixed: usize, fix_mode: FixMode, summary: &mut impl Write) -> io::Result<()> {
+    match fix_mode {
+        FixMode::Apply => writeln!(summary, "Fixed: {fixed}"),
+        FixMode::DryRun => writeln!(summary, "Fixable: {fixed}"),
+        FixMode::Off => Ok(()),
+    }
 }
 
 /// Lint a single file with a pre-loaded config and return its violations
 fn lint_file_with_config(
     file_path: &Path,
     config: &QuickmarkConfig,
-) -> anyhow::Result<Vec<RuleViolation>> {
+    fix_mode: FixMode,
+    project_index: &Arc<ProjectIndex>,
+    cache: Option<&LintCache>,
+) -> anyhow::Result<FileReport> {
+    let config = config.for_file(file_path)?;
+
     // Early exit optimization: Check if any rules are enabled before file I/O
     let has_active_rules = config
         .linters
@@ -176,15 +364,91 @@ fn lint_file_with_config(
 
     if !has_active_rules {
         // No rules are active, skip file reading and processing entirely
-        return Ok(Vec::new());
+        return Ok(FileReport::default());
     }
 
     let file_content = fs::read_to_string(file_path)
         .context(format!("Can't read file {}", file_path.to_string_lossy()))?;
 
-    let mut linter =
-        MultiRuleLinter::new_for_document(file_path.to_path_buf(), config.clone(), &file_content);
-    Ok(linter.analyze())
+    lint_content(
+        file_path,
+        config,
+        &file_content,
+        fix_mode,
+        project_index,
+        cache,
+    )
+}
+
+/// Lint already loaded file content, applying automatic fixes according to `fix_mode`.
+///
+/// With a cache, unchanged files reuse their stored result unless there is something
+/// to fix, and new results are stored for the content that ends up on disk.
+fn lint_content(
+    file_path: &Path,
+    config: QuickmarkConfig,
+    file_content: &str,
+    fix_mode: FixMode,
+    project_index: &Arc<ProjectIndex>,
+    cache: Option<&LintCache>,
+) -> anyhow::Result<FileReport> {
+    let key = cache.map(|cache| cache.key(file_path, &config, file_content));
+    if let (Some(cache), Some(key)) = (cache, &key) {
+        if let Some(cached) = cache.get(key, file_path, &config) {
+            if fix_mode == FixMode::Off || !cached.violations.iter().any(|v| v.is_fixable()) {
+                return Ok(FileReport {
+                    violations: cached.violations,
+                    summary: cached.summary,
+                    ..Default::default()
+                });
+            }
+        }
+    }
+
+    if fix_mode == FixMode::Off {
+        let mut linter = MultiRuleLinter::new_for_document_in_project(
+            file_path.to_path_buf(),
+            config,
+            file_content,
+            Arc::clone(project_index),
+        );
+        let violations = linter.analyze();
+        let summary = linter.into_summary();
+        if let (Some(cache), Some(key)) = (cache, &key) {
+            cache.put(key, &violations, summary.as_ref());
+        }
+        return Ok(FileReport {
+            violations,
+            summary,
+            ..Default::default()
+        });
+    }
+
+    let result = MultiRuleLinter::fix_document_in_project(
+        file_path.to_path_buf(),
+        config.clone(),
+        file_content,
+        Arc::clone(project_index),
+    );
+    if fix_mode == FixMode::Apply && result.content != file_content {
+        fs::write(file_path, &result.content)
+            .context(format!("Can't write file {}", file_path.to_string_lossy()))?;
+    }
+    if let Some(cache) = cache {
+        // A dry run leaves the file as it is, so only unfixed content matches the disk
+        if fix_mode == FixMode::Apply || result.fixed == 0 {
+            let key = cache.key(file_path, &config, &result.content);
+            cache.put(&key, &result.remaining, result.summary.as_ref());
+        }
+    }
+
+    let content = (fix_mode == FixMode::DryRun && result.fixed > 0).then_some(result.content);
+    Ok(FileReport {
+        violations: result.remaining,
+        fixed: result.fixed,
+        summary: result.summary,
+        content,
+    })
 }
 
 /// Lint a single file with hierarchical config discovery and return its violations
@@ -192,75 +456,301 @@ fn lint_file_with_config(
 fn lint_file_with_config_discovery(
     file_path: &Path,
     use_env_config: bool,
-) -> anyhow::Result<Vec<RuleViolation>> {
+    fix_mode: FixMode,
+    project_index: &Arc<ProjectIndex>,
+    cache: Option<&LintCache>,
+) -> anyhow::Result<FileReport> {
     let file_content = fs::read_to_string(file_path)
         .context(format!("Can't read file {}", file_path.to_string_lossy()))?;
 
     // Discover configuration for each file individually for proper hierarchical discovery
     let config = if use_env_config {
         let pwd = env::current_dir()?;
-        config_from_env_path_or_default(&pwd)?
+        config_from_env_path_or_default(&pwd)?.for_file(file_path)?
     } else {
         discover_config_or_default(file_path)?
     };
 
-    let mut linter =
-        MultiRuleLinter::new_for_document(file_path.to_path_buf(), config, &file_content);
-    Ok(linter.analyze())
+    lint_content(
+        file_path,
+        config,
+        &file_content,
+        fix_mode,
+        project_index,
+        cache,
+    )
+}
+
+/// Path the document read from stdin is reported under without --stdin-filename
+const STDIN_PATH: &str = "<stdin>";
+
+/// Path of the document to read from stdin, if stdin is checked instead of files
+fn stdin_document_path(cli: &Cli) -> anyhow::Result<Option<PathBuf>> {
+    let dash = cli.files.iter().any(|file| file.as_os_str() == "-");
+    if !cli.stdin && !dash {
+        if cli.stdin_filename.is_some() {
+            bail!("--stdin-filename requires --stdin or -");
+        }
+        return Ok(None);
+    }
+    if cli.files.iter().any(|file| file.as_os_str() != "-") {
+        bail!("Files can't be checked together with stdin");
+    }
+    if cli.cache || cli.since.is_some() || cli.staged {
+        bail!("--cache, --since and --staged can't be used with stdin");
+    }
+    if cli.fix && cli.format != OutputFormat::Text && cli.output.is_none() {
+        bail!("--fix writes the document read from stdin to stdout, use --output for the report");
+    }
+    Ok(Some(
+        cli.stdin_filename
+            .clone()
+            .unwrap_or_else(|| PathBuf::from(STDIN_PATH)),
+    ))
+}
+
+/// Lint the document read from stdin as if it was stored at `file_path`.
+/// Fixes are never written to disk; the report carries the resulting content.
+fn lint_stdin(
+    file_path: &Path,
+    fix_mode: FixMode,
+    project_index: &Arc<ProjectIndex>,
+) -> anyhow::Result<FileReport> {
+    let mut content = String::new();
+    io::stdin()
+        .read_to_string(&mut content)
+        .context("Can't read stdin")?;
+
+    let config = if env::var("QUICKMARK_CONFIG").is_ok() {
+        let pwd = env::current_dir()?;
+        config_from_env_path_or_default(&pwd)?.for_file(file_path)?
+    } else {
+        discover_config_or_default(file_path)?
+    };
+
+    let fix_mode = match fix_mode {
+        FixMode::Off => FixMode::Off,
+        FixMode::Apply | FixMode::DryRun => FixMode::DryRun,
+    };
+    let mut report = lint_content(file_path, config, &content, fix_mode, project_index, None)?;
+    report.content.get_or_insert(content);
+    Ok(report)
+}
+
+/// Run the workspace rules over all linted files and add their violations to the
+/// reports of the files they are reported against
+fn add_workspace_violations(files: &[PathBuf], reports: &mut Vec<FileReport>) {
+    let summaries: Vec<DocumentSummary> = reports
+        .iter_mut()
+        .filter_map(|report| report.summary.take())
+        .collect();
+    let report_index: HashMap<&Path, usize> = files
+        .iter()
+        .enumerate()
+        .map(|(index, file)| (file.as_path(), index))
+        .collect();
+
+    // Violations against files that were not linted themselves are reported last
+    let mut other_files = FileReport::default();
+    for violation in check_workspace(&summaries) {
+        match report_index.get(violation.location().file_path.as_path()) {
+            Some(&index) => reports[index].violations.push(violation),
+            None => other_files.violations.push(violation),
+        }
+    }
+    reports.push(other_files);
+}
+
+/// Content of the documents with violations, as the violations see it
+fn violation_sources(
+    violations: &[RuleViolation],
+    mut dry_run_contents: HashMap<PathBuf, String>,
+) -> HashMap<PathBuf, String> {
+    let mut sources = HashMap::new();
+    for violation in violations {
+        let path = &violation.location().file_path;
+        if !sources.contains_key(path) {
+            let content = dry_run_contents
+                .remove(path)
+                .or_else(|| fs::read_to_string(path).ok())
+                .unwrap_or_default();
+            sources.insert(path.clone(), content);
+        }
+    }
+    sources
 }
 
 fn main() -> anyhow::Result<()> {
     let cli = Cli::parse();
+    plugin::allow_plugins(cli.allow_plugins);
+    match cli.command {
+        Some(Command::Config { command }) => exit(config_command::run(command)?),
+        Some(Command::Cache { command }) => exit(cache::run(command)?),
+        Some(Command::Rules { path }) => exit(rules_command::list(&path)?),
+        Some(Command::Explain { rule, path }) => exit(rules_command::explain(&rule, &path)?),
+        None => {}
+    }
+    if cli.watch {
+        return watch::run(&cli.files);
+    }
+    let fix_mode = FixMode::from_cli(&cli);
+
+    let stdin_path = stdin_document_path(&cli)?;
+    let change_base = match (&cli.since, cli.staged) {
+        (Some(rev), _) => Some(ChangeBase::Since(rev.clone())),
+        (None, true) => Some(ChangeBase::Staged),
+        (None, false) => None,
+    };
 
     // Discover all markdown files to process
-    let files = discover_markdown_files(&cli.files)?;
+    let mut files = match &stdin_path {
+        Some(path) => vec![path.clone()],
+        None => discover_markdown_files(&cli.files)?,
+    };
+
+    let changes = match &change_base {
+        Some(base) => {
+            let changes = GitChanges::read(base, cli.changed_lines_only)?;
+            files.retain(|file| changes.contains(file));
+            Some(changes)
+        }
+        None => None,
+    };
 
     if files.is_empty() {
-        eprintln!("No markdown files found to lint.");
+        if changes.is_some() {
+            eprintln!("No changed markdown files found to lint.");
+        } else {
+            eprintln!("No markdown files found to lint.");
+        }
         exit(0);
     }
 
+    // Documents linked from other files are indexed once and shared by the whole run
+    let project_index = Arc::new(ProjectIndex::new());
+    let cache = if cli.cache {
+        Some(LintCache::open(&cli.cache_location)?)
+    } else {
+        None
+    };
+
     // Use optimized single config loading only when QUICKMARK_CONFIG is set
     // Otherwise, preserve hierarchical config discovery for correctness
-    let (all_violations, _config) = if std::env::var("QUICKMARK_CONFIG").is_ok() {
+    let mut reports = if let Some(path) = &stdin_path {
+        vec![lint_stdin(path, fix_mode, &project_index)?]
+    } else if std::env::var("QUICKMARK_CONFIG").is_ok() {
         // Performance optimization: Load config once when using environment config
         let pwd = env::current_dir()?;
         let config = config_from_env_path_or_default(&pwd)?;
 
-        let violations: Vec<RuleViolation> = files
+        files
             .par_iter()
             .map(|file_path| {
-                lint_file_with_config(file_path, &config).unwrap_or_else(|e| {
-                    eprintln!("Error linting {}: {}", file_path.display(), e);
-                    Vec::new()
-                })
+                lint_file_with_config(file_path, &config, fix_mode, &project_index, cache.as_ref())
+                    .unwrap_or_else(|e| {
+                        eprintln!("Error linting {}: {}", file_path.display(), e);
+                        FileReport::default()
+                    })
             })
-            .flatten()
-            .collect();
-
-        (violations, config)
+            .collect()
     } else {
         // Preserve hierarchical config discovery for correctness
-        let violations: Vec<RuleViolation> = files
+        files
             .par_iter()
             .map(|file_path| {
-                lint_file_with_config_discovery(file_path, false).unwrap_or_else(|e| {
+                lint_file_with_config_discovery(
+                    file_path,
+                    false,
+                    fix_mode,
+                    &project_index,
+                    cache.as_ref(),
+                )
+                .unwrap_or_else(|e| {
                     eprintln!("Error linting {}: {}", file_path.display(), e);
-                    Vec::new()
+                    FileReport::default()
                 })
             })
-            .flatten()
-            .collect();
+            .collect()
+    };
 
-        // For hierarchical discovery, use default config for error display
-        let default_path = PathBuf::from(".");
-        let config_path = files.first().unwrap_or(&default_path);
-        let config = discover_config_or_default(config_path)?;
+    add_workspace_violations(&files, &mut reports);
+
+    let fixed: usize = reports.iter().map(|report| report.fixed).sum();
+    let contents: HashMap<PathBuf, String> = files
+        .iter()
+        .zip(reports.iter_mut())
+        .filter_map(|(file, report)| Some((file.clone(), report.content.take()?)))
+        .collect();
+    // The fixed document read from stdin goes back to stdout, so everything else goes
+    // to stderr
+    let fixed_stdin = match (&stdin_path, fix_mode) {
+        (Some(path), FixMode::Apply) => contents.get(path).cloned(),
+        _ => None,
+    };
+    let mut all_violations: Vec<RuleViolation> = reports
+        .into_iter()
+        .flat_map(|report| report.violations)
+        .collect();
+    if let (Some(changes), true) = (&changes, cli.changed_lines_only) {
+        all_violations.retain(|violation| changes.intersects(violation));
+    }
 
-        (violations, config)
+    // Baselines and reports look at the lines of the violations
+    let report_requested = cli.format != OutputFormat::Text || cli.output.is_some();
+    let sources = if report_requested || cli.write_baseline.is_some() || cli.baseline.is_some() {
+        violation_sources(&all_violations, contents)
+    } else {
+        HashMap::new()
     };
 
-    let (errs, _) = print_cli_errors(&all_violations);
+    if let Some(path) = &cli.write_baseline {
+        let mut baseline = Baseline::for_file(path);
+        for violation in &all_violations {
+            baseline.add(violation, &sources[&violation.location().file_path]);
+        }
+        baseline.save(path)?;
+        println!(
+            "Wrote {} problems to baseline {}",
+            baseline.len(),
+            path.display()
+        );
+        exit(0);
+    }
+    if let Some(path) = &cli.baseline {
+        let baseline = Baseline::load(path)?;
+        let mut matcher = baseline.matcher();
+        all_violations.retain(|violation| {
+            !matcher.matches(violation, &sources[&violation.location().file_path])
+        });
+    }
+
+    // Machine-readable reports printed to stdout replace the human-readable output
+    let report_only = cli.format != OutputFormat::Text && cli.output.is_none();
+    if report_requested {
+        let report = Report {
+            files: &files,
+            violations: &all_violations,
+            sources: &sources,
+        };
+        write_report_to(cli.format, &report, cli.output.as_deref())?;
+    }
+
+    let (errs, _) = if report_only {
+        count_by_severity(&all_violations)
+    } else if let Some(content) = &fixed_stdin {
+        let mut stderr = io::stderr().lock();
+        let counts = print_cli_errors(&all_violations, &mut stderr)?;
+        print_fix_summary(fixed, fix_mode, &mut stderr)?;
+        let mut stdout = io::stdout().lock();
+        stdout.write_all(content.as_bytes())?;
+        stdout.flush()?;
+        counts
+    } else {
+        let mut stdout = io::stdout().lock();
+        let counts = print_cli_errors(&all_violations, &mut stdout)?;
+        print_fix_summary(fixed, fix_mode, &mut stdout)?;
+        counts
+    };
     let exit_code = min(errs, 1);
     exit(exit_code);
 }
@@ -292,9 +782,14 @@ mod tests {
         let mut linter = MultiRuleLinter::new_for_document(file_path, config.clone(), file_content);
         let results = linter.analyze();
 
-        let (errs, warns) = print_cli_errors(&results);
+        let mut summary = Vec::new();
+        let (errs, warns) = print_cli_errors(&results, &mut summary).unwrap();
         assert_eq!(1, errs);
         assert_eq!(1, warns);
+        assert_eq!(
+            "\nErrors: 1\nWarnings: 1\n",
+            String::from_utf8(summary).unwrap()
+        );
     }
 
     #[test]
@@ -0,0 +1,573 @@
+//! Machine-readable report formats for lint results.
+//!
+//! Line and column numbers in every format are 1-based, and columns count characters
+//! (Unicode code points) rather than bytes. End positions point at the character
+//! following the reported range, as in SARIF regions.
+
+use clap::ValueEnum;
+use quickmark_core::config::RuleSeverity;
+use quickmark_core::linter::RuleViolation;
+use quickmark_core::rules::Rule;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+const RULE_DOCS_URL: &str = "https://github.com/ekropotin/quickmark/blob/main/docs/rules";
+
+/// Report format selected with `--format`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Human-readable `SEVERITY: file:line:col ID/alias message` lines
+    #[default]
+    Text,
+    /// JSON array of violations
+    Json,
+    /// SARIF 2.1.0 log
+    Sarif,
+    /// Checkstyle XML
+    Checkstyle,
+    /// JUnit XML
+    Junit,
+    /// GitHub Actions workflow annotation commands
+    Github,
+    /// GitLab Code Quality report
+    Gitlab,
+}
+
+/// Results of a lint run to be rendered in a report
+pub struct Report<'a> {
+    /// All files that were linted, including the ones without violations
+    pub files: &'a [PathBuf],
+    pub violations: &'a [RuleViolation],
+    /// Content of the documents with violations, as the violations see it
+    pub sources: &'a HashMap<PathBuf, String>,
+}
+
+impl Report<'_> {
+    /// The line of a document a violation refers to, empty when it's unknown
+    fn line(&self, violation: &RuleViolation, line: usize) -> &str {
+        self.sources
+            .get(&violation.location().file_path)
+            .and_then(|source| source.lines().nth(line))
+            .unwrap_or_default()
+    }
+
+    /// Position of a violation, with columns counted in characters
+    fn span(&self, violation: &RuleViolation) -> Span {
+        let range = &violation.location().range;
+        let column = |line: usize, byte: usize| {
+            let text = self.line(violation, line);
+            // Bytes past the end of the line, e.g. of an end position on the next line,
+            // are counted as characters
+            let chars = text
+                .char_indices()
+                .take_while(|(index, _)| *index < byte)
+                .count();
+            chars + byte.saturating_sub(text.len()) + 1
+        };
+        Span {
+            start_line: range.start.line + 1,
+            start_column: column(range.start.line, range.start.character),
+            end_line: range.end.line + 1,
+            end_column: column(range.end.line, range.end.character),
+        }
+    }
+}
+
+/// 1-based start and exclusive end positions of a violation
+struct Span {
+    start_line: usize,
+    start_column: usize,
+    end_line: usize,
+    end_column: usize,
+}
+
+fn severity_name(severity: &RuleSeverity) -> &'static str {
+    match severity {
+        RuleSeverity::Error => "error",
+        RuleSeverity::Warning => "warning",
+        RuleSeverity::Off => "none",
+    }
+}
+
+fn file_name(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
+
+fn rule_name(rule: &Rule) -> String {
+    format!("{}/{}", rule.id, rule.alias)
+}
+
+fn rule_docs_url(rule: &Rule) -> String {
+    format!("{RULE_DOCS_URL}/{}.md", rule.id.to_lowercase())
+}
+
+/// Format a violation as a single human-readable line
+pub fn format_text_line(violation: &RuleViolation) -> String {
+    let prefix = match violation.severity() {
+        RuleSeverity::Error => "ERR",
+        _ => "WARN",
+    };
+    // Convert 0-based line and character numbers to 1-based for CLI display
+    format!(
+        "{}: {}:{}:{} {} {}",
+        prefix,
+        violation.location().file_path.to_string_lossy(),
+        violation.location().range.start.line + 1,
+        violation.location().range.start.character + 1,
+        rule_name(violation.rule()),
+        violation.message()
+    )
+}
+
+/// Render `report` in the given format
+pub fn write_report(format: OutputFormat, report: &Report, out: &mut dyn Write) -> io::Result<()> {
+    match format {
+        OutputFormat::Text => write_text(report, out),
+        OutputFormat::Json => write_json(report, out),
+        OutputFormat::Sarif => write_sarif(report, out),
+        OutputFormat::Checkstyle => write_checkstyle(report, out),
+        OutputFormat::Junit => write_junit(report, out),
+        OutputFormat::Github => write_github(report, out),
+        OutputFormat::Gitlab => write_gitlab(report, out),
+    }
+}
+
+fn write_text(report: &Report, out: &mut dyn Write) -> io::Result<()> {
+    for violation in report.violations {
+        writeln!(out, "{}", format_text_line(violation))?;
+    }
+    Ok(())
+}
+
+fn write_json_value(value: &Value, out: &mut dyn Write) -> io::Result<()> {
+    serde_json::to_writer_pretty(&mut *out, value)?;
+    writeln!(out)
+}
+
+fn write_json(report: &Report, out: &mut dyn Write) -> io::Result<()> {
+    let violations: Vec<Value> = report
+        .violations
+        .iter()
+        .map(|v| {
+            let span = report.span(v);
+            json!({
+                "file": file_name(&v.location().file_path),
+                "rule": v.rule().id,
+                "alias": v.rule().alias,
+                "severity": severity_name(v.severity()),
+                "message": v.message(),
+                "range": {
+                    "start": { "line": span.start_line, "column": span.start_column },
+                    "end": { "line": span.end_line, "column": span.end_column },
+                },
+                "fixable": v.is_fixable(),
+            })
+        })
+        .collect();
+    write_json_value(&Value::Array(violations), out)
+}
+
+fn write_sarif(report: &Report, out: &mut dyn Write) -> io::Result<()> {
+    // Only rules that produced results are listed in the tool description
+    let mut rules: Vec<&'static Rule> = Vec::new();
+    for violation in report.violations {
+        if !rules.iter().any(|r| r.id == violation.rule().id) {
+            rules.push(violation.rule());
+        }
+    }
+    rules.sort_by_key(|r| r.id);
+
+    let rule_descriptors: Vec<Value> = rules
+        .iter()
+        .map(|rule| {
+            json!({
+                "id": rule.id,
+                "name": rule.alias,
+                "shortDescription": { "text": rule.description },
+                "helpUri": rule_docs_url(rule),
+                "properties": { "tags": rule.tags },
+            })
+        })
+        .collect();
+
+    let results: Vec<Value> = report
+        .violations
+        .iter()
+        .map(|v| {
+            let span = report.span(v);
+            json!({
+                "ruleId": v.rule().id,
+                "ruleIndex": rules.iter().position(|r| r.id == v.rule().id),
+                "level": severity_name(v.severity()),
+                "message": { "text": format!("{} {}", rule_name(v.rule()), v.message()) },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": { "uri": file_name(&v.location().file_path) },
+                        "region": {
+                            "startLine": span.start_line,
+                            "startColumn": span.start_column,
+                            "endLine": span.end_line,
+                            "endColumn": span.end_column,
+                        },
+                    },
+                }],
+            })
+        })
+        .collect();
+
+    let log = json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "quickmark",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": env!("CARGO_PKG_HOMEPAGE"),
+                    "rules": rule_descriptors,
+                },
+            },
+            "columnKind": "unicodeCodePoints",
+            "results": results,
+        }],
+    });
+    write_json_value(&log, out)
+}
+
+/// Escape text for use in XML attributes and character data
+fn xml_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\n' => escaped.push_str("&#10;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Group violations by file, keeping files without violations
+fn violations_by_file<'a>(report: &Report<'a>) -> BTreeMap<String, Vec<&'a RuleViolation>> {
+    let mut by_file: BTreeMap<String, Vec<&RuleViolation>> = report
+        .files
+        .iter()
+        .map(|path| (file_name(path), Vec::new()))
+        .collect();
+    for violation in report.violations {
+        by_file
+            .entry(file_name(&violation.location().file_path))
+            .or_default()
+            .push(violation);
+    }
+    by_file
+}
+
+fn write_checkstyle(report: &Report, out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(out, r#"<checkstyle version="4.3">"#)?;
+    for (file, violations) in violations_by_file(report) {
+        writeln!(out, r#"  <file name="{}">"#, xml_escape(&file))?;
+        for v in violations {
+            let span = report.span(v);
+            writeln!(
+                out,
+                r#"    <error line="{}" column="{}" severity="{}" message="{}" source="quickmark.{}"/>"#,
+                span.start_line,
+                span.start_column,
+                severity_name(v.severity()),
+                xml_escape(v.message()),
+                xml_escape(&rule_name(v.rule())),
+            )?;
+        }
+        writeln!(out, "  </file>")?;
+    }
+    writeln!(out, "</checkstyle>")
+}
+
+fn write_junit(report: &Report, out: &mut dyn Write) -> io::Result<()> {
+    let by_file = violations_by_file(report);
+    // Files without violations are reported as a single passing test case
+    let tests: usize = by_file.values().map(|v| v.len().max(1)).sum();
+
+    writeln!(out, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        out,
+        r#"<testsuites name="quickmark" tests="{}" failures="{}">"#,
+        tests,
+        report.violations.len()
+    )?;
+    for (file, violations) in by_file {
+        let file = xml_escape(&file);
+        writeln!(
+            out,
+            r#"  <testsuite name="{}" tests="{}" failures="{}" errors="0">"#,
+            file,
+            violations.len().max(1),
+            violations.len()
+        )?;
+        if violations.is_empty() {
+            writeln!(out, r#"    <testcase name="{file}" classname="{file}"/>"#)?;
+        }
+        for v in violations {
+            let span = report.span(v);
+            let name = xml_escape(&rule_name(v.rule()));
+            let message = xml_escape(v.message());
+            writeln!(
+                out,
+                r#"    <testcase name="{}" classname="{}">"#,
+                name, file
+            )?;
+            writeln!(
+                out,
+                r#"      <failure message="{}" type="{}">{}:{}:{} {} {} ({})</failure>"#,
+                message,
+                severity_name(v.severity()),
+                file,
+                span.start_line,
+                span.start_column,
+                name,
+                message,
+                severity_name(v.severity())
+            )?;
+            writeln!(out, "    </testcase>")?;
+        }
+        writeln!(out, "  </testsuite>")?;
+    }
+    writeln!(out, "</testsuites>")
+}
+
+/// Escape data of a GitHub workflow command
+fn github_escape_data(text: &str) -> String {
+    text.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+/// Escape a property value of a GitHub workflow command
+fn github_escape_property(text: &str) -> String {
+    github_escape_data(text)
+        .replace(':', "%3A")
+        .replace(',', "%2C")
+}
+
+fn write_github(report: &Report, out: &mut dyn Write) -> io::Result<()> {
+    for v in report.violations {
+        let span = report.span(v);
+        let command = match v.severity() {
+            RuleSeverity::Error => "error",
+            _ => "warning",
+        };
+        writeln!(
+            out,
+            "::{} file={},line={},col={},endLine={},endColumn={},title={}::{}",
+            command,
+            github_escape_property(&file_name(&v.location().file_path)),
+            span.start_line,
+            span.start_column,
+            span.end_line,
+            span.end_column,
+            github_escape_property(&rule_name(v.rule())),
+            github_escape_data(v.message())
+        )?;
+    }
+    Ok(())
+}
+
+/// Stable 64-bit FNV-1a hash, used for report fingerprints
+fn fnv1a(parts: &[&str]) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for part in parts {
+        for byte in part.bytes().chain(std::iter::once(0)) {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+    }
+    format!("{hash:016x}")
+}
+
+fn write_gitlab(report: &Report, out: &mut dyn Write) -> io::Result<()> {
+    // Fingerprints leave out positions, so that edits elsewhere in a document don't turn
+    // known issues into new ones. Repeated issues on identical lines are numbered.
+    let mut occurrences: HashMap<String, usize> = HashMap::new();
+    let issues: Vec<Value> = report
+        .violations
+        .iter()
+        .map(|v| {
+            let span = report.span(v);
+            let path = file_name(&v.location().file_path);
+            let severity = match v.severity() {
+                RuleSeverity::Error => "major",
+                _ => "minor",
+            };
+            let line = report.line(v, v.location().range.start.line).trim();
+            let fingerprint = fnv1a(&[&path, v.rule().id, line, v.message()]);
+            let occurrence = occurrences.entry(fingerprint.clone()).or_default();
+            *occurrence += 1;
+            let fingerprint = match *occurrence {
+                1 => fingerprint,
+                n => fnv1a(&[&fingerprint, &n.to_string()]),
+            };
+            json!({
+                "type": "issue",
+                "check_name": rule_name(v.rule()),
+                "description": format!("{} {}", rule_name(v.rule()), v.message()),
+                "categories": ["Style"],
+                "severity": severity,
+                "fingerprint": fingerprint,
+                "location": {
+                    "path": path,
+                    "positions": {
+                        "begin": { "line": span.start_line, "column": span.start_column },
+                        "end": { "line": span.end_line, "column": span.end_column },
+                    },
+                },
+            })
+        })
+        .collect();
+    write_json_value(&Value::Array(issues), out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quickmark_core::linter::MultiRuleLinter;
+    use quickmark_core::test_utils::test_helpers::test_config_with_rules;
+
+    fn lint(content: &str) -> Vec<RuleViolation> {
+        let config = test_config_with_rules(vec![
+            ("heading-increment", RuleSeverity::Error),
+            ("no-trailing-spaces", RuleSeverity::Warning),
+        ]);
+        MultiRuleLinter::new_for_document(PathBuf::from("docs/a&b.md"), config, content).analyze()
+    }
+
+    fn render(format: OutputFormat, content: &str) -> String {
+        let violations = lint(content);
+        let files = vec![PathBuf::from("docs/a&b.md"), PathBuf::from("clean.md")];
+        let sources = HashMap::from([(PathBuf::from("docs/a&b.md"), content.to_string())]);
+        let report = Report {
+            files: &files,
+            violations: &violations,
+            sources: &sources,
+        };
+        let mut out = Vec::new();
+        write_report(format, &report, &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_json_report() {
+        let json: Value =
+            serde_json::from_str(&render(OutputFormat::Json, "# Title\n\n### Skipped \n")).unwrap();
+        let items = json.as_array().unwrap();
+        assert_eq!(2, items.len());
+
+        let md001 = items.iter().find(|v| v["rule"] == "MD001").unwrap();
+        assert_eq!("heading-increment", md001["alias"]);
+        assert_eq!("error", md001["severity"]);
+        assert_eq!(3, md001["range"]["start"]["line"]);
+        assert_eq!(1, md001["range"]["start"]["column"]);
+
+        let md009 = items.iter().find(|v| v["rule"] == "MD009").unwrap();
+        assert_eq!("warning", md009["severity"]);
+        assert_eq!(12, md009["range"]["start"]["column"]);
+        assert_eq!(13, md009["range"]["end"]["column"]);
+        assert_eq!(true, md009["fixable"]);
+    }
+
+    #[test]
+    fn test_sarif_report() {
+        let sarif: Value =
+            serde_json::from_str(&render(OutputFormat::Sarif, "# Title\n\n### Skipped\n")).unwrap();
+        assert_eq!("2.1.0", sarif["version"]);
+        let run = &sarif["runs"][0];
+        assert_eq!("MD001", run["tool"]["driver"]["rules"][0]["id"]);
+        assert_eq!(
+            "heading-increment",
+            run["tool"]["driver"]["rules"][0]["name"]
+        );
+        let result = &run["results"][0];
+        assert_eq!("MD001", result["ruleId"]);
+        assert_eq!(0, result["ruleIndex"]);
+        assert_eq!("error", result["level"]);
+        let region = &result["locations"][0]["physicalLocation"]["region"];
+        assert_eq!(3, region["startLine"]);
+    }
+
+    #[test]
+    fn test_checkstyle_report_escapes_and_lists_all_files() {
+        let xml = render(OutputFormat::Checkstyle, "# Title\n\n### Skipped\n");
+        assert!(xml.contains(r#"<file name="docs/a&amp;b.md">"#));
+        assert!(xml.contains(r#"<file name="clean.md">"#));
+        assert!(xml.contains(r#"line="3" column="1" severity="error""#));
+        assert!(xml.contains(r#"source="quickmark.MD001/heading-increment""#));
+    }
+
+    #[test]
+    fn test_junit_report() {
+        let xml = render(OutputFormat::Junit, "# Title\n\n### Skipped\n");
+        assert!(xml.contains(r#"<testsuites name="quickmark" tests="2" failures="1">"#));
+        assert!(xml.contains(r#"<testcase name="clean.md" classname="clean.md"/>"#));
+        assert!(xml
+            .contains(r#"<testcase name="MD001/heading-increment" classname="docs/a&amp;b.md">"#));
+    }
+
+    #[test]
+    fn test_github_report() {
+        let output = render(OutputFormat::Github, "# Title\n\n### Skipped\n");
+        assert!(output.starts_with(
+            "::error file=docs/a&b.md,line=3,col=1,endLine=4,endColumn=1,title=MD001/heading-increment::"
+        ));
+    }
+
+    #[test]
+    fn test_gitlab_report() {
+        let report: Value =
+            serde_json::from_str(&render(OutputFormat::Gitlab, "# Title\n\n### Skipped\n"))
+                .unwrap();
+        let issue = &report[0];
+        assert_eq!("MD001/heading-increment", issue["check_name"]);
+        assert_eq!("major", issue["severity"]);
+        assert_eq!("docs/a&b.md", issue["location"]["path"]);
+        assert_eq!(3, issue["location"]["positions"]["begin"]["line"]);
+        assert_eq!(16, issue["fingerprint"].as_str().unwrap().len());
+
+        // Edits above an issue don't change its fingerprint
+        let moved: Value = serde_json::from_str(&render(
+            OutputFormat::Gitlab,
+            "# Title\n\nIntro\n\n### Skipped\n",
+        ))
+        .unwrap();
+        assert_eq!(5, moved[0]["location"]["positions"]["begin"]["line"]);
+        assert_eq!(issue["fingerprint"], moved[0]["fingerprint"]);
+    }
+
+    #[test]
+    fn test_columns_count_characters() {
+        let content = "# Title\n\n### Über café \n";
+        let output = render(OutputFormat::Github, content);
+        assert!(output.contains(
+            "::warning file=docs/a&b.md,line=3,col=14,endLine=3,endColumn=15,title=MD009/no-trailing-spaces::"
+        ));
+
+        let sarif: Value = serde_json::from_str(&render(OutputFormat::Sarif, content)).unwrap();
+        let run = &sarif["runs"][0];
+        assert_eq!("unicodeCodePoints", run["columnKind"]);
+        let md009 = run["results"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|result| result["ruleId"] == "MD009")
+            .unwrap();
+        let region = &md009["locations"][0]["physicalLocation"]["region"];
+        assert_eq!(14, region["startColumn"]);
+        assert_eq!(15, region["endColumn"]);
+    }
+}
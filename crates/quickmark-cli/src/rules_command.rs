@@ -0,0 +1,177 @@
+//! `qmark rules` and `qmark explain` subcommands.
+
+use anyhow::bail;
+use quickmark_core::config::{
+    config_from_env_path_or_default, discover_config_or_default, LintersSettingsTable,
+    QuickmarkConfig, RuleSeverity,
+};
+use quickmark_core::rules::{all_rules, Rule, OPT_IN_RULES};
+use serde_json::Value;
+use std::env;
+use std::path::Path;
+
+mod docs {
+    //! Rule documentation embedded by the build script, keyed by rule ID
+    include!(concat!(env!("OUT_DIR"), "/rule_docs.rs"));
+}
+
+/// Print every rule with its tags, default severity and the severity configured for `path`
+pub fn list(path: &Path) -> anyhow::Result<i32> {
+    let config = config_for(path)?;
+    let rows: Vec<[String; 5]> = all_rules()
+        .into_iter()
+        .map(|rule| {
+            [
+                rule.id.to_string(),
+                rule.alias.to_string(),
+                rule.tags.join(","),
+                severity_name(&default_severity(rule, &config)).to_string(),
+                severity_name(&effective_severity(rule, &config)).to_string(),
+            ]
+        })
+        .collect();
+
+    let header = ["ID", "ALIAS", "TAGS", "DEFAULT", "EFFECTIVE"].map(String::from);
+    let mut widths = header.clone().map(|column| column.len());
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.len());
+        }
+    }
+    for row in std::iter::once(&header).chain(&rows) {
+        let line: Vec<String> = row
+            .iter()
+            .zip(widths)
+            .map(|(cell, width)| format!("{cell:width$}"))
+            .collect();
+        println!("{}", line.join("  ").trim_end());
+    }
+    Ok(0)
+}
+
+/// Print the documentation of a rule, given by ID or alias, and its settings for `path`
+pub fn explain(rule: &str, path: &Path) -> anyhow::Result<i32> {
+    let config = config_for(path)?;
+    let Some(rule) = all_rules()
+        .into_iter()
+        .find(|candidate| candidate.id.eq_ignore_ascii_case(rule) || candidate.alias == rule)
+    else {
+        bail!("Unknown rule '{rule}', see `qmark rules` for the available rules");
+    };
+
+    match rule_doc(rule.id) {
+        Some(doc) => println!("{}", doc.trim_end()),
+        None => {
+            println!("# `{}` - {}\n", rule.id, rule.description);
+            println!("Aliases: `{}`", rule.alias);
+            if !rule.tags.is_empty() {
+                println!("\nTags: {}", rule.tags.join(", "));
+            }
+        }
+    }
+
+    println!(
+        "\nSeverity: {} (default: {})",
+        severity_name(&effective_severity(rule, &config)),
+        severity_name(&default_severity(rule, &config))
+    );
+    let settings = rule_settings(rule.alias, &config.linters.settings);
+    let defaults = rule_settings(rule.alias, &LintersSettingsTable::default());
+    match (settings, defaults) {
+        (Some(Value::Object(settings)), Some(Value::Object(defaults))) => {
+            println!("\nSettings ([linters.settings.{}]):\n", rule.alias);
+            let width = settings.keys().map(String::len).max().unwrap_or(0);
+            for (key, value) in &settings {
+                let default = defaults.get(key).unwrap_or(&Value::Null);
+                println!(
+                    "  {key:width$} = {}  (default: {})",
+                    setting_value(value),
+                    setting_value(default)
+                );
+            }
+        }
+        _ => println!("\nThis rule has no settings."),
+    }
+    Ok(0)
+}
+
+/// Configuration of `path`, as used when linting it
+fn config_for(path: &Path) -> anyhow::Result<QuickmarkConfig> {
+    if env::var("QUICKMARK_CONFIG").is_ok() {
+        config_from_env_path_or_default(&env::current_dir()?)?.for_file(path)
+    } else {
+        discover_config_or_default(path)
+    }
+}
+
+fn rule_doc(id: &str) -> Option<&'static str> {
+    docs::RULE_DOCS
+        .iter()
+        .find(|(doc_id, _)| doc_id.eq_ignore_ascii_case(id))
+        .map(|(_, doc)| *doc)
+}
+
+/// Severity of a rule without configuration: custom rules bring their own, and opt-in
+/// rules are off
+fn default_severity(rule: &Rule, config: &QuickmarkConfig) -> RuleSeverity {
+    let default = if OPT_IN_RULES.contains(&rule.alias) {
+        RuleSeverity::Off
+    } else {
+        RuleSeverity::Error
+    };
+    config
+        .custom_rules
+        .iter()
+        .find(|table| table.alias == rule.alias)
+        .and_then(|table| table.severity.clone())
+        .unwrap_or(default)
+}
+
+fn effective_severity(rule: &Rule, config: &QuickmarkConfig) -> RuleSeverity {
+    config
+        .linters
+        .severity
+        .get(rule.alias)
+        .cloned()
+        .unwrap_or_else(|| default_severity(rule, config))
+}
+
+fn severity_name(severity: &RuleSeverity) -> &'static str {
+    match severity {
+        RuleSeverity::Error => "err",
+        RuleSeverity::Warning => "warn",
+        RuleSeverity::Off => "off",
+    }
+}
+
+/// Settings table of a rule, keyed by setting name like in `quickmark.toml`
+fn rule_settings(alias: &str, settings: &LintersSettingsTable) -> Option<Value> {
+    let mut settings = serde_json::to_value(settings).ok()?;
+    settings.get_mut(alias).map(Value::take)
+}
+
+fn setting_value(value: &Value) -> String {
+    match value {
+        Value::Null => "(not set)".to_string(),
+        value => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_embedded_docs() {
+        assert!(rule_doc("MD013").unwrap().contains("Line length"));
+        assert!(rule_doc("md054").is_some());
+        assert!(rule_doc("QM003").is_some());
+    }
+
+    #[test]
+    fn test_rule_settings() {
+        let settings = rule_settings("line-length", &LintersSettingsTable::default()).unwrap();
+        assert_eq!(80, settings["line_length"]);
+        assert!(rule_settings("no-such-rule", &LintersSettingsTable::default()).is_none());
+    }
+}
@@ -0,0 +1,419 @@
+//! `qmark --watch`: lint once, then lint again whenever files change.
+//!
+//! Only changed Markdown files are linted again, along with the files linking to them and
+//! the files whose configuration was read from a changed file, including files it
+//! extends. Configuration is cached per directory between runs, and the worker threads
+//! keep their parsers and compiled rule patterns.
+
+use crate::output::format_text_line;
+use crate::{discover_markdown_files, is_markdown_file, lint_content, FixMode};
+use anyhow::Context;
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+use quickmark_core::config::{
+    config_file_sources, config_from_env_path_or_default, discover_config_with_files_or_default,
+    is_markdownlint_config, QuickmarkConfig, RuleSeverity,
+};
+use quickmark_core::linter::RuleViolation;
+use quickmark_core::project_index::{normalize_path, ProjectIndex};
+use quickmark_core::workspace::{check_workspace, DocumentSummary};
+use rayon::prelude::*;
+use std::collections::{HashMap, HashSet};
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Arc};
+use std::time::{Duration, Instant};
+use std::{env, fs};
+
+/// Quiet period after a change before linting, so that a burst of writes (e.g. a
+/// checkout or an editor's save) is linted once
+const DEBOUNCE: Duration = Duration::from_millis(100);
+
+const CLEAR_SCREEN: &str = "\x1b[2J\x1b[H";
+
+/// Lint `paths`, then keep linting changed files until interrupted
+pub fn run(paths: &[PathBuf]) -> anyhow::Result<()> {
+    let (sender, receiver) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(sender).context("Can't watch for changes")?;
+    for (root, mode) in watch_roots(paths) {
+        watcher
+            .watch(&root, mode)
+            .with_context(|| format!("Can't watch {}", root.display()))?;
+    }
+
+    let mut state = WatchState::new(paths.to_vec());
+    let started = Instant::now();
+    let linted = state.refresh(&HashSet::new())?.unwrap_or_default();
+    state.redraw(linted, started.elapsed())?;
+
+    while let Ok(event) = receiver.recv() {
+        let mut changed = HashSet::new();
+        add_changed_paths(event, &mut changed);
+        while let Ok(event) = receiver.recv_timeout(DEBOUNCE) {
+            add_changed_paths(event, &mut changed);
+        }
+        if changed.is_empty() {
+            continue;
+        }
+
+        let started = Instant::now();
+        if let Some(linted) = state.refresh(&changed)? {
+            state.redraw(linted, started.elapsed())?;
+        }
+    }
+    Ok(())
+}
+
+/// Directories to watch recursively and files to watch on their own. Glob patterns
+/// are covered by watching the current directory.
+fn watch_roots(paths: &[PathBuf]) -> Vec<(PathBuf, RecursiveMode)> {
+    if paths.is_empty() {
+        return vec![(PathBuf::from("."), RecursiveMode::Recursive)];
+    }
+    paths
+        .iter()
+        .map(|path| {
+            if path.is_dir() {
+                (path.clone(), RecursiveMode::Recursive)
+            } else if path.is_file() {
+                (path.clone(), RecursiveMode::NonRecursive)
+            } else {
+                (PathBuf::from("."), RecursiveMode::Recursive)
+            }
+        })
+        .collect()
+}
+
+/// Add the Markdown files, configuration files and directories an event is about
+fn add_changed_paths(event: notify::Result<Event>, changed: &mut HashSet<PathBuf>) {
+    match event {
+        Ok(event) if !matches!(event.kind, EventKind::Access(_)) => {
+            // Moved or removed directories only show up by their own path. Extended
+            // configuration files can have any name.
+            let relevant = |path: &&PathBuf| {
+                is_markdown_file(path)
+                    || is_config_file(path)
+                    || is_extended_config_file(path)
+                    || path.extension().is_none()
+            };
+            changed.extend(
+                event
+                    .paths
+                    .iter()
+                    .filter(relevant)
+                    .map(|path| normalize_path(path)),
+            );
+        }
+        Ok(_) => {}
+        Err(err) => eprintln!("Watch error: {err}"),
+    }
+}
+
+/// Whether a configuration file with this name is looked for in every directory
+fn is_config_file(path: &Path) -> bool {
+    path.file_name()
+        .is_some_and(|name| name == "quickmark.toml")
+        || is_markdownlint_config(path)
+}
+
+/// Whether a file could be a configuration file extended by another one
+fn is_extended_config_file(path: &Path) -> bool {
+    path.extension().is_some_and(|extension| {
+        ["toml", "json", "jsonc", "yaml", "yml"]
+            .iter()
+            .any(|e| extension == *e)
+    })
+}
+
+/// Latest lint result of a file
+#[derive(Debug, Default)]
+struct LintedFile {
+    violations: Vec<RuleViolation>,
+    summary: Option<DocumentSummary>,
+}
+
+/// Configuration of a directory and the files it was read from
+#[derive(Debug)]
+struct CachedConfig {
+    config: QuickmarkConfig,
+    sources: Vec<PathBuf>,
+}
+
+/// Configuration per directory, kept until a configuration file changes
+#[derive(Debug, Default)]
+struct ConfigCache {
+    by_dir: HashMap<PathBuf, CachedConfig>,
+}
+
+impl ConfigCache {
+    fn config_for(&mut self, file_path: &Path) -> anyhow::Result<QuickmarkConfig> {
+        let dir = config_dir(file_path);
+        let cached = match self.by_dir.get(dir) {
+            Some(cached) => cached,
+            None => {
+                let (config, sources) = match env::var("QUICKMARK_CONFIG") {
+                    Ok(path) => (
+                        config_from_env_path_or_default(&env::current_dir()?)?,
+                        config_file_sources(Path::new(&path)),
+                    ),
+                    Err(_) => discover_config_with_files_or_default(dir)?,
+                };
+                let cached = CachedConfig { config, sources };
+                self.by_dir.entry(dir.to_path_buf()).or_insert(cached)
+            }
+        };
+        // Overrides depend on the file itself
+        cached.config.for_file(file_path)
+    }
+
+    /// Whether the configuration of a file was read from one of the changed files
+    fn depends_on(&self, file_path: &Path, changed: &HashSet<PathBuf>) -> bool {
+        self.by_dir
+            .get(config_dir(file_path))
+            .is_some_and(|cached| cached.sources.iter().any(|path| changed.contains(path)))
+    }
+}
+
+/// Directory whose configuration applies to a file
+fn config_dir(file_path: &Path) -> &Path {
+    match file_path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    }
+}
+
+/// Files and results of a watch session
+struct WatchState {
+    paths: Vec<PathBuf>,
+    /// Discovered files, as reported
+    files: Vec<PathBuf>,
+    results: HashMap<PathBuf, LintedFile>,
+    configs: ConfigCache,
+    project_index: Arc<ProjectIndex>,
+}
+
+impl WatchState {
+    fn new(paths: Vec<PathBuf>) -> Self {
+        Self {
+            paths,
+            files: Vec::new(),
+            results: HashMap::new(),
+            configs: ConfigCache::default(),
+            project_index: Arc::new(ProjectIndex::new()),
+        }
+    }
+
+    /// Discover the files again and lint the ones affected by `changed`, which holds
+    /// normalized paths. Returns the number of linted files, or `None` when neither
+    /// the files nor their results changed.
+    fn refresh(&mut self, changed: &HashSet<PathBuf>) -> anyhow::Result<Option<usize>> {
+        // New configuration files apply to the files under them, existing ones to the
+        // files whose configuration was read from them
+        let config_dirs: Vec<&Path> = changed
+            .iter()
+            .filter(|path| is_config_file(path))
+            .filter_map(|path| path.parent())
+            .collect();
+        let config_dependents: HashSet<PathBuf> = self
+            .files
+            .iter()
+            .filter(|file| self.configs.depends_on(file, changed))
+            .cloned()
+            .collect();
+        if !config_dirs.is_empty() || !config_dependents.is_empty() {
+            // Configuration files may extend each other, so all of them are read again
+            self.configs.by_dir.clear();
+        }
+        for path in changed {
+            self.project_index.invalidate(path);
+        }
+
+        let mut files = discover_markdown_files(&self.paths)?;
+        files.sort();
+        files.dedup();
+        let files_changed = files != self.files;
+        self.files = files;
+        let known: HashSet<&PathBuf> = self.files.iter().collect();
+        self.results.retain(|file, _| known.contains(file));
+
+        let to_lint: Vec<PathBuf> = self
+            .files
+            .iter()
+            .filter(|file| {
+                let path = normalize_path(file);
+                match self.results.get(*file) {
+                    None => true,
+                    Some(linted) => {
+                        changed.contains(&path)
+                            || config_dependents.contains(*file)
+                            || config_dirs.iter().any(|dir| path.starts_with(dir))
+                            || links_into(linted.summary.as_ref(), changed)
+                    }
+                }
+            })
+            .cloned()
+            .collect();
+
+        let mut configs = Vec::with_capacity(to_lint.len());
+        for file in &to_lint {
+            configs.push(self.configs.config_for(file));
+        }
+        let project_index = &self.project_index;
+        let linted: Vec<(PathBuf, LintedFile)> = to_lint
+            .into_par_iter()
+            .zip(configs)
+            .map(|(file, config)| {
+                let result = config.and_then(|config| {
+                    let content = fs::read_to_string(&file)
+                        .with_context(|| format!("Can't read file {}", file.display()))?;
+                    lint_content(&file, config, &content, FixMode::Off, project_index, None)
+                });
+                let linted = match result {
+                    Ok(report) => LintedFile {
+                        violations: report.violations,
+                        summary: report.summary,
+                    },
+                    Err(e) => {
+                        eprintln!("Error linting {}: {}", file.display(), e);
+                        LintedFile::default()
+                    }
+                };
+                (file, linted)
+            })
+            .collect();
+
+        let count = linted.len();
+        self.results.extend(linted);
+        Ok((files_changed || count > 0).then_some(count))
+    }
+
+    /// Clear the terminal and print the problems of all files, in file order
+    fn redraw(&self, linted: usize, elapsed: Duration) -> io::Result<()> {
+        // Workspace rules are cheap next to linting, so they run over all files
+        let summaries: Vec<DocumentSummary> = self
+            .files
+            .iter()
+            .filter_map(|file| self.results.get(file)?.summary.clone())
+            .collect();
+        let mut workspace: HashMap<PathBuf, Vec<RuleViolation>> = HashMap::new();
+        for violation in check_workspace(&summaries) {
+            workspace
+                .entry(violation.location().file_path.clone())
+                .or_default()
+                .push(violation);
+        }
+        let violations: Vec<&RuleViolation> = self
+            .files
+            .iter()
+            .flat_map(|file| {
+                let linted = self.results.get(file).map(|linted| &linted.violations);
+                linted.into_iter().chain(workspace.get(file)).flatten()
+            })
+            .collect();
+
+        let mut stdout = io::stdout().lock();
+        if stdout.is_terminal() {
+            write!(stdout, "{CLEAR_SCREEN}")?;
+        }
+        for violation in &violations {
+            writeln!(stdout, "{}", format_text_line(violation))?;
+        }
+        let errors = violations
+            .iter()
+            .filter(|violation| *violation.severity() == RuleSeverity::Error)
+            .count();
+        writeln!(stdout, "\nErrors: {errors}")?;
+        writeln!(stdout, "Warnings: {}", violations.len() - errors)?;
+        writeln!(
+            stdout,
+            "Linted {linted} of {} files in {} ms. Watching for changes...",
+            self.files.len(),
+            elapsed.as_millis()
+        )?;
+        stdout.flush()
+    }
+}
+
+/// Whether a document links to one of the changed files
+fn links_into(summary: Option<&DocumentSummary>, changed: &HashSet<PathBuf>) -> bool {
+    summary.is_some_and(|summary| {
+        summary
+            .links
+            .iter()
+            .any(|link| changed.contains(&link.target))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_refresh_lints_affected_files() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let root = normalize_path(dir.path());
+        fs::write(
+            root.join("quickmark.toml"),
+            "[linters.severity]\ndefault = 'off'\nrelative-links = 'err'\n",
+        )
+        .unwrap();
+        fs::create_dir(root.join("docs")).unwrap();
+        fs::write(
+            root.join("index.md"),
+            "# Index\n\nSee [guide](docs/guide.md).\n",
+        )
+        .unwrap();
+        fs::write(root.join("docs/guide.md"), "# Guide\n").unwrap();
+        fs::write(root.join("docs/other.md"), "# Other\n").unwrap();
+
+        let mut state = WatchState::new(vec![root.clone()]);
+        assert_eq!(Some(3), state.refresh(&HashSet::new()).unwrap());
+        assert_eq!(None, state.refresh(&HashSet::new()).unwrap());
+
+        // The changed file and the file linking to it
+        let changed = HashSet::from([root.join("docs/guide.md")]);
+        assert_eq!(Some(2), state.refresh(&changed).unwrap());
+
+        // New files are linted on their own
+        fs::write(root.join("docs/new.md"), "# New\n").unwrap();
+        let changed = HashSet::from([root.join("docs/new.md")]);
+        assert_eq!(Some(1), state.refresh(&changed).unwrap());
+
+        // Every file under a changed configuration file
+        fs::write(root.join("docs/quickmark.toml"), "[linters.severity]\n").unwrap();
+        let changed = HashSet::from([root.join("docs/quickmark.toml")]);
+        assert_eq!(Some(3), state.refresh(&changed).unwrap());
+
+        // Every file whose configuration extends a changed file, in any directory
+        fs::create_dir(root.join("shared")).unwrap();
+        fs::write(root.join("shared/base.toml"), "[linters.severity]\n").unwrap();
+        fs::write(
+            root.join("docs/quickmark.toml"),
+            "extends = '../shared/base.toml'\n",
+        )
+        .unwrap();
+        let changed = HashSet::from([root.join("docs/quickmark.toml")]);
+        assert_eq!(Some(3), state.refresh(&changed).unwrap());
+        fs::write(
+            root.join("quickmark.toml"),
+            "extends = 'shared/base.toml'\n\n[linters.severity]\ndefault = 'off'\nrelative-links = 'err'\n",
+        )
+        .unwrap();
+        let changed = HashSet::from([root.join("quickmark.toml")]);
+        assert_eq!(Some(4), state.refresh(&changed).unwrap());
+        fs::write(
+            root.join("shared/base.toml"),
+            "[linters.severity]\nline-length = 'err'\n",
+        )
+        .unwrap();
+        let changed = HashSet::from([root.join("shared/base.toml")]);
+        assert_eq!(Some(4), state.refresh(&changed).unwrap());
+        let changed = HashSet::from([root.join("shared/other.toml")]);
+        assert_eq!(None, state.refresh(&changed).unwrap());
+
+        // Removed files are dropped
+        fs::remove_file(root.join("docs/other.md")).unwrap();
+        let changed = HashSet::from([root.join("docs/other.md")]);
+        assert_eq!(Some(0), state.refresh(&changed).unwrap());
+        assert_eq!(3, state.files.len());
+    }
+}
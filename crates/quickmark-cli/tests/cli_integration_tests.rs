@@ -541,6 +541,25 @@ fn test_cli_quickmark_config_env_invalid() {
     assert!(stderr.contains("MD003")); // Default config should catch MD003 violations
 }
 
+/// Test CLI with QUICKMARK_CONFIG environment variable pointing to an unreadable file
+#[test]
+fn test_cli_quickmark_config_env_unreadable() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_file = temp_dir.child("config.toml");
+    config_file.write_binary(&[0xff, 0xfe, 0x00]).unwrap();
+
+    let mut cmd = Command::cargo_bin("qmark").unwrap();
+    cmd.env("QUICKMARK_CONFIG", config_file.path())
+        .arg(test_sample_path("test_md001_valid.md"));
+
+    let output = cmd.assert().failure().get_output().clone();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    // Falls back to the default config
+    assert!(stderr.contains("Error loading config from QUICKMARK_CONFIG path"));
+    assert!(stderr.contains("MD003"));
+}
+
 /// Test CLI with QUICKMARK_CONFIG environment variable taking precedence over local config
 #[test]
 fn test_cli_quickmark_config_env_precedence() {
@@ -707,3 +726,875 @@ fn test_cli_no_markdown_files() {
             "No markdown files found to lint.",
         ));
 }
+
+/// Test that --fix rewrites files and only reports problems it could not fix
+#[test]
+fn test_cli_fix_writes_fixed_files() {
+    let temp_dir = TempDir::new().unwrap();
+    temp_dir.child(".git").create_dir_all().unwrap();
+
+    let md_file = temp_dir.child("test.md");
+    md_file
+        .write_str("# Title\nSome\ttext   \n\n\n## Section\n\n#### Skipped")
+        .unwrap();
+
+    let mut cmd = Command::cargo_bin("qmark").unwrap();
+    cmd.current_dir(temp_dir.path()).arg("--fix").arg("test.md");
+
+    let output = cmd.assert().failure().get_output().clone();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    // Only the heading level jump cannot be fixed automatically
+    assert!(stderr.contains("MD001"));
+    assert!(!stderr.contains("MD009"));
+    assert!(!stderr.contains("MD010"));
+    assert!(!stderr.contains("MD047"));
+    assert!(stdout.contains("Errors: 1"));
+    assert!(stdout.contains("Fixed:"));
+
+    md_file.assert("# Title\n\nSome text\n\n## Section\n\n#### Skipped\n");
+}
+
+/// Test that --fix-dry-run reports remaining problems without touching files
+#[test]
+fn test_cli_fix_dry_run_keeps_files() {
+    let temp_dir = TempDir::new().unwrap();
+    temp_dir.child(".git").create_dir_all().unwrap();
+
+    let original = "# Title\n\nTrailing spaces   \n";
+    let md_file = temp_dir.child("test.md");
+    md_file.write_str(original).unwrap();
+
+    let mut cmd = Command::cargo_bin("qmark").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .arg("--fix-dry-run")
+        .arg("test.md");
+
+    cmd.assert()
+        .success()
+        .stdout(predicates::str::contains("Errors: 0"))
+        .stdout(predicates::str::contains("Fixable: 1"));
+
+    md_file.assert(original);
+}
+
+/// Test that --fix and --fix-dry-run cannot be combined
+#[test]
+fn test_cli_fix_conflicts_with_dry_run() {
+    let mut cmd = Command::cargo_bin("qmark").unwrap();
+    cmd.arg("--fix")
+        .arg("--fix-dry-run")
+        .arg(test_sample_path("test_md001_valid.md"));
+
+    cmd.assert()
+        .failure()
+        .stderr(predicates::str::contains("cannot be used with"));
+}
+
+/// Test that machine-readable formats replace the human-readable output on stdout
+#[test]
+fn test_cli_json_format_to_stdout() {
+    let mut cmd = Command::cargo_bin("qmark").unwrap();
+    cmd.arg("--format")
+        .arg("json")
+        .arg(test_sample_path("test_md001_violations.md"));
+
+    let output = cmd.assert().failure().get_output().clone();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    let report: serde_json::Value = serde_json::from_str(&stdout).expect("valid JSON report");
+    let violations = report.as_array().unwrap();
+    assert!(violations.iter().any(|v| v["rule"] == "MD001"
+        && v["alias"] == "heading-increment"
+        && v["severity"] == "error"
+        && v["range"]["start"]["line"].as_u64().unwrap() >= 1));
+    assert!(!stdout.contains("Errors:"));
+    assert!(!stderr.contains("ERR:"));
+}
+
+/// Test that --output writes the report to a file and keeps the summary on the terminal
+#[test]
+fn test_cli_output_file_keeps_summary() {
+    let temp_dir = TempDir::new().unwrap();
+    let report_file = temp_dir.child("report.sarif");
+
+    let mut cmd = Command::cargo_bin("qmark").unwrap();
+    cmd.arg("--format")
+        .arg("sarif")
+        .arg("--output")
+        .arg(report_file.path())
+        .arg(test_sample_path("test_md001_violations.md"));
+
+    cmd.assert()
+        .failure()
+        .stderr(predicates::str::contains("MD001/heading-increment"))
+        .stdout(predicates::str::contains("Errors:"));
+
+    let report: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(report_file.path()).unwrap()).unwrap();
+    assert_eq!("2.1.0", report["version"]);
+    assert_eq!("quickmark", report["runs"][0]["tool"]["driver"]["name"]);
+    assert!(!report["runs"][0]["results"].as_array().unwrap().is_empty());
+}
+
+/// Test that GitHub workflow annotations are printed for each violation
+#[test]
+fn test_cli_github_format() {
+    let mut cmd = Command::cargo_bin("qmark").unwrap();
+    cmd.arg("--format")
+        .arg("github")
+        .arg(test_sample_path("test_md001_violations.md"));
+
+    cmd.assert()
+        .failure()
+        .stdout(predicates::str::contains("::error file="))
+        .stdout(predicates::str::contains("title=MD001/heading-increment::"));
+}
+
+/// Test that unknown formats are rejected
+#[test]
+fn test_cli_unknown_format() {
+    let mut cmd = Command::cargo_bin("qmark").unwrap();
+    cmd.arg("--format")
+        .arg("yaml")
+        .arg(test_sample_path("test_md001_valid.md"));
+
+    cmd.assert()
+        .failure()
+        .stderr(predicates::str::contains("invalid value 'yaml'"));
+}
+
+/// Test that `[[overrides]]` apply only to the files they match
+#[test]
+fn test_cli_config_overrides() {
+    let temp_dir = TempDir::new().unwrap();
+    temp_dir
+        .child("quickmark.toml")
+        .write_str(
+            r#"
+[linters.severity]
+default = 'off'
+no-trailing-spaces = 'err'
+
+[[overrides]]
+files = ["CHANGELOG.md", "docs/generated/**"]
+exclude = ["docs/generated/index.md"]
+
+[overrides.severity]
+no-trailing-spaces = 'off'
+"#,
+        )
+        .unwrap();
+    let content = "# Title \n";
+    temp_dir.child("CHANGELOG.md").write_str(content).unwrap();
+    temp_dir
+        .child("docs/generated/api.md")
+        .write_str(content)
+        .unwrap();
+    temp_dir
+        .child("docs/generated/index.md")
+        .write_str(content)
+        .unwrap();
+    temp_dir.child("README.md").write_str(content).unwrap();
+
+    let mut cmd = Command::cargo_bin("qmark").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .arg("CHANGELOG.md")
+        .arg("docs/generated/api.md")
+        .arg("docs/generated/index.md")
+        .arg("README.md");
+
+    let output = cmd.assert().failure().get_output().clone();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("README.md:1:"), "{stderr}");
+    assert!(stderr.contains("index.md:1:"), "{stderr}");
+    assert!(!stderr.contains("CHANGELOG.md"), "{stderr}");
+    assert!(!stderr.contains("api.md"), "{stderr}");
+}
+
+/// Test that markdownlint configuration files are picked up by config discovery
+#[test]
+fn test_cli_reads_markdownlint_config() {
+    let temp_dir = TempDir::new().unwrap();
+    temp_dir
+        .child(".markdownlint.json")
+        .write_str(r#"{ "default": false, "MD009": true }"#)
+        .unwrap();
+    temp_dir
+        .child("test.md")
+        .write_str("Some text \n# Title\n")
+        .unwrap();
+
+    let mut cmd = Command::cargo_bin("qmark").unwrap();
+    cmd.current_dir(temp_dir.path()).arg("test.md");
+
+    let output = cmd.assert().failure().get_output().clone();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("MD009/no-trailing-spaces"), "{stderr}");
+    assert!(!stderr.contains("MD041"), "{stderr}");
+}
+
+/// Test converting a markdownlint configuration file to quickmark.toml
+#[test]
+fn test_cli_config_convert() {
+    let temp_dir = TempDir::new().unwrap();
+    temp_dir
+        .child(".markdownlint.yaml")
+        .write_str("default: false\nMD013:\n  line_length: 120\nMD002: false\n")
+        .unwrap();
+
+    let mut cmd = Command::cargo_bin("qmark").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .arg("config")
+        .arg("convert")
+        .arg("--output")
+        .arg("quickmark.toml");
+    cmd.assert().success().stderr(predicates::str::contains(
+        "warning: `MD002` is not a rule or tag supported by quickmark",
+    ));
+
+    let converted = std::fs::read_to_string(temp_dir.child("quickmark.toml").path()).unwrap();
+    assert!(converted.contains("[linters.severity]\ndefault = \"off\"\nline-length = \"err\"\n"));
+    assert!(converted.contains("[linters.settings.line-length]\nline_length = 120\n"));
+}
+
+/// Test that conversion fails without a markdownlint configuration file
+#[test]
+fn test_cli_config_convert_without_input() {
+    let temp_dir = TempDir::new().unwrap();
+
+    let mut cmd = Command::cargo_bin("qmark").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .arg("config")
+        .arg("convert");
+    cmd.assert().failure().stderr(predicates::str::contains(
+        "No markdownlint configuration file found",
+    ));
+}
+
+/// Test that `qmark config check` reports located configuration problems
+#[test]
+fn test_cli_config_check() {
+    let temp_dir = TempDir::new().unwrap();
+    let config = temp_dir.child("quickmark.toml");
+    config
+        .write_str("[linters.severity]\nline-lenght = 'off'\n")
+        .unwrap();
+
+    let mut cmd = Command::cargo_bin("qmark").unwrap();
+    cmd.current_dir(temp_dir.path()).arg("config").arg("check");
+    cmd.assert()
+        .failure()
+        .stderr(predicates::str::contains(
+            "quickmark.toml:2:1: Unknown rule `line-lenght`, did you mean `line-length`?",
+        ))
+        .stdout(predicates::str::contains("Found 1 problem in"));
+
+    config
+        .write_str("[linters.severity]\nline-length = 'off'\n")
+        .unwrap();
+    let mut cmd = Command::cargo_bin("qmark").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .arg("config")
+        .arg("check")
+        .arg("quickmark.toml");
+    cmd.assert()
+        .success()
+        .stdout(predicates::str::contains("quickmark.toml is valid."));
+}
+
+/// Test front matter validation with a JSON Schema file next to the configuration
+#[test]
+fn test_cli_front_matter_schema() {
+    let temp_dir = TempDir::new().unwrap();
+    temp_dir
+        .child("quickmark.toml")
+        .write_str(
+            r#"
+[linters.severity]
+default = 'off'
+front-matter-schema = 'err'
+
+[linters.settings.front-matter-schema]
+required = ["title", "owner"]
+schema = "schemas/page.json"
+
+[linters.settings.front-matter-schema.properties.owner]
+enum = ["docs", "platform"]
+"#,
+        )
+        .unwrap();
+    temp_dir
+        .child("schemas/page.json")
+        .write_str(r#"{"properties": {"tags": {"type": "array"}}}"#)
+        .unwrap();
+    temp_dir
+        .child("docs/page.md")
+        .write_str("---\ntitle: Page\nowner: sales\ntags: x\n---\n# Page\n")
+        .unwrap();
+
+    let mut cmd = Command::cargo_bin("qmark").unwrap();
+    cmd.current_dir(temp_dir.path()).arg("docs/page.md");
+
+    let output = cmd.assert().failure().get_output().clone();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("page.md:3:1 QM001/front-matter-schema Front matter key `owner` must be one of \"docs\", \"platform\", found \"sales\""),
+        "{stderr}"
+    );
+    assert!(
+        stderr.contains("page.md:4:1 QM001/front-matter-schema Front matter key `tags` must be of type array, found string"),
+        "{stderr}"
+    );
+}
+
+/// Test that relative links are checked against the other files of the project
+#[test]
+fn test_cli_relative_links() {
+    let temp_dir = TempDir::new().unwrap();
+    temp_dir
+        .child("quickmark.toml")
+        .write_str("[linters.severity]\ndefault = 'off'\nrelative-links = 'err'\n")
+        .unwrap();
+    temp_dir
+        .child("docs/setup.md")
+        .write_str("# Setup\n\n## Install\n\nBack to the [index](../index.md#overview).\n")
+        .unwrap();
+    temp_dir
+        .child("index.md")
+        .write_str(
+            "# Overview\n\nSee [install](docs/setup.md#install), [usage](docs/setup.md#usage)\nand [faq](docs/faq.md).\n",
+        )
+        .unwrap();
+
+    let mut cmd = Command::cargo_bin("qmark").unwrap();
+    cmd.current_dir(temp_dir.path()).arg(".");
+
+    let output = cmd.assert().failure().get_output().clone();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("index.md:3:47 QM002/relative-links Link fragment 'usage' does not match any heading or anchor in 'docs/setup.md'"),
+        "{stderr}"
+    );
+    assert!(
+        stderr.contains(
+            "index.md:4:11 QM002/relative-links Link target 'docs/faq.md' does not exist"
+        ),
+        "{stderr}"
+    );
+    assert!(!stderr.contains("setup.md:"), "{stderr}");
+}
+
+/// Test that workspace rules see all files of the run
+#[test]
+fn test_cli_duplicate_titles_across_files() {
+    let temp_dir = TempDir::new().unwrap();
+    temp_dir
+        .child("quickmark.toml")
+        .write_str("[linters.severity]\ndefault = 'off'\nduplicate-titles = 'warn'\n")
+        .unwrap();
+    temp_dir.child("a.md").write_str("# Setup\n").unwrap();
+    temp_dir
+        .child("guide/b.md")
+        .write_str("Intro.\n\n# Setup\n")
+        .unwrap();
+    temp_dir.child("guide/c.md").write_str("# Usage\n").unwrap();
+
+    let mut cmd = Command::cargo_bin("qmark").unwrap();
+    cmd.current_dir(temp_dir.path()).arg(".");
+
+    let output = cmd.assert().success().get_output().clone();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stderr.contains(
+            "WARN: ./guide/b.md:3:1 QM003/duplicate-titles Title 'Setup' is already used by './a.md'"
+        ),
+        "{stderr}"
+    );
+    assert!(stdout.contains("Warnings: 1"), "{stdout}");
+}
+
+/// The example plugin library, built next to the test binary as a dev-dependency
+#[cfg(unix)]
+fn example_plugin_path() -> PathBuf {
+    let deps_dir = std::env::current_exe()
+        .unwrap()
+        .parent()
+        .unwrap()
+        .to_path_buf();
+    deps_dir.join(format!(
+        "{}quickmark_plugin_example{}",
+        std::env::consts::DLL_PREFIX,
+        std::env::consts::DLL_SUFFIX
+    ))
+}
+
+/// Test rules loaded from a plugin library, with their own severity and settings
+#[cfg(unix)]
+#[test]
+fn test_cli_plugin_rules() {
+    let temp_dir = TempDir::new().unwrap();
+    let config = format!(
+        r#"
+plugins = ["{}"]
+
+[linters.severity]
+default = 'off'
+todo-markers = 'warn'
+
+[linters.settings.todo-markers]
+markers = ["TODO", "XXX"]
+"#,
+        example_plugin_path().to_string_lossy()
+    );
+    temp_dir.child("quickmark.toml").write_str(&config).unwrap();
+    temp_dir
+        .child("doc.md")
+        .write_str("# Title\n\nTODO: write this.\nFIXME and XXX\n\n<!-- quickmark-disable-next-line todo-markers -->\nTODO later\n")
+        .unwrap();
+
+    let mut cmd = Command::cargo_bin("qmark").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .arg("--allow-plugins")
+        .arg("doc.md");
+    let output = cmd.assert().success().get_output().clone();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stderr.contains("WARN: doc.md:3:1 EX001/todo-markers Unresolved 'TODO' marker"),
+        "{stderr}"
+    );
+    assert!(
+        stderr.contains("WARN: doc.md:4:11 EX001/todo-markers Unresolved 'XXX' marker"),
+        "{stderr}"
+    );
+    assert!(stdout.contains("Warnings: 2"), "{stdout}");
+
+    let mut cmd = Command::cargo_bin("qmark").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .args(["config", "check", "--allow-plugins"]);
+    cmd.assert()
+        .success()
+        .stdout(predicates::str::contains("quickmark.toml is valid."));
+
+    // Without the opt-in the plugin is not loaded
+    let mut cmd = Command::cargo_bin("qmark").unwrap();
+    cmd.current_dir(temp_dir.path()).arg("doc.md");
+    let output = cmd.assert().success().get_output().clone();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!stderr.contains("EX001"), "{stderr}");
+    assert!(stderr.contains("is not loaded"), "{stderr}");
+
+    let mut cmd = Command::cargo_bin("qmark").unwrap();
+    cmd.current_dir(temp_dir.path()).args(["config", "check"]);
+    cmd.assert().failure().stderr(predicates::str::contains(
+        "Plugins are only loaded when allowed",
+    ));
+}
+
+/// Test that plugin rules don't apply to files whose configuration doesn't list the plugin
+#[cfg(unix)]
+#[test]
+fn test_cli_plugin_rules_stay_with_their_configuration() {
+    let temp_dir = TempDir::new().unwrap();
+    temp_dir
+        .child("a/quickmark.toml")
+        .write_str(&format!(
+            "plugins = [\"{}\"]\n",
+            example_plugin_path().to_string_lossy()
+        ))
+        .unwrap();
+    temp_dir
+        .child("b/quickmark.toml")
+        .write_str("[linters.severity]\n")
+        .unwrap();
+    for dir in ["a", "b"] {
+        temp_dir
+            .child(format!("{dir}/doc.md"))
+            .write_str("# Title\n\nTODO: write this.\n")
+            .unwrap();
+    }
+
+    let mut cmd = Command::cargo_bin("qmark").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .args(["--allow-plugins", "a", "b"]);
+    let output = cmd.assert().failure().get_output().clone();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("a/doc.md:3:1 EX001/todo-markers"),
+        "{stderr}"
+    );
+    assert!(!stderr.contains("b/doc.md:3:1 EX001"), "{stderr}");
+}
+
+/// Test that a plugin that can't be loaded is reported
+#[test]
+fn test_cli_missing_plugin() {
+    let temp_dir = TempDir::new().unwrap();
+    temp_dir
+        .child("quickmark.toml")
+        .write_str("plugins = [\"plugins/missing.so\"]\n")
+        .unwrap();
+    temp_dir.child("doc.md").write_str("# Title\n").unwrap();
+
+    let mut cmd = Command::cargo_bin("qmark").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .args(["config", "check", "--allow-plugins"]);
+    cmd.assert().failure().stderr(predicates::str::contains(
+        "quickmark.toml:1:12: Can't find plugin",
+    ));
+}
+
+/// Test rules declared in `[[custom_rules]]` tables
+#[test]
+fn test_cli_custom_rules() {
+    let temp_dir = TempDir::new().unwrap();
+    temp_dir
+        .child("quickmark.toml")
+        .write_str(
+            r#"
+[linters.severity]
+default = 'off'
+
+[[custom_rules]]
+id = "CUSTOM001"
+alias = "no-simply"
+message = "Avoid '{text}'"
+severity = "warn"
+nodes = ["inline"]
+pattern = "(?i)\\bsimply\\b"
+"#,
+        )
+        .unwrap();
+    temp_dir
+        .child("doc.md")
+        .write_str("# Title\n\nSimply run the installer.\n")
+        .unwrap();
+
+    let mut cmd = Command::cargo_bin("qmark").unwrap();
+    cmd.current_dir(temp_dir.path()).arg("doc.md");
+    cmd.assert().success().stderr(predicates::str::contains(
+        "WARN: doc.md:3:1 CUSTOM001/no-simply Avoid 'Simply'",
+    ));
+}
+
+/// Test that cached results follow changes of linked files and that the cache can be cleaned
+#[test]
+fn test_cli_cache() {
+    let temp_dir = TempDir::new().unwrap();
+    temp_dir
+        .child("quickmark.toml")
+        .write_str("[linters.severity]\ndefault = 'off'\nrelative-links = 'err'\nno-trailing-spaces = 'warn'\n")
+        .unwrap();
+    temp_dir
+        .child("docs/setup.md")
+        .write_str("# Setup\n\n## Install\n")
+        .unwrap();
+    temp_dir
+        .child("index.md")
+        .write_str("# Overview \n\nSee [usage](docs/setup.md#usage).\n")
+        .unwrap();
+
+    let run = || {
+        let mut cmd = Command::cargo_bin("qmark").unwrap();
+        cmd.current_dir(temp_dir.path())
+            .arg("--cache")
+            .arg("--cache-location")
+            .arg("cache")
+            .arg(".");
+        cmd.assert()
+    };
+
+    for _ in 0..2 {
+        run()
+            .failure()
+            .stderr(predicates::str::contains(
+                "index.md:3:13 QM002/relative-links Link fragment 'usage'",
+            ))
+            .stderr(predicates::str::contains(
+                "WARN: ./index.md:1:11 MD009/no-trailing-spaces",
+            ));
+    }
+    assert!(temp_dir.child("cache/CACHEDIR.TAG").exists());
+
+    // index.md is unchanged, but the heading it links to now exists
+    temp_dir
+        .child("docs/setup.md")
+        .write_str("# Setup\n\n## Install\n\n## Usage\n")
+        .unwrap();
+    run()
+        .success()
+        .stderr(predicates::str::contains("QM002").not())
+        .stderr(predicates::str::contains("MD009/no-trailing-spaces"));
+
+    let mut cmd = Command::cargo_bin("qmark").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .args(["cache", "clean", "--cache-location", "cache"]);
+    cmd.assert().success();
+    assert!(!temp_dir.child("cache").exists());
+}
+
+/// Run git in a test repository
+fn git(dir: &std::path::Path, args: &[&str]) {
+    let status = std::process::Command::new("git")
+        .current_dir(dir)
+        .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+        .args(args)
+        .output()
+        .unwrap()
+        .status;
+    assert!(status.success(), "git {args:?} failed");
+}
+
+/// Test --since, --staged and --changed-lines-only against a git repository
+#[test]
+fn test_cli_changed_files_and_lines() {
+    let temp_dir = TempDir::new().unwrap();
+    temp_dir
+        .child("quickmark.toml")
+        .write_str("[linters.severity]\ndefault = 'off'\nno-trailing-spaces = 'err'\n")
+        .unwrap();
+    temp_dir
+        .child("old.md")
+        .write_str("# Old \n\ntext\n")
+        .unwrap();
+    temp_dir
+        .child("edited.md")
+        .write_str("# Edited \n\nfirst\n")
+        .unwrap();
+    git(temp_dir.path(), &["init", "-q"]);
+    git(temp_dir.path(), &["add", "."]);
+    git(temp_dir.path(), &["commit", "-q", "-m", "initial"]);
+
+    temp_dir
+        .child("edited.md")
+        .write_str("# Edited \n\nfirst\nsecond \n")
+        .unwrap();
+    temp_dir.child("new.md").write_str("# New \n").unwrap();
+
+    let run = |args: &[&str]| {
+        let mut cmd = Command::cargo_bin("qmark").unwrap();
+        cmd.current_dir(temp_dir.path()).args(args);
+        let output = cmd.assert().failure().get_output().clone();
+        String::from_utf8_lossy(&output.stderr).into_owned()
+    };
+
+    let stderr = run(&["--since", "HEAD"]);
+    assert!(stderr.contains("edited.md:1:"), "{stderr}");
+    assert!(stderr.contains("edited.md:4:"), "{stderr}");
+    assert!(stderr.contains("new.md:1:"), "{stderr}");
+    assert!(!stderr.contains("old.md"), "{stderr}");
+
+    let stderr = run(&["--since", "HEAD", "--changed-lines-only"]);
+    assert!(!stderr.contains("edited.md:1:"), "{stderr}");
+    assert!(stderr.contains("edited.md:4:"), "{stderr}");
+    assert!(stderr.contains("new.md:1:"), "{stderr}");
+
+    git(temp_dir.path(), &["add", "edited.md"]);
+    let stderr = run(&["--staged", "--changed-lines-only"]);
+    assert!(stderr.contains("edited.md:4:"), "{stderr}");
+    assert!(!stderr.contains("new.md"), "{stderr}");
+
+    let mut cmd = Command::cargo_bin("qmark").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .args(["--since", "no-such-rev"]);
+    cmd.assert().failure().stderr(predicates::str::contains(
+        "Unknown git revision 'no-such-rev'",
+    ));
+
+    let mut cmd = Command::cargo_bin("qmark").unwrap();
+    cmd.current_dir(temp_dir.path()).arg("--changed-lines-only");
+    cmd.assert().failure();
+}
+
+#[test]
+fn test_cli_baseline() {
+    let temp_dir = TempDir::new().unwrap();
+    temp_dir
+        .child("quickmark.toml")
+        .write_str("[linters.severity]\ndefault = 'off'\nno-trailing-spaces = 'err'\n")
+        .unwrap();
+    let doc = temp_dir.child("docs/guide.md");
+    doc.write_str("# Guide\n\nOld text \n\nEnd\n").unwrap();
+
+    let qmark = || {
+        let mut cmd = Command::cargo_bin("qmark").unwrap();
+        cmd.current_dir(temp_dir.path());
+        cmd
+    };
+
+    qmark()
+        .args(["--write-baseline", "quickmark-baseline.json", "."])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains(
+            "Wrote 1 problems to baseline quickmark-baseline.json",
+        ));
+    temp_dir
+        .child("quickmark-baseline.json")
+        .assert(predicates::str::contains("\"file\": \"docs/guide.md\""));
+
+    // Known problems move with their lines; new ones are still reported
+    doc.write_str("# Guide\n\nIntro\n\nOld text \n\nEnd\n\nNew text \n")
+        .unwrap();
+    qmark()
+        .args(["--baseline", "quickmark-baseline.json", "."])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains(
+            "guide.md:9:9 MD009/no-trailing-spaces",
+        ))
+        .stderr(predicates::str::contains("guide.md:5:").not());
+
+    qmark()
+        .args(["--baseline", "missing.json", "."])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains(
+            "Can't read baseline file missing.json",
+        ));
+}
+
+#[test]
+fn test_cli_stdin() {
+    let temp_dir = TempDir::new().unwrap();
+    temp_dir
+        .child("docs/quickmark.toml")
+        .write_str("[linters.severity]\ndefault = 'off'\nno-trailing-spaces = 'err'\n")
+        .unwrap();
+    let source = "# Title\n\nSome text \n";
+
+    // The virtual path picks the configuration of docs/, which only checks MD009
+    let mut cmd = Command::cargo_bin("qmark").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .args(["--stdin-filename", "docs/unsaved.md", "-"])
+        .write_stdin(source);
+    cmd.assert()
+        .failure()
+        .stderr(predicates::str::contains(
+            "docs/unsaved.md:3:10 MD009/no-trailing-spaces",
+        ))
+        .stderr(predicates::str::contains("MD041").not())
+        .stdout(predicates::str::contains("Errors: 1"));
+
+    // Fixed documents go to stdout and the summary to stderr
+    let mut cmd = Command::cargo_bin("qmark").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .args(["--stdin", "--stdin-filename", "docs/unsaved.md", "--fix"])
+        .write_stdin(source);
+    cmd.assert()
+        .success()
+        .stdout("# Title\n\nSome text\n")
+        .stderr(predicates::str::contains("Fixed: 1"));
+    assert!(!temp_dir.child("docs/unsaved.md").exists());
+
+    let mut cmd = Command::cargo_bin("qmark").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .args(["-", "README.md"])
+        .write_stdin(source);
+    cmd.assert().failure().stderr(predicates::str::contains(
+        "Files can't be checked together with stdin",
+    ));
+}
+
+#[test]
+fn test_cli_rules_and_explain() {
+    let temp_dir = TempDir::new().unwrap();
+    temp_dir
+        .child("quickmark.toml")
+        .write_str("[linters.severity]\nline-length = 'warn'\n\n[linters.settings.line-length]\nline_length = 120\n")
+        .unwrap();
+
+    let mut cmd = Command::cargo_bin("qmark").unwrap();
+    cmd.current_dir(temp_dir.path()).arg("rules");
+    cmd.assert()
+        .success()
+        .stdout(predicates::str::is_match(r"ID\s+ALIAS\s+TAGS\s+DEFAULT\s+EFFECTIVE").unwrap())
+        .stdout(
+            predicates::str::is_match(r"MD013\s+line-length\s+line_length\s+err\s+warn\n").unwrap(),
+        )
+        .stdout(
+            predicates::str::is_match(r"QM003\s+duplicate-titles\s+\S+\s+off\s+off\n").unwrap(),
+        );
+
+    let mut cmd = Command::cargo_bin("qmark").unwrap();
+    cmd.current_dir(temp_dir.path()).args(["explain", "MD013"]);
+    cmd.assert()
+        .success()
+        .stdout(predicates::str::contains("# `MD013` - Line length"))
+        .stdout(predicates::str::contains("Severity: warn (default: err)"))
+        .stdout(predicates::str::contains(
+            "line_length            = 120  (default: 80)",
+        ));
+
+    let mut cmd = Command::cargo_bin("qmark").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .args(["explain", "no-such-rule"]);
+    cmd.assert()
+        .failure()
+        .stderr(predicates::str::contains("Unknown rule 'no-such-rule'"));
+}
+
+#[test]
+fn test_cli_config_print() {
+    let temp_dir = TempDir::new().unwrap();
+    temp_dir.child("repo/.git").create_dir_all().unwrap();
+    temp_dir
+        .child("repo/docs/quickmark.toml")
+        .write_str("[linters.severity]\nline-length = 'warn'\n")
+        .unwrap();
+    temp_dir
+        .child("repo/notes/todo.md")
+        .write_str("# Todo\n")
+        .unwrap();
+    let repo = temp_dir.path().join("repo");
+
+    let mut cmd = Command::cargo_bin("qmark").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .env_remove("QUICKMARK_CONFIG")
+        .args(["config", "print", "repo/docs/guide.md"]);
+    cmd.assert()
+        .success()
+        .stdout(predicates::str::contains(format!(
+            "# Config file: {}",
+            repo.join("docs/quickmark.toml").display()
+        )))
+        .stdout(predicates::str::contains(format!(
+            "#   {}",
+            repo.join("docs/.markdownlint.json").display()
+        )))
+        .stdout(predicates::str::contains(
+            "which doesn't set merge_with_parent = true",
+        ))
+        .stdout(predicates::str::contains("line-length = \"warn\""))
+        .stdout(predicates::str::contains("heading-increment = \"err\""));
+
+    // Without configuration, the search ends at the repository root
+    let mut cmd = Command::cargo_bin("qmark").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .env_remove("QUICKMARK_CONFIG")
+        .args(["config", "print", "repo/notes/todo.md"]);
+    cmd.assert()
+        .success()
+        .stdout(predicates::str::contains(
+            "# Config file: none, the default configuration is used",
+        ))
+        .stdout(predicates::str::contains(format!(
+            "# Search stopped at the root of the git repository {}",
+            repo.display()
+        )));
+
+    let mut cmd = Command::cargo_bin("qmark").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .env("QUICKMARK_CONFIG", "repo/docs/quickmark.toml")
+        .args(["config", "print", "repo/notes/todo.md"]);
+    cmd.assert()
+        .success()
+        .stdout(predicates::str::contains(
+            "QUICKMARK_CONFIG is set to repo/docs/quickmark.toml",
+        ))
+        .stdout(predicates::str::contains("line-length = \"warn\""));
+}
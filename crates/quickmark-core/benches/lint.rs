@@ -0,0 +1,139 @@
+//! Throughput of parsing and linting a corpus of Markdown documents.
+//!
+//! The corpus is `scripts/benchmarks/data`, populated by `scripts/benchmarks/setup.sh`
+//! with the GitLab documentation. When it is empty, the Markdown files of this
+//! repository are used instead. Benchmark IDs name the corpus, e.g.
+//! `lint/dataset/sequential` or `lint/repository/sequential`, since the two aren't
+//! comparable. Run with:
+//!
+//! ```shell
+//! cargo bench -p quickmark-core --bench lint
+//! ```
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use quickmark_core::config::{parse_toml_config, QuickmarkConfig};
+use quickmark_core::linter::MultiRuleLinter;
+use quickmark_core::markdown_tree::parse_block_tree;
+use rayon::prelude::*;
+use tree_sitter::Parser;
+use tree_sitter_md::LANGUAGE;
+
+fn workspace_root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("../..")
+}
+
+fn collect_markdown_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if name.starts_with('.') || name == "target" || name == "node_modules" {
+            continue;
+        }
+        if path.is_dir() {
+            collect_markdown_files(&path, files);
+        } else if path.extension().is_some_and(|extension| extension == "md") {
+            files.push(path);
+        }
+    }
+}
+
+/// Name of the benchmark corpus and its documents with their content
+fn corpus() -> (&'static str, Vec<(PathBuf, String)>) {
+    let root = workspace_root();
+    let mut files = Vec::new();
+    let mut name = "dataset";
+    collect_markdown_files(&root.join("scripts/benchmarks/data"), &mut files);
+    if files.is_empty() {
+        eprintln!("scripts/benchmarks/data is empty, benchmarking the repository's documents");
+        name = "repository";
+        collect_markdown_files(&root, &mut files);
+    }
+    files.sort();
+    let documents = files
+        .into_iter()
+        .filter_map(|path| Some((path.clone(), fs::read_to_string(path).ok()?)))
+        .collect();
+    (name, documents)
+}
+
+/// The configuration of `scripts/benchmarks/comparison.sh`
+fn config() -> QuickmarkConfig {
+    let path = workspace_root().join("scripts/benchmarks/quickmark.toml");
+    parse_toml_config(&fs::read_to_string(path).unwrap()).unwrap()
+}
+
+fn lint(config: &QuickmarkConfig, (path, content): &(PathBuf, String)) -> usize {
+    MultiRuleLinter::new_for_document(path.clone(), config.clone(), content)
+        .analyze()
+        .len()
+}
+
+fn bench_parse(c: &mut Criterion, corpus: &str, documents: &[(PathBuf, String)], bytes: u64) {
+    let mut group = c.benchmark_group(format!("parse/{corpus}"));
+    group.throughput(Throughput::Bytes(bytes));
+    group.bench_function("fresh_parser_per_document", |b| {
+        b.iter(|| {
+            for (_, content) in documents {
+                let mut parser = Parser::new();
+                parser.set_language(&LANGUAGE.into()).unwrap();
+                parser.parse(content, None).unwrap();
+            }
+        })
+    });
+    group.bench_function("parser_per_thread", |b| {
+        b.iter(|| {
+            for (_, content) in documents {
+                parse_block_tree(content);
+            }
+        })
+    });
+    group.finish();
+}
+
+fn bench_lint(c: &mut Criterion, corpus: &str, documents: &[(PathBuf, String)], bytes: u64) {
+    let config = config();
+    let mut group = c.benchmark_group(format!("lint/{corpus}"));
+    group.throughput(Throughput::Bytes(bytes));
+    group.sample_size(10);
+    group.bench_function("sequential", |b| {
+        b.iter(|| {
+            documents
+                .iter()
+                .map(|document| lint(&config, document))
+                .sum::<usize>()
+        })
+    });
+    group.bench_function("parallel", |b| {
+        b.iter(|| {
+            documents
+                .par_iter()
+                .map(|document| lint(&config, document))
+                .sum::<usize>()
+        })
+    });
+    group.finish();
+}
+
+fn benches(c: &mut Criterion) {
+    let (corpus, documents) = corpus();
+    let bytes = documents
+        .iter()
+        .map(|(_, content)| content.len() as u64)
+        .sum();
+    eprintln!(
+        "Corpus {corpus}: {} documents, {bytes} bytes",
+        documents.len()
+    );
+    bench_parse(c, corpus, &documents, bytes);
+    bench_lint(c, corpus, &documents, bytes);
+}
+
+criterion_group!(lint_benches, benches);
+criterion_main!(lint_benches);
@@ -0,0 +1,288 @@
+//! Baselines of known violations, which let existing documents adopt a stricter
+//! configuration without fixing everything at once.
+//!
+//! A baseline records each violation by file, rule alias and a fingerprint of the
+//! lines around it rather than by line number. Edits elsewhere in a document move
+//! violations without making them new; editing the flagged lines or their direct
+//! neighbours does, and the violation is reported again.
+//!
+//! ```rust,no_run
+//! use quickmark_core::baseline::Baseline;
+//! use std::path::Path;
+//!
+//! # let violations: Vec<(quickmark_core::linter::RuleViolation, String)> = Vec::new();
+//! let baseline = Baseline::load(Path::new("quickmark-baseline.json")).unwrap();
+//! let mut matcher = baseline.matcher();
+//! let new_violations: Vec<_> = violations
+//!     .iter()
+//!     .filter(|(violation, source)| !matcher.matches(violation, source))
+//!     .collect();
+//! ```
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{bail, Context as _, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::{linter::RuleViolation, project_index::normalize_path};
+
+/// File name of the baseline the language server picks up from workspace roots
+pub const DEFAULT_BASELINE_FILE: &str = "quickmark-baseline.json";
+
+const BASELINE_VERSION: u32 = 1;
+
+/// Lines of context hashed above and below the lines of a violation
+const CONTEXT_LINES: usize = 1;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+struct BaselineKey {
+    /// Path relative to the baseline root, with `/` separators
+    file: String,
+    rule: String,
+    fingerprint: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BaselineEntry {
+    file: String,
+    rule: String,
+    fingerprint: String,
+    count: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BaselineFile {
+    version: u32,
+    violations: Vec<BaselineEntry>,
+}
+
+/// Known violations, relative to the directory of the baseline file
+#[derive(Debug, Clone, PartialEq)]
+pub struct Baseline {
+    root: PathBuf,
+    counts: BTreeMap<BaselineKey, usize>,
+}
+
+impl Baseline {
+    /// An empty baseline for documents under `root`
+    pub fn new(root: &Path) -> Self {
+        Self {
+            root: normalize_path(root),
+            counts: BTreeMap::new(),
+        }
+    }
+
+    /// An empty baseline to be saved at `path`
+    pub fn for_file(path: &Path) -> Self {
+        let path = normalize_path(path);
+        Self::new(path.parent().unwrap_or(&path))
+    }
+
+    /// Read a baseline file. Paths in it are relative to its directory.
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Can't read baseline file {}", path.display()))?;
+        let file: BaselineFile = serde_json::from_str(&content)
+            .with_context(|| format!("Invalid baseline file {}", path.display()))?;
+        if file.version != BASELINE_VERSION {
+            bail!(
+                "Unsupported baseline version {} in {}",
+                file.version,
+                path.display()
+            );
+        }
+
+        let mut baseline = Self::for_file(path);
+        for entry in file.violations {
+            let key = BaselineKey {
+                file: entry.file,
+                rule: entry.rule,
+                fingerprint: entry.fingerprint,
+            };
+            *baseline.counts.entry(key).or_default() += entry.count;
+        }
+        Ok(baseline)
+    }
+
+    /// Write the baseline, sorted so that it diffs well under version control
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let file = BaselineFile {
+            version: BASELINE_VERSION,
+            violations: self
+                .counts
+                .iter()
+                .map(|(key, &count)| BaselineEntry {
+                    file: key.file.clone(),
+                    rule: key.rule.clone(),
+                    fingerprint: key.fingerprint.clone(),
+                    count,
+                })
+                .collect(),
+        };
+        let mut content = serde_json::to_string_pretty(&file)?;
+        content.push('\n');
+        fs::write(path, content)
+            .with_context(|| format!("Can't write baseline file {}", path.display()))
+    }
+
+    /// Record a violation of the document `source`
+    pub fn add(&mut self, violation: &RuleViolation, source: &str) {
+        *self.counts.entry(self.key(violation, source)).or_default() += 1;
+    }
+
+    /// Number of recorded violations
+    pub fn len(&self) -> usize {
+        self.counts.values().sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    /// Whether a document lies under the directory of the baseline
+    pub fn covers(&self, path: &Path) -> bool {
+        normalize_path(path).starts_with(&self.root)
+    }
+
+    /// A matcher for one lint run. Every recorded violation matches at most once.
+    pub fn matcher(&self) -> BaselineMatcher<'_> {
+        BaselineMatcher {
+            baseline: self,
+            used: HashMap::new(),
+        }
+    }
+
+    fn key(&self, violation: &RuleViolation, source: &str) -> BaselineKey {
+        let location = violation.location();
+        let path = normalize_path(&location.file_path);
+        let file = match path.strip_prefix(&self.root) {
+            Ok(relative) => relative
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/"),
+            Err(_) => path.to_string_lossy().into_owned(),
+        };
+        BaselineKey {
+            file,
+            rule: violation.rule().alias.to_string(),
+            fingerprint: fingerprint(source, location.range.start.line, location.range.end.line),
+        }
+    }
+}
+
+/// Matches violations of a lint run against a [`Baseline`]
+#[derive(Debug)]
+pub struct BaselineMatcher<'a> {
+    baseline: &'a Baseline,
+    used: HashMap<BaselineKey, usize>,
+}
+
+impl BaselineMatcher<'_> {
+    /// Whether a violation of the document `source` is a known one. Once a document has
+    /// more violations with the same fingerprint than recorded, the extra ones are new.
+    pub fn matches(&mut self, violation: &RuleViolation, source: &str) -> bool {
+        let key = self.baseline.key(violation, source);
+        let Some(&recorded) = self.baseline.counts.get(&key) else {
+            return false;
+        };
+        let used = self.used.entry(key).or_default();
+        if *used < recorded {
+            *used += 1;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// Hash of the trimmed lines of a violation and the lines around it
+fn fingerprint(source: &str, start_line: usize, end_line: usize) -> String {
+    let first = start_line.saturating_sub(CONTEXT_LINES);
+    let last = end_line.max(start_line) + CONTEXT_LINES;
+    let mut hasher = blake3::Hasher::new();
+    for line in source.lines().skip(first).take(last - first + 1) {
+        hasher.update(line.trim().as_bytes());
+        hasher.update(b"\n");
+    }
+    hasher.finalize().to_hex()[..16].to_string()
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::{Path, PathBuf};
+
+    use super::Baseline;
+    use crate::config::{QuickmarkConfig, RuleSeverity};
+    use crate::linter::{MultiRuleLinter, RuleViolation};
+    use crate::test_utils::test_helpers::test_config_with_rules;
+
+    fn config() -> QuickmarkConfig {
+        test_config_with_rules(vec![("no-trailing-spaces", RuleSeverity::Error)])
+    }
+
+    fn lint(path: &Path, source: &str) -> Vec<RuleViolation> {
+        MultiRuleLinter::new_for_document(path.to_path_buf(), config(), source).analyze()
+    }
+
+    #[test]
+    fn test_matches_moved_violations() {
+        let path = PathBuf::from("/project/docs/a.md");
+        let source = "# Title\n\nSome text \n\nMore\n";
+        let mut baseline = Baseline::new(Path::new("/project"));
+        for violation in lint(&path, source) {
+            baseline.add(&violation, source);
+        }
+        assert_eq!(1, baseline.len());
+
+        // Lines added above move the violation without changing its surroundings
+        let moved = "# Title\n\nIntro\n\nOther\n\nSome text \n\nMore\n";
+        let mut matcher = baseline.matcher();
+        let violations = lint(&path, moved);
+        assert_eq!(1, violations.len());
+        assert!(matcher.matches(&violations[0], moved));
+
+        // A second identical violation is new
+        assert!(!matcher.matches(&violations[0], moved));
+
+        // Editing the flagged line makes it new too
+        let edited = "# Title\n\nSome other text \n\nMore\n";
+        let violations = lint(&path, edited);
+        assert!(!baseline.matcher().matches(&violations[0], edited));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("quickmark-baseline.json");
+        let path = dir.path().join("docs").join("a.md");
+        let source = "# Title\n\ntext \n";
+
+        let mut baseline = Baseline::for_file(&file);
+        for violation in lint(&path, source) {
+            baseline.add(&violation, source);
+        }
+        baseline.save(&file).unwrap();
+
+        let content = std::fs::read_to_string(&file).unwrap();
+        assert!(content.contains("\"file\": \"docs/a.md\""));
+        assert!(content.contains("\"rule\": \"no-trailing-spaces\""));
+
+        let loaded = Baseline::load(&file).unwrap();
+        assert_eq!(baseline, loaded);
+        assert!(loaded.covers(&path));
+        assert!(!loaded.covers(Path::new("/elsewhere/a.md")));
+    }
+
+    #[test]
+    fn test_rejects_unknown_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("baseline.json");
+        std::fs::write(&file, r#"{"version": 99, "violations": []}"#).unwrap();
+        assert!(Baseline::load(&file).is_err());
+    }
+}
@@ -0,0 +1,697 @@
+//! Loading of `quickmark.toml` files with `extends` inheritance.
+//!
+//! A configuration file may extend other files (paths relative to the file) and built-in
+//! presets (`preset:<name>`). Tables are merged recursively: later sources override
+//! earlier ones key by key, and the extending file overrides everything it extends.
+//! Arrays and scalar values are replaced, not concatenated.
+
+use anyhow::{anyhow, bail, Context, Result};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Display;
+use std::fs;
+use std::path::{Path, PathBuf};
+use toml::{Table, Value};
+
+use crate::plugin;
+use crate::project_index::normalize_path;
+use crate::rules::custom;
+
+use super::markdownlint::{import_markdownlint_config, is_markdownlint_config};
+use super::{normalize_severities, QuickmarkConfig, RuleSeverity};
+
+/// Key listing the configurations a file inherits from
+pub const EXTENDS_KEY: &str = "extends";
+
+/// Key opting a nested `quickmark.toml` into merging onto its ancestor's configuration
+pub const MERGE_WITH_PARENT_KEY: &str = "merge_with_parent";
+
+pub(crate) const OVERRIDES_KEY: &str = "overrides";
+
+pub(crate) const CUSTOM_RULES_KEY: &str = "custom_rules";
+
+/// Key listing plugin libraries, see [`crate::plugin`]
+pub const PLUGINS_KEY: &str = "plugins";
+
+pub(crate) const PRESET_PREFIX: &str = "preset:";
+
+/// Rule settings holding file paths, resolved relative to the configuration file
+/// that sets them
+const PATH_SETTINGS: &[(&str, &str)] = &[("front-matter-schema", "schema")];
+
+/// Built-in configurations that can be referenced as `preset:<name>`
+pub(crate) const PRESETS: &[(&str, &str)] = &[
+    ("recommended", include_str!("presets/recommended.toml")),
+    ("strict", include_str!("presets/strict.toml")),
+    ("relaxed", include_str!("presets/relaxed.toml")),
+];
+
+/// Where an effective configuration value was defined
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigOrigin {
+    /// A configuration file
+    File(PathBuf),
+    /// A built-in preset
+    Preset(String),
+    /// Configuration passed as a string, e.g. to [`super::parse_toml_config`]
+    Inline,
+}
+
+impl Display for ConfigOrigin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigOrigin::File(path) => write!(f, "{}", path.to_string_lossy()),
+            ConfigOrigin::Preset(name) => write!(f, "{PRESET_PREFIX}{name}"),
+            ConfigOrigin::Inline => write!(f, "<inline>"),
+        }
+    }
+}
+
+/// Origin of every explicitly configured value, keyed by its dotted path
+/// (e.g. `linters.settings.line-length.line_length`)
+pub type ConfigOrigins = BTreeMap<String, ConfigOrigin>;
+
+/// A configuration with its inheritance resolved, before it is turned into a
+/// [`QuickmarkConfig`]
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ConfigLayer {
+    table: Table,
+    origins: ConfigOrigins,
+    /// `[[overrides]]` tables with the directory their globs are relative to. Overrides
+    /// from every layer are kept, in order, instead of replacing each other.
+    overrides: Vec<(Value, Option<PathBuf>)>,
+    /// Whether the file asked to be merged onto its ancestor's configuration
+    pub(crate) merge_with_parent: bool,
+}
+
+impl ConfigLayer {
+    /// Merge `other` on top of this layer
+    pub(crate) fn merge(&mut self, other: ConfigLayer) {
+        deep_merge(
+            &mut self.table,
+            other.table,
+            "",
+            &mut self.origins,
+            &other.origins,
+        );
+        self.overrides.extend(other.overrides);
+    }
+
+    pub(crate) fn into_config(mut self) -> Result<(QuickmarkConfig, ConfigOrigins)> {
+        let (overrides, base_dirs): (Vec<_>, Vec<_>) = self.overrides.into_iter().unzip();
+        if !overrides.is_empty() {
+            self.table
+                .insert(OVERRIDES_KEY.to_string(), Value::Array(overrides));
+        }
+        let mut config: QuickmarkConfig = Value::Table(self.table).try_into()?;
+        // Plugin and custom rules only apply to this configuration, so their severities
+        // are resolved before normalizing drops the rules it doesn't know
+        let plugin_rules = plugin::load_config_plugins(&config.plugins)?;
+        config.plugin_rules = plugin_rules.iter().map(|r| r.alias.to_string()).collect();
+        let plugin_severities = plugin_severities(&config.plugin_rules, &config.linters.severity);
+        config.compiled_custom_rules = custom::compile_all(&config.custom_rules)?;
+        let custom_severities = custom::severities(&config.custom_rules, &config.linters.severity);
+        normalize_severities(&mut config.linters.severity);
+        config.linters.severity.extend(plugin_severities);
+        config.linters.severity.extend(custom_severities);
+        for (index, (config_override, base_dir)) in
+            config.overrides.iter_mut().zip(base_dirs).enumerate()
+        {
+            config_override.base_dir = base_dir;
+            // Surface invalid settings now rather than when a matching file is linted
+            config_override
+                .apply(&mut config.linters.clone())
+                .with_context(|| format!("Invalid override #{}", index + 1))?;
+        }
+        Ok((config, self.origins))
+    }
+}
+
+/// Severities of plugin rules, given the `[linters.severity]` table before it is
+/// normalized: the rule's own entry or else the default severity
+fn plugin_severities(
+    aliases: &[String],
+    configured: &HashMap<String, RuleSeverity>,
+) -> Vec<(String, RuleSeverity)> {
+    aliases
+        .iter()
+        .map(|alias| {
+            let severity = configured
+                .get(alias)
+                .or(configured.get("default"))
+                .cloned()
+                .unwrap_or(RuleSeverity::Error);
+            (alias.clone(), severity)
+        })
+        .collect()
+}
+
+/// Make the [`PATH_SETTINGS`] in a `[linters.settings]` table absolute
+fn resolve_setting_paths(settings: &mut Table, base_dir: &Path) {
+    for (alias, option) in PATH_SETTINGS {
+        let value = settings
+            .get_mut(*alias)
+            .and_then(Value::as_table_mut)
+            .and_then(|options| options.get_mut(*option));
+        if let Some(Value::String(path)) = value {
+            if !path.is_empty() && Path::new(path.as_str()).is_relative() {
+                *path = base_dir.join(&path).to_string_lossy().to_string();
+            }
+        }
+    }
+}
+
+/// Make the paths of the [`PLUGINS_KEY`] array absolute
+fn resolve_plugin_paths(table: &mut Table, base_dir: &Path) {
+    let Some(Value::Array(plugins)) = table.get_mut(PLUGINS_KEY) else {
+        return;
+    };
+    for plugin in plugins {
+        if let Value::String(path) = plugin {
+            if Path::new(path.as_str()).is_relative() {
+                *path = base_dir.join(&path).to_string_lossy().to_string();
+            }
+        }
+    }
+}
+
+fn join_key(prefix: &str, key: &str) -> String {
+    if prefix.is_empty() {
+        key.to_string()
+    } else {
+        format!("{prefix}.{key}")
+    }
+}
+
+/// Record `origin` for every leaf value of `table`
+fn collect_origins(
+    table: &Table,
+    prefix: &str,
+    origin: &ConfigOrigin,
+    origins: &mut ConfigOrigins,
+) {
+    for (key, value) in table {
+        let path = join_key(prefix, key);
+        match value {
+            Value::Table(nested) => collect_origins(nested, &path, origin, origins),
+            _ => {
+                origins.insert(path, origin.clone());
+            }
+        }
+    }
+}
+
+/// Recursively merge `source` into `target`
+pub(crate) fn merge_tables(target: &mut Table, source: Table) {
+    deep_merge(
+        target,
+        source,
+        "",
+        &mut ConfigOrigins::new(),
+        &ConfigOrigins::new(),
+    );
+}
+
+/// Recursively merge `source` into `target`, carrying the origins of merged leaves along
+fn deep_merge(
+    target: &mut Table,
+    source: Table,
+    prefix: &str,
+    origins: &mut ConfigOrigins,
+    source_origins: &ConfigOrigins,
+) {
+    for (key, value) in source {
+        let path = join_key(prefix, &key);
+        match (target.get_mut(&key), value) {
+            (Some(Value::Table(existing)), Value::Table(nested)) => {
+                deep_merge(existing, nested, &path, origins, source_origins);
+            }
+            (_, value) => {
+                // The replaced value may have been a table with origins of its own
+                let nested_prefix = format!("{path}.");
+                origins.retain(|k, _| k != &path && !k.starts_with(&nested_prefix));
+                for (k, origin) in source_origins.range(path.clone()..) {
+                    if k != &path && !k.starts_with(&nested_prefix) {
+                        break;
+                    }
+                    origins.insert(k.clone(), origin.clone());
+                }
+                target.insert(key, value);
+            }
+        }
+    }
+}
+
+/// Resolves `extends` chains while guarding against cycles
+#[derive(Default)]
+pub(crate) struct ConfigLoader {
+    /// Files currently being loaded, outermost first
+    stack: Vec<PathBuf>,
+    /// Every file this loader has read or tried to read, including extended files
+    pub(crate) files: Vec<PathBuf>,
+}
+
+impl ConfigLoader {
+    /// Load a configuration file and everything it extends
+    pub(crate) fn load_file(&mut self, path: &Path) -> Result<ConfigLayer> {
+        let normalized = normalize_path(path);
+        if !self.files.contains(&normalized) {
+            self.files.push(normalized);
+        }
+        let canonical = fs::canonicalize(path)
+            .with_context(|| format!("Can't read config file {}", path.to_string_lossy()))?;
+        if let Some(start) = self.stack.iter().position(|p| p == &canonical) {
+            let chain: Vec<String> = self.stack[start..]
+                .iter()
+                .chain(std::iter::once(&canonical))
+                .map(|p| p.to_string_lossy().to_string())
+                .collect();
+            bail!("Circular extends: {}", chain.join(" -> "));
+        }
+
+        let content = fs::read_to_string(&canonical)
+            .with_context(|| format!("Can't read config file {}", path.to_string_lossy()))?;
+        let base_dir = canonical.parent().map(Path::to_path_buf);
+
+        let table = if is_markdownlint_config(&canonical) {
+            import_markdownlint_config(&content, &canonical)?.table
+        } else {
+            toml::from_str(&content)?
+        };
+
+        self.stack.push(canonical.clone());
+        let layer = self.load_table(table, base_dir.as_deref(), ConfigOrigin::File(canonical));
+        self.stack.pop();
+        layer
+    }
+
+    /// Load configuration text. Relative `extends` paths are resolved against `base_dir`.
+    pub(crate) fn load_str(
+        &mut self,
+        content: &str,
+        base_dir: Option<&Path>,
+        origin: ConfigOrigin,
+    ) -> Result<ConfigLayer> {
+        self.load_table(toml::from_str(content)?, base_dir, origin)
+    }
+
+    /// Load an already parsed configuration table
+    pub(crate) fn load_table(
+        &mut self,
+        mut table: Table,
+        base_dir: Option<&Path>,
+        origin: ConfigOrigin,
+    ) -> Result<ConfigLayer> {
+        let extends = match table.remove(EXTENDS_KEY) {
+            None => Vec::new(),
+            Some(Value::String(entry)) => vec![entry],
+            Some(Value::Array(entries)) => entries
+                .into_iter()
+                .map(|entry| match entry {
+                    Value::String(entry) => Ok(entry),
+                    other => Err(anyhow!(
+                        "`{EXTENDS_KEY}` entries must be strings, found {other}"
+                    )),
+                })
+                .collect::<Result<_>>()?,
+            Some(other) => {
+                bail!("`{EXTENDS_KEY}` must be a string or an array of strings, found {other}")
+            }
+        };
+        let merge_with_parent = match table.remove(MERGE_WITH_PARENT_KEY) {
+            None => false,
+            Some(Value::Boolean(value)) => value,
+            Some(other) => bail!("`{MERGE_WITH_PARENT_KEY}` must be a boolean, found {other}"),
+        };
+
+        let mut overrides_value = table.remove(OVERRIDES_KEY);
+        if let Some(base_dir) = base_dir {
+            resolve_plugin_paths(&mut table, base_dir);
+            let linters_settings = table
+                .get_mut("linters")
+                .and_then(Value::as_table_mut)
+                .and_then(|linters| linters.get_mut("settings"))
+                .and_then(Value::as_table_mut);
+            let override_settings = overrides_value
+                .iter_mut()
+                .filter_map(Value::as_array_mut)
+                .flatten()
+                .filter_map(|entry| entry.get_mut("settings"))
+                .filter_map(Value::as_table_mut);
+            for settings in linters_settings.into_iter().chain(override_settings) {
+                resolve_setting_paths(settings, base_dir);
+            }
+        }
+
+        let overrides = match overrides_value {
+            None => Vec::new(),
+            Some(Value::Array(overrides)) => overrides
+                .into_iter()
+                .map(|entry| (entry, base_dir.map(Path::to_path_buf)))
+                .collect(),
+            Some(other) => bail!("`{OVERRIDES_KEY}` must be an array of tables, found {other}"),
+        };
+
+        let mut layer = ConfigLayer::default();
+        for entry in extends {
+            let base = match entry.strip_prefix(PRESET_PREFIX) {
+                Some(name) => Self::load_preset(name)?,
+                None => {
+                    let path = match base_dir {
+                        Some(dir) => dir.join(&entry),
+                        None => PathBuf::from(&entry),
+                    };
+                    self.load_file(&path)
+                        .with_context(|| format!("Can't extend {entry} from {origin}"))?
+                }
+            };
+            layer.merge(base);
+        }
+
+        let mut own = ConfigLayer {
+            table,
+            overrides,
+            ..Default::default()
+        };
+        collect_origins(&own.table, "", &origin, &mut own.origins);
+        layer.merge(own);
+        layer.merge_with_parent = merge_with_parent;
+        Ok(layer)
+    }
+
+    fn load_preset(name: &str) -> Result<ConfigLayer> {
+        let (_, content) = PRESETS
+            .iter()
+            .find(|(preset, _)| *preset == name)
+            .ok_or_else(|| {
+                let available: Vec<&str> = PRESETS.iter().map(|(preset, _)| *preset).collect();
+                anyhow!(
+                    "Unknown preset `{PRESET_PREFIX}{name}`, available presets: {}",
+                    available.join(", ")
+                )
+            })?;
+        // Presets don't extend anything, so a fresh loader is enough
+        ConfigLoader::default().load_str(content, None, ConfigOrigin::Preset(name.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+    use tempfile::TempDir;
+
+    use crate::config::{HeadingStyle, RuleSeverity};
+
+    use crate::rules::ALL_RULES;
+
+    use super::{ConfigLoader, ConfigOrigin, PRESETS};
+
+    #[test]
+    fn test_extends_deep_merges_tables() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("base.toml"),
+            r#"
+[linters.severity]
+line-length = "warn"
+heading-style = "warn"
+
+[linters.settings.line-length]
+line_length = 120
+code_blocks = false
+"#,
+        )
+        .unwrap();
+        let config_path = dir.path().join("quickmark.toml");
+        fs::write(
+            &config_path,
+            r#"
+extends = ["base.toml"]
+
+[linters.severity]
+heading-style = "err"
+
+[linters.settings.line-length]
+line_length = 100
+"#,
+        )
+        .unwrap();
+
+        let (config, origins) = ConfigLoader::default()
+            .load_file(&config_path)
+            .unwrap()
+            .into_config()
+            .unwrap();
+
+        let severity = &config.linters.severity;
+        assert_eq!(Some(&RuleSeverity::Warning), severity.get("line-length"));
+        assert_eq!(Some(&RuleSeverity::Error), severity.get("heading-style"));
+        let line_length = &config.linters.settings.line_length;
+        assert_eq!(100, line_length.line_length);
+        assert!(!line_length.code_blocks);
+        // Settings not configured anywhere keep the rule defaults
+        assert_eq!(80, line_length.heading_line_length);
+
+        let base = ConfigOrigin::File(fs::canonicalize(dir.path().join("base.toml")).unwrap());
+        let own = ConfigOrigin::File(fs::canonicalize(&config_path).unwrap());
+        assert_eq!(Some(&base), origins.get("linters.severity.line-length"));
+        assert_eq!(Some(&own), origins.get("linters.severity.heading-style"));
+        assert_eq!(
+            Some(&own),
+            origins.get("linters.settings.line-length.line_length")
+        );
+        assert_eq!(
+            Some(&base),
+            origins.get("linters.settings.line-length.code_blocks")
+        );
+        assert_eq!(None, origins.get("extends"));
+    }
+
+    #[test]
+    fn test_extends_presets() {
+        let (config, origins) = ConfigLoader::default()
+            .load_str(
+                "extends = \"preset:strict\"\n[linters.severity]\nline-length = \"off\"\n",
+                None,
+                ConfigOrigin::Inline,
+            )
+            .unwrap()
+            .into_config()
+            .unwrap();
+
+        assert_eq!(
+            HeadingStyle::ATX,
+            config.linters.settings.heading_style.style
+        );
+        assert!(config.linters.settings.trailing_spaces.strict);
+        assert_eq!(
+            Some(&RuleSeverity::Off),
+            config.linters.severity.get("line-length")
+        );
+        assert_eq!(
+            Some(&ConfigOrigin::Preset("strict".to_string())),
+            origins.get("linters.settings.heading-style.style")
+        );
+        assert_eq!(
+            Some(&ConfigOrigin::Inline),
+            origins.get("linters.severity.line-length")
+        );
+    }
+
+    #[test]
+    fn test_all_presets_are_valid() {
+        for (name, _) in PRESETS {
+            let (config, _) = ConfigLoader::load_preset(name)
+                .unwrap()
+                .into_config()
+                .unwrap();
+            assert_eq!(ALL_RULES.len(), config.linters.severity.len(), "{name}");
+        }
+    }
+
+    #[test]
+    fn test_extends_unknown_preset() {
+        let err = ConfigLoader::default()
+            .load_str("extends = [\"preset:nope\"]", None, ConfigOrigin::Inline)
+            .unwrap_err();
+        assert!(err.to_string().contains("Unknown preset `preset:nope`"));
+    }
+
+    #[test]
+    fn test_extends_later_entries_win() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("a.toml"),
+            "[linters.severity]\nline-length = \"warn\"\nno-hard-tabs = \"warn\"\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("b.toml"),
+            "[linters.severity]\nline-length = \"off\"\n",
+        )
+        .unwrap();
+        let config_path = dir.path().join("quickmark.toml");
+        fs::write(&config_path, "extends = [\"a.toml\", \"b.toml\"]\n").unwrap();
+
+        let (config, _) = ConfigLoader::default()
+            .load_file(&config_path)
+            .unwrap()
+            .into_config()
+            .unwrap();
+        assert_eq!(
+            Some(&RuleSeverity::Off),
+            config.linters.severity.get("line-length")
+        );
+        assert_eq!(
+            Some(&RuleSeverity::Warning),
+            config.linters.severity.get("no-hard-tabs")
+        );
+    }
+
+    #[test]
+    fn test_extends_detects_cycles() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("a.toml"), "extends = \"b.toml\"\n").unwrap();
+        fs::write(dir.path().join("b.toml"), "extends = \"a.toml\"\n").unwrap();
+
+        let err = ConfigLoader::default()
+            .load_file(&dir.path().join("a.toml"))
+            .unwrap_err();
+        let message = format!("{err:#}");
+        assert!(message.contains("Circular extends"), "{message}");
+        assert!(message.contains("a.toml -> "), "{message}");
+    }
+
+    #[test]
+    fn test_extends_missing_file() {
+        let dir = TempDir::new().unwrap();
+        let config_path = dir.path().join("quickmark.toml");
+        fs::write(&config_path, "extends = \"missing.toml\"\n").unwrap();
+
+        let err = ConfigLoader::default().load_file(&config_path).unwrap_err();
+        assert!(format!("{err:#}").contains("Can't extend missing.toml"));
+    }
+
+    #[test]
+    fn test_extends_keeps_overrides_of_every_file() {
+        let dir = TempDir::new().unwrap();
+        let shared_dir = dir.path().join("shared");
+        fs::create_dir(&shared_dir).unwrap();
+        fs::write(
+            shared_dir.join("base.toml"),
+            "[[overrides]]\nfiles = [\"CHANGELOG.md\"]\n[overrides.severity]\nline-length = \"off\"\n",
+        )
+        .unwrap();
+        let config_path = dir.path().join("quickmark.toml");
+        fs::write(
+            &config_path,
+            r#"
+extends = "shared/base.toml"
+
+[[overrides]]
+files = ["docs/**"]
+
+[overrides.settings.line-length]
+line_length = 120
+"#,
+        )
+        .unwrap();
+
+        let (config, _) = ConfigLoader::default()
+            .load_file(&config_path)
+            .unwrap()
+            .into_config()
+            .unwrap();
+
+        assert_eq!(2, config.overrides.len());
+        assert_eq!(vec!["CHANGELOG.md"], config.overrides[0].files);
+        assert_eq!(
+            Some(fs::canonicalize(&shared_dir).unwrap()),
+            config.overrides[0].base_dir
+        );
+        assert_eq!(
+            Some(fs::canonicalize(dir.path()).unwrap()),
+            config.overrides[1].base_dir
+        );
+    }
+
+    #[test]
+    fn test_invalid_override_settings() {
+        let err = ConfigLoader::default()
+            .load_str(
+                "[[overrides]]\nfiles = [\"*.md\"]\n[overrides.settings.line-length]\nline_length = \"long\"\n",
+                None,
+                ConfigOrigin::Inline,
+            )
+            .unwrap()
+            .into_config()
+            .unwrap_err();
+        assert!(format!("{err:#}").contains("Invalid override #1"));
+    }
+
+    #[test]
+    fn test_replacing_a_table_drops_its_origins() {
+        let mut loader = ConfigLoader::default();
+        let mut layer = loader
+            .load_str("[a.b]\nc = 1\n", None, ConfigOrigin::Inline)
+            .unwrap();
+        layer.merge(
+            loader
+                .load_str("[a]\nb = 2\n", None, ConfigOrigin::Preset("x".to_string()))
+                .unwrap(),
+        );
+        assert_eq!(None, layer.origins.get("a.b.c"));
+        assert_eq!(
+            Some(&ConfigOrigin::Preset("x".to_string())),
+            layer.origins.get("a.b")
+        );
+    }
+
+    #[test]
+    fn test_setting_paths_are_relative_to_their_file() {
+        let dir = TempDir::new().unwrap();
+        let shared_dir = dir.path().join("shared");
+        fs::create_dir(&shared_dir).unwrap();
+        fs::write(
+            shared_dir.join("base.toml"),
+            "[linters.settings.front-matter-schema]\nschema = \"schemas/page.json\"\n",
+        )
+        .unwrap();
+        let config_path = dir.path().join("quickmark.toml");
+        fs::write(
+            &config_path,
+            r#"
+extends = "shared/base.toml"
+
+[[overrides]]
+files = ["blog/**"]
+
+[overrides.settings.front-matter-schema]
+schema = "schemas/post.json"
+"#,
+        )
+        .unwrap();
+
+        let (config, _) = ConfigLoader::default()
+            .load_file(&config_path)
+            .unwrap()
+            .into_config()
+            .unwrap();
+
+        let shared_dir = fs::canonicalize(&shared_dir).unwrap();
+        assert_eq!(
+            shared_dir.join("schemas/page.json").to_string_lossy(),
+            config.linters.settings.front_matter_schema.schema
+        );
+        let override_schema = config.overrides[0].settings["front-matter-schema"]["schema"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        assert_eq!(
+            fs::canonicalize(dir.path())
+                .unwrap()
+                .join("schemas/post.json")
+                .to_string_lossy(),
+            override_schema
+        );
+    }
+}
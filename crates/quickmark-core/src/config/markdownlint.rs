@@ -0,0 +1,502 @@
+//! Import of markdownlint and markdownlint-cli2 configuration files.
+//!
+//! markdownlint configures each rule by ID, alias or tag, with `false` to disable it,
+//! `true` to enable it, or an object of options that enables it with those options.
+//! Since [`LintersSettingsTable`] mirrors markdownlint's option names, options are
+//! copied as-is into `[linters.settings.<alias>]`.
+
+use anyhow::{bail, Result};
+use serde::Serialize;
+use serde_json::{Map, Value as JsonValue};
+use std::iter::Peekable;
+use std::path::Path;
+use std::str::Chars;
+use toml::{Table, Value};
+
+use super::validation::SETTINGS_OPTIONS;
+use super::{RuleSeverity, EXTENDS_KEY};
+use crate::rules::{all_rules, Rule};
+
+/// markdownlint configuration files, in the order markdownlint-cli2 looks for them
+pub const MARKDOWNLINT_CONFIG_FILES: &[&str] = &[
+    ".markdownlint-cli2.jsonc",
+    ".markdownlint-cli2.yaml",
+    ".markdownlint.jsonc",
+    ".markdownlint.json",
+    ".markdownlint.yaml",
+    ".markdownlint.yml",
+];
+
+const CLI2_PREFIX: &str = ".markdownlint-cli2.";
+
+/// Whether `path` names a configuration file in markdownlint's format
+pub fn is_markdownlint_config(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.starts_with(".markdownlint"))
+}
+
+/// A markdownlint configuration translated to quickmark's TOML structure
+#[derive(Debug, Default)]
+pub struct MarkdownlintImport {
+    /// Table with the same layout as `quickmark.toml`
+    pub table: Table,
+    /// Entries that have no quickmark equivalent and were skipped
+    pub warnings: Vec<String>,
+}
+
+impl MarkdownlintImport {
+    fn severity(&mut self) -> &mut Table {
+        table_entry(table_entry(&mut self.table, "linters"), "severity")
+    }
+
+    fn settings(&mut self, alias: &str) -> &mut Table {
+        let linters = table_entry(&mut self.table, "linters");
+        table_entry(table_entry(linters, "settings"), alias)
+    }
+
+    fn set_severity(&mut self, key: &str, severity: RuleSeverity) {
+        let severity = Value::try_from(severity).expect("severity serializes to a string");
+        self.severity().insert(key.to_string(), severity);
+    }
+}
+
+fn table_entry<'a>(table: &'a mut Table, key: &str) -> &'a mut Table {
+    let entry = table
+        .entry(key.to_string())
+        .or_insert_with(|| Value::Table(Table::new()));
+    if !entry.is_table() {
+        *entry = Value::Table(Table::new());
+    }
+    entry.as_table_mut().expect("entry was just made a table")
+}
+
+/// Severity of a rule configured with `value`, or `None` if the value isn't understood
+fn severity_of(value: &JsonValue) -> Option<RuleSeverity> {
+    match value {
+        JsonValue::Null | JsonValue::Bool(false) => Some(RuleSeverity::Off),
+        JsonValue::Bool(true) => Some(RuleSeverity::Error),
+        JsonValue::String(severity) => match severity.as_str() {
+            "error" => Some(RuleSeverity::Error),
+            "warning" => Some(RuleSeverity::Warning),
+            _ => None,
+        },
+        JsonValue::Object(options) => match options.get("enabled") {
+            Some(JsonValue::Bool(false)) => Some(RuleSeverity::Off),
+            _ => match options.get("severity") {
+                Some(severity) => severity_of(severity),
+                None => Some(RuleSeverity::Error),
+            },
+        },
+        _ => None,
+    }
+}
+
+fn json_to_toml(value: &JsonValue) -> Option<Value> {
+    match value {
+        JsonValue::Null => None,
+        JsonValue::Bool(b) => Some(Value::Boolean(*b)),
+        JsonValue::Number(n) => n
+            .as_i64()
+            .map(Value::Integer)
+            .or_else(|| n.as_f64().map(Value::Float)),
+        JsonValue::String(s) => Some(Value::String(s.clone())),
+        JsonValue::Array(items) => Some(Value::Array(
+            items.iter().filter_map(json_to_toml).collect(),
+        )),
+        JsonValue::Object(map) => Some(Value::Table(
+            map.iter()
+                .filter_map(|(k, v)| json_to_toml(v).map(|v| (k.clone(), v)))
+                .collect(),
+        )),
+    }
+}
+
+fn find_rule(key: &str) -> Option<&'static Rule> {
+    all_rules()
+        .into_iter()
+        .find(|rule| rule.id.eq_ignore_ascii_case(key) || rule.alias.eq_ignore_ascii_case(key))
+}
+
+fn rules_with_tag(tag: &str) -> Vec<&'static Rule> {
+    all_rules()
+        .into_iter()
+        .filter(|rule| rule.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)))
+        .collect()
+}
+
+/// Remove comments and trailing commas so JSONC can be read as JSON
+fn strip_jsonc(content: &str) -> String {
+    remove_trailing_commas(&remove_comments(content))
+}
+
+/// Feed every character outside JSON strings to `visit`, copying strings through as-is
+fn outside_strings(
+    content: &str,
+    mut visit: impl FnMut(char, &mut Peekable<Chars>, &mut String),
+) -> String {
+    let mut result = String::with_capacity(content.len());
+    let mut chars = content.chars().peekable();
+    let mut in_string = false;
+    while let Some(c) = chars.next() {
+        if in_string {
+            result.push(c);
+            match c {
+                '\\' => result.extend(chars.next()),
+                '"' => in_string = false,
+                _ => {}
+            }
+        } else if c == '"' {
+            in_string = true;
+            result.push(c);
+        } else {
+            visit(c, &mut chars, &mut result);
+        }
+    }
+    result
+}
+
+fn remove_comments(content: &str) -> String {
+    outside_strings(content, |c, chars, result| match (c, chars.peek()) {
+        ('/', Some('/')) => {
+            for c in chars.by_ref() {
+                if c == '\n' {
+                    result.push(c);
+                    break;
+                }
+            }
+        }
+        ('/', Some('*')) => {
+            chars.next();
+            let mut previous = ' ';
+            for c in chars.by_ref() {
+                if previous == '*' && c == '/' {
+                    break;
+                }
+                previous = c;
+            }
+            result.push(' ');
+        }
+        _ => result.push(c),
+    })
+}
+
+fn remove_trailing_commas(content: &str) -> String {
+    outside_strings(content, |c, chars, result| {
+        let trailing =
+            c == ',' && matches!(chars.clone().find(|c| !c.is_whitespace()), Some('}' | ']'));
+        if !trailing {
+            result.push(c);
+        }
+    })
+}
+
+/// Parse the content of the markdownlint configuration file `path`
+fn parse(content: &str, path: &Path) -> Result<JsonValue> {
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let value = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("json" | "jsonc") => serde_json::from_str(&strip_jsonc(content))?,
+        Some("yaml" | "yml") => match serde_yaml_ng::from_str::<JsonValue>(content)? {
+            // An empty YAML document is a valid empty configuration
+            JsonValue::Null => JsonValue::Object(Map::new()),
+            value => value,
+        },
+        _ => bail!("Unsupported markdownlint configuration file `{file_name}`, only JSON, JSONC and YAML files can be read"),
+    };
+    if !value.is_object() {
+        bail!("`{file_name}` must contain an object");
+    }
+    Ok(value)
+}
+
+/// Translate a markdownlint or markdownlint-cli2 configuration file to quickmark's
+/// configuration structure. `path` selects the format and doesn't need to exist.
+pub fn import_markdownlint_config(content: &str, path: &Path) -> Result<MarkdownlintImport> {
+    let mut value = parse(content, path)?;
+    let mut import = MarkdownlintImport::default();
+
+    let is_cli2 = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.starts_with(CLI2_PREFIX));
+    if is_cli2 {
+        let options = value.as_object_mut().expect("checked by parse");
+        for key in options.keys() {
+            if !matches!(key.as_str(), "config" | "$schema") {
+                import
+                    .warnings
+                    .push(format!("markdownlint-cli2 option `{key}` is not supported"));
+            }
+        }
+        value = options
+            .remove("config")
+            .unwrap_or_else(|| JsonValue::Object(Map::new()));
+        if !value.is_object() {
+            bail!("markdownlint-cli2 `config` must be an object");
+        }
+    }
+
+    let JsonValue::Object(config) = value else {
+        unreachable!("checked above")
+    };
+    for (key, value) in &config {
+        if key == "$schema" {
+            continue;
+        }
+        if key.eq_ignore_ascii_case("extends") {
+            match value {
+                JsonValue::Null => {}
+                JsonValue::String(base) => {
+                    import.table.insert(
+                        "extends".to_string(),
+                        Value::Array(vec![Value::String(base.clone())]),
+                    );
+                }
+                _ => import
+                    .warnings
+                    .push("`extends` must be a path to another configuration file".to_string()),
+            }
+            continue;
+        }
+
+        let Some(severity) = severity_of(value) else {
+            import
+                .warnings
+                .push(format!("`{key}` has an unsupported value {value}"));
+            continue;
+        };
+
+        if key.eq_ignore_ascii_case("default") {
+            import.set_severity("default", severity);
+        } else if let Some(rule) = find_rule(key) {
+            import.set_severity(rule.alias, severity);
+            if let JsonValue::Object(options) = value {
+                import_options(&mut import, rule, options);
+            }
+        } else {
+            let rules = rules_with_tag(key);
+            if rules.is_empty() {
+                import.warnings.push(format!(
+                    "`{key}` is not a rule or tag supported by quickmark"
+                ));
+            }
+            for rule in rules {
+                import.set_severity(rule.alias, severity.clone());
+            }
+        }
+    }
+    Ok(import)
+}
+
+fn import_options(import: &mut MarkdownlintImport, rule: &Rule, options: &Map<String, JsonValue>) {
+    let known_options = SETTINGS_OPTIONS.get(rule.alias);
+    for (name, value) in options {
+        if matches!(name.as_str(), "enabled" | "severity") {
+            continue;
+        }
+        if !known_options.is_some_and(|known| known.contains(name)) {
+            import.warnings.push(format!(
+                "{}/{}: option `{name}` is not supported",
+                rule.id, rule.alias
+            ));
+            continue;
+        }
+        if let Some(value) = json_to_toml(value) {
+            import.settings(rule.alias).insert(name.clone(), value);
+        }
+    }
+}
+
+/// Render an imported configuration as the content of a `quickmark.toml` file
+pub fn to_quickmark_toml(import: &MarkdownlintImport) -> Result<String> {
+    // Structs keep their field order, so `extends` and `default` come first and severities
+    // come before settings
+    #[derive(Serialize)]
+    struct Document<'a> {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        extends: Option<&'a Value>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        linters: Option<Linters<'a>>,
+    }
+    #[derive(Serialize)]
+    struct Linters<'a> {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        severity: Option<Severity<'a>>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        settings: Option<&'a Value>,
+    }
+    #[derive(Serialize)]
+    struct Severity<'a> {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        default: Option<&'a Value>,
+        #[serde(flatten)]
+        rules: Table,
+    }
+
+    let linters = import.table.get("linters").and_then(Value::as_table);
+    let severity = linters
+        .and_then(|linters| linters.get("severity"))
+        .and_then(Value::as_table)
+        .map(|severity| Severity {
+            default: severity.get("default"),
+            rules: severity
+                .iter()
+                .filter(|(rule, _)| *rule != "default")
+                .map(|(rule, value)| (rule.clone(), value.clone()))
+                .collect(),
+        });
+    let document = Document {
+        extends: import.table.get(EXTENDS_KEY),
+        linters: linters.map(|linters| Linters {
+            severity,
+            settings: linters.get("settings"),
+        }),
+    };
+    Ok(toml::to_string_pretty(&document)?)
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::Path;
+
+    use crate::config::{HeadingStyle, QuickmarkConfig, RuleSeverity};
+
+    use super::{import_markdownlint_config, strip_jsonc, to_quickmark_toml};
+
+    fn import(content: &str, file_name: &str) -> super::MarkdownlintImport {
+        import_markdownlint_config(content, Path::new(file_name)).unwrap()
+    }
+
+    fn to_config(content: &str, file_name: &str) -> QuickmarkConfig {
+        let toml = to_quickmark_toml(&import(content, file_name)).unwrap();
+        crate::config::parse_toml_config(&toml).unwrap()
+    }
+
+    #[test]
+    fn test_strip_jsonc() {
+        let jsonc = r#"{
+  // line comment
+  "MD013": { "line_length": 120, }, /* block
+  comment */
+  "url": "http://example.com/*not a comment*/",
+  "escaped": "a \"// b",
+  "list": [1, 2, // trailing comment
+  ],
+}"#;
+        let value: serde_json::Value = serde_json::from_str(&strip_jsonc(jsonc)).unwrap();
+        assert_eq!(120, value["MD013"]["line_length"]);
+        assert_eq!("http://example.com/*not a comment*/", value["url"]);
+        assert_eq!("a \"// b", value["escaped"]);
+        assert_eq!(2, value["list"].as_array().unwrap().len());
+    }
+
+    #[test]
+    fn test_rules_and_options() {
+        let config = to_config(
+            r#"{
+  "$schema": "https://example.com/schema.json",
+  "default": true,
+  "MD013": { "line_length": 120, "code_blocks": false },
+  "no-inline-html": false,
+  "md003": { "style": "atx" },
+  "MD041": "warning"
+}"#,
+            ".markdownlint.json",
+        );
+        let severity = &config.linters.severity;
+        assert_eq!(Some(&RuleSeverity::Error), severity.get("line-length"));
+        assert_eq!(Some(&RuleSeverity::Off), severity.get("no-inline-html"));
+        assert_eq!(
+            Some(&RuleSeverity::Warning),
+            severity.get("first-line-heading")
+        );
+        assert_eq!(Some(&RuleSeverity::Error), severity.get("ul-style"));
+        let settings = &config.linters.settings;
+        assert_eq!(120, settings.line_length.line_length);
+        assert!(!settings.line_length.code_blocks);
+        assert_eq!(80, settings.line_length.heading_line_length);
+        assert_eq!(HeadingStyle::ATX, settings.heading_style.style);
+    }
+
+    #[test]
+    fn test_default_false() {
+        let config = to_config(
+            "default: false\nMD013:\n  line_length: 100\n",
+            ".markdownlint.yaml",
+        );
+        let severity = &config.linters.severity;
+        assert_eq!(Some(&RuleSeverity::Error), severity.get("line-length"));
+        assert_eq!(Some(&RuleSeverity::Off), severity.get("heading-style"));
+        assert_eq!(100, config.linters.settings.line_length.line_length);
+    }
+
+    #[test]
+    fn test_tags_follow_key_order() {
+        let config = to_config(
+            r#"{ "headings": false, "MD003": true }"#,
+            ".markdownlint.jsonc",
+        );
+        let severity = &config.linters.severity;
+        assert_eq!(Some(&RuleSeverity::Off), severity.get("heading-increment"));
+        assert_eq!(Some(&RuleSeverity::Error), severity.get("heading-style"));
+
+        let config = to_config(
+            r#"{ "MD003": true, "headings": false }"#,
+            ".markdownlint.jsonc",
+        );
+        assert_eq!(
+            Some(&RuleSeverity::Off),
+            config.linters.severity.get("heading-style")
+        );
+    }
+
+    #[test]
+    fn test_cli2_config() {
+        let import = import(
+            "config:\n  MD010: false\nglobs:\n  - '**/*.md'\n",
+            ".markdownlint-cli2.yaml",
+        );
+        assert_eq!(
+            vec!["markdownlint-cli2 option `globs` is not supported".to_string()],
+            import.warnings
+        );
+        let config =
+            crate::config::parse_toml_config(&to_quickmark_toml(&import).unwrap()).unwrap();
+        assert_eq!(
+            Some(&RuleSeverity::Off),
+            config.linters.severity.get("no-hard-tabs")
+        );
+    }
+
+    #[test]
+    fn test_unsupported_entries_are_reported() {
+        let import = import(
+            r#"{ "MD002": false, "MD013": { "line_length": 100, "unknown": 1 }, "MD010": 5 }"#,
+            ".markdownlint.json",
+        );
+        assert_eq!(
+            vec![
+                "`MD002` is not a rule or tag supported by quickmark".to_string(),
+                "MD013/line-length: option `unknown` is not supported".to_string(),
+                "`MD010` has an unsupported value 5".to_string(),
+            ],
+            import.warnings
+        );
+    }
+
+    #[test]
+    fn test_extends_and_unsupported_files() {
+        let import = import(r#"{ "extends": "base.json" }"#, ".markdownlint.json");
+        assert_eq!(
+            "extends = [\"base.json\"]\n",
+            to_quickmark_toml(&import).unwrap()
+        );
+
+        assert!(import_markdownlint_config("{}", Path::new(".markdownlint.cjs")).is_err());
+        assert!(import_markdownlint_config("[]", Path::new(".markdownlint.json")).is_err());
+        assert!(import_markdownlint_config("", Path::new(".markdownlint.yaml")).is_ok());
+    }
+}
@@ -1,14 +1,27 @@
 use anyhow::Result;
-use serde::Deserialize;
-use std::collections::{HashMap, HashSet};
-use std::{
-    fs,
-    path::{Path, PathBuf},
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use crate::rules::{ALL_RULES, OPT_IN_RULES};
+
+mod loader;
+mod markdownlint;
+mod overrides;
+mod validation;
+
+use loader::{ConfigLayer, ConfigLoader};
+pub use loader::{ConfigOrigin, ConfigOrigins, EXTENDS_KEY, MERGE_WITH_PARENT_KEY, PLUGINS_KEY};
+pub use markdownlint::{
+    import_markdownlint_config, is_markdownlint_config, to_quickmark_toml, MarkdownlintImport,
+    MARKDOWNLINT_CONFIG_FILES,
+};
+pub use overrides::ConfigOverride;
+pub use validation::{
+    validate_config, validate_config_content, validate_config_file, ConfigProblem,
 };
 
-use crate::rules::ALL_RULES;
-
-#[derive(Debug, PartialEq, Clone, Deserialize)]
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
 pub enum RuleSeverity {
     #[serde(rename = "err")]
     Error,
@@ -18,6 +31,7 @@ pub enum RuleSeverity {
     Off,
 }
 
+pub use crate::rules::custom::{CompiledCustomRules, CustomRuleTable};
 pub use crate::rules::md003::{HeadingStyle, MD003HeadingStyleTable};
 pub use crate::rules::md004::{MD004UlStyleTable, UlStyle};
 pub use crate::rules::md007::MD007UlIndentTable;
@@ -50,8 +64,12 @@ pub use crate::rules::md053::MD053LinkImageReferenceDefinitionsTable;
 pub use crate::rules::md054::MD054LinkImageStyleTable;
 pub use crate::rules::md055::{MD055TablePipeStyleTable, TablePipeStyle};
 pub use crate::rules::md059::MD059DescriptiveLinkTextTable;
+pub use crate::rules::qm001::{
+    FrontMatterProperty, FrontMatterValueType, QM001FrontMatterSchemaTable,
+};
+pub use crate::rules::qm002::QM002RelativeLinksTable;
 
-#[derive(Debug, Default, PartialEq, Clone, Deserialize)]
+#[derive(Debug, Default, PartialEq, Clone, Deserialize, Serialize)]
 pub struct LintersSettingsTable {
     #[serde(rename = "heading-style")]
     #[serde(default)]
@@ -149,9 +167,18 @@ pub struct LintersSettingsTable {
     #[serde(rename = "descriptive-link-text")]
     #[serde(default)]
     pub descriptive_link_text: MD059DescriptiveLinkTextTable,
+    #[serde(rename = "front-matter-schema")]
+    #[serde(default)]
+    pub front_matter_schema: QM001FrontMatterSchemaTable,
+    #[serde(rename = "relative-links")]
+    #[serde(default)]
+    pub relative_links: QM002RelativeLinksTable,
+    /// Settings of plugin rules, keyed by alias. They are passed to the plugin as JSON.
+    #[serde(flatten)]
+    pub plugins: BTreeMap<String, toml::Value>,
 }
 
-#[derive(Debug, Default, PartialEq, Clone, Deserialize)]
+#[derive(Debug, Default, PartialEq, Clone, Deserialize, Serialize)]
 pub struct LintersTable {
     #[serde(default)]
     pub severity: HashMap<String, RuleSeverity>,
@@ -159,13 +186,29 @@ pub struct LintersTable {
     pub settings: LintersSettingsTable,
 }
 
-#[derive(Debug, Default, PartialEq, Clone, Deserialize)]
+#[derive(Debug, Default, PartialEq, Clone, Deserialize, Serialize)]
 pub struct QuickmarkConfig {
     #[serde(default)]
     pub linters: LintersTable,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub overrides: Vec<ConfigOverride>,
+    /// Plugin libraries providing additional rules, see [`crate::plugin`]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub plugins: Vec<PathBuf>,
+    /// Aliases of the rules of `plugins`, set when the plugins are loaded. Other loaded
+    /// plugins don't apply to documents linted with this configuration.
+    #[serde(skip)]
+    pub plugin_rules: Vec<String>,
+    /// Rules declared in `[[custom_rules]]` tables
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub custom_rules: Vec<CustomRuleTable>,
+    /// `custom_rules`, compiled when the configuration is loaded
+    #[serde(skip)]
+    pub compiled_custom_rules: CompiledCustomRules,
 }
 
 pub fn normalize_severities(severities: &mut HashMap<String, RuleSeverity>) {
+    // Plugin and custom rules are normalized with the configuration that declares them
     let rule_aliases: HashSet<&str> = ALL_RULES.iter().map(|r| r.alias).collect();
 
     // Extract default severity if present, then remove it from the map
@@ -176,15 +219,43 @@ pub fn normalize_severities(severities: &mut HashMap<String, RuleSeverity>) {
 
     // Apply default severity to all rules that don't have explicit configuration
     for &rule in &rule_aliases {
-        severities
-            .entry(rule.to_string())
-            .or_insert(default_severity.clone());
+        let severity = if OPT_IN_RULES.contains(&rule) {
+            RuleSeverity::Off
+        } else {
+            default_severity.clone()
+        };
+        severities.entry(rule.to_string()).or_insert(severity);
     }
 }
 
 impl QuickmarkConfig {
     pub fn new(linters: LintersTable) -> Self {
-        Self { linters }
+        Self {
+            linters,
+            overrides: Vec::new(),
+            plugins: Vec::new(),
+            plugin_rules: Vec::new(),
+            custom_rules: Vec::new(),
+            compiled_custom_rules: CompiledCustomRules::default(),
+        }
+    }
+
+    /// Configuration for a single file, with every matching `[[overrides]]` entry applied
+    /// in declaration order
+    pub fn for_file(&self, file_path: &Path) -> Result<QuickmarkConfig> {
+        let mut linters = self.linters.clone();
+        for config_override in &self.overrides {
+            if config_override.matches(file_path)? {
+                config_override.apply(&mut linters)?;
+            }
+        }
+        Ok(QuickmarkConfig {
+            plugins: self.plugins.clone(),
+            plugin_rules: self.plugin_rules.clone(),
+            custom_rules: self.custom_rules.clone(),
+            compiled_custom_rules: self.compiled_custom_rules.clone(),
+            ..QuickmarkConfig::new(linters)
+        })
     }
 
     pub fn default_with_normalized_severities() -> Self {
@@ -208,6 +279,33 @@ pub enum ConfigSearchResult {
     Error { path: PathBuf, error: String },
 }
 
+/// Why [`ConfigDiscovery`] stopped looking for configuration in parent directories
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum SearchStop {
+    /// Found a configuration file that doesn't merge with its parent, or failed to load one
+    ConfigFile(PathBuf),
+    /// Reached a workspace root of the language server
+    WorkspaceRoot(PathBuf),
+    /// Reached the current working directory of the CLI
+    WorkingDirectory(PathBuf),
+    /// Reached the root of a git repository
+    GitRoot(PathBuf),
+    /// Reached the root of the file system
+    FileSystemRoot,
+}
+
+/// A configuration search along with every file looked for and where it stopped
+#[derive(Debug, PartialEq, Clone)]
+pub struct ConfigSearchTrace {
+    pub result: ConfigSearchResult,
+    /// Candidate files up to the directory of the nearest configuration file
+    pub searched_paths: Vec<PathBuf>,
+    pub stop: SearchStop,
+    /// Configuration files that were read, including the files they extend. The result
+    /// can change when one of them does.
+    pub config_files: Vec<PathBuf>,
+}
+
 /// Hierarchical config discovery with workspace root stopping point
 pub struct ConfigDiscovery {
     workspace_roots: Vec<PathBuf>,
@@ -240,89 +338,180 @@ impl ConfigDiscovery {
 
     /// Find configuration file starting from the given file path
     pub fn find_config(&self, file_path: &Path) -> ConfigSearchResult {
-        let start_dir = if file_path.is_file() {
-            file_path.parent().unwrap_or(file_path)
-        } else {
+        self.find_config_with_origins(file_path).0
+    }
+
+    /// Find configuration file starting from the given file path, along with the file or
+    /// preset each explicitly configured value came from.
+    ///
+    /// A nested `quickmark.toml` with `merge_with_parent = true` is merged onto the
+    /// configuration found further up the tree instead of replacing it. When `file_path`
+    /// is a file, the `[[overrides]]` matching it are already applied.
+    pub fn find_config_with_origins(
+        &self,
+        file_path: &Path,
+    ) -> (ConfigSearchResult, ConfigOrigins) {
+        let (trace, origins) = self.search(file_path);
+        (trace.result, origins)
+    }
+
+    /// Find configuration for the given file path and record how it was found
+    pub fn trace_config(&self, file_path: &Path) -> ConfigSearchTrace {
+        self.search(file_path).0
+    }
+
+    fn search(&self, file_path: &Path) -> (ConfigSearchTrace, ConfigOrigins) {
+        // Paths that don't exist are documents too, e.g. the virtual path of stdin
+        let start_dir = if file_path.is_dir() {
             file_path
+        } else {
+            file_path.parent().unwrap_or(file_path)
         };
 
         let mut searched_paths = Vec::new();
+        let mut loader = ConfigLoader::default();
+        // Nearest configuration file and the layers found so far, innermost first
+        let mut nearest: Option<PathBuf> = None;
+        let mut layers: Vec<ConfigLayer> = Vec::new();
         let mut current_dir = start_dir;
 
-        loop {
-            let config_path = current_dir.join("quickmark.toml");
-            searched_paths.push(config_path.clone());
-
-            if config_path.is_file() {
-                match fs::read_to_string(&config_path) {
-                    Ok(config_str) => match parse_toml_config(&config_str) {
-                        Ok(config) => {
-                            return ConfigSearchResult::Found {
-                                path: config_path,
-                                config: Box::new(config),
-                            }
-                        }
-                        Err(e) => {
-                            return ConfigSearchResult::Error {
-                                path: config_path,
-                                error: e.to_string(),
-                            }
+        let stop = loop {
+            let candidates = config_file_candidates(current_dir);
+            if nearest.is_none() {
+                searched_paths.extend(candidates.iter().cloned());
+            }
+
+            if let Some(config_path) = candidates.into_iter().find(|path| path.is_file()) {
+                match loader.load_file(&config_path) {
+                    Ok(layer) => {
+                        let merge_with_parent = layer.merge_with_parent;
+                        layers.push(layer);
+                        nearest.get_or_insert(config_path.clone());
+                        if !merge_with_parent {
+                            break SearchStop::ConfigFile(config_path);
                         }
-                    },
+                    }
                     Err(e) => {
-                        return ConfigSearchResult::Error {
-                            path: config_path,
-                            error: e.to_string(),
-                        }
+                        let trace = ConfigSearchTrace {
+                            result: ConfigSearchResult::Error {
+                                path: config_path.clone(),
+                                error: format!("{e:#}"),
+                            },
+                            searched_paths,
+                            stop: SearchStop::ConfigFile(config_path),
+                            config_files: loader.files,
+                        };
+                        return (trace, ConfigOrigins::new());
                     }
                 }
             }
 
             // Check if we should stop searching at this directory
-            if self.should_stop_search(current_dir) {
-                break;
+            if let Some(stop) = self.stop_reason(current_dir) {
+                break stop;
             }
 
             // Move to parent directory
             match current_dir.parent() {
                 Some(parent) => current_dir = parent,
-                None => break, // Reached filesystem root
+                None => break SearchStop::FileSystemRoot,
             }
-        }
+        };
 
-        ConfigSearchResult::NotFound { searched_paths }
+        let Some(path) = nearest else {
+            let trace = ConfigSearchTrace {
+                result: ConfigSearchResult::NotFound {
+                    searched_paths: searched_paths.clone(),
+                },
+                searched_paths,
+                stop,
+                config_files: loader.files,
+            };
+            return (trace, ConfigOrigins::new());
+        };
+
+        let mut merged = ConfigLayer::default();
+        for layer in layers.into_iter().rev() {
+            merged.merge(layer);
+        }
+        let resolved = merged.into_config().and_then(|(config, origins)| {
+            if file_path.is_dir() {
+                Ok((config, origins))
+            } else {
+                Ok((config.for_file(file_path)?, origins))
+            }
+        });
+        let (result, origins) = match resolved {
+            Ok((config, origins)) => (
+                ConfigSearchResult::Found {
+                    path,
+                    config: Box::new(config),
+                },
+                origins,
+            ),
+            Err(e) => (
+                ConfigSearchResult::Error {
+                    path,
+                    error: format!("{e:#}"),
+                },
+                ConfigOrigins::new(),
+            ),
+        };
+        let trace = ConfigSearchTrace {
+            result,
+            searched_paths,
+            stop,
+            config_files: loader.files,
+        };
+        (trace, origins)
     }
 
-    /// Determine if search should stop at the current directory
-    fn should_stop_search(&self, dir: &Path) -> bool {
+    /// Whether the search stops at the current directory, and why
+    fn stop_reason(&self, dir: &Path) -> Option<SearchStop> {
         // Stop at workspace roots (for LSP mode)
         for workspace_root in &self.workspace_roots {
             if dir == workspace_root.as_path() {
-                return true;
+                return Some(SearchStop::WorkspaceRoot(dir.to_path_buf()));
             }
         }
 
         // Stop at current working directory (for CLI mode)
         if let Some(cwd) = &self.current_working_dir {
             if dir == cwd.as_path() {
-                return true;
+                return Some(SearchStop::WorkingDirectory(dir.to_path_buf()));
             }
         }
 
         // Stop at git repository boundaries
         if dir.join(".git").exists() {
-            return true;
+            return Some(SearchStop::GitRoot(dir.to_path_buf()));
         }
 
-        false
+        None
     }
 }
 
-/// Parse a TOML configuration string into a QuickmarkConfig
+/// Parse a TOML configuration string into a QuickmarkConfig.
+///
+/// Relative `extends` paths are resolved against the current working directory.
 pub fn parse_toml_config(config_str: &str) -> Result<QuickmarkConfig> {
-    let mut config: QuickmarkConfig = toml::from_str(config_str)?;
-    normalize_severities(&mut config.linters.severity);
-    Ok(config)
+    let base_dir = std::env::current_dir().ok();
+    let layer =
+        ConfigLoader::default().load_str(config_str, base_dir.as_deref(), ConfigOrigin::Inline)?;
+    Ok(layer.into_config()?.0)
+}
+
+/// Load a configuration file, resolving everything it extends
+pub fn load_config_file(path: &Path) -> Result<(QuickmarkConfig, ConfigOrigins)> {
+    ConfigLoader::default().load_file(path)?.into_config()
+}
+
+/// The configuration file at `path` and every file it extends, as far as they could be
+/// read
+pub fn config_file_sources(path: &Path) -> Vec<PathBuf> {
+    let mut loader = ConfigLoader::default();
+    let _ = loader.load_file(path);
+    loader.files
 }
 
 /// Load configuration from QUICKMARK_CONFIG environment variable, path, or default
@@ -331,15 +520,14 @@ pub fn config_from_env_path_or_default(path: &Path) -> Result<QuickmarkConfig> {
     if let Ok(env_config_path) = std::env::var("QUICKMARK_CONFIG") {
         let env_config_file = Path::new(&env_config_path);
         if env_config_file.is_file() {
-            match fs::read_to_string(env_config_file) {
-                Ok(config) => return parse_toml_config(&config),
-                Err(e) => {
-                    eprintln!(
-                        "Error loading config from QUICKMARK_CONFIG path {env_config_path}: {e}. Default config will be used."
-                    );
-                    return Ok(QuickmarkConfig::default_with_normalized_severities());
-                }
+            // A file that can't be read falls back to the defaults, like a missing one
+            if let Err(e) = std::fs::read_to_string(env_config_file) {
+                eprintln!(
+                    "Error loading config from QUICKMARK_CONFIG path {env_config_path}: {e}. Default config will be used."
+                );
+                return Ok(QuickmarkConfig::default_with_normalized_severities());
             }
+            return Ok(load_config_file(env_config_file)?.0);
         } else {
             eprintln!(
                 "Config file was not found at QUICKMARK_CONFIG path {env_config_path}. Default config will be used."
@@ -352,13 +540,24 @@ pub fn config_from_env_path_or_default(path: &Path) -> Result<QuickmarkConfig> {
     config_in_path_or_default(path)
 }
 
+/// Configuration files looked for in `dir`, in order of precedence: `quickmark.toml`
+/// followed by markdownlint's configuration files
+fn config_file_candidates(dir: &Path) -> Vec<PathBuf> {
+    std::iter::once("quickmark.toml")
+        .chain(MARKDOWNLINT_CONFIG_FILES.iter().copied())
+        .map(|name| dir.join(name))
+        .collect()
+}
+
 /// Load configuration from a path, or return default if not found
 pub fn config_in_path_or_default(path: &Path) -> Result<QuickmarkConfig> {
-    let config_file = path.join("quickmark.toml");
-    if config_file.is_file() {
-        let config = fs::read_to_string(config_file)?;
-        return parse_toml_config(&config);
+    if let Some(config_file) = config_file_candidates(path)
+        .into_iter()
+        .find(|candidate| candidate.is_file())
+    {
+        return Ok(load_config_file(&config_file)?.0);
     }
+    let config_file = path.join("quickmark.toml");
     eprintln!(
         "Config file was not found at {}. Default config will be used.",
         config_file.to_string_lossy()
@@ -368,11 +567,19 @@ pub fn config_in_path_or_default(path: &Path) -> Result<QuickmarkConfig> {
 
 /// Convenience function that uses ConfigDiscovery to find config or return default
 pub fn discover_config_or_default(file_path: &Path) -> Result<QuickmarkConfig> {
-    let discovery = ConfigDiscovery::new();
-    match discovery.find_config(file_path) {
-        ConfigSearchResult::Found { config, .. } => Ok(*config),
+    discover_config_with_files_or_default(file_path).map(|(config, _)| config)
+}
+
+/// Like [`discover_config_or_default`], along with the configuration files that were
+/// read, see [`ConfigSearchTrace::config_files`]
+pub fn discover_config_with_files_or_default(
+    file_path: &Path,
+) -> Result<(QuickmarkConfig, Vec<PathBuf>)> {
+    let trace = ConfigDiscovery::new().trace_config(file_path);
+    let config = match trace.result {
+        ConfigSearchResult::Found { config, .. } => *config,
         ConfigSearchResult::NotFound { .. } => {
-            Ok(QuickmarkConfig::default_with_normalized_severities())
+            QuickmarkConfig::default_with_normalized_severities()
         }
         ConfigSearchResult::Error { path, error } => {
             eprintln!(
@@ -380,9 +587,10 @@ pub fn discover_config_or_default(file_path: &Path) -> Result<QuickmarkConfig> {
                 path.to_string_lossy(),
                 error
             );
-            Ok(QuickmarkConfig::default_with_normalized_severities())
+            QuickmarkConfig::default_with_normalized_severities()
         }
-    }
+    };
+    Ok((config, trace.config_files))
 }
 
 /// Convenience function for LSP usage with workspace roots
@@ -414,9 +622,9 @@ mod test {
     use tempfile::TempDir;
 
     use crate::config::{
-        config_from_env_path_or_default, discover_config_or_default,
+        config_file_sources, config_from_env_path_or_default, discover_config_or_default,
         discover_config_with_workspace_or_default, parse_toml_config, ConfigDiscovery,
-        ConfigSearchResult, HeadingStyle, LintersSettingsTable, LintersTable,
+        ConfigOrigin, ConfigSearchResult, HeadingStyle, LintersSettingsTable, LintersTable,
         MD003HeadingStyleTable, MD004UlStyleTable, MD007UlIndentTable, MD009TrailingSpacesTable,
         MD010HardTabsTable, MD012MultipleBlankLinesTable, MD013LineLengthTable,
         MD022HeadingsBlanksTable, MD024MultipleHeadingsTable, MD025SingleH1Table,
@@ -427,7 +635,8 @@ mod test {
         MD046CodeBlockStyleTable, MD048CodeFenceStyleTable, MD049EmphasisStyleTable,
         MD050StrongStyleTable, MD051LinkFragmentsTable, MD052ReferenceLinksImagesTable,
         MD053LinkImageReferenceDefinitionsTable, MD054LinkImageStyleTable,
-        MD055TablePipeStyleTable, MD059DescriptiveLinkTextTable, RuleSeverity,
+        MD055TablePipeStyleTable, MD059DescriptiveLinkTextTable, QM001FrontMatterSchemaTable,
+        QM002RelativeLinksTable, RuleSeverity, SearchStop,
     };
 
     use super::{normalize_severities, QuickmarkConfig};
@@ -454,6 +663,22 @@ mod test {
             *severity.get("no-reversed-links").unwrap()
         );
         assert_eq!(None, severity.get("some-bullshit"));
+        assert_eq!(RuleSeverity::Off, *severity.get("relative-links").unwrap());
+        assert_eq!(
+            RuleSeverity::Off,
+            *severity.get("duplicate-titles").unwrap()
+        );
+
+        // Opt-in rules are only turned on by name
+        let mut severity = HashMap::from([
+            ("default".to_string(), RuleSeverity::Warning),
+            ("relative-links".to_string(), RuleSeverity::Error),
+        ]);
+        normalize_severities(&mut severity);
+        assert_eq!(RuleSeverity::Error, severity["relative-links"]);
+        let mut severity = HashMap::from([("default".to_string(), RuleSeverity::Warning)]);
+        normalize_severities(&mut severity);
+        assert_eq!(RuleSeverity::Off, severity["relative-links"]);
     }
 
     #[test]
@@ -528,6 +753,9 @@ mod test {
                 link_image_style: MD054LinkImageStyleTable::default(),
                 table_pipe_style: MD055TablePipeStyleTable::default(),
                 descriptive_link_text: MD059DescriptiveLinkTextTable::default(),
+                front_matter_schema: QM001FrontMatterSchemaTable::default(),
+                relative_links: QM002RelativeLinksTable::default(),
+                plugins: Default::default(),
             },
         });
 
@@ -1300,6 +1528,52 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_config_discovery_for_virtual_path() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("quickmark.toml");
+        std::fs::write(&config_path, "[linters.severity]\nheading-style = 'warn'\n").unwrap();
+        std::fs::create_dir(temp_dir.path().join("docs")).unwrap();
+
+        // The document doesn't exist on disk, e.g. when it is read from stdin
+        let file_path = temp_dir.path().join("docs").join("unsaved.md");
+        let discovery = ConfigDiscovery::new();
+        match discovery.find_config(&file_path) {
+            ConfigSearchResult::Found { path, .. } => assert_eq!(config_path, path),
+            result => panic!("Expected Found result, got: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn test_config_discovery_trace() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo_dir = temp_dir.path().join("repo");
+        let docs_dir = repo_dir.join("docs");
+        std::fs::create_dir_all(repo_dir.join(".git")).unwrap();
+        std::fs::create_dir_all(&docs_dir).unwrap();
+        let file_path = docs_dir.join("guide.md");
+        std::fs::write(&file_path, "# Guide\n").unwrap();
+
+        let discovery = ConfigDiscovery::with_workspace_roots(Vec::new());
+        let trace = discovery.trace_config(&file_path);
+        assert_eq!(SearchStop::GitRoot(repo_dir.clone()), trace.stop);
+        assert!(trace
+            .searched_paths
+            .contains(&docs_dir.join("quickmark.toml")));
+        assert!(trace
+            .searched_paths
+            .contains(&repo_dir.join("quickmark.toml")));
+        assert!(matches!(trace.result, ConfigSearchResult::NotFound { .. }));
+
+        let config_path = docs_dir.join("quickmark.toml");
+        std::fs::write(&config_path, "[linters.severity]\n").unwrap();
+        let trace = discovery.trace_config(&file_path);
+        assert_eq!(SearchStop::ConfigFile(config_path), trace.stop);
+        assert!(!trace
+            .searched_paths
+            .contains(&repo_dir.join("quickmark.toml")));
+    }
+
     #[test]
     fn test_config_discovery_hierarchical_search() {
         let temp_dir = TempDir::new().unwrap();
@@ -1432,6 +1706,212 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_config_discovery_nested_config_replaces_parent() {
+        let temp_dir = TempDir::new().unwrap();
+        let project_dir = temp_dir.path().join("project");
+        let docs_dir = project_dir.join("docs");
+        std::fs::create_dir_all(&docs_dir).unwrap();
+        std::fs::create_dir(project_dir.join(".git")).unwrap();
+
+        std::fs::write(
+            project_dir.join("quickmark.toml"),
+            "[linters.severity]\nline-length = 'off'\n",
+        )
+        .unwrap();
+        std::fs::write(
+            docs_dir.join("quickmark.toml"),
+            "[linters.severity]\nheading-style = 'warn'\n",
+        )
+        .unwrap();
+        let file_path = docs_dir.join("test.md");
+        std::fs::write(&file_path, "# Test").unwrap();
+
+        let discovery = ConfigDiscovery::with_workspace_roots(vec![project_dir.clone()]);
+        match discovery.find_config(&file_path) {
+            ConfigSearchResult::Found { path, config } => {
+                assert_eq!(path, docs_dir.join("quickmark.toml"));
+                let severity = &config.linters.severity;
+                assert_eq!(Some(&RuleSeverity::Warning), severity.get("heading-style"));
+                assert_eq!(Some(&RuleSeverity::Error), severity.get("line-length"));
+            }
+            result => panic!("Expected Found result, got: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn test_config_discovery_merge_with_parent() {
+        let temp_dir = TempDir::new().unwrap();
+        let project_dir = temp_dir.path().join("project");
+        let docs_dir = project_dir.join("docs");
+        std::fs::create_dir_all(&docs_dir).unwrap();
+        std::fs::create_dir(project_dir.join(".git")).unwrap();
+
+        let parent_config = project_dir.join("quickmark.toml");
+        std::fs::write(
+            &parent_config,
+            "[linters.severity]\nline-length = 'off'\nheading-style = 'err'\n\n[linters.settings.ul-style]\nstyle = 'dash'\n",
+        )
+        .unwrap();
+        let nested_config = docs_dir.join("quickmark.toml");
+        std::fs::write(
+            &nested_config,
+            "merge_with_parent = true\n\n[linters.severity]\nheading-style = 'warn'\n",
+        )
+        .unwrap();
+        let file_path = docs_dir.join("test.md");
+        std::fs::write(&file_path, "# Test").unwrap();
+
+        let discovery = ConfigDiscovery::with_workspace_roots(vec![project_dir.clone()]);
+        let (result, origins) = discovery.find_config_with_origins(&file_path);
+        match result {
+            ConfigSearchResult::Found { path, config } => {
+                assert_eq!(path, nested_config);
+                let severity = &config.linters.severity;
+                assert_eq!(Some(&RuleSeverity::Warning), severity.get("heading-style"));
+                assert_eq!(Some(&RuleSeverity::Off), severity.get("line-length"));
+                assert_eq!(
+                    crate::config::UlStyle::Dash,
+                    config.linters.settings.ul_style.style
+                );
+            }
+            result => panic!("Expected Found result, got: {:?}", result),
+        }
+
+        let parent = ConfigOrigin::File(std::fs::canonicalize(&parent_config).unwrap());
+        let nested = ConfigOrigin::File(std::fs::canonicalize(&nested_config).unwrap());
+        assert_eq!(Some(&nested), origins.get("linters.severity.heading-style"));
+        assert_eq!(Some(&parent), origins.get("linters.severity.line-length"));
+        assert_eq!(
+            Some(&parent),
+            origins.get("linters.settings.ul-style.style")
+        );
+    }
+
+    #[test]
+    fn test_config_discovery_config_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let project_dir = temp_dir.path().join("project");
+        let docs_dir = project_dir.join("docs");
+        std::fs::create_dir_all(project_dir.join("shared")).unwrap();
+        std::fs::create_dir_all(&docs_dir).unwrap();
+        std::fs::write(
+            project_dir.join("shared/base.toml"),
+            "[linters.severity]\nline-length = 'off'\n",
+        )
+        .unwrap();
+        std::fs::write(
+            project_dir.join("quickmark.toml"),
+            "extends = 'shared/base.toml'\n",
+        )
+        .unwrap();
+        std::fs::write(
+            docs_dir.join("quickmark.toml"),
+            "merge_with_parent = true\n",
+        )
+        .unwrap();
+
+        let discovery = ConfigDiscovery::with_workspace_roots(vec![project_dir.clone()]);
+        let trace = discovery.trace_config(&docs_dir.join("test.md"));
+        assert_eq!(
+            vec![
+                docs_dir.join("quickmark.toml"),
+                project_dir.join("quickmark.toml"),
+                project_dir.join("shared/base.toml"),
+            ],
+            trace.config_files
+        );
+        assert_eq!(
+            vec![
+                project_dir.join("quickmark.toml"),
+                project_dir.join("shared/base.toml"),
+            ],
+            config_file_sources(&project_dir.join("quickmark.toml"))
+        );
+    }
+
+    #[test]
+    fn test_config_discovery_applies_overrides() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("quickmark.toml");
+        std::fs::write(
+            &config_path,
+            "[[overrides]]\nfiles = ['CHANGELOG.md']\n\n[overrides.severity]\nline-length = 'off'\n",
+        )
+        .unwrap();
+        let changelog = temp_dir.path().join("CHANGELOG.md");
+        let readme = temp_dir.path().join("README.md");
+        std::fs::write(&changelog, "# Changes").unwrap();
+        std::fs::write(&readme, "# Readme").unwrap();
+
+        let discovery = ConfigDiscovery::new();
+        let line_length_severity = |file: &Path| match discovery.find_config(file) {
+            ConfigSearchResult::Found { config, .. } => {
+                assert!(config.overrides.is_empty());
+                config.linters.severity["line-length"].clone()
+            }
+            result => panic!("Expected Found result, got: {:?}", result),
+        };
+        assert_eq!(RuleSeverity::Off, line_length_severity(&changelog));
+        assert_eq!(RuleSeverity::Error, line_length_severity(&readme));
+    }
+
+    #[test]
+    fn test_config_discovery_markdownlint_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let markdownlint_path = temp_dir.path().join(".markdownlint.yaml");
+        std::fs::write(
+            &markdownlint_path,
+            "default: false\nMD013:\n  line_length: 120\n",
+        )
+        .unwrap();
+        let file_path = temp_dir.path().join("test.md");
+        std::fs::write(&file_path, "# Test").unwrap();
+
+        let discovery = ConfigDiscovery::new();
+        match discovery.find_config(&file_path) {
+            ConfigSearchResult::Found { path, config } => {
+                assert_eq!(path, markdownlint_path);
+                let severity = &config.linters.severity;
+                assert_eq!(Some(&RuleSeverity::Error), severity.get("line-length"));
+                assert_eq!(Some(&RuleSeverity::Off), severity.get("heading-style"));
+                assert_eq!(120, config.linters.settings.line_length.line_length);
+            }
+            result => panic!("Expected Found result, got: {:?}", result),
+        }
+
+        // quickmark.toml takes precedence over markdownlint files in the same directory
+        let config_path = temp_dir.path().join("quickmark.toml");
+        std::fs::write(&config_path, "[linters.severity]\nline-length = 'off'\n").unwrap();
+        match discovery.find_config(&file_path) {
+            ConfigSearchResult::Found { path, config } => {
+                assert_eq!(path, config_path);
+                assert_eq!(
+                    Some(&RuleSeverity::Off),
+                    config.linters.severity.get("line-length")
+                );
+            }
+            result => panic!("Expected Found result, got: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn test_config_discovery_extends_error() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("quickmark.toml");
+        std::fs::write(&config_path, "extends = 'preset:unknown'\n").unwrap();
+        let file_path = temp_dir.path().join("test.md");
+        std::fs::write(&file_path, "# Test").unwrap();
+
+        match ConfigDiscovery::new().find_config(&file_path) {
+            ConfigSearchResult::Error { path, error } => {
+                assert_eq!(path, config_path);
+                assert!(error.contains("Unknown preset"), "{error}");
+            }
+            result => panic!("Expected Error result, got: {:?}", result),
+        }
+    }
+
     #[test]
     fn test_discover_config_or_default_found() {
         let temp_dir = TempDir::new().unwrap();
@@ -1514,8 +1994,11 @@ mod test {
         let discovery = ConfigDiscovery::with_workspace_roots(vec![workspace_dir.clone()]);
 
         // Should stop at workspace (highest priority)
-        assert!(discovery.should_stop_search(&workspace_dir));
+        assert_eq!(
+            Some(SearchStop::WorkspaceRoot(workspace_dir.clone())),
+            discovery.stop_reason(&workspace_dir)
+        );
         // Should not stop at .git when workspace root is set
-        assert!(!discovery.should_stop_search(&git_dir));
+        assert_eq!(None, discovery.stop_reason(&git_dir));
     }
 }
@@ -0,0 +1,247 @@
+//! Per-file configuration overrides declared with `[[overrides]]` tables.
+
+use anyhow::{Context, Result};
+use globset::{Glob, GlobBuilder, GlobSet, GlobSetBuilder};
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use toml::{Table, Value};
+
+use super::loader::merge_tables;
+use super::{LintersSettingsTable, LintersTable, RuleSeverity};
+
+/// Severities and settings applied on top of the base configuration for matching files.
+///
+/// `files` and `exclude` are glob patterns relative to the directory of the configuration
+/// file declaring the override. Patterns without a `/` match at any depth.
+#[derive(Debug, Default, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ConfigOverride {
+    pub files: Vec<String>,
+    pub exclude: Vec<String>,
+    pub severity: HashMap<String, RuleSeverity>,
+    /// Partial `[linters.settings]` table, merged key by key onto the base settings
+    pub settings: Table,
+    /// Directory the globs are relative to, set when the override is loaded from a file
+    #[serde(skip)]
+    pub base_dir: Option<PathBuf>,
+}
+
+fn glob_set(patterns: &[String]) -> Result<GlobSet> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        let pattern = pattern.trim_start_matches("./");
+        let pattern = if pattern.contains('/') {
+            pattern.trim_start_matches('/').to_string()
+        } else {
+            format!("**/{pattern}")
+        };
+        let glob: Glob = GlobBuilder::new(&pattern)
+            .literal_separator(true)
+            .build()
+            .with_context(|| format!("Invalid override glob `{pattern}`"))?;
+        builder.add(glob);
+    }
+    Ok(builder.build()?)
+}
+
+/// Bound on the glob sets cached by a thread, see [`is_match`]
+const MAX_CACHED_GLOB_SETS: usize = 256;
+
+thread_local! {
+    static GLOB_SETS: RefCell<HashMap<Vec<String>, GlobSet>> = RefCell::new(HashMap::new());
+}
+
+/// Whether `path` matches any of `patterns`. Overrides are matched against every
+/// linted file, so each thread compiles a list of patterns only once.
+fn is_match(patterns: &[String], path: &Path) -> Result<bool> {
+    GLOB_SETS.with(|glob_sets| {
+        let mut glob_sets = glob_sets.borrow_mut();
+        if let Some(glob_set) = glob_sets.get(patterns) {
+            return Ok(glob_set.is_match(path));
+        }
+        if glob_sets.len() >= MAX_CACHED_GLOB_SETS {
+            glob_sets.clear();
+        }
+        let glob_set = glob_set(patterns)?;
+        let is_match = glob_set.is_match(path);
+        glob_sets.insert(patterns.to_vec(), glob_set);
+        Ok(is_match)
+    })
+}
+
+impl ConfigOverride {
+    /// Whether the override applies to `file_path`
+    pub fn matches(&self, file_path: &Path) -> Result<bool> {
+        let absolute = std::path::absolute(file_path).unwrap_or_else(|_| file_path.to_path_buf());
+        let absolute = absolute.canonicalize().unwrap_or(absolute);
+        let relative = match &self.base_dir {
+            Some(base_dir) => match absolute.strip_prefix(base_dir) {
+                Ok(relative) => relative,
+                Err(_) => return Ok(false),
+            },
+            None => file_path,
+        };
+        Ok(is_match(&self.files, relative)? && !is_match(&self.exclude, relative)?)
+    }
+
+    /// Apply the override's severities and settings to `linters`, whose normalized
+    /// severities list the rules of the configuration
+    pub fn apply(&self, linters: &mut LintersTable) -> Result<()> {
+        if let Some(default) = self.severity.get("default") {
+            for severity in linters.severity.values_mut() {
+                *severity = default.clone();
+            }
+        }
+        for (rule, severity) in &self.severity {
+            if let Some(current) = linters.severity.get_mut(rule) {
+                *current = severity.clone();
+            }
+        }
+
+        if !self.settings.is_empty() {
+            let mut settings = match Value::try_from(&linters.settings)? {
+                Value::Table(settings) => settings,
+                _ => Table::new(),
+            };
+            merge_tables(&mut settings, self.settings.clone());
+            linters.settings = Value::Table(settings)
+                .try_into::<LintersSettingsTable>()
+                .context("Invalid override settings")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::{Path, PathBuf};
+
+    use crate::config::{parse_toml_config, LintersSettingsTable, RuleSeverity};
+
+    use super::ConfigOverride;
+
+    fn override_for(files: &[&str], exclude: &[&str]) -> ConfigOverride {
+        ConfigOverride {
+            files: files.iter().map(|f| f.to_string()).collect(),
+            exclude: exclude.iter().map(|f| f.to_string()).collect(),
+            base_dir: Some(PathBuf::from("/project")),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_matches() {
+        let changelog = override_for(&["CHANGELOG.md"], &[]);
+        assert!(changelog
+            .matches(Path::new("/project/CHANGELOG.md"))
+            .unwrap());
+        assert!(changelog
+            .matches(Path::new("/project/sub/CHANGELOG.md"))
+            .unwrap());
+        assert!(!changelog.matches(Path::new("/other/CHANGELOG.md")).unwrap());
+
+        let generated = override_for(&["docs/generated/**"], &["docs/generated/keep.md"]);
+        assert!(generated
+            .matches(Path::new("/project/docs/generated/api/a.md"))
+            .unwrap());
+        assert!(!generated
+            .matches(Path::new("/project/docs/generated/keep.md"))
+            .unwrap());
+        assert!(!generated
+            .matches(Path::new("/project/other/docs/generated/a.md"))
+            .unwrap());
+
+        let single_level = override_for(&["docs/*.md"], &[]);
+        assert!(single_level
+            .matches(Path::new("/project/docs/a.md"))
+            .unwrap());
+        assert!(!single_level
+            .matches(Path::new("/project/docs/nested/a.md"))
+            .unwrap());
+    }
+
+    #[test]
+    fn test_invalid_glob() {
+        let invalid = override_for(&["docs/[.md"], &[]);
+        assert!(invalid.matches(Path::new("/project/docs/a.md")).is_err());
+    }
+
+    #[test]
+    fn test_apply_merges_settings() {
+        let mut config = parse_toml_config(
+            r#"
+[linters.settings.line-length]
+line_length = 100
+code_blocks = false
+"#,
+        )
+        .unwrap();
+
+        let mut settings = toml::Table::new();
+        settings.insert(
+            "line-length".to_string(),
+            toml::Value::Table(toml::toml! { line_length = 200 }),
+        );
+        let line_length_off = ConfigOverride {
+            severity: [
+                ("heading-style".to_string(), RuleSeverity::Warning),
+                ("unknown-rule".to_string(), RuleSeverity::Off),
+            ]
+            .into(),
+            settings,
+            ..override_for(&["*.md"], &[])
+        };
+        line_length_off.apply(&mut config.linters).unwrap();
+
+        assert_eq!(200, config.linters.settings.line_length.line_length);
+        assert!(!config.linters.settings.line_length.code_blocks);
+        assert_eq!(
+            Some(&RuleSeverity::Warning),
+            config.linters.severity.get("heading-style")
+        );
+        assert_eq!(None, config.linters.severity.get("unknown-rule"));
+    }
+
+    #[test]
+    fn test_apply_default_severity() {
+        let mut config = parse_toml_config("").unwrap();
+        let all_warnings = ConfigOverride {
+            severity: [
+                ("default".to_string(), RuleSeverity::Warning),
+                ("line-length".to_string(), RuleSeverity::Off),
+            ]
+            .into(),
+            ..Default::default()
+        };
+        all_warnings.apply(&mut config.linters).unwrap();
+
+        assert_eq!(
+            Some(&RuleSeverity::Warning),
+            config.linters.severity.get("heading-style")
+        );
+        assert_eq!(
+            Some(&RuleSeverity::Off),
+            config.linters.severity.get("line-length")
+        );
+        assert_eq!(None, config.linters.severity.get("default"));
+    }
+
+    #[test]
+    fn test_default_settings_round_trip() {
+        let mut linters = crate::config::LintersTable::default();
+        let mut settings = toml::Table::new();
+        settings.insert(
+            "ul-style".to_string(),
+            toml::Value::Table(toml::Table::new()),
+        );
+        ConfigOverride {
+            settings,
+            ..Default::default()
+        }
+        .apply(&mut linters)
+        .unwrap();
+        assert_eq!(LintersSettingsTable::default(), linters.settings);
+    }
+}
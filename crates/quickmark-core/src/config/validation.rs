@@ -0,0 +1,904 @@
+//! Strict validation of `quickmark.toml` files.
+//!
+//! Loading a configuration is lenient: unknown rules and settings are ignored. This pass
+//! reports them, along with values the rules can't use, at their location in the file.
+
+use anyhow::Result;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::ops::Range as Span;
+use std::path::{Path, PathBuf};
+use toml::{Table, Value};
+use toml_edit::{ImDocument, Item, TableLike};
+
+use super::loader::{
+    ConfigLoader, ConfigOrigin, CUSTOM_RULES_KEY, EXTENDS_KEY, MERGE_WITH_PARENT_KEY,
+    OVERRIDES_KEY, PLUGINS_KEY, PRESETS, PRESET_PREFIX,
+};
+use super::markdownlint::{import_markdownlint_config, is_markdownlint_config};
+use super::{CustomRuleTable, LintersSettingsTable};
+use crate::linter::{CharPosition, Range};
+use crate::plugin::{self, PLUGINS_NOT_ALLOWED};
+use crate::rules::{all_rules, custom, qm001};
+
+/// Option names of every rule with settings, keyed by rule alias
+pub(crate) static SETTINGS_OPTIONS: Lazy<HashMap<String, HashSet<String>>> = Lazy::new(|| {
+    let Ok(Value::Table(settings)) = Value::try_from(LintersSettingsTable::default()) else {
+        return HashMap::new();
+    };
+    settings
+        .into_iter()
+        .map(|(alias, options)| {
+            let options = match options {
+                Value::Table(options) => options.into_iter().map(|(name, _)| name).collect(),
+                _ => HashSet::new(),
+            };
+            (alias, options)
+        })
+        .collect()
+});
+
+const SEVERITIES: &[&str] = &["err", "warn", "off"];
+
+/// A problem found in a configuration file
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigProblem {
+    pub message: String,
+    /// Location in the file, in zero-based lines and byte columns
+    pub range: Range,
+}
+
+/// Levenshtein distance, used to suggest the key a typo was meant to be
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+fn suggestion<'a>(key: &str, candidates: impl IntoIterator<Item = &'a str>) -> String {
+    candidates
+        .into_iter()
+        .map(|candidate| (edit_distance(key, candidate), candidate))
+        .filter(|(distance, _)| *distance <= 3)
+        .min()
+        .map(|(_, candidate)| format!(", did you mean `{candidate}`?"))
+        .unwrap_or_default()
+}
+
+/// Constraint on a setting that its type alone doesn't express
+fn check_setting_value(alias: &str, option: &str, value: &Value) -> Option<String> {
+    let at_least = |minimum: i64| match value {
+        Value::Integer(n) if *n < minimum => Some(format!("must be at least {minimum}")),
+        Value::Array(items) => items.iter().find_map(|item| match item {
+            Value::Integer(n) if *n < minimum => {
+                Some(format!("values must be at least {minimum}, found {n}"))
+            }
+            _ => None,
+        }),
+        _ => None,
+    };
+    let problem = match (alias, option) {
+        ("ul-indent", "indent") => at_least(1),
+        ("line-length", "line_length" | "code_block_line_length" | "heading_line_length") => {
+            at_least(1)
+        }
+        ("list-marker-space", _) => at_least(1),
+        ("blanks-around-headings", "lines_above" | "lines_below") => at_least(-1),
+        ("single-h1" | "first-line-heading", "level") => match value {
+            Value::Integer(level) if !(1..=6).contains(level) => {
+                Some("must be a heading level between 1 and 6".to_string())
+            }
+            _ => None,
+        },
+        ("single-h1" | "first-line-heading", "front_matter_title")
+        | ("link-fragments" | "relative-links", "ignored_pattern") => match value {
+            Value::String(pattern) => Regex::new(pattern)
+                .err()
+                .map(|e| format!("is not a valid regular expression: {e}")),
+            _ => None,
+        },
+        ("front-matter-schema", "properties") => match value {
+            Value::Table(properties) => properties.iter().find_map(|(key, property)| {
+                let pattern = property.get("pattern")?.as_str()?;
+                Regex::new(pattern)
+                    .err()
+                    .map(|e| format!("has an invalid pattern for `{key}`: {e}"))
+            }),
+            _ => None,
+        },
+        _ => None,
+    };
+    problem.map(|problem| format!("`{alias}.{option}` {problem}"))
+}
+
+struct Validator {
+    line_starts: Vec<usize>,
+    problems: Vec<ConfigProblem>,
+    /// Directory relative plugin paths are resolved against, the working directory if unset
+    base_dir: Option<PathBuf>,
+}
+
+impl Validator {
+    fn new(content: &str) -> Self {
+        Self {
+            line_starts: std::iter::once(0)
+                .chain(content.match_indices('\n').map(|(i, _)| i + 1))
+                .collect(),
+            problems: Vec::new(),
+            base_dir: None,
+        }
+    }
+
+    fn position(&self, offset: usize) -> CharPosition {
+        let line = self.line_starts.partition_point(|&start| start <= offset) - 1;
+        CharPosition {
+            line,
+            character: offset - self.line_starts[line],
+        }
+    }
+
+    fn report(&mut self, span: Option<Span<usize>>, message: impl Into<String>) {
+        let span = span.unwrap_or(0..0);
+        self.problems.push(ConfigProblem {
+            message: message.into(),
+            range: Range {
+                start: self.position(span.start),
+                end: self.position(span.end),
+            },
+        });
+    }
+
+    fn check_root(&mut self, table: &dyn TableLike, values: &Table) {
+        for (key, item) in table.iter() {
+            let key_span = key_span(table, key);
+            match key {
+                EXTENDS_KEY => self.check_extends(item),
+                MERGE_WITH_PARENT_KEY => {
+                    if !item.is_bool() {
+                        self.report(item.span(), format!("`{key}` must be a boolean"));
+                    }
+                }
+                "linters" => match (item.as_table_like(), table_at(values, key)) {
+                    (Some(linters), Some(values)) => self.check_linters(linters, values, key),
+                    _ => self.report(item.span(), "`linters` must be a table"),
+                },
+                OVERRIDES_KEY => self.check_overrides(item, values.get(key)),
+                PLUGINS_KEY => self.check_plugins(item),
+                CUSTOM_RULES_KEY => self.check_custom_rules(item, values.get(key)),
+                _ => {
+                    let known = [
+                        "linters",
+                        EXTENDS_KEY,
+                        MERGE_WITH_PARENT_KEY,
+                        OVERRIDES_KEY,
+                        PLUGINS_KEY,
+                        CUSTOM_RULES_KEY,
+                    ];
+                    self.report(
+                        key_span,
+                        format!("Unknown key `{key}`{}", suggestion(key, known)),
+                    );
+                }
+            }
+        }
+    }
+
+    fn check_extends(&mut self, item: &Item) {
+        let entries: Vec<(&str, Option<Span<usize>>)> = match item.as_value() {
+            Some(toml_edit::Value::String(entry)) => vec![(entry.value().as_str(), item.span())],
+            Some(toml_edit::Value::Array(entries)) => entries
+                .iter()
+                .filter_map(|entry| match entry.as_str() {
+                    Some(value) => Some((value, entry.span())),
+                    None => {
+                        self.report(entry.span(), "`extends` entries must be strings");
+                        None
+                    }
+                })
+                .collect(),
+            _ => {
+                self.report(
+                    item.span(),
+                    "`extends` must be a string or an array of strings",
+                );
+                return;
+            }
+        };
+        for (entry, span) in entries {
+            if let Some(name) = entry.strip_prefix(PRESET_PREFIX) {
+                if !PRESETS.iter().any(|(preset, _)| *preset == name) {
+                    let available = PRESETS.iter().map(|(preset, _)| *preset);
+                    self.report(
+                        span,
+                        format!("Unknown preset `{entry}`{}", suggestion(name, available)),
+                    );
+                }
+            }
+        }
+    }
+
+    /// Check that the plugins load, which also makes their rules known to the
+    /// `linters` checks. Plugins are only loaded when allowed.
+    fn check_plugins(&mut self, item: &Item) {
+        let Some(entries) = item.as_array() else {
+            self.report(item.span(), "`plugins` must be an array of paths");
+            return;
+        };
+        for entry in entries.iter() {
+            let Some(path) = entry.as_str() else {
+                self.report(entry.span(), "`plugins` entries must be strings");
+                continue;
+            };
+            let path = match &self.base_dir {
+                Some(base_dir) => base_dir.join(path),
+                None => PathBuf::from(path),
+            };
+            if !plugin::plugins_allowed() {
+                self.report(entry.span(), PLUGINS_NOT_ALLOWED);
+            } else if let Err(e) = plugin::load_plugin(&path) {
+                self.report(entry.span(), format!("{e:#}"));
+            }
+        }
+    }
+
+    /// Check that a front matter schema file can be read, relative to the configuration
+    fn check_schema_file(&mut self, span: Option<Span<usize>>, schema: &str) {
+        if schema.is_empty() {
+            return;
+        }
+        let path = match &self.base_dir {
+            Some(base_dir) => base_dir.join(schema),
+            None => PathBuf::from(schema),
+        };
+        if let Err(message) = qm001::read_schema(&path) {
+            self.report(span, message);
+        }
+    }
+
+    fn check_linters(&mut self, table: &dyn TableLike, values: &Table, path: &str) {
+        for (key, item) in table.iter() {
+            match key {
+                "severity" => match (item.as_table_like(), table_at(values, key)) {
+                    (Some(severity), Some(_)) => self.check_severity(severity),
+                    _ => self.report(item.span(), format!("`{path}.{key}` must be a table")),
+                },
+                "settings" => match (item.as_table_like(), table_at(values, key)) {
+                    (Some(settings), Some(values)) => self.check_settings(settings, values),
+                    _ => self.report(item.span(), format!("`{path}.{key}` must be a table")),
+                },
+                _ => self.report(
+                    key_span(table, key),
+                    format!(
+                        "Unknown key `{path}.{key}`{}",
+                        suggestion(key, ["severity", "settings"])
+                    ),
+                ),
+            }
+        }
+    }
+
+    fn check_severity(&mut self, table: &dyn TableLike) {
+        for (rule, item) in table.iter() {
+            if rule != "default" && !all_rules().iter().any(|r| r.alias == rule) {
+                self.report(key_span(table, rule), unknown_rule(rule));
+                continue;
+            }
+            if !item.as_str().is_some_and(|s| SEVERITIES.contains(&s)) {
+                self.report(
+                    item.span(),
+                    format!(
+                        "Invalid severity for `{rule}`, expected one of: {}",
+                        SEVERITIES.join(", ")
+                    ),
+                );
+            }
+        }
+    }
+
+    fn check_settings(&mut self, table: &dyn TableLike, values: &Table) {
+        for (alias, item) in table.iter() {
+            // Settings of plugin rules are only interpreted by the plugin
+            if plugin::rules().iter().any(|r| r.alias == alias) {
+                if !item.is_table_like() {
+                    self.report(
+                        item.span(),
+                        format!("Settings of `{alias}` must be a table"),
+                    );
+                }
+                continue;
+            }
+            let Some(known_options) = SETTINGS_OPTIONS.get(alias) else {
+                let message = if all_rules().iter().any(|r| r.alias == alias) {
+                    format!("Rule `{alias}` has no settings")
+                } else {
+                    unknown_rule(alias)
+                };
+                self.report(key_span(table, alias), message);
+                continue;
+            };
+            let (Some(options), Some(option_values)) =
+                (item.as_table_like(), table_at(values, alias))
+            else {
+                self.report(
+                    item.span(),
+                    format!("Settings of `{alias}` must be a table"),
+                );
+                continue;
+            };
+
+            for (option, option_item) in options.iter() {
+                if !known_options.contains(option) {
+                    self.report(
+                        key_span(options, option),
+                        format!(
+                            "Unknown setting `{option}` for `{alias}`{}",
+                            suggestion(option, known_options.iter().map(String::as_str))
+                        ),
+                    );
+                    continue;
+                }
+                let Some(value) = option_values.get(option) else {
+                    continue;
+                };
+                let mut single = Table::new();
+                single.insert(
+                    alias.to_string(),
+                    Value::Table(Table::from_iter([(option.to_string(), value.clone())])),
+                );
+                // Tables declared with a header have no span of their own
+                let span = option_item.span().or_else(|| key_span(options, option));
+                if let Err(e) = Value::Table(single).try_into::<LintersSettingsTable>() {
+                    let message = e.message().trim().to_string();
+                    self.report(
+                        span,
+                        format!("Invalid value for `{alias}.{option}`: {message}"),
+                    );
+                } else if let Some(problem) = check_setting_value(alias, option, value) {
+                    self.report(span, problem);
+                } else if let ("front-matter-schema", "schema", Value::String(schema)) =
+                    (alias, option, value)
+                {
+                    self.check_schema_file(span, schema);
+                }
+            }
+        }
+    }
+
+    fn check_overrides(&mut self, item: &Item, values: Option<&Value>) {
+        let Some(entries) = tables_of(item) else {
+            self.report(item.span(), "`overrides` must be an array of tables");
+            return;
+        };
+        let empty = Vec::new();
+        let values = values.and_then(Value::as_array).unwrap_or(&empty);
+
+        for (entry, values) in entries.into_iter().zip(values) {
+            let Some(values) = values.as_table() else {
+                continue;
+            };
+            if !entry.contains_key("files") {
+                let span = entry
+                    .iter()
+                    .next()
+                    .and_then(|(key, _)| key_span(entry, key));
+                self.report(span, "Overrides must list the `files` they apply to");
+            }
+            for (key, item) in entry.iter() {
+                match key {
+                    "files" | "exclude" => self.check_globs(key, item),
+                    "severity" => match item.as_table_like() {
+                        Some(severity) => self.check_severity(severity),
+                        None => self.report(item.span(), "`overrides.severity` must be a table"),
+                    },
+                    "settings" => match (item.as_table_like(), table_at(values, key)) {
+                        (Some(settings), Some(values)) => self.check_settings(settings, values),
+                        _ => self.report(item.span(), "`overrides.settings` must be a table"),
+                    },
+                    _ => {
+                        let known = ["files", "exclude", "severity", "settings"];
+                        self.report(
+                            key_span(entry, key),
+                            format!("Unknown key `overrides.{key}`{}", suggestion(key, known)),
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    fn check_custom_rules(&mut self, item: &Item, values: Option<&Value>) {
+        let Some(entries) = tables_of(item) else {
+            self.report(item.span(), "`custom_rules` must be an array of tables");
+            return;
+        };
+        let empty = Vec::new();
+        let values = values.and_then(Value::as_array).unwrap_or(&empty);
+        let known = [
+            "id", "alias", "message", "severity", "nodes", "query", "pattern", "negate",
+        ];
+        let mut names = HashSet::new();
+
+        for (entry, value) in entries.into_iter().zip(values) {
+            let first_key = entry
+                .iter()
+                .next()
+                .and_then(|(key, _)| key_span(entry, key));
+            for (key, _) in entry.iter() {
+                if !known.contains(&key) {
+                    self.report(
+                        key_span(entry, key),
+                        format!("Unknown key `custom_rules.{key}`{}", suggestion(key, known)),
+                    );
+                }
+            }
+            let table: CustomRuleTable = match value.clone().try_into() {
+                Ok(table) => table,
+                Err(e) => {
+                    let message = e.message().trim().to_string();
+                    self.report(first_key, format!("Invalid custom rule: {message}"));
+                    continue;
+                }
+            };
+            let span = key_span(entry, "alias").or(first_key);
+            for name in [&table.id, &table.alias] {
+                if !name.is_empty() && !names.insert(name.to_lowercase()) {
+                    self.report(
+                        span.clone(),
+                        format!("Custom rule `{name}` is defined more than once"),
+                    );
+                }
+            }
+            if let Err(e) = custom::compile(&table) {
+                self.report(span, format!("Invalid custom rule `{}`: {e}", table.alias));
+            }
+        }
+    }
+
+    fn check_globs(&mut self, key: &str, item: &Item) {
+        let Some(patterns) = item.as_array() else {
+            self.report(
+                item.span(),
+                format!("`overrides.{key}` must be an array of glob patterns"),
+            );
+            return;
+        };
+        for pattern in patterns.iter() {
+            match pattern.as_str() {
+                Some(glob) => {
+                    if let Err(e) = globset::Glob::new(glob) {
+                        self.report(
+                            pattern.span(),
+                            format!("Invalid glob `{glob}`: {}", e.kind()),
+                        );
+                    }
+                }
+                None => self.report(
+                    pattern.span(),
+                    format!("`overrides.{key}` entries must be strings"),
+                ),
+            }
+        }
+    }
+}
+
+/// Entries of an array of tables, declared with `[[...]]` headers or inline
+fn tables_of(item: &Item) -> Option<Vec<&dyn TableLike>> {
+    match item {
+        Item::ArrayOfTables(tables) => Some(tables.iter().map(|t| t as &dyn TableLike).collect()),
+        Item::Value(toml_edit::Value::Array(array))
+            if array.iter().all(|value| value.is_inline_table()) =>
+        {
+            Some(
+                array
+                    .iter()
+                    .filter_map(|value| value.as_inline_table())
+                    .map(|t| t as &dyn TableLike)
+                    .collect(),
+            )
+        }
+        _ => None,
+    }
+}
+
+fn key_span(table: &dyn TableLike, key: &str) -> Option<Span<usize>> {
+    table.get_key_value(key).and_then(|(key, _)| key.span())
+}
+
+fn table_at<'a>(values: &'a Table, key: &str) -> Option<&'a Table> {
+    values.get(key).and_then(Value::as_table)
+}
+
+fn unknown_rule(key: &str) -> String {
+    if let Some(rule) = all_rules()
+        .into_iter()
+        .find(|r| r.id.eq_ignore_ascii_case(key))
+    {
+        return format!(
+            "Rules are configured by alias, use `{}` instead of `{key}`",
+            rule.alias
+        );
+    }
+    format!(
+        "Unknown rule `{key}`{}",
+        suggestion(key, all_rules().into_iter().map(|r| r.alias))
+    )
+}
+
+/// Validate the content of a `quickmark.toml` file
+pub fn validate_config(content: &str) -> Vec<ConfigProblem> {
+    validate_toml(content, None)
+}
+
+fn validate_toml(content: &str, base_dir: Option<&Path>) -> Vec<ConfigProblem> {
+    let mut validator = Validator::new(content);
+    validator.base_dir = base_dir.map(Path::to_path_buf);
+    let document = match ImDocument::parse(content) {
+        Ok(document) => document,
+        Err(e) => {
+            validator.report(e.span(), e.message().trim());
+            return validator.problems;
+        }
+    };
+    let values: Table = toml::from_str(content).unwrap_or_default();
+    // Custom rules may be configured before they are declared, so they are compiled
+    // first. Their problems are reported by `check_custom_rules`.
+    let custom_rules = values.get(CUSTOM_RULES_KEY).and_then(Value::as_array);
+    for value in custom_rules.into_iter().flatten() {
+        if let Ok(table) = value.clone().try_into::<CustomRuleTable>() {
+            let _ = custom::compile(&table);
+        }
+    }
+    validator.check_root(document.as_table(), &values);
+    validator.problems
+}
+
+/// Validate a configuration file, including the files and presets it extends.
+///
+/// markdownlint files are checked for entries that can't be imported; those problems
+/// aren't located more precisely than the start of the file.
+pub fn validate_config_file(path: &Path) -> Result<Vec<ConfigProblem>> {
+    let content = fs::read_to_string(path)?;
+    Ok(validate_config_content(&content, path))
+}
+
+/// Validate `content` as the content of the configuration file at `path`, which is used to
+/// pick the file format and to resolve `extends`. The file itself doesn't need to exist.
+pub fn validate_config_content(content: &str, path: &Path) -> Vec<ConfigProblem> {
+    let mut validator = Validator::new(content);
+    let base_dir = path.parent();
+    let origin = ConfigOrigin::File(path.to_path_buf());
+
+    let markdownlint = is_markdownlint_config(path);
+    let table = if markdownlint {
+        match import_markdownlint_config(content, path) {
+            Ok(import) => {
+                for warning in import.warnings {
+                    validator.report(None, warning);
+                }
+                Some(import.table)
+            }
+            Err(e) => {
+                validator.report(None, format!("{e:#}"));
+                None
+            }
+        }
+    } else {
+        toml::from_str(content).ok()
+    };
+
+    // Loading the whole chain before validating the file makes the rules of plugins
+    // listed by extended files known
+    let loaded = table.map(|table| {
+        ConfigLoader::default()
+            .load_table(table, base_dir, origin)
+            .and_then(|layer| layer.into_config())
+    });
+
+    let extends_span = if markdownlint {
+        None
+    } else {
+        validator.problems.extend(validate_toml(content, base_dir));
+        ImDocument::parse(content)
+            .ok()
+            .and_then(|document| document.get(EXTENDS_KEY).and_then(Item::span))
+    };
+
+    // Problems in extended files only show up once the whole chain is loaded
+    if let (true, Some(Err(e))) = (validator.problems.is_empty(), loaded) {
+        validator.report(extends_span, format!("{e:#}"));
+    }
+    validator.problems
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+    use tempfile::TempDir;
+
+    use super::{validate_config, validate_config_file, ConfigProblem};
+
+    fn problems(content: &str) -> Vec<(usize, usize, String)> {
+        validate_config(content)
+            .into_iter()
+            .map(|ConfigProblem { message, range }| {
+                (range.start.line + 1, range.start.character + 1, message)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_valid_config() {
+        let content = r#"
+extends = ["preset:strict"]
+
+[linters.severity]
+default = 'warn'
+line-length = 'off'
+
+[linters.settings.line-length]
+line_length = 120
+
+[linters.settings.blanks-around-headings]
+lines_above = [-1, 1]
+
+[[overrides]]
+files = ["CHANGELOG.md"]
+
+[overrides.severity]
+heading-style = 'err'
+"#;
+        assert_eq!(Vec::<(usize, usize, String)>::new(), problems(content));
+    }
+
+    #[test]
+    fn test_unknown_rules_and_keys() {
+        let content = r#"[linters.severity]
+line-lenght = 'off'
+MD013 = 'warn'
+heading-style = 'error'
+
+[linters.settings.line-length]
+line_lenght = 100
+
+[linters.settings.heading-increment]
+style = 'atx'
+
+[linter]
+"#;
+        assert_eq!(
+            vec![
+                (
+                    2,
+                    1,
+                    "Unknown rule `line-lenght`, did you mean `line-length`?".to_string()
+                ),
+                (
+                    3,
+                    1,
+                    "Rules are configured by alias, use `line-length` instead of `MD013`"
+                        .to_string()
+                ),
+                (
+                    4,
+                    17,
+                    "Invalid severity for `heading-style`, expected one of: err, warn, off"
+                        .to_string()
+                ),
+                (
+                    7,
+                    1,
+                    "Unknown setting `line_lenght` for `line-length`, did you mean `line_length`?"
+                        .to_string()
+                ),
+                (
+                    9,
+                    19,
+                    "Rule `heading-increment` has no settings".to_string()
+                ),
+                (
+                    12,
+                    2,
+                    "Unknown key `linter`, did you mean `linters`?".to_string()
+                ),
+            ],
+            problems(content)
+        );
+    }
+
+    #[test]
+    fn test_invalid_values() {
+        let content = r#"[linters.settings.blanks-around-headings]
+lines_above = [1, -2]
+
+[linters.settings.first-line-heading]
+level = 7
+front_matter_title = '^(title'
+
+[linters.settings.line-length]
+line_length = 'long'
+
+[linters.settings.heading-style]
+style = 'fancy'
+"#;
+        let problems = problems(content);
+        assert_eq!(5, problems.len(), "{problems:?}");
+        assert_eq!(
+            (
+                2,
+                15,
+                "`blanks-around-headings.lines_above` values must be at least -1, found -2"
+                    .to_string()
+            ),
+            problems[0]
+        );
+        assert_eq!(
+            (
+                5,
+                9,
+                "`first-line-heading.level` must be a heading level between 1 and 6".to_string()
+            ),
+            problems[1]
+        );
+        assert_eq!((6, 22), (problems[2].0, problems[2].1));
+        assert!(problems[2].2.starts_with(
+            "`first-line-heading.front_matter_title` is not a valid regular expression"
+        ));
+        assert_eq!((9, 15), (problems[3].0, problems[3].1));
+        assert!(problems[3]
+            .2
+            .starts_with("Invalid value for `line-length.line_length`: invalid type"));
+        assert_eq!((12, 9), (problems[4].0, problems[4].1));
+        assert!(problems[4].2.contains("unknown variant `fancy`"));
+    }
+
+    #[test]
+    fn test_front_matter_schema_settings() {
+        let content = r#"[linters.settings.front-matter-schema]
+required = ["title"]
+
+[linters.settings.front-matter-schema.properties.owner]
+enum = ["docs"]
+pattern = '^(docs'
+"#;
+        let problems = problems(content);
+        assert_eq!(1, problems.len(), "{problems:?}");
+        assert_eq!((4, 39), (problems[0].0, problems[0].1));
+        assert!(problems[0]
+            .2
+            .starts_with("`front-matter-schema.properties` has an invalid pattern for `owner`"));
+
+        let problems = self::problems(
+            "[linters.settings.front-matter-schema.properties.tags]\ntype = \"list\"\n",
+        );
+        assert_eq!(1, problems.len(), "{problems:?}");
+        assert!(problems[0].2.contains("unknown variant `list`"));
+    }
+
+    #[test]
+    fn test_overrides_and_extends() {
+        let content = r#"extends = "preset:strikt"
+
+[[overrides]]
+exclude = ["docs/[.md"]
+severity = { line-lenght = 'off' }
+"#;
+        assert_eq!(
+            vec![
+                (
+                    1,
+                    11,
+                    "Unknown preset `preset:strikt`, did you mean `strict`?".to_string()
+                ),
+                (
+                    4,
+                    1,
+                    "Overrides must list the `files` they apply to".to_string()
+                ),
+                (
+                    4,
+                    12,
+                    "Invalid glob `docs/[.md`: unclosed character class; missing ']'".to_string()
+                ),
+                (
+                    5,
+                    14,
+                    "Unknown rule `line-lenght`, did you mean `line-length`?".to_string()
+                ),
+            ],
+            problems(content)
+        );
+    }
+
+    #[test]
+    fn test_custom_rules() {
+        let content = r#"[linters.severity]
+no-simply = 'warn'
+
+[[custom_rules]]
+id = "CUSTOM001"
+alias = "no-simply"
+message = "Avoid 'simply'"
+nodes = ["inline"]
+pattern = "\\bsimply\\b"
+
+[[custom_rules]]
+id = "CUSTOM002"
+alias = "bad-pattern"
+message = "Message"
+nodes = ["inline"]
+pattern = "("
+patern = "x"
+"#;
+        let problems = problems(content);
+        assert_eq!(2, problems.len(), "{problems:?}");
+        assert_eq!(
+            (
+                17,
+                1,
+                "Unknown key `custom_rules.patern`, did you mean `pattern`?".to_string()
+            ),
+            problems[0]
+        );
+        assert_eq!((13, 1), (problems[1].0, problems[1].1));
+        assert!(problems[1]
+            .2
+            .starts_with("Invalid custom rule `bad-pattern`: Invalid `pattern`"));
+    }
+
+    #[test]
+    fn test_syntax_error() {
+        let problems = problems("[linters.severity\nline-length = 'off'\n");
+        assert_eq!(1, problems.len());
+        assert_eq!(1, problems[0].0);
+    }
+
+    #[test]
+    fn test_validate_config_file() {
+        let dir = TempDir::new().unwrap();
+        let config_path = dir.path().join("quickmark.toml");
+        fs::write(&config_path, "extends = \"missing.toml\"\n").unwrap();
+        let problems = validate_config_file(&config_path).unwrap();
+        assert_eq!(1, problems.len());
+        assert_eq!(10, problems[0].range.start.character);
+        assert!(problems[0].message.contains("Can't extend missing.toml"));
+
+        let markdownlint_path = dir.path().join(".markdownlint.json");
+        fs::write(&markdownlint_path, r#"{ "MD013": { "line_lenght": 100 } }"#).unwrap();
+        let problems = validate_config_file(&markdownlint_path).unwrap();
+        assert_eq!(
+            vec!["MD013/line-length: option `line_lenght` is not supported".to_string()],
+            problems.into_iter().map(|p| p.message).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_front_matter_schema_file() {
+        let dir = TempDir::new().unwrap();
+        let config_path = dir.path().join("quickmark.toml");
+        fs::write(dir.path().join("page.json"), "{ \"type\": ").unwrap();
+        fs::write(
+            &config_path,
+            "[linters.settings.front-matter-schema]\nschema = \"page.json\"\n",
+        )
+        .unwrap();
+        let problems = validate_config_file(&config_path).unwrap();
+        assert_eq!(1, problems.len(), "{problems:?}");
+        assert_eq!(1, problems[0].range.start.line);
+        assert!(problems[0]
+            .message
+            .starts_with("Invalid front matter schema"));
+
+        fs::write(dir.path().join("page.json"), "{ \"type\": \"object\" }").unwrap();
+        assert!(validate_config_file(&config_path).unwrap().is_empty());
+    }
+}
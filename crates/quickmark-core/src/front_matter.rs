@@ -0,0 +1,594 @@
+//! Front matter at the start of a document.
+//!
+//! Three formats are recognized, matching what static site generators accept:
+//!
+//! ```markdown
+//! ---
+//! title: YAML front matter
+//! ---
+//!
+//! +++
+//! title = "TOML front matter"
+//! +++
+//!
+//! {
+//!   "title": "JSON front matter"
+//! }
+//! ```
+//!
+//! The front matter is parsed into a [`serde_json::Value`] regardless of its format, and
+//! every key is mapped back to the document line it is declared on so that rules can
+//! point at the offending line. Front matter that is not closed is treated as Markdown.
+
+use std::collections::HashMap;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde_json::{Map, Value};
+use toml_edit::{ImDocument, Item, TableLike};
+
+/// Default `front_matter_title` pattern of the title rules
+pub const DEFAULT_FRONT_MATTER_TITLE: &str = r"^\s*title\s*[:=]";
+
+static YAML_KEY_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"^("[^"]*"|'[^']*'|[^\s#'"\-:][^:#]*?|-[^\s:#][^:#]*?)\s*:(?:\s+(.*))?$"#).unwrap()
+});
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrontMatterFormat {
+    Yaml,
+    Toml,
+    Json,
+}
+
+impl FrontMatterFormat {
+    fn from_delimiter(line: &str) -> Option<Self> {
+        match line.trim_end() {
+            "{" => Some(Self::Json),
+            line => match line.trim_start() {
+                "---" => Some(Self::Yaml),
+                "+++" => Some(Self::Toml),
+                _ => None,
+            },
+        }
+    }
+
+    fn is_closing_delimiter(self, line: &str) -> bool {
+        match self {
+            Self::Yaml => line.trim() == "---",
+            Self::Toml => line.trim() == "+++",
+            Self::Json => line.trim_end() == "}",
+        }
+    }
+}
+
+/// Front matter that could not be parsed
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrontMatterError {
+    pub message: String,
+    /// Document line (0-based) the error was reported on
+    pub line: usize,
+}
+
+/// Front matter of a document together with its parsed value
+#[derive(Debug, Clone)]
+pub struct FrontMatter {
+    pub format: FrontMatterFormat,
+    /// Document line (0-based) of the closing delimiter
+    pub end_line: usize,
+    /// Byte offset right after the closing delimiter, including its line break
+    pub end_byte: usize,
+    /// Text between the delimiters. JSON front matter includes its braces.
+    pub content: String,
+    /// Parsed front matter, `None` when it is not valid in its format
+    pub value: Option<Value>,
+    pub error: Option<FrontMatterError>,
+    /// Document line of the first line in `content`
+    content_line: usize,
+    key_lines: HashMap<String, usize>,
+}
+
+impl FrontMatter {
+    /// Detect and parse front matter at the very start of `source`
+    pub fn parse(source: &str) -> Option<Self> {
+        let mut lines = source.split_inclusive('\n');
+        let first = lines.next()?;
+        let format = FrontMatterFormat::from_delimiter(first)?;
+
+        let mut offset = first.len();
+        for (index, line) in lines.enumerate() {
+            let end_line = index + 1;
+            if format.is_closing_delimiter(line) {
+                let (content_start, content_end, content_line) = match format {
+                    FrontMatterFormat::Json => (0, offset + line.trim_end().len(), 0),
+                    _ => (first.len(), offset, 1),
+                };
+                let mut front_matter = Self {
+                    format,
+                    end_line,
+                    end_byte: offset + line.len(),
+                    content: source[content_start..content_end].to_string(),
+                    value: None,
+                    error: None,
+                    content_line,
+                    key_lines: HashMap::new(),
+                };
+                front_matter.parse_content();
+                return Some(front_matter);
+            }
+            offset += line.len();
+        }
+
+        None
+    }
+
+    /// Whether the document line (0-based) belongs to the front matter, delimiters included
+    pub fn contains_line(&self, line: usize) -> bool {
+        line <= self.end_line
+    }
+
+    /// Lines between the delimiters
+    pub fn lines(&self) -> impl Iterator<Item = &str> {
+        self.content.lines()
+    }
+
+    /// Whether any front matter line matches the `front_matter_title` pattern of a rule
+    pub fn has_title(&self, title_pattern: &Regex) -> bool {
+        self.lines().any(|line| title_pattern.is_match(line))
+    }
+
+    /// Value at a dotted path such as `author.name` or `tags.0`
+    pub fn get(&self, path: &str) -> Option<&Value> {
+        path.split('.')
+            .try_fold(self.value.as_ref()?, |value, segment| match value {
+                Value::Object(map) => map.get(segment),
+                Value::Array(items) => segment.parse::<usize>().ok().and_then(|i| items.get(i)),
+                _ => None,
+            })
+    }
+
+    /// Document line (0-based) a dotted path is declared on.
+    ///
+    /// Paths that can't be located exactly fall back to their closest located parent and
+    /// finally to the first line of the document.
+    pub fn line_of(&self, path: &str) -> usize {
+        let mut path = path;
+        loop {
+            if let Some(line) = self.key_lines.get(path) {
+                return *line;
+            }
+            match path.rfind('.') {
+                Some(index) => path = &path[..index],
+                None => return 0,
+            }
+        }
+    }
+
+    fn parse_content(&mut self) {
+        let result = match self.format {
+            FrontMatterFormat::Yaml => self.parse_yaml(),
+            FrontMatterFormat::Toml => self.parse_toml(),
+            FrontMatterFormat::Json => self.parse_json(),
+        };
+        match result {
+            Ok(value) => self.value = Some(value),
+            Err(error) => self.error = Some(error),
+        }
+    }
+
+    fn error(&self, message: impl ToString, content_line: Option<usize>) -> FrontMatterError {
+        FrontMatterError {
+            message: message.to_string(),
+            line: self.content_line + content_line.unwrap_or(0),
+        }
+    }
+
+    fn parse_yaml(&mut self) -> Result<Value, FrontMatterError> {
+        if self.content.trim().is_empty() {
+            return Ok(Value::Object(Map::new()));
+        }
+        let value = serde_yaml_ng::from_str::<Value>(&self.content).map_err(|e| {
+            let line = e
+                .location()
+                .map(|location| location.line().saturating_sub(1));
+            self.error(e, line)
+        })?;
+        self.key_lines = yaml_key_lines(&self.content, self.content_line);
+        Ok(value)
+    }
+
+    fn parse_toml(&mut self) -> Result<Value, FrontMatterError> {
+        let document = ImDocument::parse(self.content.as_str()).map_err(|e| {
+            let line = e.span().map(|span| line_at(&self.content, span.start));
+            self.error(e.message(), line)
+        })?;
+        let mut key_lines = HashMap::new();
+        collect_toml_lines(
+            document.as_table(),
+            "",
+            &self.content,
+            self.content_line,
+            &mut key_lines,
+        );
+        self.key_lines = key_lines;
+
+        let table: toml::Table = toml::from_str(&self.content).map_err(|e| self.error(e, None))?;
+        serde_json::to_value(table).map_err(|e| self.error(e, None))
+    }
+
+    fn parse_json(&mut self) -> Result<Value, FrontMatterError> {
+        let value = serde_json::from_str::<Value>(&self.content)
+            .map_err(|e| self.error(&e, Some(e.line().saturating_sub(1))))?;
+        self.key_lines = json_key_lines(&self.content, self.content_line);
+        Ok(value)
+    }
+}
+
+fn line_at(content: &str, byte: usize) -> usize {
+    content[..byte.min(content.len())].matches('\n').count()
+}
+
+fn join_path(parent: &str, segment: &str) -> String {
+    if parent.is_empty() {
+        segment.to_string()
+    } else {
+        format!("{parent}.{segment}")
+    }
+}
+
+struct YamlEntry {
+    indent: usize,
+    path: String,
+    is_item: bool,
+    items: usize,
+}
+
+/// Map YAML keys and block sequence items to lines by following indentation.
+///
+/// Flow collections and multi-line keys are not followed, values inside them resolve to
+/// the key that holds them.
+fn yaml_key_lines(content: &str, first_line: usize) -> HashMap<String, usize> {
+    let mut key_lines = HashMap::new();
+    let mut stack: Vec<YamlEntry> = Vec::new();
+    let mut block_scalar_indent = None;
+
+    for (index, line) in content.lines().enumerate() {
+        let trimmed = line.trim_start();
+        let mut indent = line.len() - trimmed.len();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        if let Some(scalar_indent) = block_scalar_indent {
+            if indent > scalar_indent {
+                continue;
+            }
+            block_scalar_indent = None;
+        }
+
+        let mut rest = trimmed;
+        while rest == "-" || rest.starts_with("- ") {
+            while stack
+                .last()
+                .is_some_and(|top| top.indent > indent || (top.indent == indent && top.is_item))
+            {
+                stack.pop();
+            }
+            let (parent, index_in_parent) = match stack.last_mut() {
+                Some(top) => {
+                    top.items += 1;
+                    (top.path.clone(), top.items - 1)
+                }
+                None => (String::new(), 0),
+            };
+            let path = join_path(&parent, &index_in_parent.to_string());
+            key_lines.entry(path.clone()).or_insert(first_line + index);
+            stack.push(YamlEntry {
+                indent,
+                path,
+                is_item: true,
+                items: 0,
+            });
+
+            let item = rest[1..].trim_start();
+            indent += rest.len() - item.len();
+            rest = item;
+        }
+
+        let Some(captures) = YAML_KEY_RE.captures(rest) else {
+            continue;
+        };
+        let key = captures[1].trim_matches(|c| c == '"' || c == '\'');
+        while stack.last().is_some_and(|top| top.indent >= indent) {
+            stack.pop();
+        }
+        let path = join_path(stack.last().map_or("", |top| top.path.as_str()), key);
+        key_lines.insert(path.clone(), first_line + index);
+        if captures
+            .get(2)
+            .is_some_and(|value| value.as_str().starts_with(['|', '>']))
+        {
+            block_scalar_indent = Some(indent);
+        }
+        stack.push(YamlEntry {
+            indent,
+            path,
+            is_item: false,
+            items: 0,
+        });
+    }
+
+    key_lines
+}
+
+fn collect_toml_lines(
+    table: &dyn TableLike,
+    parent: &str,
+    content: &str,
+    first_line: usize,
+    key_lines: &mut HashMap<String, usize>,
+) {
+    for (key, item) in table.iter() {
+        let path = join_path(parent, key);
+        let span = table
+            .get_key_value(key)
+            .and_then(|(key, _)| key.span())
+            .or_else(|| item.span());
+        if let Some(span) = span {
+            key_lines.insert(path.clone(), first_line + line_at(content, span.start));
+        }
+        collect_toml_item(item, &path, content, first_line, key_lines);
+    }
+}
+
+fn collect_toml_item(
+    item: &Item,
+    path: &str,
+    content: &str,
+    first_line: usize,
+    key_lines: &mut HashMap<String, usize>,
+) {
+    match item {
+        Item::Table(table) => collect_toml_lines(table, path, content, first_line, key_lines),
+        Item::ArrayOfTables(tables) => {
+            for (index, table) in tables.iter().enumerate() {
+                let path = join_path(path, &index.to_string());
+                if let Some(span) = table.span() {
+                    key_lines.insert(path.clone(), first_line + line_at(content, span.start));
+                }
+                collect_toml_lines(table, &path, content, first_line, key_lines);
+            }
+        }
+        Item::Value(toml_edit::Value::InlineTable(table)) => {
+            collect_toml_lines(table, path, content, first_line, key_lines)
+        }
+        Item::Value(toml_edit::Value::Array(array)) => {
+            for (index, value) in array.iter().enumerate() {
+                let path = join_path(path, &index.to_string());
+                if let Some(span) = value.span() {
+                    key_lines.insert(path.clone(), first_line + line_at(content, span.start));
+                }
+                if let toml_edit::Value::InlineTable(table) = value {
+                    collect_toml_lines(table, &path, content, first_line, key_lines);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+enum JsonContainer {
+    Object { path: String, key: Option<String> },
+    Array { path: String, index: usize },
+}
+
+/// Map JSON object keys and array elements to lines with a small scanner over the
+/// already validated JSON text.
+fn json_key_lines(content: &str, first_line: usize) -> HashMap<String, usize> {
+    let mut key_lines = HashMap::new();
+    let mut stack: Vec<JsonContainer> = Vec::new();
+    let mut line = first_line;
+    let mut expect_element = false;
+    let mut chars = content.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c == '\n' {
+            line += 1;
+            continue;
+        }
+        if c.is_whitespace() {
+            continue;
+        }
+        if expect_element && c != ']' {
+            if let Some(JsonContainer::Array { path, index }) = stack.last() {
+                key_lines.insert(join_path(path, &index.to_string()), line);
+            }
+        }
+        expect_element = false;
+
+        match c {
+            '"' => {
+                let mut string = String::new();
+                while let Some(c) = chars.next() {
+                    match c {
+                        '\\' => {
+                            if let Some(escaped) = chars.next() {
+                                string.push(escaped);
+                            }
+                        }
+                        '"' => break,
+                        _ => string.push(c),
+                    }
+                }
+                if let Some(JsonContainer::Object { path, key }) = stack.last_mut() {
+                    if key.is_none() {
+                        key_lines.insert(join_path(path, &string), line);
+                        *key = Some(string);
+                    }
+                }
+            }
+            '{' | '[' => {
+                let path = match stack.last() {
+                    Some(JsonContainer::Object { path, key }) => {
+                        join_path(path, key.as_deref().unwrap_or_default())
+                    }
+                    Some(JsonContainer::Array { path, index }) => {
+                        join_path(path, &index.to_string())
+                    }
+                    None => String::new(),
+                };
+                if c == '{' {
+                    stack.push(JsonContainer::Object { path, key: None });
+                } else {
+                    stack.push(JsonContainer::Array { path, index: 0 });
+                    expect_element = true;
+                }
+            }
+            '}' | ']' => {
+                stack.pop();
+            }
+            ',' => match stack.last_mut() {
+                Some(JsonContainer::Object { key, .. }) => *key = None,
+                Some(JsonContainer::Array { index, .. }) => {
+                    *index += 1;
+                    expect_element = true;
+                }
+                None => {}
+            },
+            _ => {}
+        }
+    }
+
+    key_lines
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_no_front_matter() {
+        assert!(FrontMatter::parse("# Title\n").is_none());
+        assert!(FrontMatter::parse("").is_none());
+        assert!(FrontMatter::parse("text\n---\ntitle: x\n---\n").is_none());
+    }
+
+    #[test]
+    fn test_unclosed_front_matter() {
+        assert!(FrontMatter::parse("---\ntitle: x\n# Title\n").is_none());
+        assert!(FrontMatter::parse("+++\ntitle = 'x'\n---\n").is_none());
+    }
+
+    #[test]
+    fn test_yaml_front_matter() {
+        let source = "---\ntitle: Hello\ntags:\n  - a\n  - b\n---\n# Heading\n";
+        let front_matter = FrontMatter::parse(source).unwrap();
+        assert_eq!(FrontMatterFormat::Yaml, front_matter.format);
+        assert_eq!(5, front_matter.end_line);
+        assert_eq!(source.find("# Heading").unwrap(), front_matter.end_byte);
+        assert_eq!("title: Hello\ntags:\n  - a\n  - b\n", front_matter.content);
+        assert_eq!(
+            Some(json!({"title": "Hello", "tags": ["a", "b"]})),
+            front_matter.value
+        );
+        assert_eq!(Some(&json!("b")), front_matter.get("tags.1"));
+        assert_eq!(1, front_matter.line_of("title"));
+        assert_eq!(2, front_matter.line_of("tags"));
+        assert_eq!(4, front_matter.line_of("tags.1"));
+        assert!(front_matter.contains_line(5));
+        assert!(!front_matter.contains_line(6));
+    }
+
+    #[test]
+    fn test_yaml_nested_line_mapping() {
+        let source = "---\nauthor:\n  name: Jane\n  links:\n  - url: a\n    label: b\ndescription: |\n  owner: not a key\nowner: team\n---\n";
+        let front_matter = FrontMatter::parse(source).unwrap();
+        assert!(front_matter.error.is_none());
+        assert_eq!(2, front_matter.line_of("author.name"));
+        assert_eq!(4, front_matter.line_of("author.links.0"));
+        assert_eq!(5, front_matter.line_of("author.links.0.label"));
+        assert_eq!(6, front_matter.line_of("description"));
+        assert_eq!(8, front_matter.line_of("owner"));
+        assert_eq!(1, front_matter.line_of("author.missing"));
+        assert_eq!(0, front_matter.line_of("missing"));
+    }
+
+    #[test]
+    fn test_empty_front_matter() {
+        let front_matter = FrontMatter::parse("---\n---\n# Title\n").unwrap();
+        assert_eq!(Some(json!({})), front_matter.value);
+        assert_eq!(1, front_matter.end_line);
+    }
+
+    #[test]
+    fn test_invalid_yaml_front_matter() {
+        let front_matter = FrontMatter::parse("---\ntitle: x\ntags: [a\n---\n").unwrap();
+        assert!(front_matter.value.is_none());
+        let error = front_matter.error.unwrap();
+        assert!(error.line >= 2, "{error:?}");
+    }
+
+    #[test]
+    fn test_toml_front_matter() {
+        let source = "+++\ntitle = \"Hello\"\n\n[author]\nname = \"Jane\"\n+++\n";
+        let front_matter = FrontMatter::parse(source).unwrap();
+        assert_eq!(FrontMatterFormat::Toml, front_matter.format);
+        assert_eq!(
+            Some(json!({"title": "Hello", "author": {"name": "Jane"}})),
+            front_matter.value
+        );
+        assert_eq!(1, front_matter.line_of("title"));
+        assert_eq!(3, front_matter.line_of("author"));
+        assert_eq!(4, front_matter.line_of("author.name"));
+        assert_eq!(source.len(), front_matter.end_byte);
+    }
+
+    #[test]
+    fn test_invalid_toml_front_matter() {
+        let front_matter = FrontMatter::parse("+++\ntitle = \"Hello\"\ntags = \n+++\n").unwrap();
+        assert!(front_matter.value.is_none());
+        assert_eq!(2, front_matter.error.unwrap().line);
+    }
+
+    #[test]
+    fn test_json_front_matter() {
+        let source = "{\n  \"title\": \"Hello\",\n  \"tags\": [\n    \"a\",\n    {\"name\": \"b\"}\n  ]\n}\n# Heading\n";
+        let front_matter = FrontMatter::parse(source).unwrap();
+        assert_eq!(FrontMatterFormat::Json, front_matter.format);
+        assert_eq!(6, front_matter.end_line);
+        assert!(front_matter.content.starts_with('{'));
+        assert!(front_matter.content.ends_with('}'));
+        assert_eq!(Some(&json!("Hello")), front_matter.get("title"));
+        assert_eq!(1, front_matter.line_of("title"));
+        assert_eq!(2, front_matter.line_of("tags"));
+        assert_eq!(3, front_matter.line_of("tags.0"));
+        assert_eq!(4, front_matter.line_of("tags.1.name"));
+    }
+
+    #[test]
+    fn test_invalid_json_front_matter() {
+        let front_matter =
+            FrontMatter::parse("{\n  \"title\": \"Hello\"\n  \"x\": 1\n}\n").unwrap();
+        assert!(front_matter.value.is_none());
+        assert_eq!(2, front_matter.error.unwrap().line);
+    }
+
+    #[test]
+    fn test_has_title() {
+        let title = Regex::new(r"^\s*title\s*[:=]").unwrap();
+        let front_matter = FrontMatter::parse("---\nauthor: x\ntitle: y\n---\n").unwrap();
+        assert!(front_matter.has_title(&title));
+        let front_matter = FrontMatter::parse("+++\nauthor = 'x'\n+++\n").unwrap();
+        assert!(!front_matter.has_title(&title));
+    }
+
+    #[test]
+    fn test_crlf_front_matter() {
+        let source = "---\r\ntitle: x\r\n---\r\n# Heading\r\n";
+        let front_matter = FrontMatter::parse(source).unwrap();
+        assert_eq!(2, front_matter.end_line);
+        assert_eq!(source.find('#').unwrap(), front_matter.end_byte);
+        assert_eq!(Some(&json!("x")), front_matter.get("title"));
+    }
+}
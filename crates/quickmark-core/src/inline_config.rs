@@ -0,0 +1,553 @@
+//! Inline configuration through HTML comments.
+//!
+//! The directive syntax is compatible with markdownlint. Both the `quickmark-` and the
+//! `markdownlint-` prefixes are recognized, so existing documents keep working:
+//!
+//! ```markdown
+//! <!-- quickmark-disable line-length MD033 -->
+//! <!-- quickmark-enable line-length -->
+//! <!-- quickmark-disable-line -->
+//! <!-- quickmark-disable-next-line no-bare-urls -->
+//! <!-- quickmark-disable-file -->
+//! <!-- quickmark-enable-file -->
+//! <!-- quickmark-capture -->
+//! <!-- quickmark-restore -->
+//! <!-- quickmark-configure-file { "line-length": { "line_length": 120 } } -->
+//! ```
+//!
+//! Rules are referenced by ID or alias (case-insensitive). A directive without rules
+//! applies to all rules. Directives inside code blocks are ignored.
+
+use std::collections::HashSet;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use tree_sitter::Tree;
+
+use crate::{
+    config::{QuickmarkConfig, RuleSeverity},
+    front_matter::FrontMatter,
+    linter::{CharPosition, Edit},
+    markdown_tree::parse_block_tree,
+    rules::{all_rules, Rule, OPT_IN_RULES},
+    tree_sitter_walker::TreeSitterWalker,
+};
+
+static DIRECTIVE_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"<!--\s*(?:quickmark|markdownlint)-([a-z-]+)((?:[^-]|-[^-])*?)-->").unwrap()
+});
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Action {
+    Disable,
+    Enable,
+    DisableLine,
+    DisableNextLine,
+    DisableFile,
+    EnableFile,
+    Capture,
+    Restore,
+    ConfigureFile,
+}
+
+impl Action {
+    fn parse(name: &str) -> Option<Self> {
+        match name {
+            "disable" => Some(Self::Disable),
+            "enable" => Some(Self::Enable),
+            "disable-line" => Some(Self::DisableLine),
+            "disable-next-line" => Some(Self::DisableNextLine),
+            "disable-file" => Some(Self::DisableFile),
+            "enable-file" => Some(Self::EnableFile),
+            "capture" => Some(Self::Capture),
+            "restore" => Some(Self::Restore),
+            "configure-file" => Some(Self::ConfigureFile),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Directive {
+    action: Action,
+    parameters: String,
+    start_line: usize,
+    end_line: usize,
+}
+
+/// Set of rules that are disabled at some point of the document, keyed by rule alias.
+type DisabledRules = HashSet<&'static str>;
+
+/// Inline configuration collected from the comments of a single document.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(into = "InlineConfigRecord", from = "InlineConfigRecord")]
+pub struct InlineConfig {
+    /// Rules disabled on each line of the document.
+    disabled_per_line: Vec<DisabledRules>,
+    /// Objects of all `configure-file` directives, in document order.
+    configure_file: Vec<Map<String, Value>>,
+}
+
+/// Serialized form of [`InlineConfig`], with rule aliases as owned strings
+#[derive(Serialize, Deserialize)]
+struct InlineConfigRecord {
+    disabled_per_line: Vec<Vec<String>>,
+    configure_file: Vec<Map<String, Value>>,
+}
+
+impl From<InlineConfig> for InlineConfigRecord {
+    fn from(inline_config: InlineConfig) -> Self {
+        let disabled_per_line = inline_config
+            .disabled_per_line
+            .iter()
+            .map(|disabled| {
+                let mut aliases: Vec<String> = disabled.iter().map(|a| a.to_string()).collect();
+                aliases.sort_unstable();
+                aliases
+            })
+            .collect();
+        Self {
+            disabled_per_line,
+            configure_file: inline_config.configure_file,
+        }
+    }
+}
+
+impl From<InlineConfigRecord> for InlineConfig {
+    fn from(record: InlineConfigRecord) -> Self {
+        // Aliases of rules that are no longer known are dropped
+        let disabled_per_line = record
+            .disabled_per_line
+            .iter()
+            .map(|aliases| {
+                aliases
+                    .iter()
+                    .filter_map(|alias| find_rule(alias))
+                    .map(|rule| rule.alias)
+                    .collect()
+            })
+            .collect();
+        Self {
+            disabled_per_line,
+            configure_file: record.configure_file,
+        }
+    }
+}
+
+/// Find the rule referenced by an inline directive parameter.
+fn find_rule(name: &str) -> Option<&'static Rule> {
+    all_rules()
+        .into_iter()
+        .find(|r| r.id.eq_ignore_ascii_case(name) || r.alias.eq_ignore_ascii_case(name))
+}
+
+/// Resolve the rules a directive applies to. No parameters means all rules.
+fn directive_rules(parameters: &str) -> Vec<&'static str> {
+    let names: Vec<&str> = parameters
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|name| !name.is_empty())
+        .collect();
+
+    if names.is_empty() {
+        return all_rules().into_iter().map(|r| r.alias).collect();
+    }
+    names
+        .into_iter()
+        .filter_map(find_rule)
+        .map(|r| r.alias)
+        .collect()
+}
+
+fn apply_enable_disable(action: Action, parameters: &str, disabled: &mut DisabledRules) {
+    for alias in directive_rules(parameters) {
+        match action {
+            Action::Enable | Action::EnableFile => {
+                disabled.remove(alias);
+            }
+            _ => {
+                disabled.insert(alias);
+            }
+        }
+    }
+}
+
+/// Lines covered by fenced or indented code blocks, where directives are not honoured.
+fn code_lines(tree: &Tree, line_count: usize) -> Vec<bool> {
+    let mut is_code = vec![false; line_count];
+    TreeSitterWalker::new(tree).walk(|node| {
+        if matches!(node.kind(), "fenced_code_block" | "indented_code_block") {
+            let start = node.start_position().row;
+            let end = node.end_position().row.min(line_count.saturating_sub(1));
+            for line in is_code.iter_mut().take(end + 1).skip(start) {
+                *line = true;
+            }
+        }
+    });
+    is_code
+}
+
+/// Edit appending a `disable-line` directive for `alias` to `line` (zero-based), the way a
+/// user would suppress a single violation.
+///
+/// Returns `None` where a comment can't be placed without changing the document: in code
+/// blocks, where it would also be ignored, in front matter and on table delimiter rows.
+/// In table rows with a trailing pipe the comment goes into the last cell.
+pub fn disable_line_edit(document: &str, line: usize, alias: &str) -> Option<Edit> {
+    if FrontMatter::parse(document).is_some_and(|front_matter| front_matter.contains_line(line)) {
+        return None;
+    }
+    let text = document.lines().nth(line)?;
+    let tree = parse_block_tree(document);
+    let line_count = document.lines().count().max(line + 1);
+    if code_lines(&tree, line_count)[line] {
+        return None;
+    }
+
+    let mut table_row = None;
+    TreeSitterWalker::new(&tree).walk(|node| {
+        if node.start_position().row == line
+            && matches!(
+                node.kind(),
+                "pipe_table_header" | "pipe_table_row" | "pipe_table_delimiter_row"
+            )
+        {
+            table_row = Some(node.kind());
+        }
+    });
+
+    let content = text.trim_end();
+    let (character, directive) = match table_row {
+        Some("pipe_table_delimiter_row") => return None,
+        Some(_) if content.ends_with('|') => (
+            content.len() - 1,
+            format!("<!-- quickmark-disable-line {alias} --> "),
+        ),
+        _ if content.is_empty() => (0, format!("<!-- quickmark-disable-line {alias} -->")),
+        _ => (
+            text.len(),
+            format!(" <!-- quickmark-disable-line {alias} -->"),
+        ),
+    };
+    Some(Edit::insert(CharPosition { line, character }, directive))
+}
+
+impl InlineConfig {
+    /// Quick check that lets callers skip parsing when a document has no directives.
+    pub fn has_directives(document: &str) -> bool {
+        DIRECTIVE_RE.is_match(document)
+    }
+
+    /// Collect the inline configuration of `document`, whose syntax tree is `tree`.
+    pub fn new(document: &str, tree: &Tree) -> Self {
+        let line_starts: Vec<usize> = std::iter::once(0)
+            .chain(document.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        let line_count = line_starts.len();
+        let line_of = |offset: usize| line_starts.partition_point(|&start| start <= offset) - 1;
+        let is_code = code_lines(tree, line_count);
+
+        let directives: Vec<Directive> = DIRECTIVE_RE
+            .captures_iter(document)
+            .filter_map(|caps| {
+                let whole = caps.get(0)?;
+                let start_line = line_of(whole.start());
+                if is_code[start_line] {
+                    return None;
+                }
+                Some(Directive {
+                    action: Action::parse(&caps[1])?,
+                    parameters: caps[2].trim().to_string(),
+                    start_line,
+                    end_line: line_of(whole.end().saturating_sub(1)),
+                })
+            })
+            .collect();
+
+        let configure_file = directives
+            .iter()
+            .filter(|d| d.action == Action::ConfigureFile)
+            .filter_map(|d| match serde_json::from_str(&d.parameters) {
+                // Like markdownlint, malformed inline configuration is ignored
+                Ok(Value::Object(map)) => Some(map),
+                _ => None,
+            })
+            .collect();
+
+        // File-wide directives establish the initial state regardless of their position
+        let mut file_state = DisabledRules::new();
+        for directive in &directives {
+            if matches!(directive.action, Action::DisableFile | Action::EnableFile) {
+                apply_enable_disable(directive.action, &directive.parameters, &mut file_state);
+            }
+        }
+
+        let mut disabled_per_line = Vec::with_capacity(line_count);
+        let mut state = file_state.clone();
+        let mut captured = file_state;
+        let mut pending = directives.iter().peekable();
+        for line in 0..line_count {
+            while let Some(directive) = pending.next_if(|d| d.start_line == line) {
+                match directive.action {
+                    Action::Disable | Action::Enable => {
+                        apply_enable_disable(directive.action, &directive.parameters, &mut state)
+                    }
+                    Action::Capture => captured = state.clone(),
+                    Action::Restore => state = captured.clone(),
+                    _ => {}
+                }
+            }
+            disabled_per_line.push(state.clone());
+        }
+
+        for directive in &directives {
+            let line = match directive.action {
+                Action::DisableLine => directive.start_line,
+                Action::DisableNextLine => directive.end_line + 1,
+                _ => continue,
+            };
+            if let Some(disabled) = disabled_per_line.get_mut(line) {
+                apply_enable_disable(directive.action, &directive.parameters, disabled);
+            }
+        }
+
+        Self {
+            disabled_per_line,
+            configure_file,
+        }
+    }
+
+    /// Whether violations of `rule` reported on `line` (zero-based) are suppressed.
+    pub fn is_disabled(&self, rule: &Rule, line: usize) -> bool {
+        self.disabled_per_line
+            .get(line)
+            .is_some_and(|disabled| disabled.contains(rule.alias))
+    }
+
+    /// Apply `configure-file` directives on top of `config`.
+    ///
+    /// Keys are rule IDs or aliases, or `default`. A value of `false` turns the rule off,
+    /// `true` turns it on, a severity string (`"err"`, `"warn"`, `"off"`) sets the severity
+    /// and an object turns the rule on and overrides the given settings of the rule.
+    pub fn configure(&self, config: &mut QuickmarkConfig) {
+        for map in &self.configure_file {
+            if let Some(severity) = map.get("default").and_then(Self::severity_for) {
+                for (alias, value) in config.linters.severity.iter_mut() {
+                    if !OPT_IN_RULES.contains(&alias.as_str()) {
+                        *value = severity.clone();
+                    }
+                }
+            }
+
+            for (key, value) in map {
+                let Some(rule) = find_rule(key) else {
+                    continue;
+                };
+                let current = config.linters.severity.get(rule.alias);
+                let severity = match value {
+                    Value::Object(_) | Value::Bool(true)
+                        if matches!(current, None | Some(RuleSeverity::Off)) =>
+                    {
+                        Some(RuleSeverity::Error)
+                    }
+                    Value::Object(_) | Value::Bool(true) => None,
+                    other => Self::severity_for(other),
+                };
+                if let Some(severity) = severity {
+                    config
+                        .linters
+                        .severity
+                        .insert(rule.alias.to_string(), severity);
+                }
+
+                if let Value::Object(settings) = value {
+                    Self::merge_settings(config, rule.alias, settings);
+                }
+            }
+        }
+    }
+
+    fn severity_for(value: &Value) -> Option<RuleSeverity> {
+        match value {
+            Value::Bool(true) => Some(RuleSeverity::Error),
+            Value::Bool(false) => Some(RuleSeverity::Off),
+            Value::String(_) => serde_json::from_value(value.clone()).ok(),
+            _ => None,
+        }
+    }
+
+    fn merge_settings(config: &mut QuickmarkConfig, alias: &str, settings: &Map<String, Value>) {
+        let Ok(Value::Object(mut table)) = serde_json::to_value(&config.linters.settings) else {
+            return;
+        };
+        // Rules without settings have no entry in the table
+        let Some(Value::Object(rule_settings)) = table.get_mut(alias) else {
+            return;
+        };
+        for (key, value) in settings {
+            rule_settings.insert(key.clone(), value.clone());
+        }
+        if let Ok(merged) = serde_json::from_value(Value::Object(table)) {
+            config.linters.settings = merged;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use tree_sitter::Parser;
+    use tree_sitter_md::LANGUAGE;
+
+    use crate::{
+        config::RuleSeverity,
+        rules::{md013::MD013, md033::MD033},
+        test_utils::test_helpers::test_config_with_rules,
+    };
+
+    use super::{disable_line_edit, InlineConfig};
+
+    fn inline_config(document: &str) -> InlineConfig {
+        let mut parser = Parser::new();
+        parser.set_language(&LANGUAGE.into()).unwrap();
+        let tree = parser.parse(document, None).unwrap();
+        InlineConfig::new(document, &tree)
+    }
+
+    #[test]
+    fn test_disable_and_enable() {
+        let config = inline_config(
+            "line 0\n<!-- quickmark-disable line-length -->\nline 2\n<!-- quickmark-enable MD013 -->\nline 4\n",
+        );
+        assert!(!config.is_disabled(&MD013, 0));
+        assert!(config.is_disabled(&MD013, 1));
+        assert!(config.is_disabled(&MD013, 2));
+        assert!(!config.is_disabled(&MD013, 3));
+        assert!(!config.is_disabled(&MD013, 4));
+        assert!(!config.is_disabled(&MD033, 2));
+    }
+
+    #[test]
+    fn test_disable_without_rules_disables_all() {
+        let config = inline_config("<!-- markdownlint-disable -->\ntext\n");
+        assert!(config.is_disabled(&MD013, 1));
+        assert!(config.is_disabled(&MD033, 1));
+    }
+
+    #[test]
+    fn test_disable_line_and_next_line() {
+        let config = inline_config(
+            "text <!-- quickmark-disable-line md013 -->\n<!-- quickmark-disable-next-line no-inline-html line-length -->\ntext\ntext\n",
+        );
+        assert!(config.is_disabled(&MD013, 0));
+        assert!(!config.is_disabled(&MD013, 1));
+        assert!(config.is_disabled(&MD013, 2));
+        assert!(config.is_disabled(&MD033, 2));
+        assert!(!config.is_disabled(&MD013, 3));
+    }
+
+    #[test]
+    fn test_disable_file_applies_to_whole_document() {
+        let config = inline_config("text\ntext\n<!-- quickmark-disable-file MD013 -->\n");
+        assert!(config.is_disabled(&MD013, 0));
+        assert!(config.is_disabled(&MD013, 3));
+        assert!(!config.is_disabled(&MD033, 0));
+    }
+
+    #[test]
+    fn test_capture_and_restore() {
+        let config = inline_config(
+            "<!-- quickmark-disable MD013 -->\n<!-- quickmark-capture -->\n<!-- quickmark-disable MD033 -->\n<!-- quickmark-enable -->\n<!-- quickmark-restore -->\ntext\n",
+        );
+        assert!(config.is_disabled(&MD033, 2));
+        assert!(!config.is_disabled(&MD013, 3));
+        assert!(config.is_disabled(&MD013, 5));
+        assert!(!config.is_disabled(&MD033, 5));
+    }
+
+    #[test]
+    fn test_directives_in_code_blocks_are_ignored() {
+        let config = inline_config("```markdown\n<!-- quickmark-disable -->\n```\n\ntext\n");
+        assert!(!config.is_disabled(&MD013, 1));
+        assert!(!config.is_disabled(&MD013, 4));
+    }
+
+    #[test]
+    fn test_unknown_directives_and_rules_are_ignored() {
+        let config = inline_config(
+            "<!-- quickmark-frobnicate MD013 -->\n<!-- quickmark-disable not-a-rule -->\ntext\n",
+        );
+        assert!(!config.is_disabled(&MD013, 2));
+        assert!(!config.is_disabled(&MD033, 2));
+    }
+
+    #[test]
+    fn test_configure_file() {
+        let mut config = test_config_with_rules(vec![
+            ("line-length", RuleSeverity::Error),
+            ("no-inline-html", RuleSeverity::Error),
+            ("heading-increment", RuleSeverity::Off),
+        ]);
+        inline_config(
+            r#"<!-- quickmark-configure-file {
+  "MD013": { "line_length": 120 },
+  "no-inline-html": false,
+  "heading-increment": "warn"
+} -->
+"#,
+        )
+        .configure(&mut config);
+
+        let severity = &config.linters.severity;
+        assert_eq!(Some(&RuleSeverity::Error), severity.get("line-length"));
+        assert_eq!(Some(&RuleSeverity::Off), severity.get("no-inline-html"));
+        assert_eq!(
+            Some(&RuleSeverity::Warning),
+            severity.get("heading-increment")
+        );
+        assert_eq!(120, config.linters.settings.line_length.line_length);
+        // Settings not mentioned keep their configured values
+        assert_eq!(80, config.linters.settings.line_length.heading_line_length);
+    }
+
+    #[test]
+    fn test_configure_file_ignores_malformed_json() {
+        let mut config = test_config_with_rules(vec![("line-length", RuleSeverity::Error)]);
+        let expected = config.clone();
+        inline_config("<!-- quickmark-configure-file { MD013: nope } -->\n").configure(&mut config);
+        assert_eq!(expected, config);
+    }
+
+    fn with_disable_line(document: &str, line: usize) -> Option<String> {
+        let edit = disable_line_edit(document, line, "line-length")?;
+        let mut lines: Vec<String> = document.lines().map(str::to_string).collect();
+        lines[line].insert_str(edit.range.start.character, &edit.replacement);
+        Some(lines.join("\n"))
+    }
+
+    #[test]
+    fn test_disable_line_edit() {
+        assert_eq!(
+            Some("# Title\ntext <!-- quickmark-disable-line line-length -->".to_string()),
+            with_disable_line("# Title\ntext", 1)
+        );
+
+        // The directive takes effect where it is placed
+        let document = with_disable_line("# Title\n\nsome text\nmore text\n", 2).unwrap();
+        let config = inline_config(&document);
+        assert!(config.is_disabled(&MD013, 2));
+        assert!(!config.is_disabled(&MD013, 3));
+
+        // Table rows keep their column count
+        let table = "| a | b |\n| - | - |\n| c | d |\n";
+        assert_eq!(
+            "| c | d <!-- quickmark-disable-line line-length --> |",
+            with_disable_line(table, 2).unwrap().lines().nth(2).unwrap()
+        );
+        assert_eq!(None, with_disable_line(table, 1));
+
+        // Code and front matter would change meaning
+        assert_eq!(None, with_disable_line("```\ncode\n```\n", 1));
+        assert_eq!(None, with_disable_line("+++\ntitle = 'x'\n+++\ntext\n", 1));
+    }
+}
@@ -10,6 +10,13 @@
 //!
 //! This design eliminates state management complexity.
 //!
+//! Contexts are immutable once built and rule linters are `Send`, so documents can be
+//! linted on any thread, e.g. with rayon. Each thread keeps its own tree-sitter parsers
+//! and the regexes compiled from the configuration, and reuses them for every document.
+//!
+//! Rules that compare documents with each other run after this per-document pass,
+//! over summaries of all documents. See the [`workspace`] module.
+//!
 //! ### Usage Pattern
 //! ```rust,no_run
 //! use quickmark_core::linter::MultiRuleLinter;
@@ -27,10 +34,19 @@
 //! // linter is now invalid - create new one for next document
 //! ```
 
+pub mod baseline;
 pub mod config;
+pub mod front_matter;
+pub mod inline_config;
 pub mod linter;
+pub mod markdown_tree;
+pub mod node_cache;
+pub mod plugin;
+pub mod project_index;
+pub(crate) mod regex_cache;
 pub mod rules;
 pub mod tree_sitter_walker;
+pub mod workspace;
 
 #[cfg(any(test, feature = "testing"))]
 pub mod test_utils;
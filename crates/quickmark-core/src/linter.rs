@@ -1,20 +1,36 @@
-use std::{cell::RefCell, collections::HashMap, fmt::Display, path::PathBuf, rc::Rc};
-use tree_sitter::{Node, Parser};
-use tree_sitter_md::LANGUAGE;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashSet},
+    fmt::Display,
+    path::PathBuf,
+    sync::Arc,
+};
+use tree_sitter::Node;
+use tree_sitter_md::INLINE_LANGUAGE;
 
 use crate::{
     config::{QuickmarkConfig, RuleSeverity},
-    rules::{Rule, ALL_RULES},
+    front_matter::{FrontMatter, DEFAULT_FRONT_MATTER_TITLE},
+    inline_config::InlineConfig,
+    markdown_tree::MarkdownTree,
+    node_cache::{LineIndex, NodeCache},
+    plugin,
+    project_index::ProjectIndex,
+    regex_cache::cached_regex,
+    rules::{Rule, RuleType, ALL_RULES},
     tree_sitter_walker::TreeSitterWalker,
+    workspace::DocumentSummary,
 };
 
-#[derive(Debug, Clone)]
+pub use crate::node_cache::NodeInfo;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct CharPosition {
     pub line: usize,
     pub character: usize,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Range {
     pub start: CharPosition,
     pub end: CharPosition,
@@ -25,12 +41,46 @@ pub struct Location {
     pub range: Range,
 }
 
+/// A single text replacement proposed by a rule's automatic fix.
+///
+/// Positions follow the tree-sitter convention used by [`Range`]: zero-based
+/// line numbers and byte columns within the line.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Edit {
+    pub range: Range,
+    pub replacement: String,
+}
+
+impl Edit {
+    pub fn replace(range: Range, replacement: impl Into<String>) -> Self {
+        Self {
+            range,
+            replacement: replacement.into(),
+        }
+    }
+
+    pub fn insert(position: CharPosition, text: impl Into<String>) -> Self {
+        Self::replace(
+            Range {
+                start: position.clone(),
+                end: position,
+            },
+            text,
+        )
+    }
+
+    pub fn delete(range: Range) -> Self {
+        Self::replace(range, String::new())
+    }
+}
+
 #[derive(Debug)]
 pub struct RuleViolation {
     location: Location,
     message: String,
     rule: &'static Rule,
     pub(crate) severity: RuleSeverity,
+    fix: Vec<Edit>,
 }
 
 impl RuleViolation {
@@ -40,9 +90,18 @@ impl RuleViolation {
             message,
             location: Location { file_path, range },
             severity: RuleSeverity::Error, // Default, will be overridden by MultiRuleLinter
+            fix: Vec::new(),
         }
     }
 
+    /// Attach an automatic fix to the violation.
+    ///
+    /// All edits of a fix are applied together or not at all.
+    pub fn with_fix(mut self, edits: Vec<Edit>) -> Self {
+        self.fix = edits;
+        self
+    }
+
     pub fn location(&self) -> &Location {
         &self.location
     }
@@ -58,6 +117,104 @@ impl RuleViolation {
     pub fn severity(&self) -> &RuleSeverity {
         &self.severity
     }
+
+    /// Set the severity of a violation that did not come from a [`MultiRuleLinter`],
+    /// such as one read back from a cache
+    pub fn with_severity(mut self, severity: RuleSeverity) -> Self {
+        self.severity = severity;
+        self
+    }
+
+    pub fn fix(&self) -> &[Edit] {
+        &self.fix
+    }
+
+    pub fn is_fixable(&self) -> bool {
+        !self.fix.is_empty()
+    }
+}
+
+/// Convert a line/column position into a byte offset within `source`.
+///
+/// Columns past the end of a line are clamped to the line terminator and lines
+/// past the end of the document resolve to the end of the document.
+fn position_to_offset(source: &str, line_starts: &[usize], position: &CharPosition) -> usize {
+    match line_starts.get(position.line) {
+        Some(&line_start) => {
+            let line_end = line_starts
+                .get(position.line + 1)
+                .map(|next| next - 1)
+                .unwrap_or(source.len());
+            (line_start + position.character).min(line_end)
+        }
+        None => source.len(),
+    }
+}
+
+/// Apply the fixes carried by `violations` to `source`.
+///
+/// Fixes are applied in document order. A fix whose edits overlap an edit that
+/// was already accepted is skipped; it will be picked up again when the fixed
+/// document is re-linted. Returns the new content and the number of applied fixes.
+pub fn apply_fixes(source: &str, violations: &[RuleViolation]) -> (String, usize) {
+    let line_starts: Vec<usize> = std::iter::once(0)
+        .chain(source.match_indices('\n').map(|(i, _)| i + 1))
+        .collect();
+
+    let mut fixes: Vec<Vec<(usize, usize, &str)>> = violations
+        .iter()
+        .filter(|v| v.is_fixable())
+        .map(|v| {
+            let mut edits: Vec<_> = v
+                .fix()
+                .iter()
+                .map(|edit| {
+                    let start = position_to_offset(source, &line_starts, &edit.range.start);
+                    let end = position_to_offset(source, &line_starts, &edit.range.end);
+                    (start, end.max(start), edit.replacement.as_str())
+                })
+                .collect();
+            edits.sort_by_key(|(start, end, _)| (*start, *end));
+            edits
+        })
+        .collect();
+    fixes.sort_by_key(|edits| edits.first().map(|(start, end, _)| (*start, *end)));
+
+    // Accepted edits keyed by start offset, used to detect overlaps
+    let mut accepted: BTreeMap<usize, (usize, &str)> = BTreeMap::new();
+    let mut applied = 0;
+    for edits in fixes {
+        let overlaps = edits.iter().enumerate().any(|(i, &(start, end, _))| {
+            let clashes_with_own = edits[..i].iter().any(|&(s, e, _)| start < e || s == start);
+            let clashes_with_previous = accepted
+                .range(..=start)
+                .next_back()
+                .is_some_and(|(&s, &(e, _))| start < e || s == start);
+            let clashes_with_next = accepted
+                .range(start..)
+                .next()
+                .is_some_and(|(&s, _)| s < end || s == start);
+            clashes_with_own || clashes_with_previous || clashes_with_next
+        });
+        if overlaps {
+            continue;
+        }
+        for (start, end, replacement) in edits {
+            accepted.insert(start, (end, replacement));
+        }
+        applied += 1;
+    }
+
+    let mut result = String::with_capacity(source.len());
+    let mut cursor = 0;
+    for (start, (end, replacement)) in accepted {
+        result.push_str(&source[cursor..start]);
+        result.push_str(replacement);
+        cursor = end;
+    }
+    result.push_str(&source[cursor..]);
+
+    (result, applied)
 }
 
 /// Convert from tree-sitter range to library range
@@ -92,35 +249,40 @@ impl Display for RuleViolation {
 /// **SINGLE-USE CONTRACT**: Context instances are designed for one-time use only.
 ///
 /// Each Context instance should be used to analyze exactly one source document.
-/// The lazy initialization of caches (lines, node_cache) happens once and the
-/// context becomes immutable after that point.
+/// All caches (lines, node_cache) are built by [`Context::new`] and the context is
+/// immutable afterwards, so it can be shared between threads in an `Arc`.
 ///
 #[derive(Debug)]
 pub struct Context {
     pub file_path: PathBuf,
     pub config: QuickmarkConfig,
     /// Raw text lines for line-based rules (MD013, MD010, etc.) - initialized once per document
-    pub lines: RefCell<Vec<String>>,
-    /// Cached AST nodes filtered by type for efficient access - initialized once per document
-    pub node_cache: RefCell<HashMap<String, Vec<NodeInfo>>>,
+    pub lines: Vec<String>,
+    /// Cached block nodes of the kinds required by the active rules - initialized once per document
+    pub node_cache: NodeCache,
+    /// Cached nodes of the inline trees (links, images, code spans, emphasis, ...)
+    /// of the kinds required by the active rules - initialized once per document
+    pub inline_node_cache: NodeCache,
+    /// Innermost block node of each line - initialized once per document
+    pub line_index: LineIndex,
     /// Original document content for byte-based access - initialized once per document
-    pub document_content: RefCell<String>,
-}
-
-/// Lightweight node information for caching
-#[derive(Debug, Clone)]
-pub struct NodeInfo {
-    pub line_start: usize,
-    pub line_end: usize,
-    pub kind: String,
+    pub document_content: String,
+    /// YAML, TOML or JSON front matter at the start of the document, if any
+    pub front_matter: Option<FrontMatter>,
+    /// Other documents of the project, shared by all documents of a lint run
+    pub project_index: Arc<ProjectIndex>,
 }
 
 impl Context {
+    /// Build the context of a document. Only nodes of the `required_nodes` kinds,
+    /// the union of [`Rule::required_nodes`] of the active rules, are cached.
     pub fn new(
         file_path: PathBuf,
         config: QuickmarkConfig,
         source: &str,
-        root_node: &Node,
+        markdown_tree: &MarkdownTree,
+        required_nodes: &[&str],
+        project_index: Arc<ProjectIndex>,
     ) -> Self {
         // Parse lines in a way that's compatible with markdownlint's line counting
         // markdownlint counts a trailing newline as creating an additional empty line
@@ -130,98 +292,110 @@ impl Context {
         if source.ends_with('\n') {
             lines.push(String::new());
         }
-        let node_cache = Self::build_node_cache(root_node);
+        let block_tree = markdown_tree.block_tree();
+        let node_cache = NodeCache::for_block_tree(block_tree, required_nodes);
+        let inline_node_cache = NodeCache::for_inline_trees(
+            INLINE_LANGUAGE.into(),
+            markdown_tree.inline_trees(),
+            required_nodes,
+        );
 
         Self {
             file_path,
             config,
-            lines: RefCell::new(lines),
-            node_cache: RefCell::new(node_cache),
-            document_content: RefCell::new(source.to_string()),
+            lines,
+            node_cache,
+            inline_node_cache,
+            line_index: LineIndex::new(block_tree),
+            document_content: source.to_string(),
+            front_matter: FrontMatter::parse(source),
+            project_index,
         }
     }
 
-    /// Get the full document content as a string reference
-    /// Returns a reference to the original document content stored during initialization
-    pub fn get_document_content(&self) -> std::cell::Ref<'_, String> {
-        self.document_content.borrow()
+    /// Whether a line (0-based) is part of the front matter, delimiters included.
+    /// Line-based rules skip these lines since they are not Markdown.
+    pub fn is_front_matter_line(&self, line_index: usize) -> bool {
+        self.front_matter
+            .as_ref()
+            .is_some_and(|front_matter| front_matter.contains_line(line_index))
     }
 
-    /// Build cache of nodes filtered by type for efficient rule access
-    fn build_node_cache(root_node: &Node) -> HashMap<String, Vec<NodeInfo>> {
-        let mut cache = HashMap::new();
-        Self::collect_nodes_recursive(root_node, &mut cache);
-        cache
-    }
-
-    fn collect_nodes_recursive(node: &Node, cache: &mut HashMap<String, Vec<NodeInfo>>) {
-        let kind = node.kind();
-        let kind_string = kind.to_string();
-        let node_info = NodeInfo {
-            line_start: node.start_position().row,
-            line_end: node.end_position().row,
-            kind: kind_string.clone(),
-        };
-
-        // Add to cache for this node type
-        cache
-            .entry(kind_string)
-            .or_default()
-            .push(node_info.clone());
-
-        // Add to cache for pattern-based lookups (e.g., all heading types)
-        if kind.contains("heading") {
-            cache
-                .entry("*heading*".to_string())
-                .or_default()
-                .push(node_info);
+    /// Whether the front matter matches a rule's `front_matter_title` pattern.
+    /// An empty pattern disables the check.
+    pub fn front_matter_has_title(&self, title_pattern: &str) -> bool {
+        if title_pattern.is_empty() {
+            return false;
         }
+        let Some(front_matter) = &self.front_matter else {
+            return false;
+        };
+        // Patterns are validated with the configuration, fall back to the default otherwise
+        let title_regex = cached_regex(title_pattern)
+            .unwrap_or_else(|_| cached_regex(DEFAULT_FRONT_MATTER_TITLE).unwrap());
+        front_matter.has_title(&title_regex)
+    }
 
-        // Recursively process children
-        for i in 0..node.child_count() {
-            if let Some(child) = node.child(i) {
-                Self::collect_nodes_recursive(&child, cache);
-            }
-        }
+    /// Get the full document content as a string reference
+    /// Returns a reference to the original document content stored during initialization
+    pub fn get_document_content(&self) -> &str {
+        &self.document_content
     }
 
     /// Get cached nodes of specific types - optimized equivalent of filterByTypesCached
     pub fn get_nodes(&self, node_types: &[&str]) -> Vec<NodeInfo> {
-        let cache = self.node_cache.borrow();
-        let mut result = Vec::new();
-        for node_type in node_types {
-            if let Some(nodes) = cache.get(*node_type) {
-                result.extend(nodes.iter().cloned());
-            }
-        }
+        node_types
+            .iter()
+            .filter_map(|node_type| self.node_cache.get(node_type))
+            .flatten()
+            .copied()
+            .collect()
+    }
+
+    /// Get cached nodes of the inline trees of specific types, such as
+    /// `inline_link`, `image` or `code_span`, in document order
+    pub fn get_inline_nodes(&self, node_types: &[&str]) -> Vec<NodeInfo> {
+        let mut result: Vec<NodeInfo> = node_types
+            .iter()
+            .filter_map(|node_type| self.inline_node_cache.get(node_type))
+            .flatten()
+            .copied()
+            .collect();
+        result.sort_by_key(|node| (node.start_byte, node.end_byte));
         result
     }
 
-    /// Get the most specific node type that contains a given line number
-    pub fn get_node_type_for_line(&self, line_number: usize) -> String {
-        let cache = self.node_cache.borrow();
-        // Find the most specific (smallest range) node that contains this line
-        let mut best_match: Option<&NodeInfo> = None;
-        let mut smallest_range = usize::MAX;
-
-        for nodes in cache.values() {
-            for node in nodes {
-                if line_number >= node.line_start && line_number <= node.line_end {
-                    let range_size = node.line_end - node.line_start;
-                    if range_size < smallest_range {
-                        smallest_range = range_size;
-                        best_match = Some(node);
-                    }
-                }
-            }
-        }
+    /// Normalized labels of the link reference definitions in the document.
+    /// Bracketed text only forms a shortcut link when its label is defined.
+    /// Rules calling this must list `link_label` in their required nodes.
+    pub fn link_reference_labels(&self) -> HashSet<String> {
+        let document_content = &self.document_content;
+        self.get_nodes(&["link_label"])
+            .iter()
+            .map(|label| normalize_link_label(&document_content[label.start_byte..label.end_byte]))
+            .collect()
+    }
 
-        best_match
-            .map(|n| n.kind.clone())
-            .unwrap_or_else(|| "text".to_string())
+    /// Get the most specific node type that contains a given line number
+    pub fn get_node_type_for_line(&self, line_number: usize) -> &'static str {
+        self.line_index
+            .innermost_node(line_number)
+            .map_or("text", |node| node.kind)
     }
 }
 
+/// Normalize a `[label]` the way CommonMark matches link reference labels:
+/// case-insensitively and with collapsed whitespace
+pub fn normalize_link_label(label: &str) -> String {
+    label
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .to_lowercase()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
 /// **SINGLE-USE CONTRACT**: RuleLinter instances are designed for one-time use only.
 ///
 /// Each RuleLinter instance should be used to analyze exactly one source document
@@ -252,7 +426,7 @@ impl Context {
 /// let mut linter2 = MultiRuleLinter::new_for_document(path, config, source2);
 /// let violations2 = linter2.analyze(); // Fresh linter, no contamination
 /// ```
-pub trait RuleLinter {
+pub trait RuleLinter: Send {
     /// Process a single AST node and accumulate state for violation detection.
     ///
     /// **CONTRACT**: This method will be called exactly once per AST node
@@ -271,8 +445,10 @@ pub trait RuleLinter {
 /// After calling `analyze()`, the linter and all its rule instances should be discarded.
 pub struct MultiRuleLinter {
     linters: Vec<Box<dyn RuleLinter>>,
-    tree: Option<tree_sitter::Tree>,
+    tree: Option<MarkdownTree>,
+    context: Option<Arc<Context>>,
     config: QuickmarkConfig,
+    inline_config: InlineConfig,
 }
 
 impl MultiRuleLinter {
@@ -286,53 +462,116 @@ impl MultiRuleLinter {
     ///
     /// After calling `analyze()`, this linter instance should be discarded.
     pub fn new_for_document(file_path: PathBuf, config: QuickmarkConfig, document: &str) -> Self {
+        Self::new_for_document_in_project(file_path, config, document, Arc::default())
+    }
+
+    /// Create a MultiRuleLinter for a document that belongs to a larger project.
+    ///
+    /// Rules that follow links into other files look them up in `project_index`.
+    /// Sharing one index between all documents of a run means each linked file
+    /// is read and parsed at most once.
+    pub fn new_for_document_in_project(
+        file_path: PathBuf,
+        mut config: QuickmarkConfig,
+        document: &str,
+        project_index: Arc<ProjectIndex>,
+    ) -> Self {
+        // Inline configuration comments may change which rules are active, so they are
+        // collected first. Documents without directives skip this step entirely.
+        let mut tree = None;
+        let inline_config = if InlineConfig::has_directives(document) {
+            let parsed = MarkdownTree::parse(document);
+            let inline_config = InlineConfig::new(document, parsed.block_tree());
+            tree = Some(parsed);
+            inline_config
+        } else {
+            InlineConfig::default()
+        };
+        inline_config.configure(&mut config);
+
         // Early exit optimization: Check if any rules are enabled before expensive operations
+        let is_active = |rule: &Rule| {
+            config
+                .linters
+                .severity
+                .get(rule.alias)
+                .is_some_and(|severity| *severity != RuleSeverity::Off)
+        };
+        // Plugins loaded for other configurations don't apply to this document
+        let plugin_rules = plugin::rules()
+            .into_iter()
+            .filter(|r| config.plugin_rules.iter().any(|alias| alias == r.alias));
         let active_rules: Vec<_> = ALL_RULES
             .iter()
-            .filter(|r| {
-                config
-                    .linters
-                    .severity
-                    .get(r.alias)
-                    .map(|severity| *severity != RuleSeverity::Off)
-                    .unwrap_or(false)
-            })
+            .chain(plugin_rules)
+            .filter(|r| is_active(r))
+            .collect();
+        // Custom rules come from the configuration itself, since rules with the same
+        // alias may have been compiled for other configurations
+        let active_custom_rules: Vec<_> = config
+            .compiled_custom_rules
+            .iter()
+            .filter(|compiled| is_active(compiled.rule()))
+            .cloned()
             .collect();
 
         // If no rules are active, create minimal linter that does no work
-        if active_rules.is_empty() {
+        if active_rules.is_empty() && active_custom_rules.is_empty() {
             return Self {
                 linters: Vec::new(),
                 tree: None,
+                context: None,
                 config,
+                inline_config,
             };
         }
 
         // Parse the document only when we have active rules
-        let mut parser = Parser::new();
-        parser
-            .set_language(&LANGUAGE.into())
-            .expect("Error loading Markdown grammar");
-        let tree = parser.parse(document, None).expect("Parse failed");
+        let tree = tree.unwrap_or_else(|| MarkdownTree::parse(document));
 
         // Create context with pre-initialized cache only for active rules
-        let context = Rc::new(Context::new(
+        let mut required_nodes: Vec<&str> = active_rules
+            .iter()
+            .flat_map(|rule| rule.required_nodes.iter().copied())
+            .chain(
+                active_custom_rules
+                    .iter()
+                    .flat_map(|compiled| compiled.rule().required_nodes.iter().copied()),
+            )
+            .collect();
+        required_nodes.sort_unstable();
+        required_nodes.dedup();
+        let context = Arc::new(Context::new(
             file_path,
             config.clone(),
             document,
-            &tree.root_node(),
+            &tree,
+            &required_nodes,
+            project_index,
         ));
 
-        // Create rule linters for active rules only
+        // Create rule linters for active rules only. Workspace rules run later,
+        // over the summaries of all documents.
         let linters = active_rules
             .iter()
-            .map(|r| ((r.new_linter)(context.clone())))
+            .filter(|r| r.rule_type != RuleType::Workspace)
+            .map(|r| match plugin::find_plugin_rule(r) {
+                Some(plugin_rule) => plugin_rule.new_linter(context.clone()),
+                None => (r.new_linter)(context.clone()),
+            })
+            .chain(
+                active_custom_rules
+                    .into_iter()
+                    .map(|compiled| compiled.new_linter(context.clone())),
+            )
             .collect();
 
         Self {
             linters,
             tree: Some(tree),
+            context: Some(context),
             config,
+            inline_config,
         }
     }
 
@@ -352,7 +591,7 @@ impl MultiRuleLinter {
             None => return Vec::new(),
         };
 
-        let walker = TreeSitterWalker::new(tree);
+        let walker = TreeSitterWalker::for_markdown(tree);
 
         // Feed all nodes to all linters
         walker.walk(|node| {
@@ -379,8 +618,92 @@ impl MultiRuleLinter {
             violations.extend(linter_violations);
         }
 
+        // Drop violations suppressed by inline configuration comments
+        violations.retain(|violation| {
+            !self
+                .inline_config
+                .is_disabled(violation.rule(), violation.location().range.start.line)
+        });
+
         violations
     }
+
+    /// Summarize the document for the workspace rules, see [`crate::workspace`].
+    ///
+    /// Returns `None` when every rule is turned off for the document, which then
+    /// takes no part in the workspace phase.
+    pub fn into_summary(self) -> Option<DocumentSummary> {
+        let context = self.context?;
+        let tree = self.tree?;
+        Some(DocumentSummary::new(&context, &tree, self.inline_config))
+    }
+
+    /// Apply automatic fixes to a document until it is stable.
+    ///
+    /// Each pass lints the current content with a fresh `MultiRuleLinter`, applies all
+    /// non-overlapping fixes and re-lints the result. Fixing stops when a pass applies
+    /// nothing or after [`MAX_FIX_PASSES`] passes.
+    pub fn fix_document(file_path: PathBuf, config: QuickmarkConfig, document: &str) -> FixResult {
+        Self::fix_document_in_project(file_path, config, document, Arc::default())
+    }
+
+    /// Apply automatic fixes to a document that belongs to a larger project,
+    /// see [`MultiRuleLinter::new_for_document_in_project`]
+    pub fn fix_document_in_project(
+        file_path: PathBuf,
+        config: QuickmarkConfig,
+        document: &str,
+        project_index: Arc<ProjectIndex>,
+    ) -> FixResult {
+        let mut content = document.to_string();
+        let mut fixed = 0;
+
+        for _ in 0..MAX_FIX_PASSES {
+            let mut linter = Self::new_for_document_in_project(
+                file_path.clone(),
+                config.clone(),
+                &content,
+                Arc::clone(&project_index),
+            );
+            let violations = linter.analyze();
+            let (new_content, applied) = apply_fixes(&content, &violations);
+            if applied == 0 || new_content == content {
+                return FixResult {
+                    content,
+                    fixed,
+                    remaining: violations,
+                    summary: linter.into_summary(),
+                };
+            }
+            fixed += applied;
+            content = new_content;
+        }
+
+        let mut linter =
+            Self::new_for_document_in_project(file_path, config, &content, project_index);
+        FixResult {
+            content,
+            fixed,
+            remaining: linter.analyze(),
+            summary: linter.into_summary(),
+        }
+    }
+}
+
+/// Upper bound on lint/fix iterations performed by [`MultiRuleLinter::fix_document`]
+pub const MAX_FIX_PASSES: usize = 10;
+
+/// Outcome of [`MultiRuleLinter::fix_document`]
+#[derive(Debug)]
+pub struct FixResult {
+    /// Document content after all applicable fixes
+    pub content: String,
+    /// Number of fixes applied across all passes
+    pub fixed: usize,
+    /// Violations still present in the fixed content
+    pub remaining: Vec<RuleViolation>,
+    /// Summary of the fixed content for the workspace rules
+    pub summary: Option<DocumentSummary>,
 }
 
 #[cfg(test)]
@@ -389,10 +712,39 @@ mod test {
 
     use crate::{
         config::{self, QuickmarkConfig, RuleSeverity},
-        rules::{md001::MD001, md003::MD003, md013::MD013},
+        rules::{md001::MD001, md003::MD003, md009::MD009, md013::MD013},
+        test_utils::test_helpers::test_config_with_rules,
     };
 
-    use super::MultiRuleLinter;
+    use super::{apply_fixes, CharPosition, Context, Edit, MultiRuleLinter, Range, RuleViolation};
+
+    fn range(start: (usize, usize), end: (usize, usize)) -> Range {
+        Range {
+            start: CharPosition {
+                line: start.0,
+                character: start.1,
+            },
+            end: CharPosition {
+                line: end.0,
+                character: end.1,
+            },
+        }
+    }
+
+    #[test]
+    fn test_linter_moves_between_threads() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<Context>();
+
+        let config = test_config_with_rules(vec![("heading-increment", RuleSeverity::Error)]);
+        let mut linter = MultiRuleLinter::new_for_document(
+            PathBuf::from("a.md"),
+            config,
+            "# One\n\n### Three\n",
+        );
+        let violations = std::thread::spawn(move || linter.analyze()).join().unwrap();
+        assert_eq!(1, violations.len());
+    }
 
     #[test]
     fn test_multiple_violations() {
@@ -414,6 +766,7 @@ mod test {
                     ..Default::default()
                 },
             },
+            ..Default::default()
         };
 
         // This creates a setext h1 after an ATX h1, which should violate:
@@ -438,4 +791,111 @@ Second heading
         assert_eq!(MD003.id, violations[1].rule().id);
         assert_eq!(2, violations[1].location().range.start.line);
     }
+
+    #[test]
+    fn test_apply_fixes_skips_overlapping_fixes() {
+        let source = "abc def\nghi\n";
+        let violation = |edits| {
+            RuleViolation::new(
+                &MD009,
+                String::new(),
+                PathBuf::from("test.md"),
+                range((0, 0), (0, 0)),
+            )
+            .with_fix(edits)
+        };
+        let violations = vec![
+            violation(vec![Edit::replace(range((0, 0), (0, 3)), "ABC")]),
+            violation(vec![Edit::replace(range((0, 2), (0, 5)), "XYZ")]),
+            violation(vec![
+                Edit::delete(range((0, 3), (0, 4))),
+                Edit::insert(
+                    CharPosition {
+                        line: 1,
+                        character: 3,
+                    },
+                    "!",
+                ),
+            ]),
+        ];
+
+        let (fixed, applied) = apply_fixes(source, &violations);
+        assert_eq!(2, applied);
+        assert_eq!("ABCdef\nghi!\n", fixed);
+    }
+
+    #[test]
+    fn test_apply_fixes_clamps_positions_to_document() {
+        let source = "abc\n";
+        let violations = vec![RuleViolation::new(
+            &MD009,
+            String::new(),
+            PathBuf::from("test.md"),
+            range((0, 0), (0, 0)),
+        )
+        .with_fix(vec![Edit::replace(range((0, 10), (5, 0)), "!")])];
+
+        let (fixed, applied) = apply_fixes(source, &violations);
+        assert_eq!(1, applied);
+        assert_eq!("abc!", fixed);
+    }
+
+    #[test]
+    fn test_fix_document_until_stable() {
+        let config = test_config_with_rules(vec![
+            ("no-trailing-spaces", RuleSeverity::Error),
+            ("no-hard-tabs", RuleSeverity::Error),
+            ("no-multiple-blanks", RuleSeverity::Error),
+            ("single-trailing-newline", RuleSeverity::Error),
+            ("blanks-around-headings", RuleSeverity::Error),
+            ("line-length", RuleSeverity::Error),
+        ]);
+        let input = "# Title\nSome\ttext   \n\n\n\nMore text";
+
+        let result = MultiRuleLinter::fix_document(PathBuf::from("test.md"), config, input);
+        assert_eq!("# Title\n\nSome text\n\nMore text\n", result.content);
+        assert!(result.remaining.is_empty());
+        assert!(result.fixed >= 5);
+    }
+
+    #[test]
+    fn test_fix_document_keeps_unfixable_violations() {
+        let config = test_config_with_rules(vec![
+            ("heading-increment", RuleSeverity::Error),
+            ("no-trailing-spaces", RuleSeverity::Error),
+        ]);
+        let input = "# Title \n\n### Subsection\n";
+
+        let result = MultiRuleLinter::fix_document(PathBuf::from("test.md"), config, input);
+        assert_eq!("# Title\n\n### Subsection\n", result.content);
+        assert_eq!(1, result.fixed);
+        assert_eq!(1, result.remaining.len());
+        assert_eq!(MD001.id, result.remaining[0].rule().id);
+    }
+
+    #[test]
+    fn test_inline_directives_suppress_violations() {
+        let config = test_config_with_rules(vec![
+            ("heading-increment", RuleSeverity::Error),
+            ("no-trailing-spaces", RuleSeverity::Error),
+        ]);
+        let input = "# Title\n\n<!-- quickmark-disable-next-line MD001 -->\n### Skipped\n\ntext \n\n#### Allowed <!-- quickmark-disable-line no-trailing-spaces -->\n";
+
+        let violations =
+            MultiRuleLinter::new_for_document(PathBuf::from("test.md"), config, input).analyze();
+        assert_eq!(1, violations.len());
+        assert_eq!(MD009.id, violations[0].rule().id);
+        assert_eq!(5, violations[0].location().range.start.line);
+    }
+
+    #[test]
+    fn test_inline_configure_file_enables_rules() {
+        let config = test_config_with_rules(vec![("line-length", RuleSeverity::Off)]);
+        let input = "<!-- quickmark-configure-file { \"line-length\": { \"line_length\": 20 } } -->\nThis line is longer than twenty characters.\n";
+
+        let violations =
+            MultiRuleLinter::new_for_document(PathBuf::from("test.md"), config, input).analyze();
+        assert_eq!(2, violations.len());
+        assert!(violations.iter().all(|v| v.rule().id == MD013.id));
+    }
 }
@@ -0,0 +1,177 @@
+//! Block and inline syntax trees of a Markdown document.
+//!
+//! tree-sitter-md splits Markdown into two grammars. The block grammar produces the
+//! document structure and leaves the text of paragraphs, headings and table cells as
+//! opaque `inline` nodes. Each of those is parsed separately with the inline grammar,
+//! which produces links, images, code spans, emphasis and the like.
+
+use std::{cell::RefCell, collections::HashMap};
+
+use tree_sitter::{Language, Node, Parser, Range, Tree};
+use tree_sitter_md::{INLINE_LANGUAGE, LANGUAGE};
+
+/// Block nodes whose content is parsed with the inline grammar
+const INLINE_CONTAINERS: &[&str] = &["inline", "pipe_table_cell"];
+
+thread_local! {
+    // Creating a parser allocates its parse stack and lexer state, so each thread keeps
+    // one parser per grammar and reuses it for every document it parses
+    static BLOCK_PARSER: RefCell<Parser> = RefCell::new(new_parser(LANGUAGE.into()));
+    static INLINE_PARSER: RefCell<Parser> = RefCell::new(new_parser(INLINE_LANGUAGE.into()));
+}
+
+fn new_parser(language: Language) -> Parser {
+    let mut parser = Parser::new();
+    parser
+        .set_language(&language)
+        .expect("Error loading Markdown grammar");
+    parser
+}
+
+/// A parsed Markdown document: its block tree plus one inline tree per inline container
+#[derive(Debug)]
+pub struct MarkdownTree {
+    block_tree: Tree,
+    inline_trees: Vec<Tree>,
+    /// Block node id to the index of its inline tree
+    inline_indices: HashMap<usize, usize>,
+}
+
+/// Parse only the block structure of a document
+pub fn parse_block_tree(source: &str) -> Tree {
+    BLOCK_PARSER.with(|parser| {
+        parser
+            .borrow_mut()
+            .parse(source, None)
+            .expect("Parse failed")
+    })
+}
+
+impl MarkdownTree {
+    pub fn parse(source: &str) -> Self {
+        let block_tree = parse_block_tree(source);
+
+        let mut inline_trees = Vec::new();
+        let mut inline_indices = HashMap::new();
+        let mut containers = Vec::new();
+        collect_inline_containers(block_tree.root_node(), &mut containers);
+
+        INLINE_PARSER.with(|parser| {
+            let mut parser = parser.borrow_mut();
+            for node in containers {
+                let ranges = inline_ranges(&node);
+                if ranges.is_empty() || parser.set_included_ranges(&ranges).is_err() {
+                    continue;
+                }
+                if let Some(inline_tree) = parser.parse(source, None) {
+                    inline_indices.insert(node.id(), inline_trees.len());
+                    inline_trees.push(inline_tree);
+                }
+            }
+        });
+
+        Self {
+            block_tree,
+            inline_trees,
+            inline_indices,
+        }
+    }
+
+    pub fn block_tree(&self) -> &Tree {
+        &self.block_tree
+    }
+
+    /// Inline tree of an `inline` or `pipe_table_cell` node of the block tree
+    pub fn inline_tree(&self, node: &Node) -> Option<&Tree> {
+        self.inline_indices
+            .get(&node.id())
+            .map(|index| &self.inline_trees[*index])
+    }
+
+    pub fn inline_trees(&self) -> &[Tree] {
+        &self.inline_trees
+    }
+}
+
+fn collect_inline_containers<'tree>(node: Node<'tree>, containers: &mut Vec<Node<'tree>>) {
+    if INLINE_CONTAINERS.contains(&node.kind()) {
+        containers.push(node);
+        return;
+    }
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_inline_containers(child, containers);
+    }
+}
+
+/// Ranges of an inline container without its named children, such as the
+/// `block_continuation` markers of lines inside block quotes
+fn inline_ranges(node: &Node) -> Vec<Range> {
+    let mut range = node.range();
+    let mut ranges = Vec::new();
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        let child_range = child.range();
+        if child_range.start_byte > range.start_byte {
+            ranges.push(Range {
+                start_byte: range.start_byte,
+                start_point: range.start_point,
+                end_byte: child_range.start_byte,
+                end_point: child_range.start_point,
+            });
+        }
+        range.start_byte = child_range.end_byte;
+        range.start_point = child_range.end_point;
+    }
+    if range.end_byte > range.start_byte {
+        ranges.push(range);
+    }
+    ranges
+}
+
+#[cfg(test)]
+mod test {
+    use super::MarkdownTree;
+    use crate::tree_sitter_walker::TreeSitterWalker;
+
+    fn inline_kinds(source: &str) -> Vec<String> {
+        let tree = MarkdownTree::parse(source);
+        let mut kinds = Vec::new();
+        TreeSitterWalker::for_markdown(&tree).walk(|node| {
+            if node.is_named() {
+                kinds.push(node.kind().to_string());
+            }
+        });
+        kinds
+    }
+
+    #[test]
+    fn test_inline_trees() {
+        let tree = MarkdownTree::parse("# Title with `code`\n\nSome [link](url) and *emphasis*.\n");
+        assert_eq!(2, tree.inline_trees().len());
+        let kinds = inline_kinds("Some [link](url) and *emphasis*.\n");
+        assert!(kinds.contains(&"inline_link".to_string()), "{kinds:?}");
+        assert!(kinds.contains(&"link_text".to_string()), "{kinds:?}");
+        assert!(kinds.contains(&"emphasis".to_string()), "{kinds:?}");
+    }
+
+    #[test]
+    fn test_inline_tree_skips_block_quote_markers() {
+        let tree = MarkdownTree::parse("> A [multi\n> line](url) link\n");
+        let source = "> A [multi\n> line](url) link\n";
+        let mut link = None;
+        TreeSitterWalker::for_markdown(&tree).walk(|node| {
+            if node.kind() == "inline_link" {
+                link = Some(node.utf8_text(source.as_bytes()).unwrap().to_string());
+            }
+        });
+        assert_eq!(Some("[multi\n> line](url)".to_string()), link);
+    }
+
+    #[test]
+    fn test_table_cells_are_parsed() {
+        let kinds = inline_kinds("| a | b |\n| - | - |\n| `x` | ![i](i.png) |\n");
+        assert!(kinds.contains(&"code_span".to_string()), "{kinds:?}");
+        assert!(kinds.contains(&"image".to_string()), "{kinds:?}");
+    }
+}
@@ -0,0 +1,222 @@
+//! Cached nodes of a parsed document, looked up by node kind or by line.
+//!
+//! Nodes are stored in an arena indexed by the tree-sitter `kind_id` of their kind.
+//! Only the kinds listed in [`Rule::required_nodes`](crate::rules::Rule) of the active
+//! rules are collected, so documents linted with few rules cache few nodes.
+
+use tree_sitter::{Language, Node, Tree};
+
+/// Lightweight node information for caching
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NodeInfo {
+    pub line_start: usize,
+    pub line_end: usize,
+    pub start_byte: usize,
+    pub end_byte: usize,
+    pub kind: &'static str,
+}
+
+impl NodeInfo {
+    fn new(node: &Node) -> Self {
+        Self {
+            line_start: node.start_position().row,
+            line_end: node.end_position().row,
+            start_byte: node.start_byte(),
+            end_byte: node.end_byte(),
+            kind: node.kind(),
+        }
+    }
+}
+
+/// Nodes of the requested kinds of one grammar, in document order per kind
+#[derive(Debug)]
+pub struct NodeCache {
+    language: Language,
+    /// Indexed by `kind_id`; `None` for kinds that were not requested
+    nodes_by_kind: Vec<Option<Vec<NodeInfo>>>,
+}
+
+impl NodeCache {
+    fn new(language: Language, kinds: &[&str]) -> Self {
+        let mut nodes_by_kind = vec![None; language.node_kind_count()];
+        for kind in kinds {
+            let kind_id = language.id_for_node_kind(kind, true) as usize;
+            // Kinds of the other grammar resolve to the builtin `end` symbol, 0
+            if kind_id != 0 {
+                if let Some(nodes) = nodes_by_kind.get_mut(kind_id) {
+                    *nodes = Some(Vec::new());
+                }
+            }
+        }
+        Self {
+            language,
+            nodes_by_kind,
+        }
+    }
+
+    /// Cache the nodes of `kinds` in the block tree of a document
+    pub fn for_block_tree(tree: &Tree, kinds: &[&str]) -> Self {
+        let mut cache = Self::new(Language::clone(&tree.language()), kinds);
+        if cache.is_empty_request() {
+            return cache;
+        }
+        cache.collect(&tree.root_node());
+        cache
+    }
+
+    /// Cache the nodes of `kinds` in the inline trees of a document. The roots of the
+    /// inline trees are skipped; they span the block nodes they were parsed from.
+    pub fn for_inline_trees(language: Language, trees: &[Tree], kinds: &[&str]) -> Self {
+        let mut cache = Self::new(language, kinds);
+        if cache.is_empty_request() {
+            return cache;
+        }
+        for tree in trees {
+            let root_node = tree.root_node();
+            let mut cursor = root_node.walk();
+            for child in root_node.children(&mut cursor) {
+                cache.collect(&child);
+            }
+        }
+        cache
+    }
+
+    fn is_empty_request(&self) -> bool {
+        self.nodes_by_kind.iter().all(Option::is_none)
+    }
+
+    fn collect(&mut self, node: &Node) {
+        let mut cursor = node.walk();
+        loop {
+            let node = cursor.node();
+            if let Some(Some(nodes)) = self.nodes_by_kind.get_mut(node.kind_id() as usize) {
+                nodes.push(NodeInfo::new(&node));
+            }
+            if cursor.goto_first_child() {
+                continue;
+            }
+            loop {
+                if cursor.goto_next_sibling() {
+                    break;
+                }
+                if !cursor.goto_parent() {
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Cached nodes of a kind, in document order.
+    ///
+    /// Returns `None` when the kind does not exist in the grammar. Kinds must be listed
+    /// in the `required_nodes` of the rule looking them up; debug builds panic otherwise.
+    pub fn get(&self, kind: &str) -> Option<&[NodeInfo]> {
+        let kind_id = self.language.id_for_node_kind(kind, true) as usize;
+        if kind_id == 0 {
+            return None;
+        }
+        let nodes = self.nodes_by_kind.get(kind_id)?.as_deref();
+        debug_assert!(
+            nodes.is_some(),
+            "node kind `{kind}` is not listed in the required_nodes of an active rule"
+        );
+        nodes
+    }
+}
+
+/// Innermost block node of every line of a document
+#[derive(Debug, Default)]
+pub struct LineIndex {
+    innermost: Vec<Option<NodeInfo>>,
+}
+
+impl LineIndex {
+    pub fn new(tree: &Tree) -> Self {
+        let root_node = tree.root_node();
+        let line_count = root_node.end_position().row + 1;
+        let mut innermost: Vec<Option<NodeInfo>> = vec![None; line_count];
+
+        // Children never span more lines than their parent, so visiting nodes in
+        // pre-order and replacing strictly wider matches leaves the innermost node,
+        // and of equally narrow nodes the first one
+        let mut cursor = root_node.walk();
+        loop {
+            let info = NodeInfo::new(&cursor.node());
+            let span = info.line_end - info.line_start;
+            for slot in &mut innermost[info.line_start..=info.line_end] {
+                if slot.is_none_or(|current| span < current.line_end - current.line_start) {
+                    *slot = Some(info);
+                }
+            }
+            if cursor.goto_first_child() {
+                continue;
+            }
+            loop {
+                if cursor.goto_next_sibling() {
+                    break;
+                }
+                if !cursor.goto_parent() {
+                    return Self { innermost };
+                }
+            }
+        }
+    }
+
+    /// The most specific node that contains a line, if any
+    pub fn innermost_node(&self, line: usize) -> Option<&NodeInfo> {
+        self.innermost.get(line)?.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{LineIndex, NodeCache};
+    use crate::markdown_tree::MarkdownTree;
+
+    #[test]
+    fn test_caches_only_requested_kinds() {
+        let source = "# Title\n\n```rust\nfn main() {}\n```\n\n    indented\n";
+        let tree = MarkdownTree::parse(source);
+        let cache = NodeCache::for_block_tree(tree.block_tree(), &["fenced_code_block"]);
+
+        let blocks = cache.get("fenced_code_block").unwrap();
+        assert_eq!(1, blocks.len());
+        assert_eq!((2, 5), (blocks[0].line_start, blocks[0].line_end));
+        assert_eq!("fenced_code_block", blocks[0].kind);
+        assert_eq!(None, cache.get("no_such_kind"));
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "not listed in the required_nodes")]
+    fn test_unrequested_kind_panics_in_debug_builds() {
+        let tree = MarkdownTree::parse("# Title\n");
+        let cache = NodeCache::for_block_tree(tree.block_tree(), &["paragraph"]);
+        let _ = cache.get("atx_heading");
+    }
+
+    #[test]
+    fn test_inline_nodes() {
+        let tree = MarkdownTree::parse("Some `code` and `more`.\n");
+        let cache = NodeCache::for_inline_trees(
+            tree_sitter_md::INLINE_LANGUAGE.into(),
+            tree.inline_trees(),
+            &["code_span", "fenced_code_block"],
+        );
+        assert_eq!(2, cache.get("code_span").unwrap().len());
+    }
+
+    #[test]
+    fn test_line_index_finds_innermost_node() {
+        let source = "# Title\n\n```\ncode\n```\n\ntext\n";
+        let tree = MarkdownTree::parse(source);
+        let index = LineIndex::new(tree.block_tree());
+        assert_eq!("atx_h1_marker", index.innermost_node(0).unwrap().kind);
+        assert_eq!(
+            "fenced_code_block_delimiter",
+            index.innermost_node(2).unwrap().kind
+        );
+        assert_eq!("inline", index.innermost_node(6).unwrap().kind);
+        assert!(index.innermost_node(100).is_none());
+    }
+}
@@ -0,0 +1,559 @@
+//! Rules loaded at runtime from plugin libraries.
+//!
+//! A plugin is a dynamic library (`.so` on Linux, `.dylib` on macOS) listed in the
+//! `plugins` key of a configuration file. It exports a function named
+//! [`PLUGIN_ENTRY_SYMBOL`] that returns a [`QmPlugin`] describing its rules:
+//!
+//! ```toml
+//! plugins = ["target/release/libacme_rules.so"]
+//!
+//! [linters.severity]
+//! acme-no-internal-links = 'warn'
+//!
+//! [linters.settings.acme-no-internal-links]
+//! hosts = ["wiki.acme.internal"]
+//! ```
+//!
+//! The interface is a plain C ABI, versioned by [`PLUGIN_ABI_VERSION`], so plugins
+//! don't have to be built with the same compiler as `qmark`. Each rule is a C-style
+//! counterpart of [`RuleLinter`]: a linter is created per document, fed every node of
+//! the block and inline trees in document order, and finalized once to report its
+//! violations. Plugin rules take part in severity handling, inline configuration and
+//! `[[overrides]]` like built-in rules. Their `[linters.settings.<alias>]` table is
+//! passed to the linter as JSON.
+//!
+//! Loading a library runs its code, so configuration files only load plugins once the
+//! application allows it with [`allow_plugins`], e.g. for `qmark --allow-plugins`.
+//! Otherwise the `plugins` key is ignored with a warning. Plugin rules only apply to
+//! documents whose configuration lists the plugin, see [`QuickmarkConfig::plugin_rules`].
+//!
+//! Documents are linted in parallel, so the functions of a plugin must be safe to call
+//! from several threads at once for different linters. Libraries stay loaded until
+//! the process exits.
+//!
+//! [`QuickmarkConfig::plugin_rules`]: crate::config::QuickmarkConfig::plugin_rules
+
+use std::{
+    collections::HashSet,
+    ffi::c_void,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, RwLock,
+    },
+};
+
+use anyhow::{anyhow, bail, Context as _, Result};
+use once_cell::sync::Lazy;
+use tree_sitter::Node;
+
+use crate::{
+    linter::{CharPosition, Context, Range, RuleLinter, RuleViolation},
+    rules::{NoDocumentLinter, Rule, RuleType, ALL_RULES},
+};
+
+/// Version of the plugin interface described by the `Qm*` types
+pub const PLUGIN_ABI_VERSION: u32 = 1;
+
+/// Name of the function a plugin library exports, with the signature of [`QmPluginEntry`]
+pub const PLUGIN_ENTRY_SYMBOL: &str = "quickmark_plugin_v1";
+
+/// Why the plugins of a configuration were not loaded
+pub const PLUGINS_NOT_ALLOWED: &str = "Plugins are only loaded when allowed with \
+    `qmark --allow-plugins` or the `allowPlugins` initialization option of the language server";
+
+/// UTF-8 text that is not NUL-terminated
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct QmStr {
+    pub ptr: *const u8,
+    pub len: usize,
+}
+
+impl QmStr {
+    pub const fn from_static(text: &'static str) -> Self {
+        Self {
+            ptr: text.as_ptr(),
+            len: text.len(),
+        }
+    }
+
+    /// Borrow `text` without copying. The caller keeps it alive while the `QmStr` is used.
+    pub fn new(text: &str) -> Self {
+        Self {
+            ptr: text.as_ptr(),
+            len: text.len(),
+        }
+    }
+
+    /// The text, or `None` when the pointer is null or the bytes are not UTF-8
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must point to `len` readable bytes that outlive `'a`.
+    pub unsafe fn as_str<'a>(&self) -> Option<&'a str> {
+        if self.ptr.is_null() {
+            return None;
+        }
+        std::str::from_utf8(std::slice::from_raw_parts(self.ptr, self.len)).ok()
+    }
+}
+
+/// Zero-based row and byte column
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct QmPoint {
+    pub row: usize,
+    pub column: usize,
+}
+
+/// A node of the block or inline tree of a document
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct QmNode {
+    /// tree-sitter-md node kind, such as `atx_heading` or `inline_link`
+    pub kind: QmStr,
+    pub start_byte: usize,
+    pub end_byte: usize,
+    pub start: QmPoint,
+    pub end: QmPoint,
+}
+
+/// The document a linter is created for. The strings stay valid until the linter is
+/// freed, the struct itself only during the `new_linter` call.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct QmDocument {
+    pub path: QmStr,
+    pub source: QmStr,
+    /// The rule's `[linters.settings.<alias>]` table as a JSON object
+    pub settings_json: QmStr,
+}
+
+/// A violation reported by a plugin linter. The message is copied during the report call.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct QmViolation {
+    pub message: QmStr,
+    pub start: QmPoint,
+    pub end: QmPoint,
+}
+
+/// Callback passed to `finalize`, to be called once per violation with the given `sink`
+pub type QmReportFn = unsafe extern "C" fn(sink: *mut c_void, violation: *const QmViolation);
+
+/// A rule provided by a plugin
+#[repr(C)]
+#[derive(Debug)]
+pub struct QmRule {
+    pub id: QmStr,
+    pub alias: QmStr,
+    pub description: QmStr,
+    pub tags: *const QmStr,
+    pub tag_count: usize,
+    /// Create the linter state for a document, or return null to skip the document
+    pub new_linter: Option<unsafe extern "C" fn(document: *const QmDocument) -> *mut c_void>,
+    pub feed: Option<unsafe extern "C" fn(linter: *mut c_void, node: *const QmNode)>,
+    pub finalize:
+        Option<unsafe extern "C" fn(linter: *mut c_void, sink: *mut c_void, report: QmReportFn)>,
+    pub free_linter: Option<unsafe extern "C" fn(linter: *mut c_void)>,
+}
+
+/// What the entry point of a plugin returns. It must stay valid while the library is loaded.
+#[repr(C)]
+#[derive(Debug)]
+pub struct QmPlugin {
+    /// Must be [`PLUGIN_ABI_VERSION`]
+    pub abi_version: u32,
+    pub name: QmStr,
+    pub rules: *const QmRule,
+    pub rule_count: usize,
+}
+
+/// Signature of the [`PLUGIN_ENTRY_SYMBOL`] function
+pub type QmPluginEntry = unsafe extern "C" fn() -> *const QmPlugin;
+
+// Plugin descriptors are immutable once returned, and are declared as statics
+unsafe impl Sync for QmStr {}
+unsafe impl Send for QmStr {}
+unsafe impl Sync for QmRule {}
+unsafe impl Sync for QmPlugin {}
+
+/// A rule of a loaded plugin
+#[derive(Debug)]
+pub struct PluginRule {
+    pub rule: &'static Rule,
+    abi: &'static QmRule,
+}
+
+#[derive(Debug)]
+struct LoadedPlugin {
+    /// Canonical path of the library, or the origin passed to [`register_plugin`]
+    origin: PathBuf,
+    rules: Vec<PluginRule>,
+}
+
+static PLUGINS: Lazy<RwLock<Vec<&'static LoadedPlugin>>> = Lazy::new(Default::default);
+
+static PLUGINS_ALLOWED: AtomicBool = AtomicBool::new(false);
+
+/// Plugins that were not loaded because plugins are not allowed, warned about once
+static SKIPPED_PLUGINS: Lazy<Mutex<HashSet<PathBuf>>> = Lazy::new(Default::default);
+
+/// Allow configuration files to load plugin libraries, which runs their code
+pub fn allow_plugins(allowed: bool) {
+    PLUGINS_ALLOWED.store(allowed, Ordering::Relaxed);
+}
+
+pub fn plugins_allowed() -> bool {
+    PLUGINS_ALLOWED.load(Ordering::Relaxed)
+}
+
+/// Rules of all loaded plugins, in loading order
+pub fn rules() -> Vec<&'static Rule> {
+    plugin_rules().into_iter().map(|rule| rule.rule).collect()
+}
+
+pub(crate) fn plugin_rules() -> Vec<&'static PluginRule> {
+    PLUGINS
+        .read()
+        .unwrap()
+        .iter()
+        .flat_map(|plugin| plugin.rules.iter())
+        .collect()
+}
+
+/// The plugin rule described by `rule`, `None` for built-in rules
+pub(crate) fn find_plugin_rule(rule: &Rule) -> Option<&'static PluginRule> {
+    plugin_rules()
+        .into_iter()
+        .find(|plugin_rule| std::ptr::eq(plugin_rule.rule, rule))
+}
+
+/// Load the plugins of a configuration, if plugins are allowed, and return their rules
+pub(crate) fn load_config_plugins(paths: &[PathBuf]) -> Result<Vec<&'static Rule>> {
+    if plugins_allowed() {
+        let mut rules = Vec::new();
+        for path in paths {
+            rules.extend(load_plugin(path)?);
+        }
+        return Ok(rules);
+    }
+    let mut skipped = SKIPPED_PLUGINS.lock().unwrap();
+    for path in paths {
+        if skipped.insert(path.clone()) {
+            eprintln!(
+                "Plugin {} is not loaded. {PLUGINS_NOT_ALLOWED}.",
+                path.to_string_lossy()
+            );
+        }
+    }
+    Ok(Vec::new())
+}
+
+/// Load a plugin library unless it is already loaded, and return its rules
+pub fn load_plugin(path: &Path) -> Result<Vec<&'static Rule>> {
+    // Descriptors registered in-process are found by their origin
+    let absolute = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+    if let Some(rules) = loaded_rules(&absolute) {
+        return Ok(rules);
+    }
+    let canonical = path
+        .canonicalize()
+        .with_context(|| format!("Can't find plugin {}", path.to_string_lossy()))?;
+    if let Some(rules) = loaded_rules(&canonical) {
+        return Ok(rules);
+    }
+    let plugin = open_library(&canonical)
+        .with_context(|| format!("Can't load plugin {}", path.to_string_lossy()))?;
+    // SAFETY: the library stays loaded, so its descriptor lives for the rest of the process
+    unsafe { register_plugin(plugin, &canonical) }
+        .with_context(|| format!("Invalid plugin {}", path.to_string_lossy()))
+}
+
+/// Rules of the plugin loaded from `origin`, `None` when it isn't loaded
+fn loaded_rules(origin: &Path) -> Option<Vec<&'static Rule>> {
+    PLUGINS
+        .read()
+        .unwrap()
+        .iter()
+        .find(|plugin| plugin.origin == origin)
+        .map(|plugin| plugin.rules.iter().map(|rule| rule.rule).collect())
+}
+
+/// Register the rules of a plugin descriptor, e.g. one linked into the application
+/// instead of loaded from a library, and return them. Registering the same `origin`
+/// twice returns the rules registered the first time.
+///
+/// # Safety
+///
+/// `plugin` must follow the interface documented on the `Qm*` types: its strings and
+/// rule array must be valid, and its functions must be safe to call from any thread,
+/// as long as a linter state is not used by two threads at once.
+pub unsafe fn register_plugin(
+    plugin: &'static QmPlugin,
+    origin: &Path,
+) -> Result<Vec<&'static Rule>> {
+    if plugin.abi_version != PLUGIN_ABI_VERSION {
+        bail!(
+            "Plugin was built for plugin interface version {}, expected {PLUGIN_ABI_VERSION}",
+            plugin.abi_version
+        );
+    }
+    let descriptors: &'static [QmRule] = if plugin.rule_count == 0 {
+        &[]
+    } else if plugin.rules.is_null() {
+        bail!(
+            "Plugin declares {} rules but no rule array",
+            plugin.rule_count
+        );
+    } else {
+        std::slice::from_raw_parts(plugin.rules, plugin.rule_count)
+    };
+
+    let mut plugins = PLUGINS.write().unwrap();
+    if let Some(loaded) = plugins.iter().find(|loaded| loaded.origin == origin) {
+        return Ok(loaded.rules.iter().map(|rule| rule.rule).collect());
+    }
+    let mut known: Vec<&str> = ALL_RULES
+        .iter()
+        .chain(plugins.iter().flat_map(|p| p.rules.iter().map(|r| r.rule)))
+        .flat_map(|rule| [rule.id, rule.alias])
+        .collect();
+
+    let mut rules = Vec::with_capacity(descriptors.len());
+    for abi in descriptors {
+        let rule = plugin_rule(abi)?;
+        for name in [rule.id, rule.alias] {
+            if known.iter().any(|known| known.eq_ignore_ascii_case(name)) {
+                bail!("Rule `{name}` is already defined");
+            }
+        }
+        known.extend([rule.id, rule.alias]);
+        rules.push(PluginRule {
+            rule: Box::leak(Box::new(rule)),
+            abi,
+        });
+    }
+
+    let loaded: &'static LoadedPlugin = Box::leak(Box::new(LoadedPlugin {
+        origin: origin.to_path_buf(),
+        rules,
+    }));
+    plugins.push(loaded);
+    Ok(loaded.rules.iter().map(|rule| rule.rule).collect())
+}
+
+/// Build the [`Rule`] describing a plugin rule, copying its strings
+unsafe fn plugin_rule(abi: &QmRule) -> Result<Rule> {
+    let text = |value: &QmStr, field: &str| -> Result<&'static str> {
+        let text = value
+            .as_str()
+            .filter(|text| !text.trim().is_empty())
+            .ok_or_else(|| anyhow!("Rule `{field}` must be non-empty UTF-8"))?;
+        Ok(Box::leak(text.to_string().into_boxed_str()))
+    };
+    let id = text(&abi.id, "id")?;
+    let alias = text(&abi.alias, "alias")?;
+    let description = text(&abi.description, "description")?;
+    let tags: Vec<&'static str> = if abi.tags.is_null() {
+        Vec::new()
+    } else {
+        std::slice::from_raw_parts(abi.tags, abi.tag_count)
+            .iter()
+            .map(|tag| text(tag, "tags"))
+            .collect::<Result<_>>()?
+    };
+    if abi.new_linter.is_none()
+        || abi.feed.is_none()
+        || abi.finalize.is_none()
+        || abi.free_linter.is_none()
+    {
+        bail!("Rule `{id}` must provide new_linter, feed, finalize and free_linter");
+    }
+
+    Ok(Rule {
+        id,
+        alias,
+        tags: Box::leak(tags.into_boxed_slice()),
+        description,
+        rule_type: RuleType::Document,
+        required_nodes: &[],
+        // Plugin linters are created by `PluginRule::new_linter`
+        new_linter: |_| Box::new(NoDocumentLinter),
+    })
+}
+
+impl PluginRule {
+    pub(crate) fn new_linter(&'static self, context: Arc<Context>) -> Box<dyn RuleLinter> {
+        Box::new(PluginLinter::new(self, context))
+    }
+}
+
+/// Adapts the linter of a plugin rule to [`RuleLinter`]
+struct PluginLinter {
+    rule: &'static PluginRule,
+    context: Arc<Context>,
+    /// Plugin linter state, null when the plugin skipped the document
+    state: *mut c_void,
+    // Strings handed to the plugin, kept alive until the state is freed
+    _path: String,
+    _settings_json: String,
+}
+
+impl PluginLinter {
+    fn new(rule: &'static PluginRule, context: Arc<Context>) -> Self {
+        let path = context.file_path.to_string_lossy().to_string();
+        let settings_json = context
+            .config
+            .linters
+            .settings
+            .plugins
+            .get(rule.rule.alias)
+            .and_then(|settings| serde_json::to_string(settings).ok())
+            .unwrap_or_else(|| "{}".to_string());
+        // The content is never modified after the context is built, so the pointer
+        // stays valid as long as the context, which outlives this linter
+        let source = QmStr::new(&context.document_content);
+        let document = QmDocument {
+            path: QmStr::new(&path),
+            source,
+            settings_json: QmStr::new(&settings_json),
+        };
+        let state = match rule.abi.new_linter {
+            // SAFETY: the plugin contract of `register_plugin`
+            Some(new_linter) => unsafe { new_linter(&document) },
+            None => std::ptr::null_mut(),
+        };
+
+        Self {
+            rule,
+            context,
+            state,
+            _path: path,
+            _settings_json: settings_json,
+        }
+    }
+}
+
+// SAFETY: a linter state is only used by the thread that currently owns the linter,
+// and the plugin contract of `register_plugin` allows calls from any thread
+unsafe impl Send for PluginLinter {}
+
+/// Collects violations reported by a plugin into a `Vec<(String, Range)>` sink
+unsafe extern "C" fn collect_violation(sink: *mut c_void, violation: *const QmViolation) {
+    let (Some(violations), Some(violation)) = (
+        (sink as *mut Vec<(String, Range)>).as_mut(),
+        violation.as_ref(),
+    ) else {
+        return;
+    };
+    let point = |point: QmPoint| CharPosition {
+        line: point.row,
+        character: point.column,
+    };
+    violations.push((
+        violation.message.as_str().unwrap_or_default().to_string(),
+        Range {
+            start: point(violation.start),
+            end: point(violation.end),
+        },
+    ));
+}
+
+impl RuleLinter for PluginLinter {
+    fn feed(&mut self, node: &Node) {
+        let (Some(feed), false) = (self.rule.abi.feed, self.state.is_null()) else {
+            return;
+        };
+        let point = |point: tree_sitter::Point| QmPoint {
+            row: point.row,
+            column: point.column,
+        };
+        let node = QmNode {
+            kind: QmStr::new(node.kind()),
+            start_byte: node.start_byte(),
+            end_byte: node.end_byte(),
+            start: point(node.start_position()),
+            end: point(node.end_position()),
+        };
+        // SAFETY: the plugin contract of `register_plugin`
+        unsafe { feed(self.state, &node) };
+    }
+
+    fn finalize(&mut self) -> Vec<RuleViolation> {
+        let (Some(finalize), false) = (self.rule.abi.finalize, self.state.is_null()) else {
+            return Vec::new();
+        };
+        let mut reported: Vec<(String, Range)> = Vec::new();
+        // SAFETY: the plugin contract of `register_plugin`; the sink outlives the call
+        unsafe {
+            finalize(
+                self.state,
+                &mut reported as *mut _ as *mut c_void,
+                collect_violation,
+            )
+        };
+        reported
+            .into_iter()
+            .map(|(message, range)| {
+                RuleViolation::new(
+                    self.rule.rule,
+                    message,
+                    self.context.file_path.clone(),
+                    range,
+                )
+            })
+            .collect()
+    }
+}
+
+impl Drop for PluginLinter {
+    fn drop(&mut self) {
+        if let (Some(free_linter), false) = (self.rule.abi.free_linter, self.state.is_null()) {
+            // SAFETY: the plugin contract of `register_plugin`; the state is freed once
+            unsafe { free_linter(self.state) };
+        }
+    }
+}
+
+/// Open a library and call its entry point
+#[cfg(unix)]
+fn open_library(path: &Path) -> Result<&'static QmPlugin> {
+    use std::ffi::{CStr, CString};
+    use std::os::unix::ffi::OsStrExt;
+
+    fn last_error() -> String {
+        // SAFETY: dlerror returns null or a NUL-terminated message
+        unsafe {
+            let message = libc::dlerror();
+            if message.is_null() {
+                "unknown error".to_string()
+            } else {
+                CStr::from_ptr(message).to_string_lossy().to_string()
+            }
+        }
+    }
+
+    let c_path = CString::new(path.as_os_str().as_bytes())?;
+    // SAFETY: loading a library runs its initializers, which is what loading a plugin means
+    let handle = unsafe { libc::dlopen(c_path.as_ptr(), libc::RTLD_NOW | libc::RTLD_LOCAL) };
+    if handle.is_null() {
+        bail!(last_error());
+    }
+    let symbol = CString::new(PLUGIN_ENTRY_SYMBOL)?;
+    // SAFETY: the handle is valid and never closed
+    let entry = unsafe { libc::dlsym(handle, symbol.as_ptr()) };
+    if entry.is_null() {
+        bail!("The library doesn't export `{PLUGIN_ENTRY_SYMBOL}`");
+    }
+    // SAFETY: plugins export the entry point with the `QmPluginEntry` signature
+    let plugin = unsafe { std::mem::transmute::<*mut c_void, QmPluginEntry>(entry)() };
+    // SAFETY: the descriptor lives as long as the library, which is never unloaded
+    unsafe { plugin.as_ref() }.ok_or_else(|| anyhow!("`{PLUGIN_ENTRY_SYMBOL}` returned null"))
+}
+
+#[cfg(not(unix))]
+fn open_library(_path: &Path) -> Result<&'static QmPlugin> {
+    bail!("Plugins are not supported on this platform")
+}
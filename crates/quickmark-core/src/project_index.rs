@@ -0,0 +1,442 @@
+//! Index of the Markdown documents of a project.
+//!
+//! Rules that follow links into other files need to know which anchors those files
+//! define. The [`ProjectIndex`] is shared by all documents of a lint run: documents are
+//! parsed lazily from disk the first time a link points at them, and editors can feed
+//! the content of unsaved buffers with [`ProjectIndex::update`].
+
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::{Component, Path, PathBuf},
+    sync::{Arc, RwLock},
+    time::SystemTime,
+};
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use tree_sitter::Node;
+
+use crate::{markdown_tree::parse_block_tree, tree_sitter_walker::TreeSitterWalker};
+
+// GitHub line fragment regex matching:
+// ^#(?:L\d+(?:C\d+)?-L\d+(?:C\d+)?|L\d+)$
+// This allows: L123, L12C5-L34C10. It also matches L12-L34.
+static LINE_FRAGMENT_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^L\d+(?:C\d+)?-L\d+(?:C\d+)?$|^L\d+$").unwrap());
+
+static ID_PATTERN: Lazy<Regex> = Lazy::new(|| Regex::new(r#"id\s*=\s*["']([^"']+)["']"#).unwrap());
+
+static NAME_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"name\s*=\s*["']([^"']+)["']"#).unwrap());
+
+// URLs with a scheme such as `https:` or `mailto:`
+static SCHEME_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^[A-Za-z][A-Za-z0-9+.\-]*:").unwrap());
+
+/// Extensions of the files treated as Markdown documents
+pub const MARKDOWN_EXTENSIONS: &[&str] = &["md", "markdown", "mdown", "mkd", "mkdn"];
+
+/// Whether a path has one of the [`MARKDOWN_EXTENSIONS`]
+pub fn is_markdown_path(path: &Path) -> bool {
+    path.extension().is_some_and(|extension| {
+        let extension = extension.to_string_lossy().to_lowercase();
+        MARKDOWN_EXTENSIONS.contains(&extension.as_str())
+    })
+}
+
+/// Fragments a document can be linked to: generated heading anchors, custom
+/// `{#anchor}` attributes and the `id`/`name` attributes of HTML elements
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct DocumentAnchors {
+    fragments: HashSet<String>,
+    // Pre-computed lowercase for case-insensitive lookups
+    fragments_lowercase: HashSet<String>,
+}
+
+impl DocumentAnchors {
+    pub fn from_source(source: &str) -> Self {
+        let tree = parse_block_tree(source);
+        let mut anchors = Self::default();
+        TreeSitterWalker::new(&tree).walk(|node| anchors.add_node(&node, source));
+        anchors
+    }
+
+    /// Record the anchors defined by a block node. Headings must be added in
+    /// document order so that duplicate headings get numbered suffixes.
+    pub fn add_node(&mut self, node: &Node, source: &str) {
+        match node.kind() {
+            "atx_heading" | "setext_heading" => {
+                if let Some(heading_text) = heading_text(node, source) {
+                    self.add_heading(&heading_text);
+                }
+            }
+            "inline" | "html_block" => {
+                let html_content = &source[node.start_byte()..node.end_byte()];
+                for pattern in [&ID_PATTERN, &NAME_PATTERN] {
+                    for cap in pattern.captures_iter(html_content) {
+                        if let Some(id) = cap.get(1) {
+                            self.insert(id.as_str().to_string());
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn add_heading(&mut self, heading_text: &str) {
+        // Check for custom anchor first
+        if let Some(custom_anchor) = extract_custom_anchor(heading_text) {
+            self.insert(custom_anchor.clone());
+            // Also generate the default fragment from the heading text without the anchor
+            let clean_text = heading_text
+                .replace(&format!("{{#{custom_anchor}}}"), "")
+                .trim()
+                .to_string();
+            if !clean_text.is_empty() {
+                let fragment = generate_github_fragment(&clean_text);
+                if !fragment.is_empty() {
+                    self.insert(fragment);
+                }
+            }
+        } else {
+            // Generate GitHub-style fragment
+            let fragment = generate_github_fragment(heading_text);
+            if !fragment.is_empty() {
+                // Handle duplicate headings by checking if fragment already exists
+                let mut unique_fragment = fragment.clone();
+                let mut counter = 1;
+                while self.fragments.contains(&unique_fragment) {
+                    unique_fragment = format!("{fragment}-{counter}");
+                    counter += 1;
+                }
+                self.insert(unique_fragment);
+            }
+        }
+    }
+
+    fn insert(&mut self, fragment: String) {
+        self.fragments_lowercase.insert(fragment.to_lowercase());
+        self.fragments.insert(fragment);
+    }
+
+    /// Whether a fragment (without the leading `#`) points into the document.
+    /// GitHub's `top` and line fragments are always valid.
+    pub fn contains(&self, fragment: &str, ignore_case: bool) -> bool {
+        if is_github_special_fragment(fragment) {
+            return true;
+        }
+        if ignore_case {
+            self.fragments_lowercase.contains(&fragment.to_lowercase())
+        } else {
+            self.fragments.contains(fragment)
+        }
+    }
+}
+
+pub(crate) fn heading_text(node: &Node, source: &str) -> Option<String> {
+    let inline_node = if node.kind() == "atx_heading" {
+        node.children(&mut node.walk())
+            .find(|c| c.kind() == "inline")
+    } else {
+        node.children(&mut node.walk())
+            .find(|c| c.kind() == "paragraph")
+            .and_then(|p| p.children(&mut p.walk()).find(|gc| gc.kind() == "inline"))
+    };
+
+    inline_node.map(|n| source[n.start_byte()..n.end_byte()].trim().to_string())
+}
+
+fn generate_github_fragment(heading_text: &str) -> String {
+    // GitHub fragment generation rules based on reverse engineering:
+    // 1. Convert to lowercase
+    // 2. Replace spaces with hyphens
+    // 3. Keep only alphanumeric, hyphens, and underscores
+    // 4. Remove leading/trailing hyphens
+    let lower = heading_text.trim().to_lowercase().replace(' ', "-");
+    let mut fragment = String::with_capacity(lower.len());
+
+    for c in lower.chars() {
+        if c.is_alphanumeric() || c == '-' || c == '_' {
+            fragment.push(c);
+        }
+    }
+
+    // Remove leading and trailing hyphens
+    fragment.trim_matches('-').to_string()
+}
+
+fn extract_custom_anchor(heading_text: &str) -> Option<String> {
+    // Look for {#custom-anchor} syntax
+    let start = heading_text.rfind("{#")?;
+    let end = heading_text[start..].find('}')?;
+    Some(heading_text[start + 2..start + end].to_string())
+}
+
+fn is_github_special_fragment(fragment: &str) -> bool {
+    // GitHub special fragments according to GitHub specification
+    // Reference: https://docs.github.com/en/get-started/writing-on-github/working-with-advanced-formatting/creating-a-permanent-link-to-a-code-snippet
+    fragment == "top" || LINE_FRAGMENT_PATTERN.is_match(fragment)
+}
+
+#[derive(Debug)]
+enum EntrySource {
+    /// Content provided by the caller, e.g. an unsaved editor buffer
+    Buffer,
+    /// Content read from disk, with the modification time it was read at
+    Disk(Option<SystemTime>),
+}
+
+#[derive(Debug)]
+struct IndexEntry {
+    anchors: Arc<DocumentAnchors>,
+    source: EntrySource,
+}
+
+/// Anchors of the Markdown documents of a project, keyed by normalized absolute path.
+///
+/// The index can be shared between threads. Documents read from disk are re-read when
+/// their modification time changes; documents provided with [`ProjectIndex::update`]
+/// take precedence until they are [invalidated](ProjectIndex::invalidate).
+#[derive(Debug, Default)]
+pub struct ProjectIndex {
+    entries: RwLock<HashMap<PathBuf, IndexEntry>>,
+}
+
+impl ProjectIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Index the content of a document, overriding what is on disk.
+    /// Returns whether the anchors of the document changed.
+    pub fn update(&self, path: &Path, content: &str) -> bool {
+        let anchors = DocumentAnchors::from_source(content);
+        let mut entries = self.entries.write().unwrap();
+        let key = normalize_path(path);
+        let changed = entries
+            .get(&key)
+            .is_none_or(|entry| *entry.anchors != anchors);
+        entries.insert(
+            key,
+            IndexEntry {
+                anchors: Arc::new(anchors),
+                source: EntrySource::Buffer,
+            },
+        );
+        changed
+    }
+
+    /// Forget a document so that it is read from disk again on the next lookup
+    pub fn invalidate(&self, path: &Path) {
+        self.entries.write().unwrap().remove(&normalize_path(path));
+    }
+
+    /// Whether a file or directory exists, either on disk or as an indexed buffer
+    pub fn exists(&self, path: &Path) -> bool {
+        let key = normalize_path(path);
+        let is_buffer = self
+            .entries
+            .read()
+            .unwrap()
+            .get(&key)
+            .is_some_and(|entry| matches!(entry.source, EntrySource::Buffer));
+        is_buffer || key.exists()
+    }
+
+    /// Anchors of a Markdown document, or `None` if it can't be read
+    pub fn anchors(&self, path: &Path) -> Option<Arc<DocumentAnchors>> {
+        let key = normalize_path(path);
+        let modified = || fs::metadata(&key).and_then(|m| m.modified()).ok();
+
+        if let Some(entry) = self.entries.read().unwrap().get(&key) {
+            match entry.source {
+                EntrySource::Buffer => return Some(Arc::clone(&entry.anchors)),
+                EntrySource::Disk(read_at) if read_at == modified() => {
+                    return Some(Arc::clone(&entry.anchors));
+                }
+                EntrySource::Disk(_) => {}
+            }
+        }
+
+        let read_at = modified();
+        let content = fs::read_to_string(&key).ok()?;
+        let anchors = Arc::new(DocumentAnchors::from_source(&content));
+        self.entries.write().unwrap().insert(
+            key,
+            IndexEntry {
+                anchors: Arc::clone(&anchors),
+                source: EntrySource::Disk(read_at),
+            },
+        );
+        Some(anchors)
+    }
+}
+
+/// Make a path absolute and resolve `.` and `..` components without touching the file system
+pub fn normalize_path(path: &Path) -> PathBuf {
+    let absolute = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        std::env::current_dir()
+            .map(|dir| dir.join(path))
+            .unwrap_or_else(|_| path.to_path_buf())
+    };
+
+    let mut normalized = PathBuf::new();
+    for component in absolute.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+/// A link target relative to the document: `path/to/file.md#fragment`
+#[derive(Debug, Clone, PartialEq)]
+pub struct RelativeTarget {
+    /// Percent-decoded path, without query
+    pub path: String,
+    pub fragment: Option<String>,
+}
+
+impl RelativeTarget {
+    /// Split a link destination, returning `None` for absolute URLs, root-relative
+    /// paths and fragments into the same document (which MD051 checks)
+    pub fn parse(destination: &str) -> Option<Self> {
+        let destination = destination
+            .strip_prefix('<')
+            .and_then(|destination| destination.strip_suffix('>'))
+            .unwrap_or(destination)
+            .trim();
+        if destination.is_empty()
+            || destination.starts_with('#')
+            || destination.starts_with('/')
+            || SCHEME_PATTERN.is_match(destination)
+        {
+            return None;
+        }
+
+        let (path, fragment) = match destination.split_once('#') {
+            Some((path, fragment)) => (path, Some(percent_decode(fragment))),
+            None => (destination, None),
+        };
+        let path = path.split('?').next().unwrap_or(path);
+        Some(Self {
+            path: percent_decode(path),
+            fragment: fragment.filter(|fragment| !fragment.is_empty()),
+        })
+    }
+}
+
+/// Decode `%XX` escapes, keeping invalid sequences as they are
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = (bytes[i] == b'%')
+            .then(|| text.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8(decoded).unwrap_or_else(|_| text.to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+    use std::path::Path;
+
+    use super::{normalize_path, percent_decode, DocumentAnchors, ProjectIndex, RelativeTarget};
+
+    #[test]
+    fn test_document_anchors() {
+        let anchors = DocumentAnchors::from_source(
+            "# Getting Started\n\n## Install {#setup}\n\n## FAQ\n\n## FAQ\n\n<a id=\"custom\"></a>\n",
+        );
+        assert!(anchors.contains("getting-started", false));
+        assert!(anchors.contains("setup", false));
+        assert!(anchors.contains("install", false));
+        assert!(anchors.contains("faq", false));
+        assert!(anchors.contains("faq-1", false));
+        assert!(anchors.contains("custom", false));
+        assert!(anchors.contains("top", false));
+        assert!(anchors.contains("L10-L20", false));
+        assert!(!anchors.contains("Getting-Started", false));
+        assert!(anchors.contains("Getting-Started", true));
+        assert!(!anchors.contains("missing", true));
+    }
+
+    #[test]
+    fn test_normalize_path() {
+        assert_eq!(
+            Path::new("/docs/guide/setup.md"),
+            normalize_path(Path::new("/docs/api/../guide/./setup.md"))
+        );
+    }
+
+    #[test]
+    fn test_parse_target() {
+        assert_eq!(None, RelativeTarget::parse("https://example.com/a.md"));
+        assert_eq!(None, RelativeTarget::parse("mailto:docs@example.com"));
+        assert_eq!(None, RelativeTarget::parse("#local"));
+        assert_eq!(None, RelativeTarget::parse("/site/root.md"));
+        assert_eq!(
+            Some(RelativeTarget {
+                path: "my guide.md".to_string(),
+                fragment: Some("install".to_string()),
+            }),
+            RelativeTarget::parse("<my%20guide.md?plain=1#install>")
+        );
+        assert_eq!("100%", percent_decode("100%"));
+    }
+
+    #[test]
+    fn test_buffers_override_disk() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("guide.md");
+        fs::write(&path, "# On Disk\n").unwrap();
+
+        let index = ProjectIndex::new();
+        assert!(index.anchors(&path).unwrap().contains("on-disk", false));
+
+        assert!(index.update(&path, "# In Editor\n"));
+        assert!(!index.update(&path, "# In Editor\n\nMore text.\n"));
+        let anchors = index.anchors(&path).unwrap();
+        assert!(anchors.contains("in-editor", false));
+        assert!(!anchors.contains("on-disk", false));
+
+        index.invalidate(&path);
+        assert!(index.anchors(&path).unwrap().contains("on-disk", false));
+    }
+
+    #[test]
+    fn test_unsaved_buffers_exist() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("new.md");
+
+        let index = ProjectIndex::new();
+        assert!(!index.exists(&path));
+        assert!(index.anchors(&path).is_none());
+
+        index.update(&path, "# New\n");
+        assert!(index.exists(&path));
+        assert!(index.exists(dir.path()));
+    }
+}
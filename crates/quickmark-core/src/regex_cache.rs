@@ -0,0 +1,49 @@
+//! Regexes compiled from configuration settings, cached per thread.
+//!
+//! Rule linters are created for every document, so patterns from the configuration,
+//! such as `ignored_pattern` or the proper names of MD044, would otherwise be compiled
+//! again for each document. Each thread keeps the regexes it compiled instead; cloning a
+//! `Regex` is cheap and shares the compiled program.
+
+use std::{cell::RefCell, collections::HashMap};
+
+use regex::Regex;
+
+/// Patterns come from the configuration, so the cache only grows past this in
+/// long-running processes whose configuration keeps changing
+const MAX_CACHED_REGEXES: usize = 1024;
+
+thread_local! {
+    static REGEXES: RefCell<HashMap<String, Result<Regex, regex::Error>>> =
+        RefCell::new(HashMap::new());
+}
+
+/// Compile `pattern`, or reuse the regex this thread compiled for it before
+pub(crate) fn cached_regex(pattern: &str) -> Result<Regex, regex::Error> {
+    REGEXES.with(|regexes| {
+        let mut regexes = regexes.borrow_mut();
+        if let Some(regex) = regexes.get(pattern) {
+            return regex.clone();
+        }
+        if regexes.len() >= MAX_CACHED_REGEXES {
+            regexes.clear();
+        }
+        let regex = Regex::new(pattern);
+        regexes.insert(pattern.to_string(), regex.clone());
+        regex
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::cached_regex;
+
+    #[test]
+    fn test_cached_regex() {
+        let regex = cached_regex(r"^\d+$").unwrap();
+        assert!(regex.is_match("42"));
+        assert_eq!(regex.as_str(), cached_regex(r"^\d+$").unwrap().as_str());
+        assert!(cached_regex("(").is_err());
+        assert!(cached_regex("(").is_err());
+    }
+}
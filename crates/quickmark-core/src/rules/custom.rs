@@ -0,0 +1,605 @@
+use anyhow::{anyhow, bail, Result};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex, PoisonError, RwLock};
+
+use tree_sitter::{Language, Node, Query, QueryCursor, StreamingIterator};
+use tree_sitter_md::{INLINE_LANGUAGE, LANGUAGE};
+
+use crate::{
+    config::RuleSeverity,
+    linter::{range_from_tree_sitter, CharPosition, Context, Range, RuleLinter, RuleViolation},
+    plugin,
+    rules::{NoDocumentLinter, Rule, RuleType, ALL_RULES},
+};
+
+/// Placeholder in messages replaced by the reported text
+const TEXT_PLACEHOLDER: &str = "{text}";
+
+/// A rule declared in a `[[custom_rules]]` table
+#[derive(Debug, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct CustomRuleTable {
+    pub id: String,
+    pub alias: String,
+    /// Message of the violations, where `{text}` stands for the reported text
+    pub message: String,
+    /// Severity used unless `[linters.severity]` configures the rule
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub severity: Option<RuleSeverity>,
+    /// Kinds of the nodes to check, e.g. `atx_heading` or `inline_link`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub nodes: Vec<String>,
+    /// tree-sitter query selecting the nodes to check. The `@match` capture, or else
+    /// the first capture, is reported.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub query: Option<String>,
+    /// Regular expression each match of which is reported in the text of the nodes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+    /// Report nodes whose text does not match `pattern` instead
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub negate: bool,
+}
+
+/// A query compiled for one of the grammars, with the index of the capture to report
+struct TreeQuery {
+    query: Query,
+    capture: u32,
+}
+
+/// How a custom rule selects the nodes it checks
+enum Selector {
+    Nodes(HashSet<String>),
+    /// A query compiled for the block grammar, the inline grammar or both. Node kinds
+    /// such as `link_destination` exist in both.
+    Query {
+        block: Option<TreeQuery>,
+        inline: Option<TreeQuery>,
+    },
+}
+
+/// A custom rule compiled from its table
+pub(crate) struct CompiledCustomRule {
+    table: CustomRuleTable,
+    rule: &'static Rule,
+    selector: Selector,
+    pattern: Option<Regex>,
+}
+
+/// The custom rules of a configuration, compiled when it is loaded
+#[derive(Clone, Default)]
+pub struct CompiledCustomRules(Vec<Arc<CompiledCustomRule>>);
+
+impl CompiledCustomRules {
+    pub(crate) fn iter(&self) -> impl Iterator<Item = &Arc<CompiledCustomRule>> {
+        self.0.iter()
+    }
+}
+
+impl std::fmt::Debug for CompiledCustomRules {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list()
+            .entries(self.0.iter().map(|compiled| &compiled.table.alias))
+            .finish()
+    }
+}
+
+impl PartialEq for CompiledCustomRules {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.len() == other.0.len()
+            && self.0.iter().zip(&other.0).all(|(a, b)| a.table == b.table)
+    }
+}
+
+/// The rule of the latest compiled version of each custom rule, by alias, so that
+/// [`crate::rules::all_rules`] knows the aliases used in any configuration. A changed
+/// table replaces the rule of its alias, so editing a configuration doesn't accumulate
+/// rules.
+static CUSTOM_RULES: Lazy<RwLock<Vec<&'static Rule>>> = Lazy::new(Default::default);
+
+/// Id, alias and description of a custom rule
+type RuleKey = (String, String, String);
+
+/// Rules handed out for custom rules, by id, alias and description. Violations refer to
+/// their rule for the rest of the process, so a rule can't be freed once compiled, but
+/// recompiling a rule with the same names and message reuses it.
+static RULES: Lazy<Mutex<HashMap<RuleKey, &'static Rule>>> = Lazy::new(Default::default);
+
+/// Rules of the custom rules compiled so far, one per alias
+pub fn rules() -> Vec<&'static Rule> {
+    CUSTOM_RULES.read().unwrap().clone()
+}
+
+/// Compile a custom rule and make its alias known to [`rules`]
+pub(crate) fn compile(table: &CustomRuleTable) -> Result<Arc<CompiledCustomRule>> {
+    let compiled = Arc::new(compile_table(table)?);
+    let mut custom_rules = CUSTOM_RULES.write().unwrap();
+    match custom_rules
+        .iter_mut()
+        .find(|rule| rule.alias == table.alias)
+    {
+        Some(existing) => *existing = compiled.rule,
+        None => custom_rules.push(compiled.rule),
+    }
+    Ok(compiled)
+}
+
+/// Compile the custom rules of a configuration, which must have distinct ids and aliases
+pub(crate) fn compile_all(tables: &[CustomRuleTable]) -> Result<CompiledCustomRules> {
+    let mut compiled = Vec::with_capacity(tables.len());
+    let mut names = HashSet::new();
+    for table in tables {
+        let own: HashSet<String> = [&table.id, &table.alias]
+            .into_iter()
+            .map(|name| name.to_lowercase())
+            .collect();
+        for name in own {
+            if !names.insert(name.clone()) {
+                bail!("Custom rule `{name}` is defined more than once");
+            }
+        }
+        compiled.push(
+            compile(table).map_err(|e| anyhow!("Invalid custom rule `{}`: {e}", table.alias))?,
+        );
+    }
+    Ok(CompiledCustomRules(compiled))
+}
+
+fn compile_table(table: &CustomRuleTable) -> Result<CompiledCustomRule> {
+    for (field, value) in [
+        ("id", &table.id),
+        ("alias", &table.alias),
+        ("message", &table.message),
+    ] {
+        if value.trim().is_empty() {
+            bail!("`{field}` must not be empty");
+        }
+    }
+    let builtin = ALL_RULES.iter().chain(plugin::rules());
+    for rule in builtin {
+        for name in [&table.id, &table.alias] {
+            if rule.id.eq_ignore_ascii_case(name) || rule.alias.eq_ignore_ascii_case(name) {
+                bail!("`{name}` is already used by rule {}", rule.id);
+            }
+        }
+    }
+
+    let selector = match (&table.query, table.nodes.is_empty()) {
+        (Some(query), true) => compile_query(query)?,
+        (None, false) => {
+            let block: Language = LANGUAGE.into();
+            let inline: Language = INLINE_LANGUAGE.into();
+            for kind in &table.nodes {
+                if block.id_for_node_kind(kind, true) == 0
+                    && inline.id_for_node_kind(kind, true) == 0
+                {
+                    bail!("Unknown node kind `{kind}`");
+                }
+            }
+            Selector::Nodes(table.nodes.iter().cloned().collect())
+        }
+        _ => bail!("Exactly one of `nodes` and `query` must be set"),
+    };
+    let pattern = match &table.pattern {
+        Some(pattern) => Some(Regex::new(pattern).map_err(|e| anyhow!("Invalid `pattern`: {e}"))?),
+        None if table.negate => bail!("`negate` requires a `pattern`"),
+        None => None,
+    };
+
+    Ok(CompiledCustomRule {
+        table: table.clone(),
+        rule: rule_for(table),
+        selector,
+        pattern,
+    })
+}
+
+fn rule_for(table: &CustomRuleTable) -> &'static Rule {
+    let key = (table.id.clone(), table.alias.clone(), table.message.clone());
+    let mut rules = RULES.lock().unwrap_or_else(PoisonError::into_inner);
+    rules.entry(key).or_insert_with(|| {
+        let rule = Rule {
+            id: leak(&table.id),
+            alias: leak(&table.alias),
+            tags: &["custom"],
+            description: leak(&table.message),
+            rule_type: RuleType::Document,
+            required_nodes: &[],
+            // Custom rule linters are created by `new_linter`
+            new_linter: |_| Box::new(NoDocumentLinter),
+        };
+        Box::leak(Box::new(rule))
+    })
+}
+
+fn leak(text: &str) -> &'static str {
+    Box::leak(text.to_string().into_boxed_str())
+}
+
+/// Compile a query for each grammar it is valid for
+fn compile_query(source: &str) -> Result<Selector> {
+    let block = Query::new(&LANGUAGE.into(), source);
+    let inline = Query::new(&INLINE_LANGUAGE.into(), source);
+    let (block, inline) = match (block, inline) {
+        (Err(block_error), Err(inline_error)) => {
+            // Report the error of the grammar that got further
+            let error = if inline_error.offset > block_error.offset {
+                inline_error
+            } else {
+                block_error
+            };
+            bail!("Invalid `query`: {error}");
+        }
+        (block, inline) => (block.ok(), inline.ok()),
+    };
+    let with_capture = |query: Query| {
+        let capture = match query.capture_index_for_name("match") {
+            Some(capture) => capture,
+            None if !query.capture_names().is_empty() => 0,
+            None => bail!("`query` must capture the node to report, e.g. with `@match`"),
+        };
+        Ok(TreeQuery { query, capture })
+    };
+    Ok(Selector::Query {
+        block: block.map(with_capture).transpose()?,
+        inline: inline.map(with_capture).transpose()?,
+    })
+}
+
+/// Severities of custom rules, given the `[linters.severity]` table before it is
+/// normalized: the rule's own entry, the rule's `severity` or else the default severity
+pub(crate) fn severities(
+    tables: &[CustomRuleTable],
+    configured: &HashMap<String, RuleSeverity>,
+) -> Vec<(String, RuleSeverity)> {
+    tables
+        .iter()
+        .map(|table| {
+            let severity = configured
+                .get(&table.alias)
+                .or(table.severity.as_ref())
+                .or(configured.get("default"))
+                .cloned()
+                .unwrap_or(RuleSeverity::Error);
+            (table.alias.clone(), severity)
+        })
+        .collect()
+}
+
+impl CompiledCustomRule {
+    pub(crate) fn rule(&self) -> &'static Rule {
+        self.rule
+    }
+
+    pub(crate) fn new_linter(self: Arc<Self>, context: Arc<Context>) -> Box<dyn RuleLinter> {
+        Box::new(CustomRuleLinter {
+            context,
+            compiled: self,
+            violations: Vec::new(),
+        })
+    }
+}
+
+/// Checks the nodes selected by a custom rule against its pattern
+struct CustomRuleLinter {
+    context: Arc<Context>,
+    compiled: Arc<CompiledCustomRule>,
+    violations: Vec<RuleViolation>,
+}
+
+impl CustomRuleLinter {
+    /// Nodes matched by the query, run once on the root of each tree
+    fn query_nodes<'tree>(&self, node: &Node<'tree>) -> Vec<Node<'tree>> {
+        let Selector::Query { block, inline } = &self.compiled.selector else {
+            return Vec::new();
+        };
+        let (tree_query, root) = match node.parent() {
+            None if node.kind() == "document" => (block, *node),
+            // The walker skips the roots of inline trees, so inline queries run when
+            // the first node of an inline tree is fed
+            Some(parent)
+                if node.prev_sibling().is_none()
+                    && parent.parent().is_none()
+                    && parent.kind() == "inline" =>
+            {
+                (inline, parent)
+            }
+            _ => return Vec::new(),
+        };
+        let Some(TreeQuery { query, capture }) = tree_query else {
+            return Vec::new();
+        };
+
+        let source = &self.context.document_content;
+        let mut cursor = QueryCursor::new();
+        let mut matches = cursor.matches(query, root, source.as_bytes());
+        let mut nodes = Vec::new();
+        while let Some(query_match) = matches.next() {
+            let reported = query_match
+                .captures
+                .iter()
+                .find(|capture_node| capture_node.index == *capture)
+                .or_else(|| query_match.captures.first());
+            if let Some(reported) = reported {
+                if !nodes.contains(&reported.node) {
+                    nodes.push(reported.node);
+                }
+            }
+        }
+        nodes
+    }
+
+    fn check(&mut self, node: &Node) {
+        let text = {
+            let source = &self.context.document_content;
+            source[node.start_byte()..node.end_byte()].to_string()
+        };
+        let node_range = range_from_tree_sitter(&node.range());
+        match &self.compiled.pattern {
+            None => self.report(&text, node_range),
+            Some(pattern) if self.compiled.table.negate => {
+                if !pattern.is_match(&text) {
+                    self.report(&text, node_range);
+                }
+            }
+            Some(pattern) => {
+                let matches: Vec<_> = pattern
+                    .find_iter(&text)
+                    .filter(|found| !found.is_empty())
+                    .map(|found| {
+                        let start = position(&text, found.start(), &node_range.start);
+                        let end = position(&text, found.end(), &node_range.start);
+                        (found.as_str().to_string(), Range { start, end })
+                    })
+                    .collect();
+                for (found, range) in matches {
+                    self.report(&found, range);
+                }
+            }
+        }
+    }
+
+    fn report(&mut self, text: &str, range: Range) {
+        let message = self
+            .compiled
+            .table
+            .message
+            .replace(TEXT_PLACEHOLDER, text.trim());
+        self.violations.push(RuleViolation::new(
+            self.compiled.rule,
+            message,
+            self.context.file_path.clone(),
+            range,
+        ));
+    }
+}
+
+/// Position of byte `offset` in `text`, which starts at `start`
+fn position(text: &str, offset: usize, start: &CharPosition) -> CharPosition {
+    let before = &text[..offset];
+    match before.rfind('\n') {
+        Some(newline) => CharPosition {
+            line: start.line + before.matches('\n').count(),
+            character: offset - newline - 1,
+        },
+        None => CharPosition {
+            line: start.line,
+            character: start.character + offset,
+        },
+    }
+}
+
+impl RuleLinter for CustomRuleLinter {
+    fn feed(&mut self, node: &Node) {
+        let nodes = match &self.compiled.selector {
+            Selector::Nodes(kinds) if kinds.contains(node.kind()) => vec![*node],
+            Selector::Nodes(_) => return,
+            Selector::Query { .. } => self.query_nodes(node),
+        };
+        for node in nodes {
+            self.check(&node);
+        }
+    }
+
+    fn finalize(&mut self) -> Vec<RuleViolation> {
+        std::mem::take(&mut self.violations)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use crate::config::{parse_toml_config, RuleSeverity};
+    use crate::linter::MultiRuleLinter;
+
+    fn lint(config: &str, path: &str, input: &str) -> Vec<(usize, usize, String)> {
+        let config = parse_toml_config(config).unwrap();
+        let config = config.for_file(&PathBuf::from(path)).unwrap();
+        let mut linter = MultiRuleLinter::new_for_document(PathBuf::from(path), config, input);
+        linter
+            .analyze()
+            .iter()
+            .map(|violation| {
+                let start = &violation.location().range.start;
+                (start.line, start.character, violation.message().to_string())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_forbidden_words() {
+        let config = r#"
+[linters.severity]
+default = 'off'
+
+[[custom_rules]]
+id = "CUSTOM001"
+alias = "no-simply"
+message = "Avoid '{text}'"
+severity = "warn"
+nodes = ["inline"]
+pattern = "(?i)\\b(simply|just)\\b"
+"#;
+        let input = "# Simply put\n\nIt's just a\nsimple matter, `simply`.\n\n    simply code\n";
+        assert_eq!(
+            vec![
+                (0, 2, "Avoid 'Simply'".to_string()),
+                (2, 5, "Avoid 'just'".to_string()),
+                (3, 16, "Avoid 'simply'".to_string()),
+            ],
+            lint(config, "doc.md", input)
+        );
+
+        let parsed = parse_toml_config(config).unwrap();
+        assert_eq!(
+            Some(&RuleSeverity::Warning),
+            parsed.linters.severity.get("no-simply")
+        );
+    }
+
+    #[test]
+    fn test_query_with_negated_pattern() {
+        let config = r#"
+[linters.severity]
+default = 'off'
+fence-language = 'off'
+
+[[custom_rules]]
+id = "CUSTOM002"
+alias = "fence-language"
+message = "Code fences must use `rust` or `console`, found `{text}`"
+query = "(fenced_code_block (info_string (language) @match))"
+pattern = "^(rust|console)$"
+negate = true
+
+[[overrides]]
+files = ["docs/api/**"]
+
+[overrides.severity]
+fence-language = 'err'
+"#;
+        let input = "```rust\nfn main() {}\n```\n\n```python\nprint()\n```\n\n```\nplain\n```\n";
+        assert_eq!(
+            vec![(
+                4,
+                3,
+                "Code fences must use `rust` or `console`, found `python`".to_string()
+            )],
+            lint(config, "docs/api/index.md", input)
+        );
+        assert!(lint(config, "docs/guide.md", input).is_empty());
+    }
+
+    #[test]
+    fn test_query_predicates_and_inline_grammar() {
+        let config = r#"
+[linters.severity]
+default = 'off'
+
+[[custom_rules]]
+id = "CUSTOM003"
+alias = "title-case-h2"
+message = "H2 headings must be Title Case"
+severity = "err"
+query = '(atx_heading (atx_h2_marker) heading_content: (inline) @match (#not-match? @match "^([A-Z0-9][^ ]*( |$))+$"))'
+
+[[custom_rules]]
+id = "CUSTOM004"
+alias = "no-http-links"
+message = "Use https for {text}"
+severity = "err"
+query = '((link_destination) @match (#match? @match "^http:"))'
+"#;
+        let input = "# intro\n\n## Getting Started\n\n## Next steps\n\nSee [a](http://a.com) and [b](https://b.com).\n\n<!-- quickmark-disable-next-line no-http-links -->\n[c](http://c.com)\n";
+        assert_eq!(
+            vec![
+                (4, 3, "H2 headings must be Title Case".to_string()),
+                (6, 8, "Use https for http://a.com".to_string()),
+            ],
+            lint(config, "doc.md", input)
+        );
+    }
+
+    #[test]
+    fn test_invalid_custom_rules() {
+        let error = |table: &str| {
+            let config = format!("[[custom_rules]]\nid = \"CUSTOM009\"\nalias = \"custom-rule\"\nmessage = \"Message\"\n{table}");
+            format!("{:#}", parse_toml_config(&config).unwrap_err())
+        };
+        assert!(error("").contains("Exactly one of `nodes` and `query` must be set"));
+        assert!(error("nodes = [\"heading\"]").contains("Unknown node kind `heading`"));
+        assert!(error("nodes = [\"inline\"]\npattern = \"(\"").contains("Invalid `pattern`"));
+        assert!(error("query = \"(atx_heading\"").contains("Invalid `query`"));
+        assert!(error("query = \"(atx_heading)\"").contains("must capture the node"));
+        assert!(error("nodes = [\"inline\"]\nnegate = true").contains("requires a `pattern`"));
+
+        let clash = "[[custom_rules]]\nid = \"CUSTOM010\"\nalias = \"line-length\"\nmessage = \"M\"\nnodes = [\"inline\"]\n";
+        let message = format!("{:#}", parse_toml_config(clash).unwrap_err());
+        assert!(
+            message.contains("`line-length` is already used by rule MD013"),
+            "{message}"
+        );
+    }
+
+    #[test]
+    fn test_configurations_keep_their_own_rules() {
+        let config = |pattern: &str| {
+            format!(
+                "[linters.severity]\ndefault = 'off'\n\n[[custom_rules]]\nid = \"CUSTOM901\"\nalias = \"shared-alias\"\nmessage = \"Found {{text}}\"\nseverity = \"err\"\nnodes = [\"inline\"]\npattern = \"{pattern}\"\n"
+            )
+        };
+        let foo = parse_toml_config(&config("foo")).unwrap();
+        let bar = parse_toml_config(&config("bar")).unwrap();
+        let lint = |config: &crate::config::QuickmarkConfig| {
+            MultiRuleLinter::new_for_document(PathBuf::from("doc.md"), config.clone(), "foo bar\n")
+                .analyze()
+                .iter()
+                .map(|violation| violation.message().to_string())
+                .collect::<Vec<_>>()
+        };
+        for _ in 0..2 {
+            assert_eq!(vec!["Found foo"], lint(&foo));
+            assert_eq!(vec!["Found bar"], lint(&bar));
+        }
+
+        // Compile errors are reported when the configuration is loaded
+        let error = parse_toml_config(&config("(")).unwrap_err();
+        assert!(
+            format!("{error:#}").contains("Invalid custom rule `shared-alias`"),
+            "{error:#}"
+        );
+    }
+
+    #[test]
+    fn test_changed_table_replaces_rule() {
+        let table = super::CustomRuleTable {
+            id: "CUSTOM900".to_string(),
+            alias: "replaced-rule".to_string(),
+            message: "Found {text}".to_string(),
+            nodes: vec!["inline".to_string()],
+            pattern: Some("foo".to_string()),
+            ..Default::default()
+        };
+        let first = super::compile(&table).unwrap();
+        let edited = super::CustomRuleTable {
+            pattern: Some("bar".to_string()),
+            message: "Found {text} again".to_string(),
+            ..table.clone()
+        };
+        let second = super::compile(&edited).unwrap();
+
+        let registered: Vec<_> = super::rules()
+            .into_iter()
+            .filter(|rule| rule.alias == "replaced-rule")
+            .collect();
+        assert_eq!(1, registered.len());
+        assert!(std::ptr::eq(second.rule(), registered[0]));
+        // Same names and message, same rule
+        let third = super::compile(&table).unwrap();
+        assert!(std::ptr::eq(first.rule(), third.rule()));
+    }
+}
@@ -1,4 +1,4 @@
-use std::rc::Rc;
+use std::sync::Arc;
 
 use tree_sitter::Node;
 
@@ -8,13 +8,13 @@ use crate::{
 };
 
 pub(crate) struct MD001Linter {
-    context: Rc<Context>,
+    context: Arc<Context>,
     current_heading_level: u8,
     violations: Vec<RuleViolation>,
 }
 
 impl MD001Linter {
-    pub fn new(context: Rc<Context>) -> Self {
+    pub fn new(context: Arc<Context>) -> Self {
         Self {
             context,
             current_heading_level: 0,
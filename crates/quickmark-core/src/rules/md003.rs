@@ -1,6 +1,6 @@
 use core::fmt;
-use serde::Deserialize;
-use std::rc::Rc;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 use tree_sitter::Node;
 
 use crate::linter::{range_from_tree_sitter, Context, RuleLinter, RuleViolation};
@@ -8,9 +8,10 @@ use crate::linter::{range_from_tree_sitter, Context, RuleLinter, RuleViolation};
 use super::{Rule, RuleType};
 
 // MD003-specific configuration types
-#[derive(Debug, PartialEq, Clone, Deserialize)]
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize, Default)]
 pub enum HeadingStyle {
     #[serde(rename = "consistent")]
+    #[default]
     Consistent,
     #[serde(rename = "atx")]
     ATX,
@@ -24,15 +25,9 @@ pub enum HeadingStyle {
     SetextWithATXClosed,
 }
 
-impl Default for HeadingStyle {
-    fn default() -> Self {
-        Self::Consistent
-    }
-}
-
-#[derive(Debug, PartialEq, Clone, Deserialize)]
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(default)]
 pub struct MD003HeadingStyleTable {
-    #[serde(default)]
     pub style: HeadingStyle,
 }
 
@@ -62,13 +57,13 @@ impl fmt::Display for Style {
 }
 
 pub(crate) struct MD003Linter {
-    context: Rc<Context>,
+    context: Arc<Context>,
     enforced_style: Option<Style>,
     violations: Vec<RuleViolation>,
 }
 
 impl MD003Linter {
-    pub fn new(context: Rc<Context>) -> Self {
+    pub fn new(context: Arc<Context>) -> Self {
         // Access MD003 config through the centralized config structure
         let md003_config = &context.config.linters.settings.heading_style;
         let enforced_style = match md003_config.style {
@@ -1,20 +1,21 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::rc::Rc;
+use std::sync::Arc;
 
 use tree_sitter::Node;
 
 use crate::{
-    linter::{range_from_tree_sitter, RuleViolation},
+    linter::{range_from_tree_sitter, CharPosition, Edit, Range, RuleViolation},
     rules::{Context, Rule, RuleLinter, RuleType},
 };
 
 // MD004-specific configuration types
-#[derive(Debug, PartialEq, Clone, Deserialize)]
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize, Default)]
 pub enum UlStyle {
     #[serde(rename = "asterisk")]
     Asterisk,
     #[serde(rename = "consistent")]
+    #[default]
     Consistent,
     #[serde(rename = "dash")]
     Dash,
@@ -24,15 +25,9 @@ pub enum UlStyle {
     Sublist,
 }
 
-impl Default for UlStyle {
-    fn default() -> Self {
-        Self::Consistent
-    }
-}
-
-#[derive(Debug, PartialEq, Clone, Deserialize)]
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(default)]
 pub struct MD004UlStyleTable {
-    #[serde(default)]
     pub style: UlStyle,
 }
 
@@ -45,14 +40,14 @@ impl Default for MD004UlStyleTable {
 }
 
 pub(crate) struct MD004Linter {
-    context: Rc<Context>,
+    context: Arc<Context>,
     violations: Vec<RuleViolation>,
     nesting_styles: HashMap<usize, char>, // Track expected markers by nesting level for sublist style
     document_expected_style: Option<char>, // Track expected style for the entire document in consistent mode
 }
 
 impl MD004Linter {
-    pub fn new(context: Rc<Context>) -> Self {
+    pub fn new(context: Arc<Context>) -> Self {
         Self {
             context,
             violations: Vec::new(),
@@ -89,10 +84,11 @@ impl MD004Linter {
         }
     }
 
-    /// Find list item markers within a list node
-    fn find_list_item_markers<'a>(&self, list_node: &Node<'a>) -> Vec<(Node<'a>, char)> {
+    /// Find list item markers within a list node, along with the marker character's
+    /// byte offset within the marker node
+    fn find_list_item_markers<'a>(&self, list_node: &Node<'a>) -> Vec<(Node<'a>, char, usize)> {
         let mut markers = Vec::new();
-        let content = self.context.document_content.borrow();
+        let content = &self.context.document_content;
         let source_bytes = content.as_bytes();
         let mut list_cursor = list_node.walk();
 
@@ -102,12 +98,11 @@ impl MD004Linter {
                 let mut item_cursor = list_item.walk();
                 for child in list_item.children(&mut item_cursor) {
                     if child.kind().starts_with("list_marker") {
-                        if let Some(marker_char) = child
-                            .utf8_text(source_bytes)
-                            .ok()
-                            .and_then(Self::extract_marker)
-                        {
-                            markers.push((child, marker_char));
+                        if let Ok(text) = child.utf8_text(source_bytes) {
+                            if let Some(marker_char) = Self::extract_marker(text) {
+                                let offset = text.len() - text.trim_start().len();
+                                markers.push((child, marker_char, offset));
+                            }
                         }
                         // Once we find a marker for a list_item, we can stop searching its children.
                         break;
@@ -138,11 +133,11 @@ impl MD004Linter {
         let style = &self.context.config.linters.settings.ul_style.style;
 
         // Extract marker information immediately to avoid lifetime issues
-        let marker_info: Vec<(tree_sitter::Range, char)> = {
+        let marker_info: Vec<(tree_sitter::Range, char, usize)> = {
             let markers = self.find_list_item_markers(node);
             markers
                 .into_iter()
-                .map(|(node, marker)| (node.range(), marker))
+                .map(|(node, marker, offset)| (node.range(), marker, offset))
                 .collect()
         };
 
@@ -188,7 +183,7 @@ impl MD004Linter {
                     expected_marker = Some(
                         marker_info
                             .first()
-                            .map(|(_, marker)| *marker)
+                            .map(|(_, marker, _)| *marker)
                             .unwrap_or('*'),
                     );
                 }
@@ -202,8 +197,20 @@ impl MD004Linter {
 
         // Check all markers against expected and collect violations
         if let Some(expected) = expected_marker {
-            for (range, actual_marker) in marker_info {
+            for (range, actual_marker, offset) in marker_info {
                 if actual_marker != expected {
+                    let marker_start = CharPosition {
+                        line: range.start_point.row,
+                        character: range.start_point.column + offset,
+                    };
+                    let marker_range = Range {
+                        end: CharPosition {
+                            line: marker_start.line,
+                            character: marker_start.character + 1,
+                        },
+                        start: marker_start,
+                    };
+
                     let message = format!(
                         "{} [Expected: {}; Actual: {}]",
                         MD004.description,
@@ -211,12 +218,15 @@ impl MD004Linter {
                         Self::marker_to_style_name(actual_marker)
                     );
 
-                    self.violations.push(RuleViolation::new(
-                        &MD004,
-                        message,
-                        self.context.file_path.clone(),
-                        range_from_tree_sitter(&range),
-                    ));
+                    self.violations.push(
+                        RuleViolation::new(
+                            &MD004,
+                            message,
+                            self.context.file_path.clone(),
+                            range_from_tree_sitter(&range),
+                        )
+                        .with_fix(vec![Edit::replace(marker_range, expected.to_string())]),
+                    );
                 }
             }
         }
@@ -247,7 +257,7 @@ impl MD004Linter {
                 let mut item_cursor = list_item.walk();
                 for child in list_item.children(&mut item_cursor) {
                     if child.kind().starts_with("list_marker") {
-                        let content = self.context.document_content.borrow();
+                        let content = &self.context.document_content;
                         if let Ok(text) = child.utf8_text(content.as_bytes()) {
                             if let Some(marker_char) = text.trim().chars().next() {
                                 return matches!(marker_char, '*' | '+' | '-');
@@ -595,4 +605,13 @@ Some paragraph text
         // First list uses asterisk, so second list using dash should violate
         assert_eq!(2, violations.len());
     }
+
+    #[test]
+    fn test_fix_replaces_markers() {
+        let input = "* Item 1\n+ Item 2\n  - Nested\n";
+        let result =
+            MultiRuleLinter::fix_document(PathBuf::from("test.md"), test_config_dash(), input);
+        assert_eq!("- Item 1\n- Item 2\n  - Nested\n", result.content);
+        assert!(result.remaining.is_empty());
+    }
 }
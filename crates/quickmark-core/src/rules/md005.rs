@@ -1,4 +1,4 @@
-use std::rc::Rc;
+use std::sync::Arc;
 
 use tree_sitter::Node;
 
@@ -8,12 +8,12 @@ use crate::{
 };
 
 pub(crate) struct MD005Linter {
-    context: Rc<Context>,
+    context: Arc<Context>,
     violations: Vec<RuleViolation>,
 }
 
 impl MD005Linter {
-    pub fn new(context: Rc<Context>) -> Self {
+    pub fn new(context: Arc<Context>) -> Self {
         Self {
             context,
             violations: Vec::new(),
@@ -41,10 +41,8 @@ impl MD005Linter {
             return;
         }
 
-        let is_ordered = Self::is_ordered_list_static(
-            list_node,
-            self.context.document_content.borrow().as_bytes(),
-        );
+        let is_ordered =
+            Self::is_ordered_list_static(list_node, self.context.document_content.as_bytes());
 
         if is_ordered {
             self.check_ordered_list_indentation(list_node, &list_items);
@@ -148,7 +146,7 @@ impl MD005Linter {
             .children(&mut cursor)
             .find(|c| c.kind().starts_with("list_marker"))
         {
-            let content = self.context.document_content.borrow();
+            let content = &self.context.document_content;
             if let Ok(text) = marker_node.utf8_text(content.as_bytes()) {
                 return text.trim().len();
             }
@@ -157,7 +155,7 @@ impl MD005Linter {
     }
 
     fn get_list_item_indentation(&self, list_item: &Node) -> usize {
-        let content = self.context.document_content.borrow();
+        let content = &self.context.document_content;
         let start_line = list_item.start_position().row;
 
         if let Some(line) = content.lines().nth(start_line) {
@@ -1,5 +1,5 @@
-use serde::Deserialize;
-use std::rc::Rc;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 
 use tree_sitter::Node;
 
@@ -9,13 +9,11 @@ use crate::{
 };
 
 // MD007-specific configuration types
-#[derive(Debug, PartialEq, Clone, Deserialize)]
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(default)]
 pub struct MD007UlIndentTable {
-    #[serde(default)]
     pub indent: usize,
-    #[serde(default)]
     pub start_indent: usize,
-    #[serde(default)]
     pub start_indented: bool,
 }
 
@@ -30,12 +28,12 @@ impl Default for MD007UlIndentTable {
 }
 
 pub(crate) struct MD007Linter {
-    context: Rc<Context>,
+    context: Arc<Context>,
     violations: Vec<RuleViolation>,
 }
 
 impl MD007Linter {
-    pub fn new(context: Rc<Context>) -> Self {
+    pub fn new(context: Arc<Context>) -> Self {
         Self {
             context,
             violations: Vec::new(),
@@ -66,7 +64,7 @@ impl MD007Linter {
             let mut item_cursor = first_item.walk();
             for child in first_item.children(&mut item_cursor) {
                 if child.kind().starts_with("list_marker") {
-                    let content = self.context.document_content.borrow();
+                    let content = &self.context.document_content;
                     if let Ok(text) = child.utf8_text(content.as_bytes()) {
                         // Check if it's an unordered list marker
                         if let Some(marker_char) = text.trim().chars().next() {
@@ -121,7 +119,7 @@ impl MD007Linter {
     }
 
     fn get_list_item_indentation(&self, list_item: &Node) -> usize {
-        let content = self.context.document_content.borrow();
+        let content = &self.context.document_content;
         let start_line = list_item.start_position().row;
 
         if let Some(line) = content.lines().nth(start_line) {
@@ -1,22 +1,20 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
-use std::rc::Rc;
+use std::sync::Arc;
 
 use tree_sitter::Node;
 
 use crate::{
-    linter::{range_from_tree_sitter, RuleViolation},
+    linter::{range_from_tree_sitter, Edit, RuleViolation},
     rules::{Context, Rule, RuleLinter, RuleType},
 };
 
 // MD009-specific configuration types
-#[derive(Debug, PartialEq, Clone, Deserialize)]
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(default)]
 pub struct MD009TrailingSpacesTable {
-    #[serde(default)]
     pub br_spaces: usize,
-    #[serde(default)]
     pub list_item_empty_lines: bool,
-    #[serde(default)]
     pub strict: bool,
 }
 
@@ -36,12 +34,12 @@ impl Default for MD009TrailingSpacesTable {
 /// After processing a document (via feed() calls and finalize()), the linter
 /// should be discarded. The violations state is not cleared between uses.
 pub(crate) struct MD009Linter {
-    context: Rc<Context>,
+    context: Arc<Context>,
     violations: Vec<RuleViolation>,
 }
 
 impl MD009Linter {
-    pub fn new(context: Rc<Context>) -> Self {
+    pub fn new(context: Arc<Context>) -> Self {
         Self {
             context,
             violations: Vec::new(),
@@ -52,7 +50,7 @@ impl MD009Linter {
     /// Context cache is already initialized by MultiRuleLinter
     fn analyze_all_lines(&mut self) {
         let settings = &self.context.config.linters.settings.trailing_spaces;
-        let lines = self.context.lines.borrow();
+        let lines = &self.context.lines;
 
         // Determine effective br_spaces (< 2 becomes 0)
         let expected_spaces = if settings.br_spaces < 2 {
@@ -74,6 +72,7 @@ impl MD009Linter {
             let trailing_spaces = line.len() - line.trim_end().len();
 
             if trailing_spaces > 0
+                && !self.context.is_front_matter_line(line_index)
                 && !code_block_lines.contains(&line_number)
                 && !list_item_empty_lines.contains(&line_number)
             {
@@ -99,10 +98,10 @@ impl MD009Linter {
     /// Returns a set of line numbers that are part of code blocks.
     /// This is performant as it uses the pre-parsed node cache.
     fn get_code_block_lines(&self) -> HashSet<usize> {
-        let node_cache = self.context.node_cache.borrow();
+        let node_cache = &self.context.node_cache;
         ["indented_code_block", "fenced_code_block"]
             .iter()
-            .filter_map(|kind| node_cache.get(*kind))
+            .filter_map(|kind| node_cache.get(kind))
             .flatten()
             .flat_map(|node_info| (node_info.line_start + 1)..=(node_info.line_end + 1))
             .collect()
@@ -111,8 +110,8 @@ impl MD009Linter {
     /// Returns a set of line numbers for empty lines within list items.
     /// This is more robust and performant than manual parsing, as it relies on the AST.
     fn get_list_item_empty_lines(&self) -> HashSet<usize> {
-        let node_cache = self.context.node_cache.borrow();
-        let lines = self.context.lines.borrow();
+        let node_cache = &self.context.node_cache;
+        let lines = &self.context.lines;
 
         node_cache.get("list").map_or_else(HashSet::new, |lists| {
             lists
@@ -168,26 +167,29 @@ impl MD009Linter {
         let start_column = line.trim_end().len();
         let end_column = line.len();
 
+        let range = range_from_tree_sitter(&tree_sitter::Range {
+            // FIXME: Byte offsets are not correctly calculated as line start offset is unavailable here.
+            // This may result in incorrect highlighting in some tools.
+            // The primary information is in the points (row/column).
+            start_byte: 0,
+            end_byte: 0,
+            start_point: tree_sitter::Point {
+                row: line_index,
+                column: start_column,
+            },
+            end_point: tree_sitter::Point {
+                row: line_index,
+                column: end_column,
+            },
+        });
+
         RuleViolation::new(
             &MD009,
             message,
             self.context.file_path.clone(),
-            range_from_tree_sitter(&tree_sitter::Range {
-                // FIXME: Byte offsets are not correctly calculated as line start offset is unavailable here.
-                // This may result in incorrect highlighting in some tools.
-                // The primary information is in the points (row/column).
-                start_byte: 0,
-                end_byte: 0,
-                start_point: tree_sitter::Point {
-                    row: line_index,
-                    column: start_column,
-                },
-                end_point: tree_sitter::Point {
-                    row: line_index,
-                    column: end_column,
-                },
-            }),
+            range.clone(),
         )
+        .with_fix(vec![Edit::delete(range)])
     }
 }
 
@@ -213,7 +215,7 @@ pub const MD009: Rule = Rule {
     rule_type: RuleType::Line,
     // This is a line-based rule and does not require specific nodes from the AST.
     // The logic runs once for the entire file content.
-    required_nodes: &[],
+    required_nodes: &["indented_code_block", "fenced_code_block", "list"],
     new_linter: |context| Box::new(MD009Linter::new(context)),
 };
 
@@ -515,4 +517,28 @@ Normal paragraph without any trailing spaces."#;
         );
         assert!(!line_numbers.contains(&2), "Line 2 should NOT be reported (trailing spaces before empty line create actual line break)");
     }
+
+    #[test]
+    fn test_fix_removes_trailing_spaces() {
+        #[rustfmt::skip]
+        let input = "Line with one space \nLine with break  \nLine with three   \nEnd";
+
+        let result = MultiRuleLinter::fix_document(PathBuf::from("test.md"), test_config(), input);
+        assert_eq!(
+            "Line with one space\nLine with break  \nLine with three\nEnd",
+            result.content
+        );
+        assert!(result.remaining.is_empty());
+    }
+
+    #[test]
+    fn test_front_matter_is_ignored() {
+        let input = "---\ntitle: Trailing   \n---\n# Heading   \n";
+
+        let config = test_config();
+        let mut linter = MultiRuleLinter::new_for_document(PathBuf::from("test.md"), config, input);
+        let violations = linter.analyze();
+        assert_eq!(1, violations.len());
+        assert_eq!(3, violations[0].location().range.start.line);
+    }
 }
@@ -1,21 +1,19 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
-use std::rc::Rc;
+use std::sync::Arc;
 use tree_sitter::Node;
 
 use crate::{
-    linter::{range_from_tree_sitter, RuleViolation},
+    linter::{range_from_tree_sitter, Edit, RuleViolation},
     rules::{Context, Rule, RuleLinter, RuleType},
 };
 
 // MD010-specific configuration types
-#[derive(Debug, PartialEq, Clone, Deserialize)]
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(default)]
 pub struct MD010HardTabsTable {
-    #[serde(default)]
     pub code_blocks: bool,
-    #[serde(default)]
     pub ignore_code_languages: Vec<String>,
-    #[serde(default)]
     pub spaces_per_tab: usize,
 }
 
@@ -35,12 +33,12 @@ impl Default for MD010HardTabsTable {
 /// After processing a document (via feed() calls and finalize()), the linter
 /// should be discarded. The violations state is not cleared between uses.
 pub(crate) struct MD010Linter {
-    context: Rc<Context>,
+    context: Arc<Context>,
     violations: Vec<RuleViolation>,
 }
 
 impl MD010Linter {
-    pub fn new(context: Rc<Context>) -> Self {
+    pub fn new(context: Arc<Context>) -> Self {
         Self {
             context,
             violations: Vec::new(),
@@ -51,7 +49,7 @@ impl MD010Linter {
     /// Context cache is already initialized by MultiRuleLinter.
     fn analyze_all_lines(&mut self) {
         let settings = &self.context.config.linters.settings.hard_tabs;
-        let lines = self.context.lines.borrow();
+        let lines = &self.context.lines;
 
         // Determine which lines to exclude from hard tab checks.
         // If `code_blocks` is true (default), we check tabs in code blocks,
@@ -66,7 +64,9 @@ impl MD010Linter {
         for (line_index, line) in lines.iter().enumerate() {
             let line_number = line_index + 1;
 
-            if excluded_lines.contains(&line_number) {
+            if excluded_lines.contains(&line_number)
+                || self.context.is_front_matter_line(line_index)
+            {
                 continue;
             }
 
@@ -91,11 +91,11 @@ impl MD010Linter {
             return HashSet::new();
         }
 
-        let node_cache = self.context.node_cache.borrow();
+        let node_cache = &self.context.node_cache;
         let mut excluded_lines = HashSet::new();
 
         if let Some(fenced_code_blocks) = node_cache.get("fenced_code_block") {
-            let lines = self.context.lines.borrow();
+            let lines = &self.context.lines;
             for node_info in fenced_code_blocks {
                 if let Some(first_line) = lines.get(node_info.line_start) {
                     if let Some(language) = self.extract_code_block_language(first_line) {
@@ -114,10 +114,10 @@ impl MD010Linter {
 
     /// Returns a set of all line numbers that are part of any code block.
     fn get_all_code_block_lines(&self) -> HashSet<usize> {
-        let node_cache = self.context.node_cache.borrow();
+        let node_cache = &self.context.node_cache;
         ["indented_code_block", "fenced_code_block"]
             .iter()
-            .filter_map(|kind| node_cache.get(*kind))
+            .filter_map(|kind| node_cache.get(kind))
             .flatten()
             .flat_map(|node_info| (node_info.line_start + 1)..=(node_info.line_end + 1))
             .collect()
@@ -154,26 +154,29 @@ impl MD010Linter {
             format!("Hard tabs (replace with {spaces_per_tab} spaces)")
         };
 
+        let range = range_from_tree_sitter(&tree_sitter::Range {
+            // FIXME: Byte offsets are not correctly calculated as line start offset is unavailable here.
+            // This may result in incorrect highlighting in some tools.
+            // The primary information is in the points (row/column).
+            start_byte: 0,
+            end_byte: 0,
+            start_point: tree_sitter::Point {
+                row: line_index,
+                column: tab_position,
+            },
+            end_point: tree_sitter::Point {
+                row: line_index,
+                column: tab_position + 1,
+            },
+        });
+
         RuleViolation::new(
             &MD010,
             message,
             self.context.file_path.clone(),
-            range_from_tree_sitter(&tree_sitter::Range {
-                // FIXME: Byte offsets are not correctly calculated as line start offset is unavailable here.
-                // This may result in incorrect highlighting in some tools.
-                // The primary information is in the points (row/column).
-                start_byte: 0,
-                end_byte: 0,
-                start_point: tree_sitter::Point {
-                    row: line_index,
-                    column: tab_position,
-                },
-                end_point: tree_sitter::Point {
-                    row: line_index,
-                    column: tab_position + 1,
-                },
-            }),
+            range.clone(),
         )
+        .with_fix(vec![Edit::replace(range, " ".repeat(spaces_per_tab))])
     }
 }
 
@@ -199,7 +202,7 @@ pub const MD010: Rule = Rule {
     rule_type: RuleType::Line,
     // This is a line-based rule and does not require specific nodes from the AST.
     // The logic runs once for the entire file content.
-    required_nodes: &[],
+    required_nodes: &["indented_code_block", "fenced_code_block"],
     new_linter: |context| Box::new(MD010Linter::new(context)),
 };
 
@@ -350,4 +353,29 @@ Another	line	with	tabs"###;
         let violations = linter.analyze();
         assert_eq!(4, violations.len()); // Should report violations for each tab (1 + 3 tabs)
     }
+
+    #[test]
+    fn test_fix_replaces_tabs_with_spaces() {
+        let config = test_config_with_hard_tabs(MD010HardTabsTable {
+            code_blocks: true,
+            ignore_code_languages: Vec::new(),
+            spaces_per_tab: 4,
+        });
+        let input = "Text\twith\ttabs\n";
+
+        let result = MultiRuleLinter::fix_document(PathBuf::from("test.md"), config, input);
+        assert_eq!("Text    with    tabs\n", result.content);
+        assert!(result.remaining.is_empty());
+    }
+
+    #[test]
+    fn test_front_matter_is_ignored() {
+        let input = "+++\ntitle =\t\"Tabs\"\n+++\n\tText\n";
+
+        let config = test_config();
+        let mut linter = MultiRuleLinter::new_for_document(PathBuf::from("test.md"), config, input);
+        let violations = linter.analyze();
+        assert_eq!(1, violations.len());
+        assert_eq!(3, violations[0].location().range.start.line);
+    }
 }
@@ -1,6 +1,6 @@
 use once_cell::sync::Lazy;
 use regex::Regex;
-use std::rc::Rc;
+use std::sync::Arc;
 use tree_sitter::Node;
 
 use crate::{
@@ -11,50 +11,55 @@ use crate::{
 static REVERSED_LINK_REGEX: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"(^|[^\\])\(([^()]+)\)\[([^\]^][^\]]*)\]").unwrap());
 
-static INLINE_CODE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"`([^`]+)`").unwrap());
-
 /// MD011 Reversed Link Syntax Rule Linter
 ///
 /// **SINGLE-USE CONTRACT**: This linter is designed for one-time use only.
 /// After processing a document (via feed() calls and finalize()), the linter
 /// should be discarded. The violations state is not cleared between uses.
 pub(crate) struct MD011Linter {
-    context: Rc<Context>,
+    context: Arc<Context>,
     violations: Vec<RuleViolation>,
     line_offsets: Vec<usize>,
+    /// Byte ranges of the code spans in the document
+    code_spans: Vec<(usize, usize)>,
 }
 
 impl MD011Linter {
-    pub fn new(context: Rc<Context>) -> Self {
-        let line_offsets = context
-            .lines
-            .borrow()
+    pub fn new(context: Arc<Context>) -> Self {
+        let line_offsets = std::iter::once(0)
+            .chain(
+                context
+                    .get_document_content()
+                    .match_indices('\n')
+                    .map(|(index, _)| index + 1),
+            )
+            .collect();
+        let code_spans = context
+            .get_inline_nodes(&["code_span"])
             .iter()
-            .scan(0, |state, line| {
-                let offset = *state;
-                // Assuming LF line endings. The +1 accounts for the newline character.
-                *state += line.len() + 1;
-                Some(offset)
-            })
+            .map(|span| (span.start_byte, span.end_byte))
             .collect();
 
         Self {
             context,
             violations: Vec::new(),
             line_offsets,
+            code_spans,
         }
     }
 
     /// Analyze all lines and store all violations for reporting via finalize().
     /// Context cache is already initialized by MultiRuleLinter.
     fn analyze_all_lines(&mut self) {
-        let lines = self.context.lines.borrow();
+        let lines = &self.context.lines;
         let excluded_lines = self.get_excluded_lines();
 
         for (line_index, line) in lines.iter().enumerate() {
             let line_number = line_index + 1;
 
-            if excluded_lines.contains(&line_number) {
+            if excluded_lines.contains(&line_number)
+                || self.context.is_front_matter_line(line_index)
+            {
                 continue;
             }
 
@@ -95,38 +100,29 @@ impl MD011Linter {
     /// Returns a set of line numbers that should be excluded from checking.
     /// This includes code blocks.
     fn get_excluded_lines(&self) -> std::collections::HashSet<usize> {
-        let node_cache = self.context.node_cache.borrow();
+        let node_cache = &self.context.node_cache;
 
         ["indented_code_block", "fenced_code_block"]
             .iter()
-            .filter_map(|block_type| node_cache.get(*block_type))
+            .filter_map(|block_type| node_cache.get(block_type))
             .flatten()
             .flat_map(|node_info| (node_info.line_start + 1)..=(node_info.line_end + 1))
             .collect()
     }
 
-    /// Check if a match overlaps with any inline code spans on the same line.
+    /// Check if a match overlaps with any code span, which may span several lines.
     fn overlaps_with_inline_code(
         &self,
         line_index: usize,
         match_start: usize,
         match_length: usize,
     ) -> bool {
-        let lines = self.context.lines.borrow();
-        if let Some(line) = lines.get(line_index) {
-            let match_end = match_start + match_length;
-
-            for code_match in INLINE_CODE_REGEX.find_iter(line) {
-                let code_start = code_match.start();
-                let code_end = code_match.end();
-
-                if match_start < code_end && match_end > code_start {
-                    return true;
-                }
-            }
-        }
+        let match_start = self.line_offsets[line_index] + match_start;
+        let match_end = match_start + match_length;
 
-        false
+        self.code_spans
+            .iter()
+            .any(|(code_start, code_end)| match_start < *code_end && match_end > *code_start)
     }
 
     /// Creates a RuleViolation for a reversed link at the specified position.
@@ -181,7 +177,7 @@ pub const MD011: Rule = Rule {
     tags: &["links"],
     description: "Reversed link syntax",
     rule_type: RuleType::Line,
-    required_nodes: &["indented_code_block", "fenced_code_block"],
+    required_nodes: &["indented_code_block", "fenced_code_block", "code_span"],
     new_linter: |context| Box::new(MD011Linter::new(context)),
 };
 
@@ -415,4 +411,26 @@ Another (bad)[example] here."###;
         let violations = linter.analyze();
         assert_eq!(0, violations.len());
     }
+
+    #[test]
+    fn test_reversed_link_in_multiline_code_span_ignored() {
+        let input =
+            "This is `a code span\n(reversed)[link]` over two lines.\n\nBut (this)[one] is not.";
+
+        let config = test_config();
+        let mut linter = MultiRuleLinter::new_for_document(PathBuf::from("test.md"), config, input);
+        let violations = linter.analyze();
+        assert_eq!(1, violations.len());
+        assert_eq!(3, violations[0].location().range.start.line);
+    }
+
+    #[test]
+    fn test_unclosed_backtick_does_not_hide_reversed_link() {
+        let input = "A stray ` backtick and (reversed)[link]";
+
+        let config = test_config();
+        let mut linter = MultiRuleLinter::new_for_document(PathBuf::from("test.md"), config, input);
+        let violations = linter.analyze();
+        assert_eq!(1, violations.len());
+    }
 }
@@ -1,17 +1,17 @@
-use serde::Deserialize;
-use std::rc::Rc;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 
 use tree_sitter::Node;
 
 use crate::{
-    linter::{range_from_tree_sitter, RuleViolation},
+    linter::{range_from_tree_sitter, CharPosition, Edit, Range, RuleViolation},
     rules::{Context, Rule, RuleLinter, RuleType},
 };
 
 // MD012-specific configuration types
-#[derive(Debug, PartialEq, Clone, Deserialize)]
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(default)]
 pub struct MD012MultipleBlankLinesTable {
-    #[serde(default)]
     pub maximum: usize,
 }
 
@@ -27,12 +27,12 @@ impl Default for MD012MultipleBlankLinesTable {
 /// After processing a document (via feed() calls and finalize()), the linter
 /// should be discarded. The violations state is not cleared between uses.
 pub(crate) struct MD012Linter {
-    context: Rc<Context>,
+    context: Arc<Context>,
     violations: Vec<RuleViolation>,
 }
 
 impl MD012Linter {
-    pub fn new(context: Rc<Context>) -> Self {
+    pub fn new(context: Arc<Context>) -> Self {
         Self {
             context,
             violations: Vec::new(),
@@ -43,7 +43,7 @@ impl MD012Linter {
     /// Context cache is already initialized by MultiRuleLinter
     fn analyze_all_lines(&mut self) {
         let settings = &self.context.config.linters.settings.multiple_blank_lines;
-        let lines = self.context.lines.borrow();
+        let lines = &self.context.lines;
         let maximum = settings.maximum;
 
         // Create a boolean mask for lines that are part of code blocks.
@@ -58,8 +58,9 @@ impl MD012Linter {
             let is_blank = line.trim().is_empty();
             // Use the boolean mask for an O(1) lookup.
             let is_in_code_block = code_block_mask.get(line_index).copied().unwrap_or(false);
+            let is_in_front_matter = self.context.is_front_matter_line(line_index);
 
-            if is_blank && !is_in_code_block {
+            if is_blank && !is_in_code_block && !is_in_front_matter {
                 consecutive_blanks += 1;
 
                 // Report violation immediately when maximum is exceeded
@@ -86,8 +87,8 @@ impl MD012Linter {
     /// Note: Works around a tree-sitter-md issue where fenced code blocks
     /// incorrectly include a blank line immediately after the closing fence.
     fn populate_code_block_mask(&self, mask: &mut [bool]) {
-        let node_cache = self.context.node_cache.borrow();
-        let lines = self.context.lines.borrow();
+        let node_cache = &self.context.node_cache;
+        let lines = &self.context.lines;
 
         // Handle indented code blocks
         if let Some(indented_blocks) = node_cache.get("indented_code_block") {
@@ -162,6 +163,33 @@ impl MD012Linter {
                 },
             }),
         )
+        .with_fix(vec![self.delete_line_edit(line_index)])
+    }
+
+    /// Builds an edit removing the blank line at `line_index` together with one line break.
+    /// The last line has no terminator of its own, so the preceding line break is removed instead.
+    fn delete_line_edit(&self, line_index: usize) -> Edit {
+        let lines = &self.context.lines;
+        let (start, end) = if line_index + 1 < lines.len() {
+            ((line_index, 0), (line_index + 1, 0))
+        } else {
+            let previous = line_index.saturating_sub(1);
+            (
+                (previous, lines[previous].len()),
+                (line_index, lines[line_index].len()),
+            )
+        };
+
+        Edit::delete(Range {
+            start: CharPosition {
+                line: start.0,
+                character: start.1,
+            },
+            end: CharPosition {
+                line: end.0,
+                character: end.1,
+            },
+        })
     }
 }
 
@@ -187,7 +215,7 @@ pub const MD012: Rule = Rule {
     rule_type: RuleType::Line,
     // This is a line-based rule and does not require specific nodes from the AST.
     // The logic runs once for the entire file content.
-    required_nodes: &[],
+    required_nodes: &["indented_code_block", "fenced_code_block"],
     new_linter: |context| Box::new(MD012Linter::new(context)),
 };
 
@@ -519,4 +547,13 @@ More normal text"#;
         // 3 blank lines (including the implicit one from trailing newline) = 2 violations
         assert_eq!(2, violations.len());
     }
+
+    #[test]
+    fn test_fix_removes_extra_blank_lines() {
+        let input = "Line one\n\n\n\nLine two\n\n\n";
+
+        let result = MultiRuleLinter::fix_document(PathBuf::from("test.md"), test_config(), input);
+        assert_eq!("Line one\n\nLine two\n", result.content);
+        assert!(result.remaining.is_empty());
+    }
 }
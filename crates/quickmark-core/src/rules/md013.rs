@@ -1,5 +1,5 @@
-use serde::Deserialize;
-use std::rc::Rc;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 
 use tree_sitter::Node;
 
@@ -9,23 +9,16 @@ use crate::{
 };
 
 // MD013-specific configuration types
-#[derive(Debug, PartialEq, Clone, Deserialize)]
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(default)]
 pub struct MD013LineLengthTable {
-    #[serde(default)]
     pub line_length: usize,
-    #[serde(default)]
     pub code_block_line_length: usize,
-    #[serde(default)]
     pub heading_line_length: usize,
-    #[serde(default)]
     pub code_blocks: bool,
-    #[serde(default)]
     pub headings: bool,
-    #[serde(default)]
     pub tables: bool,
-    #[serde(default)]
     pub strict: bool,
-    #[serde(default)]
     pub stern: bool,
 }
 
@@ -50,12 +43,12 @@ impl Default for MD013LineLengthTable {
 /// After processing a document (via feed() calls and finalize()), the linter
 /// should be discarded. The pending_violations state is not cleared between uses.
 pub(crate) struct MD013Linter {
-    context: Rc<Context>,
+    context: Arc<Context>,
     violations: Vec<RuleViolation>,
 }
 
 impl MD013Linter {
-    pub fn new(context: Rc<Context>) -> Self {
+    pub fn new(context: Arc<Context>) -> Self {
         Self {
             context,
             violations: Vec::new(),
@@ -65,19 +58,22 @@ impl MD013Linter {
     /// Analyze all lines and store all violations for reporting via finalize()
     /// Context cache is already initialized by MultiRuleLinter
     fn analyze_all_lines(&mut self) {
-        let lines = self.context.lines.borrow();
+        let lines = &self.context.lines;
 
         for (line_index, line) in lines.iter().enumerate() {
+            if self.context.is_front_matter_line(line_index) {
+                continue;
+            }
             let node_kind = self.context.get_node_type_for_line(line_index);
-            let should_check = self.should_check_node_type(&node_kind);
+            let should_check = self.should_check_node_type(node_kind);
             let should_violate = if should_check {
-                self.should_violate_line(line, line_index, &node_kind)
+                self.should_violate_line(line, line_index, node_kind)
             } else {
                 false
             };
 
             if should_violate {
-                let violation = self.create_violation_for_line(line, line_index, &node_kind);
+                let violation = self.create_violation_for_line(line, line_index, node_kind);
                 self.violations.push(violation);
             }
         }
@@ -826,4 +822,17 @@ Another short line.";
         assert_eq!("MD013", violations[0].rule().id);
         assert!(violations[0].message().contains("Expected: <= 80"));
     }
+
+    #[test]
+    fn test_front_matter_is_ignored() {
+        let long_value = "word ".repeat(20);
+        let input = format!("---\ndescription: {long_value}\n---\n\n{long_value}\n");
+
+        let config = test_config();
+        let mut linter =
+            MultiRuleLinter::new_for_document(PathBuf::from("test.md"), config, &input);
+        let violations = linter.analyze();
+        assert_eq!(1, violations.len());
+        assert_eq!(4, violations[0].location().range.start.line);
+    }
 }
@@ -1,5 +1,6 @@
+use once_cell::sync::Lazy;
 use regex::Regex;
-use std::rc::Rc;
+use std::sync::Arc;
 use tree_sitter::Node;
 
 use crate::linter::{CharPosition, Context, Range, RuleLinter, RuleViolation};
@@ -8,30 +9,30 @@ use super::{Rule, RuleType};
 
 const VIOLATION_MESSAGE: &str = "Dollar signs used before commands without showing output";
 
+static DOLLAR_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"^(\s*)\$\s+").unwrap());
+
 pub(crate) struct MD014Linter {
-    context: Rc<Context>,
+    context: Arc<Context>,
     violations: Vec<RuleViolation>,
-    dollar_regex: Regex,
 }
 
 impl MD014Linter {
-    pub fn new(context: Rc<Context>) -> Self {
+    pub fn new(context: Arc<Context>) -> Self {
         Self {
             context,
             violations: Vec::new(),
-            dollar_regex: Regex::new(r"^(\s*)\$\s+").unwrap(),
         }
     }
 
     /// Analyze all code blocks using cached nodes
     fn analyze_all_code_blocks(&mut self) {
-        let node_cache = self.context.node_cache.borrow();
-        let lines = self.context.lines.borrow();
+        let node_cache = &self.context.node_cache;
+        let lines = &self.context.lines;
 
         // Check fenced code blocks
         if let Some(fenced_blocks) = node_cache.get("fenced_code_block") {
             for node_info in fenced_blocks {
-                if let Some(violation) = self.check_code_block_info(node_info, &lines, true) {
+                if let Some(violation) = self.check_code_block_info(node_info, lines, true) {
                     self.violations.push(violation);
                 }
             }
@@ -40,7 +41,7 @@ impl MD014Linter {
         // Check indented code blocks
         if let Some(indented_blocks) = node_cache.get("indented_code_block") {
             for node_info in indented_blocks {
-                if let Some(violation) = self.check_code_block_info(node_info, &lines, false) {
+                if let Some(violation) = self.check_code_block_info(node_info, lines, false) {
                     self.violations.push(violation);
                 }
             }
@@ -94,7 +95,7 @@ impl MD014Linter {
         // Check if ALL non-empty lines start with dollar sign
         let all_have_dollar = content_lines
             .iter()
-            .all(|(_, line)| self.dollar_regex.is_match(line));
+            .all(|(_, line)| DOLLAR_REGEX.is_match(line));
 
         if all_have_dollar {
             // Report violation on the first line with dollar sign
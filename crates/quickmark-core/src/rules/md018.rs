@@ -1,5 +1,5 @@
 use std::collections::HashSet;
-use std::rc::Rc;
+use std::sync::Arc;
 
 use tree_sitter::Node;
 
@@ -9,12 +9,12 @@ use crate::{
 };
 
 pub(crate) struct MD018Linter {
-    context: Rc<Context>,
+    context: Arc<Context>,
     violations: Vec<RuleViolation>,
 }
 
 impl MD018Linter {
-    pub fn new(context: Rc<Context>) -> Self {
+    pub fn new(context: Arc<Context>) -> Self {
         Self {
             context,
             violations: Vec::new(),
@@ -23,14 +23,16 @@ impl MD018Linter {
 
     /// Analyze all lines and store all violations for reporting via finalize()
     fn analyze_all_lines(&mut self) {
-        let lines = self.context.lines.borrow();
+        let lines = &self.context.lines;
 
         // We need to identify lines that are in code blocks or HTML blocks to ignore them
         let ignore_lines = self.get_ignore_lines();
 
         for (line_index, line) in lines.iter().enumerate() {
-            if ignore_lines.contains(&(line_index + 1)) {
-                continue; // Skip lines in code blocks or HTML blocks
+            if ignore_lines.contains(&(line_index + 1))
+                || self.context.is_front_matter_line(line_index)
+            {
+                continue; // Skip lines in code blocks, HTML blocks or front matter
             }
 
             if self.is_md018_violation(line) {
@@ -43,7 +45,7 @@ impl MD018Linter {
     /// Get line numbers that should be ignored (inside code blocks or HTML blocks)
     fn get_ignore_lines(&self) -> HashSet<usize> {
         let mut ignore_lines = HashSet::new();
-        let node_cache = self.context.node_cache.borrow();
+        let node_cache = &self.context.node_cache;
 
         for node_type in ["fenced_code_block", "indented_code_block", "html_block"] {
             if let Some(blocks) = node_cache.get(node_type) {
@@ -121,7 +123,7 @@ pub const MD018: Rule = Rule {
     tags: &["atx", "headings", "spaces"],
     description: "No space after hash on atx style heading",
     rule_type: RuleType::Line,
-    required_nodes: &[], // Line-based rules don't require specific nodes
+    required_nodes: &["fenced_code_block", "indented_code_block", "html_block"],
     new_linter: |context| Box::new(MD018Linter::new(context)),
 };
 
@@ -1,4 +1,4 @@
-use std::rc::Rc;
+use std::sync::Arc;
 use tree_sitter::Node;
 
 use crate::linter::{Context, RuleLinter, RuleViolation};
@@ -6,12 +6,12 @@ use crate::linter::{Context, RuleLinter, RuleViolation};
 use super::{Rule, RuleType};
 
 pub(crate) struct MD019Linter {
-    context: Rc<Context>,
+    context: Arc<Context>,
     violations: Vec<RuleViolation>,
 }
 
 impl MD019Linter {
-    pub fn new(context: Rc<Context>) -> Self {
+    pub fn new(context: Arc<Context>) -> Self {
         Self {
             context,
             violations: Vec::new(),
@@ -1,7 +1,7 @@
 use once_cell::sync::Lazy;
 use regex::Regex;
 use std::collections::HashSet;
-use std::rc::Rc;
+use std::sync::Arc;
 use tree_sitter::Node;
 
 use crate::linter::{range_from_tree_sitter, Context, RuleLinter, RuleViolation};
@@ -14,12 +14,12 @@ static CLOSED_ATX_REGEX: Lazy<Regex> = Lazy::new(|| {
 });
 
 pub(crate) struct MD020Linter {
-    context: Rc<Context>,
+    context: Arc<Context>,
     violations: Vec<RuleViolation>,
 }
 
 impl MD020Linter {
-    pub fn new(context: Rc<Context>) -> Self {
+    pub fn new(context: Arc<Context>) -> Self {
         Self {
             context,
             violations: Vec::new(),
@@ -27,14 +27,16 @@ impl MD020Linter {
     }
 
     fn analyze_all_lines(&mut self) {
-        let lines = self.context.lines.borrow();
+        let lines = &self.context.lines;
 
         // Get line numbers that should be ignored (inside code blocks or HTML blocks)
         let ignore_lines = self.get_ignore_lines();
 
         for (line_index, line) in lines.iter().enumerate() {
-            if ignore_lines.contains(&(line_index + 1)) {
-                continue; // Skip lines in code blocks or HTML blocks
+            if ignore_lines.contains(&(line_index + 1))
+                || self.context.is_front_matter_line(line_index)
+            {
+                continue; // Skip lines in code blocks, HTML blocks or front matter
             }
 
             if let Some(violation) = self.check_line(line, line_index) {
@@ -46,7 +48,7 @@ impl MD020Linter {
     /// Get line numbers that should be ignored (inside code blocks or HTML blocks)
     fn get_ignore_lines(&self) -> HashSet<usize> {
         let mut ignore_lines = HashSet::new();
-        let node_cache = self.context.node_cache.borrow();
+        let node_cache = &self.context.node_cache;
 
         for node_type in ["fenced_code_block", "indented_code_block", "html_block"] {
             if let Some(blocks) = node_cache.get(node_type) {
@@ -117,7 +119,7 @@ pub const MD020: Rule = Rule {
     tags: &["headings", "atx_closed", "spaces"],
     description: "No space inside hashes on closed atx style heading",
     rule_type: RuleType::Line,
-    required_nodes: &[], // Line-based rules don't require specific nodes
+    required_nodes: &["fenced_code_block", "indented_code_block", "html_block"],
     new_linter: |context| Box::new(MD020Linter::new(context)),
 };
 
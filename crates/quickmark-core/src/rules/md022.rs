@@ -1,17 +1,16 @@
-use serde::Deserialize;
-use std::rc::Rc;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 use tree_sitter::Node;
 
 use crate::linter::{range_from_tree_sitter, Context, RuleLinter, RuleViolation};
 
-use super::{Rule, RuleType};
+use super::{insert_blank_lines, Rule, RuleType};
 
 // MD022-specific configuration types
-#[derive(Debug, PartialEq, Clone, Deserialize)]
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(default)]
 pub struct MD022HeadingsBlanksTable {
-    #[serde(default)]
     pub lines_above: Vec<i32>,
-    #[serde(default)]
     pub lines_below: Vec<i32>,
 }
 
@@ -25,12 +24,12 @@ impl Default for MD022HeadingsBlanksTable {
 }
 
 pub(crate) struct MD022Linter {
-    context: Rc<Context>,
+    context: Arc<Context>,
     violations: Vec<RuleViolation>,
 }
 
 impl MD022Linter {
-    pub fn new(context: Rc<Context>) -> Self {
+    pub fn new(context: Arc<Context>) -> Self {
         Self {
             context,
             violations: Vec::new(),
@@ -88,10 +87,13 @@ impl MD022Linter {
         }
     }
 
+    /// Lines holding nothing but blockquote markers count as blank, as in markdownlint
     fn is_line_blank(&self, line_number: usize) -> bool {
-        let lines = self.context.lines.borrow();
+        let lines = &self.context.lines;
         if line_number < lines.len() {
-            lines[line_number].trim().is_empty()
+            lines[line_number]
+                .trim_start_matches(|c: char| c.is_whitespace() || c == '>')
+                .is_empty()
         } else {
             true // Consider out-of-bounds lines as blank
         }
@@ -121,7 +123,7 @@ impl MD022Linter {
     }
 
     fn count_blank_lines_below(&self, end_line: usize) -> usize {
-        let lines = self.context.lines.borrow();
+        let lines = &self.context.lines;
         let mut count = 0;
         let mut line_idx = end_line + 1;
 
@@ -158,7 +160,7 @@ impl MD022Linter {
             start_line
         };
 
-        let lines = self.context.lines.borrow();
+        let lines = &self.context.lines;
 
         // Check lines above (only if required_above >= 0 and there's content above)
         if required_above >= 0 && actual_start_line > 0 {
@@ -168,15 +170,23 @@ impl MD022Linter {
             if has_content_above {
                 let actual_above = self.count_blank_lines_above(actual_start_line);
                 if (actual_above as i32) < required_above {
-                    self.violations.push(RuleViolation::new(
-                        &MD022,
-                        format!(
-                            "{} [Above: Expected: {}; Actual: {}]",
-                            MD022.description, required_above, actual_above
-                        ),
-                        self.context.file_path.clone(),
-                        range_from_tree_sitter(&node.range()),
-                    ));
+                    let fix = insert_blank_lines(
+                        actual_start_line,
+                        required_above as usize - actual_above,
+                        &lines[actual_start_line],
+                    );
+                    self.violations.push(
+                        RuleViolation::new(
+                            &MD022,
+                            format!(
+                                "{} [Above: Expected: {}; Actual: {}]",
+                                MD022.description, required_above, actual_above
+                            ),
+                            self.context.file_path.clone(),
+                            range_from_tree_sitter(&node.range()),
+                        )
+                        .with_fix(vec![fix]),
+                    );
                 }
             }
         }
@@ -211,15 +221,23 @@ impl MD022Linter {
             if has_content_below {
                 let actual_below = self.count_blank_lines_below(effective_end_line);
                 if (actual_below as i32) < required_below {
-                    self.violations.push(RuleViolation::new(
-                        &MD022,
-                        format!(
-                            "{} [Below: Expected: {}; Actual: {}]",
-                            MD022.description, required_below, actual_below
-                        ),
-                        self.context.file_path.clone(),
-                        range_from_tree_sitter(&node.range()),
-                    ));
+                    let fix = insert_blank_lines(
+                        effective_end_line + 1,
+                        required_below as usize - actual_below,
+                        &lines[effective_end_line],
+                    );
+                    self.violations.push(
+                        RuleViolation::new(
+                            &MD022,
+                            format!(
+                                "{} [Below: Expected: {}; Actual: {}]",
+                                MD022.description, required_below, actual_below
+                            ),
+                            self.context.file_path.clone(),
+                            range_from_tree_sitter(&node.range()),
+                        )
+                        .with_fix(vec![fix]),
+                    );
                 }
             }
         }
@@ -499,4 +517,20 @@ Text";
             .message()
             .contains("Above: Expected: 2; Actual: 1"));
     }
+
+    #[test]
+    fn test_fix_inserts_missing_blank_lines() {
+        let config = test_config_with_blanks(MD022HeadingsBlanksTable {
+            lines_above: vec![2],
+            lines_below: vec![1],
+        });
+        let input = "Text\n# Heading\nMore text\n\n> Quote\n> ## Quoted heading\n> Quoted text\n";
+
+        let result = MultiRuleLinter::fix_document(PathBuf::from("test.md"), config, input);
+        assert_eq!(
+            "Text\n\n\n# Heading\n\nMore text\n\n> Quote\n>\n>\n> ## Quoted heading\n>\n> Quoted text\n",
+            result.content
+        );
+        assert!(result.remaining.is_empty());
+    }
 }
@@ -1,4 +1,4 @@
-use std::rc::Rc;
+use std::sync::Arc;
 use tree_sitter::Node;
 
 use crate::linter::{range_from_tree_sitter, Context, RuleLinter, RuleViolation};
@@ -6,12 +6,12 @@ use crate::linter::{range_from_tree_sitter, Context, RuleLinter, RuleViolation};
 use super::{Rule, RuleType};
 
 pub(crate) struct MD023Linter {
-    context: Rc<Context>,
+    context: Arc<Context>,
     violations: Vec<RuleViolation>,
 }
 
 impl MD023Linter {
-    pub fn new(context: Rc<Context>) -> Self {
+    pub fn new(context: Arc<Context>) -> Self {
         Self {
             context,
             violations: Vec::new(),
@@ -19,15 +19,14 @@ impl MD023Linter {
     }
 
     fn check_atx_heading_indentation(&mut self, node: &Node) {
-        let lines = self.context.lines.borrow();
-        if let Some(violation) = self.check_line_for_indentation(node.start_position().row, &lines)
-        {
+        let lines = &self.context.lines;
+        if let Some(violation) = self.check_line_for_indentation(node.start_position().row, lines) {
             self.violations.push(violation);
         }
     }
 
     fn check_setext_heading_indentation(&mut self, node: &Node) {
-        let lines = self.context.lines.borrow();
+        let lines = &self.context.lines;
 
         let mut cursor = node.walk();
         let mut text_line_num = None;
@@ -46,14 +45,14 @@ impl MD023Linter {
         }
 
         if let Some(line_num) = text_line_num {
-            if let Some(violation) = self.check_line_for_indentation(line_num, &lines) {
+            if let Some(violation) = self.check_line_for_indentation(line_num, lines) {
                 self.violations.push(violation);
                 return; // Report one violation per heading
             }
         }
 
         if let Some(line_num) = underline_line_num {
-            if let Some(violation) = self.check_line_for_indentation(line_num, &lines) {
+            if let Some(violation) = self.check_line_for_indentation(line_num, lines) {
                 self.violations.push(violation);
             }
         }
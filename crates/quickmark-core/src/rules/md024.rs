@@ -1,5 +1,5 @@
-use serde::Deserialize;
-use std::rc::Rc;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 
 use tree_sitter::Node;
 
@@ -9,16 +9,15 @@ use crate::{
 };
 
 // MD024-specific configuration types
-#[derive(Debug, PartialEq, Clone, Deserialize, Default)]
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize, Default)]
+#[serde(default)]
 pub struct MD024MultipleHeadingsTable {
-    #[serde(default)]
     pub siblings_only: bool,
-    #[serde(default)]
     pub allow_different_nesting: bool,
 }
 
 pub(crate) struct MD024Linter {
-    context: Rc<Context>,
+    context: Arc<Context>,
     violations: Vec<RuleViolation>,
     headings: Vec<HeadingInfo>,
 }
@@ -32,7 +31,7 @@ struct HeadingInfo {
 }
 
 impl MD024Linter {
-    pub fn new(context: Rc<Context>) -> Self {
+    pub fn new(context: Arc<Context>) -> Self {
         Self {
             context,
             violations: Vec::new(),
@@ -1,19 +1,19 @@
-use serde::Deserialize;
-use std::rc::Rc;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 
 use tree_sitter::Node;
 
 use crate::{
+    front_matter::DEFAULT_FRONT_MATTER_TITLE,
     linter::{range_from_tree_sitter, Context, RuleLinter, RuleViolation},
     rules::{Rule, RuleType},
 };
 
 // MD025-specific configuration types
-#[derive(Debug, PartialEq, Clone, Deserialize)]
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(default)]
 pub struct MD025SingleH1Table {
-    #[serde(default)]
     pub level: u8,
-    #[serde(default)]
     pub front_matter_title: String,
 }
 
@@ -21,7 +21,7 @@ impl Default for MD025SingleH1Table {
     fn default() -> Self {
         Self {
             level: 1,
-            front_matter_title: r"^\s*title\s*[:=]".to_string(),
+            front_matter_title: DEFAULT_FRONT_MATTER_TITLE.to_string(),
         }
     }
 }
@@ -34,19 +34,17 @@ struct HeadingInfo {
 }
 
 pub(crate) struct MD025Linter {
-    context: Rc<Context>,
+    context: Arc<Context>,
     violations: Vec<RuleViolation>,
     matching_headings: Vec<HeadingInfo>,
-    has_front_matter_title: Option<bool>,
 }
 
 impl MD025Linter {
-    pub fn new(context: Rc<Context>) -> Self {
+    pub fn new(context: Arc<Context>) -> Self {
         Self {
             context,
             violations: Vec::new(),
             matching_headings: Vec::new(),
-            has_front_matter_title: None,
         }
     }
 
@@ -100,92 +98,26 @@ impl MD025Linter {
         }
     }
 
-    fn check_front_matter_has_title(&mut self) -> bool {
-        if self.has_front_matter_title.is_some() {
-            return self.has_front_matter_title.unwrap();
-        }
-
-        let config = &self.context.config.linters.settings.single_h1;
-        if config.front_matter_title.is_empty() {
-            self.has_front_matter_title = Some(false);
-            return false; // Front matter checking disabled
-        }
-
-        let content = self.context.get_document_content();
-
-        // Check if document starts with front matter (---)
-        if !content.starts_with("---") {
-            self.has_front_matter_title = Some(false);
-            return false;
-        }
-
-        // Find the end of front matter
-        let lines: Vec<&str> = content.lines().collect();
-        if lines.len() < 3 {
-            self.has_front_matter_title = Some(false);
-            return false; // Too short to have valid front matter
-        }
-
-        let mut end_index = None;
-        for (i, line) in lines.iter().enumerate().skip(1) {
-            if line.trim() == "---" {
-                end_index = Some(i);
-                break;
-            }
-        }
-
-        let end_index = match end_index {
-            Some(idx) => idx,
-            None => {
-                self.has_front_matter_title = Some(false);
-                return false; // No closing front matter delimiter
-            }
-        };
-
-        // Check for title in front matter
-        let front_matter_lines = &lines[1..end_index];
-        let title_regex = regex::Regex::new(&config.front_matter_title).unwrap_or_else(|_| {
-            // Fallback to default regex if invalid
-            regex::Regex::new(r"^\s*title\s*[:=]").unwrap()
-        });
-
-        let has_title = front_matter_lines
-            .iter()
-            .any(|line| title_regex.is_match(line));
-        self.has_front_matter_title = Some(has_title);
-        has_title
-    }
-
     fn is_first_content_heading(&self, node: &Node) -> bool {
         let content = self.context.get_document_content();
         let node_start_byte = node.start_byte();
         let target_level = self.context.config.linters.settings.single_h1.level;
 
-        // Get text before this heading
-        let text_before = &content[..node_start_byte];
-
-        // Check if there's only whitespace, comments, front matter,
+        // Get text between the front matter and this heading
+        let front_matter_end = self
+            .context
+            .front_matter
+            .as_ref()
+            .map_or(0, |front_matter| front_matter.end_byte);
+        let text_before = content
+            .get(front_matter_end..node_start_byte)
+            .unwrap_or_default();
+
+        // Check if there's only whitespace, comments,
         // or headings above the target level before this heading
-        let mut in_front_matter = false;
-
         for line in text_before.lines() {
             let trimmed = line.trim();
 
-            if trimmed == "---" {
-                if !in_front_matter {
-                    in_front_matter = true;
-                    continue;
-                } else {
-                    // End of front matter
-                    in_front_matter = false;
-                    continue;
-                }
-            }
-
-            if in_front_matter {
-                continue; // Skip front matter content
-            }
-
             // Check if this line is a heading above target level
             if trimmed.starts_with('#') {
                 let heading_level = trimmed.chars().take_while(|&c| c == '#').count() as u8;
@@ -253,7 +185,15 @@ impl RuleLinter for MD025Linter {
             return Vec::new();
         }
 
-        let has_front_matter_title = self.check_front_matter_has_title();
+        let has_front_matter_title = self.context.front_matter_has_title(
+            &self
+                .context
+                .config
+                .linters
+                .settings
+                .single_h1
+                .front_matter_title,
+        );
 
         // Determine if we have a "top-level heading" scenario
         let has_top_level_heading = has_front_matter_title
@@ -534,4 +474,29 @@ Final content";
         let violations = linter.analyze();
         assert_eq!(violations.len(), 0);
     }
+
+    #[test]
+    fn test_toml_and_json_front_matter_title() {
+        let inputs = [
+            "+++\ntitle = \"Welcome\"\n+++\n# Top level heading\n",
+            "{\n\"layout\": \"post\",\ntitle: 1\n}\n# Top level heading\n",
+        ];
+        for input in inputs {
+            let config = test_config(1, r"^\s*title\s*[:=]");
+            let mut linter =
+                MultiRuleLinter::new_for_document(PathBuf::from("test.md"), config, input);
+            let violations = linter.analyze();
+            assert_eq!(violations.len(), 1, "{input}");
+        }
+    }
+
+    #[test]
+    fn test_thematic_break_before_heading_is_content() {
+        let config = test_config(1, r"^\s*title\s*[:=]");
+        let input = "Intro\n\n---\n\n# First\n\n# Second\n";
+
+        let mut linter = MultiRuleLinter::new_for_document(PathBuf::from("test.md"), config, input);
+        let violations = linter.analyze();
+        assert_eq!(violations.len(), 0);
+    }
 }
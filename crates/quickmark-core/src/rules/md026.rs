@@ -1,5 +1,5 @@
-use serde::Deserialize;
-use std::rc::Rc;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 
 use once_cell::sync::Lazy;
 use regex::Regex;
@@ -10,9 +10,9 @@ use crate::linter::{range_from_tree_sitter, Context, RuleLinter, RuleViolation};
 use super::{Rule, RuleType};
 
 // MD026-specific configuration types
-#[derive(Debug, PartialEq, Clone, Deserialize)]
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(default)]
 pub struct MD026TrailingPunctuationTable {
-    #[serde(default)]
     pub punctuation: String,
 }
 
@@ -33,12 +33,12 @@ impl MD026TrailingPunctuationTable {
 }
 
 pub(crate) struct MD026Linter {
-    context: Rc<Context>,
+    context: Arc<Context>,
     violations: Vec<RuleViolation>,
 }
 
 impl MD026Linter {
-    pub fn new(context: Rc<Context>) -> Self {
+    pub fn new(context: Arc<Context>) -> Self {
         Self {
             context,
             violations: Vec::new(),
@@ -69,7 +69,7 @@ impl MD026Linter {
 
     fn check_trailing_punctuation(&mut self, node: &Node) {
         let source = self.context.get_document_content();
-        let heading_text = self.extract_heading_text(node, &source);
+        let heading_text = self.extract_heading_text(node, source);
         if heading_text.is_empty() {
             return;
         }
@@ -1,5 +1,5 @@
-use serde::Deserialize;
-use std::rc::Rc;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 
 use tree_sitter::Node;
 
@@ -9,9 +9,9 @@ use crate::{
 };
 
 // MD027-specific configuration types
-#[derive(Debug, PartialEq, Clone, Deserialize)]
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(default)]
 pub struct MD027BlockquoteSpacesTable {
-    #[serde(default)]
     pub list_items: bool,
 }
 
@@ -27,12 +27,12 @@ impl Default for MD027BlockquoteSpacesTable {
 /// After processing a document (via feed() calls and finalize()), the linter
 /// should be discarded. The violations state is not cleared between uses.
 pub(crate) struct MD027Linter {
-    context: Rc<Context>,
+    context: Arc<Context>,
     violations: Vec<RuleViolation>,
 }
 
 impl MD027Linter {
-    pub fn new(context: Rc<Context>) -> Self {
+    pub fn new(context: Arc<Context>) -> Self {
         Self {
             context,
             violations: Vec::new(),
@@ -48,7 +48,7 @@ impl MD027Linter {
             .settings
             .blockquote_spaces
             .clone();
-        let lines = self.context.lines.borrow();
+        let lines = &self.context.lines;
 
         // Get code block lines to exclude using AST
         let code_block_lines = self.get_code_block_lines();
@@ -145,7 +145,7 @@ impl MD027Linter {
 
     /// Returns a set of line numbers that are part of code blocks using AST
     fn get_code_block_lines(&self) -> std::collections::HashSet<usize> {
-        let node_cache = self.context.node_cache.borrow();
+        let node_cache = &self.context.node_cache;
         let mut code_block_lines = std::collections::HashSet::new();
 
         // Add indented code block lines
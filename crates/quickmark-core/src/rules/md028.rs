@@ -1,5 +1,5 @@
 use std::collections::HashSet;
-use std::rc::Rc;
+use std::sync::Arc;
 
 use tree_sitter::Node;
 
@@ -14,12 +14,12 @@ use crate::{
 /// After processing a document (via feed() calls and finalize()), the linter
 /// should be discarded. The violations state is not cleared between uses.
 pub(crate) struct MD028Linter {
-    context: Rc<Context>,
+    context: Arc<Context>,
     violations: Vec<RuleViolation>,
 }
 
 impl MD028Linter {
-    pub fn new(context: Rc<Context>) -> Self {
+    pub fn new(context: Arc<Context>) -> Self {
         Self {
             context,
             violations: Vec::new(),
@@ -28,7 +28,7 @@ impl MD028Linter {
 
     fn analyze_all_lines(&mut self) {
         let code_block_lines = self.get_code_block_lines();
-        let lines = self.context.lines.borrow();
+        let lines = &self.context.lines;
 
         let mut last_line_was_blockquote = false;
         let mut blank_line_sequence_start: Option<usize> = None;
@@ -74,11 +74,11 @@ impl MD028Linter {
     }
 
     fn get_code_block_lines(&self) -> HashSet<usize> {
-        let node_cache = self.context.node_cache.borrow();
+        let node_cache = &self.context.node_cache;
         let mut code_block_lines = HashSet::new();
         let node_types = ["indented_code_block", "fenced_code_block", "html_block"];
         for node_type in &node_types {
-            if let Some(nodes) = node_cache.get(*node_type) {
+            if let Some(nodes) = node_cache.get(node_type) {
                 for node_info in nodes {
                     code_block_lines.extend((node_info.line_start + 1)..=(node_info.line_end + 1));
                 }
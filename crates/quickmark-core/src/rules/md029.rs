@@ -1,5 +1,5 @@
-use serde::Deserialize;
-use std::rc::Rc;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 
 use tree_sitter::Node;
 
@@ -9,27 +9,22 @@ use crate::{
 };
 
 // MD029-specific configuration types
-#[derive(Debug, PartialEq, Clone, Copy, Deserialize)]
+#[derive(Debug, PartialEq, Clone, Copy, Deserialize, Serialize, Default)]
 pub enum OlPrefixStyle {
     #[serde(rename = "one")]
     One,
     #[serde(rename = "ordered")]
     Ordered,
     #[serde(rename = "one_or_ordered")]
+    #[default]
     OneOrOrdered,
     #[serde(rename = "zero")]
     Zero,
 }
 
-impl Default for OlPrefixStyle {
-    fn default() -> Self {
-        Self::OneOrOrdered
-    }
-}
-
-#[derive(Debug, PartialEq, Clone, Deserialize)]
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(default)]
 pub struct MD029OlPrefixTable {
-    #[serde(default)]
     pub style: OlPrefixStyle,
 }
 
@@ -42,7 +37,7 @@ impl Default for MD029OlPrefixTable {
 }
 
 pub(crate) struct MD029Linter {
-    context: Rc<Context>,
+    context: Arc<Context>,
     violations: Vec<RuleViolation>,
     // Document-wide state for one_or_ordered mode
     document_style: Option<OlPrefixStyle>,
@@ -51,7 +46,7 @@ pub(crate) struct MD029Linter {
 }
 
 impl MD029Linter {
-    pub fn new(context: Rc<Context>) -> Self {
+    pub fn new(context: Arc<Context>) -> Self {
         Self {
             context,
             violations: Vec::new(),
@@ -62,7 +57,7 @@ impl MD029Linter {
 
     /// Extract the numeric value from an ordered list item prefix
     fn extract_list_item_value(&self, list_item_node: &Node) -> Option<u32> {
-        let content = self.context.document_content.borrow();
+        let content = &self.context.document_content;
         let source_bytes = content.as_bytes();
 
         // Find the list marker within this list item
@@ -131,7 +126,7 @@ impl MD029Linter {
         // Performance: Collect lines once to avoid re-iterating the whole document content
         // for each list item pair.
         let logical_lists = {
-            let content = self.context.document_content.borrow();
+            let content = &self.context.document_content;
             let lines: Vec<&str> = content.lines().collect();
             self.split_into_logical_lists(&list_items_with_values, &lines)
         };
@@ -316,82 +311,78 @@ impl MD029Linter {
                     }
                 }
             }
-            OlPrefixStyle::Ordered => {
-                // Ordered style: in one_or_ordered mode, handle first list vs separated lists differently
-                if !list_items_with_values.is_empty() {
-                    let list_start_value = list_items_with_values[0].1;
-
-                    // Special case: single-item lists should follow "one" style (start at 1)
-                    // regardless of document's ordered style
-                    if list_items_with_values.len() == 1 && !is_first_multi_item_list {
-                        // Single item should use "1" regardless of ordered document style
-                        let expected_value = 1;
-                        let actual_value = list_items_with_values[0].1;
-
-                        if actual_value != expected_value {
-                            let message = format!(
-                                "{} [Expected: {}; Actual: {}; Style: {}]",
-                                MD029.description,
-                                expected_value,
-                                actual_value,
-                                "1/1/1" // Single items use one style
-                            );
-
-                            self.violations.push(RuleViolation::new(
-                                &MD029,
-                                message,
-                                self.context.file_path.clone(),
-                                range_from_tree_sitter(&list_items_with_values[0].0.range()),
-                            ));
-                        }
-                        return; // Early return for single items
+            // Ordered style: in one_or_ordered mode, handle first list vs separated lists differently
+            OlPrefixStyle::Ordered if !list_items_with_values.is_empty() => {
+                let list_start_value = list_items_with_values[0].1;
+
+                // Special case: single-item lists should follow "one" style (start at 1)
+                // regardless of document's ordered style
+                if list_items_with_values.len() == 1 && !is_first_multi_item_list {
+                    // Single item should use "1" regardless of ordered document style
+                    let expected_value = 1;
+                    let actual_value = list_items_with_values[0].1;
+
+                    if actual_value != expected_value {
+                        let message = format!(
+                            "{} [Expected: {}; Actual: {}; Style: {}]",
+                            MD029.description,
+                            expected_value,
+                            actual_value,
+                            "1/1/1" // Single items use one style
+                        );
+
+                        self.violations.push(RuleViolation::new(
+                            &MD029,
+                            message,
+                            self.context.file_path.clone(),
+                            range_from_tree_sitter(&list_items_with_values[0].0.range()),
+                        ));
                     }
+                    return; // Early return for single items
+                }
 
-                    // For ordered style, allow both 1-based and 0-based patterns
-                    let expected_start = if is_first_multi_item_list {
-                        // This is the first multi-item list establishing style - allow natural start (0 or 1)
-                        list_start_value
+                // For ordered style, allow both 1-based and 0-based patterns
+                let expected_start = if is_first_multi_item_list {
+                    // This is the first multi-item list establishing style - allow natural start (0 or 1)
+                    list_start_value
+                } else {
+                    // For subsequent lists in ordered style, allow valid ordered patterns:
+                    // - 1-based: 1,2,3...
+                    // - 0-based: 0,1,2...
+                    // Check if this list follows a valid ordered pattern
+                    let is_valid_pattern = self.is_valid_ordered_pattern(list_items_with_values);
+                    let is_zero_based_pattern = list_start_value == 0 && is_valid_pattern;
+
+                    // Special case: if document was established as zero-based,
+                    // separated lists cannot use zero-based patterns (must start at 1)
+                    if is_zero_based_pattern && self.is_zero_based {
+                        1 // Force separated lists to start at 1 in zero-based documents
+                    } else if is_valid_pattern {
+                        list_start_value // Allow the natural start if it's a valid ordered pattern
                     } else {
-                        // For subsequent lists in ordered style, allow valid ordered patterns:
-                        // - 1-based: 1,2,3...
-                        // - 0-based: 0,1,2...
-                        // Check if this list follows a valid ordered pattern
-                        let is_valid_pattern =
-                            self.is_valid_ordered_pattern(list_items_with_values);
-                        let is_zero_based_pattern = list_start_value == 0 && is_valid_pattern;
-
-                        // Special case: if document was established as zero-based,
-                        // separated lists cannot use zero-based patterns (must start at 1)
-                        if is_zero_based_pattern && self.is_zero_based {
-                            1 // Force separated lists to start at 1 in zero-based documents
-                        } else if is_valid_pattern {
-                            list_start_value // Allow the natural start if it's a valid ordered pattern
-                        } else {
-                            1 // Default to 1-based if not a valid pattern
-                        }
-                    };
-
-                    // Check if the first item in this logical list starts with the correct value
-                    let mut expected_value = expected_start;
-
-                    for (list_item, actual_value) in list_items_with_values {
-                        if actual_value != &expected_value {
-                            let message = format!(
-                                "{} [Expected: {}; Actual: {}; Style: {}]",
-                                MD029.description,
-                                expected_value,
-                                actual_value,
-                                self.get_style_example(&effective_style)
-                            );
-
-                            self.violations.push(RuleViolation::new(
-                                &MD029,
-                                message,
-                                self.context.file_path.clone(),
-                                range_from_tree_sitter(&list_item.range()),
-                            ));
-                        }
-                        expected_value += 1;
+                        1 // Default to 1-based if not a valid pattern
+                    }
+                };
+
+                // Check if the first item in this logical list starts with the correct value
+                for ((list_item, actual_value), expected_value) in
+                    list_items_with_values.iter().zip(expected_start..)
+                {
+                    if actual_value != &expected_value {
+                        let message = format!(
+                            "{} [Expected: {}; Actual: {}; Style: {}]",
+                            MD029.description,
+                            expected_value,
+                            actual_value,
+                            self.get_style_example(&effective_style)
+                        );
+
+                        self.violations.push(RuleViolation::new(
+                            &MD029,
+                            message,
+                            self.context.file_path.clone(),
+                            range_from_tree_sitter(&list_item.range()),
+                        ));
                     }
                 }
             }
@@ -1,5 +1,5 @@
-use serde::Deserialize;
-use std::rc::Rc;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 
 use tree_sitter::Node;
 
@@ -9,15 +9,12 @@ use crate::{
 };
 
 // MD030-specific configuration types
-#[derive(Debug, PartialEq, Clone, Deserialize)]
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(default)]
 pub struct MD030ListMarkerSpaceTable {
-    #[serde(default)]
     pub ul_single: usize,
-    #[serde(default)]
     pub ol_single: usize,
-    #[serde(default)]
     pub ul_multi: usize,
-    #[serde(default)]
     pub ol_multi: usize,
 }
 
@@ -33,12 +30,12 @@ impl Default for MD030ListMarkerSpaceTable {
 }
 
 pub(crate) struct MD030Linter {
-    context: Rc<Context>,
+    context: Arc<Context>,
     violations: Vec<RuleViolation>,
 }
 
 impl MD030Linter {
-    pub fn new(context: Rc<Context>) -> Self {
+    pub fn new(context: Arc<Context>) -> Self {
         Self {
             context,
             violations: Vec::new(),
@@ -113,7 +110,7 @@ impl MD030Linter {
     }
 
     fn check_list_item_spacing(&mut self, list_item: &Node, expected_spaces: usize) {
-        let content = self.context.document_content.borrow();
+        let content = &self.context.document_content;
         let item_text = match list_item.utf8_text(content.as_bytes()) {
             Ok(text) => text,
             Err(_) => return, // Ignore if text cannot be decoded
@@ -1,15 +1,15 @@
-use serde::Deserialize;
-use std::rc::Rc;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 use tree_sitter::Node;
 
 use crate::linter::{range_from_tree_sitter, Context, RuleLinter, RuleViolation};
 
-use super::{Rule, RuleType};
+use super::{insert_blank_lines, Rule, RuleType};
 
 // MD031-specific configuration types
-#[derive(Debug, PartialEq, Clone, Deserialize)]
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(default)]
 pub struct MD031FencedCodeBlanksTable {
-    #[serde(default)]
     pub list_items: bool,
 }
 
@@ -26,12 +26,12 @@ const MISSING_BLANK_AFTER: &str =
     "Fenced code blocks should be surrounded by blank lines [Missing blank line after]";
 
 pub(crate) struct MD031Linter {
-    context: Rc<Context>,
+    context: Arc<Context>,
     violations: Vec<RuleViolation>,
 }
 
 impl MD031Linter {
-    pub fn new(context: Rc<Context>) -> Self {
+    pub fn new(context: Arc<Context>) -> Self {
         Self {
             context,
             violations: Vec::new(),
@@ -40,10 +40,13 @@ impl MD031Linter {
 
     /// Check if a line is blank, handling out-of-bounds safely.
     /// Out-of-bounds lines are considered blank to avoid false violations at document boundaries.
+    /// Lines containing only blockquote markers (e.g., "> " or ">") are considered blank.
     #[inline]
     fn is_line_blank_cached(&self, line_number: usize, lines: &[String]) -> bool {
         if line_number < lines.len() {
-            lines[line_number].trim().is_empty()
+            lines[line_number]
+                .trim_start_matches(|c: char| c.is_whitespace() || c == '>')
+                .is_empty()
         } else {
             true // Consider out-of-bounds lines as blank
         }
@@ -84,20 +87,26 @@ impl MD031Linter {
 
         let start_line = node.start_position().row;
         let end_line = node.end_position().row;
-        // Single borrow for the entire function to avoid multiple RefCell runtime checks
-        let lines = self.context.lines.borrow();
+        let lines = &self.context.lines;
         let total_lines = lines.len();
 
         // Check blank line above (only if not at document start)
         if start_line > 0 {
             let line_above = start_line - 1;
-            if !self.is_line_blank_cached(line_above, &lines) {
-                self.violations.push(RuleViolation::new(
-                    &MD031,
-                    MISSING_BLANK_BEFORE.to_string(),
-                    self.context.file_path.clone(),
-                    range_from_tree_sitter(&node.range()),
-                ));
+            if !self.is_line_blank_cached(line_above, lines) {
+                self.violations.push(
+                    RuleViolation::new(
+                        &MD031,
+                        MISSING_BLANK_BEFORE.to_string(),
+                        self.context.file_path.clone(),
+                        range_from_tree_sitter(&node.range()),
+                    )
+                    .with_fix(vec![insert_blank_lines(
+                        start_line,
+                        1,
+                        &lines[start_line],
+                    )]),
+                );
             }
         }
 
@@ -115,16 +124,26 @@ impl MD031Linter {
         }
 
         // Check for violation using cached line access
-        let end_line_blank = self.is_line_blank_cached(end_line, &lines);
-        let prev_line_blank = self.is_line_blank_cached(end_line.saturating_sub(1), &lines);
+        let end_line_blank = self.is_line_blank_cached(end_line, lines);
+        let prev_line_blank = self.is_line_blank_cached(end_line.saturating_sub(1), lines);
 
         if !end_line_blank && !prev_line_blank {
-            self.violations.push(RuleViolation::new(
-                &MD031,
-                MISSING_BLANK_AFTER.to_string(),
-                self.context.file_path.clone(),
-                range_from_tree_sitter(&node.range()),
-            ));
+            // The block ends at the start of the line following its closing fence;
+            // an unterminated block has no such line, so no fix is offered for it
+            let fix = if node.end_position().column == 0 {
+                vec![insert_blank_lines(end_line, 1, &lines[end_line - 1])]
+            } else {
+                Vec::new()
+            };
+            self.violations.push(
+                RuleViolation::new(
+                    &MD031,
+                    MISSING_BLANK_AFTER.to_string(),
+                    self.context.file_path.clone(),
+                    range_from_tree_sitter(&node.range()),
+                )
+                .with_fix(fix),
+            );
         }
     }
 }
@@ -352,4 +371,30 @@ More text";
         let violations = linter.analyze();
         assert_eq!(2, violations.len()); // Should detect violations in nested structures
     }
+
+    #[test]
+    fn test_fix_inserts_missing_blank_lines() {
+        let input = "Some text\n```rust\nlet x = 1;\n```\nMore text\n";
+
+        let result =
+            MultiRuleLinter::fix_document(PathBuf::from("test.md"), test_config_default(), input);
+        assert_eq!(
+            "Some text\n\n```rust\nlet x = 1;\n```\n\nMore text\n",
+            result.content
+        );
+        assert!(result.remaining.is_empty());
+    }
+
+    #[test]
+    fn test_fix_keeps_blockquote_markers() {
+        let input = "> Some text\n> ```\n> code\n> ```\n>\n> More text\n";
+
+        let result =
+            MultiRuleLinter::fix_document(PathBuf::from("test.md"), test_config_default(), input);
+        assert_eq!(
+            "> Some text\n>\n> ```\n> code\n> ```\n>\n> More text\n",
+            result.content
+        );
+        assert!(result.remaining.is_empty());
+    }
 }
@@ -1,9 +1,9 @@
-use std::rc::Rc;
+use std::sync::Arc;
 use tree_sitter::Node;
 
 use crate::linter::{range_from_tree_sitter, Context, RuleLinter, RuleViolation};
 
-use super::{Rule, RuleType};
+use super::{insert_blank_lines, Rule, RuleType};
 
 // Pre-computed violation messages to avoid format! allocations
 const MISSING_BLANK_BEFORE: &str =
@@ -12,12 +12,12 @@ const MISSING_BLANK_AFTER: &str =
     "Lists should be surrounded by blank lines [Missing blank line after]";
 
 pub(crate) struct MD032Linter {
-    context: Rc<Context>,
+    context: Arc<Context>,
     violations: Vec<RuleViolation>,
 }
 
 impl MD032Linter {
-    pub fn new(context: Rc<Context>) -> Self {
+    pub fn new(context: Arc<Context>) -> Self {
         Self {
             context,
             violations: Vec::new(),
@@ -78,7 +78,7 @@ impl MD032Linter {
         let tree_sitter_end_line = node.end_position().row;
 
         // Borrow lines to examine content
-        let lines = self.context.lines.borrow();
+        let lines = &self.context.lines;
 
         // For blockquoted lists, we need to handle them differently
         // If this is a blockquoted list, trust tree-sitter more
@@ -139,20 +139,26 @@ impl MD032Linter {
         let start_line = node.start_position().row;
         let end_line = self.find_visual_end_line(node);
 
-        // Single borrow for the entire function to avoid multiple RefCell runtime checks
-        let lines = self.context.lines.borrow();
+        let lines = &self.context.lines;
         let total_lines = lines.len();
 
         // Check blank line above (only if not at document start)
         if start_line > 0 {
             let line_above = start_line - 1;
-            if !self.is_line_blank_cached(line_above, &lines) {
-                self.violations.push(RuleViolation::new(
-                    &MD032,
-                    MISSING_BLANK_BEFORE.to_string(),
-                    self.context.file_path.clone(),
-                    range_from_tree_sitter(&node.range()),
-                ));
+            if !self.is_line_blank_cached(line_above, lines) {
+                self.violations.push(
+                    RuleViolation::new(
+                        &MD032,
+                        MISSING_BLANK_BEFORE.to_string(),
+                        self.context.file_path.clone(),
+                        range_from_tree_sitter(&node.range()),
+                    )
+                    .with_fix(vec![insert_blank_lines(
+                        start_line,
+                        1,
+                        &lines[start_line],
+                    )]),
+                );
             }
         }
 
@@ -160,17 +166,24 @@ impl MD032Linter {
         // The original checks lines[lastLineNumber] where lastLineNumber is the line after the list
         let line_after_list_idx = end_line + 1;
         if line_after_list_idx < total_lines {
-            let is_blank = self.is_line_blank_cached(line_after_list_idx, &lines);
+            let is_blank = self.is_line_blank_cached(line_after_list_idx, lines);
 
             // If the line immediately after the list is not blank, report a violation
             // This matches the original markdownlint behavior exactly
             if !is_blank {
-                self.violations.push(RuleViolation::new(
-                    &MD032,
-                    MISSING_BLANK_AFTER.to_string(),
-                    self.context.file_path.clone(),
-                    range_from_tree_sitter(&node.range()),
-                ));
+                self.violations.push(
+                    RuleViolation::new(
+                        &MD032,
+                        MISSING_BLANK_AFTER.to_string(),
+                        self.context.file_path.clone(),
+                        range_from_tree_sitter(&node.range()),
+                    )
+                    .with_fix(vec![insert_blank_lines(
+                        line_after_list_idx,
+                        1,
+                        &lines[end_line],
+                    )]),
+                );
             }
         }
     }
@@ -486,4 +499,14 @@ More text";
         // List spans entire document - no violations expected
         assert_eq!(0, violations.len());
     }
+
+    #[test]
+    fn test_fix_inserts_missing_blank_lines() {
+        let input = "Some text\n* Item 1\n* Item 2\n---\n";
+
+        let result =
+            MultiRuleLinter::fix_document(PathBuf::from("test.md"), test_config_default(), input);
+        assert_eq!("Some text\n\n* Item 1\n* Item 2\n\n---\n", result.content);
+        assert!(result.remaining.is_empty());
+    }
 }
@@ -1,7 +1,7 @@
 use once_cell::sync::Lazy;
 use regex::Regex;
-use serde::Deserialize;
-use std::{collections::HashSet, rc::Rc};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashSet, sync::Arc};
 use tree_sitter::Node;
 
 use crate::{
@@ -10,9 +10,9 @@ use crate::{
 };
 
 // MD033-specific configuration types
-#[derive(Debug, PartialEq, Clone, Deserialize, Default)]
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize, Default)]
+#[serde(default)]
 pub struct MD033InlineHtmlTable {
-    #[serde(default)]
     pub allowed_elements: Vec<String>,
 }
 
@@ -25,14 +25,14 @@ static CODE_SPAN_REGEX: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"`[^`]*`").expect("Invalid code span regex"));
 
 pub(crate) struct MD033Linter {
-    context: Rc<Context>,
+    context: Arc<Context>,
     violations: Vec<RuleViolation>,
     allowed_elements: HashSet<String>,
     line_starts: Vec<usize>,
 }
 
 impl MD033Linter {
-    pub fn new(context: Rc<Context>) -> Self {
+    pub fn new(context: Arc<Context>) -> Self {
         // Pre-process allowed elements into a HashSet for O(1) lookups
         let allowed_elements: HashSet<String> = context
             .config
@@ -49,7 +49,6 @@ impl MD033Linter {
             .chain(
                 context
                     .document_content
-                    .borrow()
                     .match_indices('\n')
                     .map(|(i, _)| i + 1),
             )
@@ -98,7 +97,7 @@ impl MD033Linter {
         let start_byte = node.start_byte();
         let end_byte = node.end_byte();
         let content = {
-            let document_content = self.context.document_content.borrow();
+            let document_content = &self.context.document_content;
             document_content[start_byte..end_byte].to_string()
         };
 
@@ -192,11 +191,9 @@ impl RuleLinter for MD033Linter {
     fn feed(&mut self, node: &Node) {
         // Process inline and html_block nodes that may contain HTML
         match node.kind() {
-            "inline" => {
-                // Check if this inline node is inside a code span by looking at its parent
-                if !self.is_in_code_context(node) {
-                    self.process_html_in_node(node);
-                }
+            // Check if this inline node is inside a code span by looking at its parent
+            "inline" if !self.is_in_code_context(node) => {
+                self.process_html_in_node(node);
             }
             "html_block" => {
                 // HTML blocks should always be processed unless they are in code blocks
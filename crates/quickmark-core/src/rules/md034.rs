@@ -1,4 +1,4 @@
-use std::rc::Rc;
+use std::sync::Arc;
 
 use linkify::{LinkFinder, LinkKind};
 use tree_sitter::Node;
@@ -9,12 +9,12 @@ use crate::{
 };
 
 pub(crate) struct MD034Linter {
-    context: Rc<Context>,
+    context: Arc<Context>,
     violations: Vec<RuleViolation>,
 }
 
 impl MD034Linter {
-    pub fn new(context: Rc<Context>) -> Self {
+    pub fn new(context: Arc<Context>) -> Self {
         Self {
             context,
             violations: Vec::new(),
@@ -26,10 +26,9 @@ impl RuleLinter for MD034Linter {
     fn feed(&mut self, node: &Node) {
         // Process paragraph nodes to find bare URLs within them
         if node.kind() == "paragraph" {
-            let content = self.context.document_content.borrow();
+            let content = &self.context.document_content;
             let text = node.utf8_text(content.as_bytes()).unwrap_or("").to_string();
             let node_range = node.range();
-            drop(content); // Release the borrow before calling mutable methods
 
             self.check_for_bare_urls_in_text(&text, &node_range);
         }
@@ -1,5 +1,5 @@
-use serde::Deserialize;
-use std::rc::Rc;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 
 use tree_sitter::Node;
 
@@ -9,9 +9,9 @@ use crate::{
 };
 
 // MD035-specific configuration types
-#[derive(Debug, PartialEq, Clone, Deserialize)]
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(default)]
 pub struct MD035HrStyleTable {
-    #[serde(default)]
     pub style: String,
 }
 
@@ -24,13 +24,13 @@ impl Default for MD035HrStyleTable {
 }
 
 pub(crate) struct MD035Linter {
-    context: Rc<Context>,
+    context: Arc<Context>,
     violations: Vec<RuleViolation>,
     expected_style: Option<String>,
 }
 
 impl MD035Linter {
-    pub fn new(context: Rc<Context>) -> Self {
+    pub fn new(context: Arc<Context>) -> Self {
         Self {
             context,
             violations: Vec::new(),
@@ -42,7 +42,7 @@ impl MD035Linter {
 impl RuleLinter for MD035Linter {
     fn feed(&mut self, node: &Node) {
         if node.kind() == "thematic_break" {
-            let content = self.context.document_content.borrow();
+            let content = &self.context.document_content;
             let text = match node.utf8_text(content.as_bytes()) {
                 Ok(text) => text.trim(),
                 Err(_) => return, // Ignore if text cannot be decoded
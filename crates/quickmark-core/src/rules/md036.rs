@@ -1,5 +1,5 @@
-use serde::Deserialize;
-use std::rc::Rc;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 
 use tree_sitter::Node;
 
@@ -8,9 +8,9 @@ use crate::linter::{range_from_tree_sitter, Context, RuleLinter, RuleViolation};
 use super::{Rule, RuleType};
 
 // MD036-specific configuration types
-#[derive(Debug, PartialEq, Clone, Deserialize)]
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(default)]
 pub struct MD036EmphasisAsHeadingTable {
-    #[serde(default)]
     pub punctuation: String,
 }
 
@@ -23,12 +23,12 @@ impl Default for MD036EmphasisAsHeadingTable {
 }
 
 pub(crate) struct MD036Linter {
-    context: Rc<Context>,
+    context: Arc<Context>,
     violations: Vec<RuleViolation>,
 }
 
 impl MD036Linter {
-    pub fn new(context: Rc<Context>) -> Self {
+    pub fn new(context: Arc<Context>) -> Self {
         Self {
             context,
             violations: Vec::new(),
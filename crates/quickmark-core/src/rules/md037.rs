@@ -1,4 +1,4 @@
-use std::rc::Rc;
+use std::sync::Arc;
 
 use once_cell::sync::Lazy;
 use regex::Regex;
@@ -24,12 +24,12 @@ static CODE_SPAN_REGEX: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"`[^`\n]*`").expect("Invalid code span regex"));
 
 pub(crate) struct MD037Linter {
-    context: Rc<Context>,
+    context: Arc<Context>,
     violations: Vec<RuleViolation>,
 }
 
 impl MD037Linter {
-    pub fn new(context: Rc<Context>) -> Self {
+    pub fn new(context: Arc<Context>) -> Self {
         Self {
             context,
             violations: Vec::new(),
@@ -1,52 +1,51 @@
-use std::rc::Rc;
+use std::{collections::HashSet, sync::Arc};
 
-use once_cell::sync::Lazy;
-use regex::Regex;
 use tree_sitter::Node;
 
 use crate::{
-    linter::{range_from_tree_sitter, RuleViolation},
+    linter::{normalize_link_label, range_from_tree_sitter, RuleViolation},
     rules::{Context, Rule, RuleLinter, RuleType},
 };
 
-// Using once_cell::sync::Lazy for safe, one-time compilation of regexes.
-// Regular inline links: [text](url) - but NOT images ![text](url)
-static RE_INLINE_LINK: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r"(?:^|[^!])\[([^\]]*)\]\(([^)]+)\)").unwrap());
-
-// Reference links: [text][ref] - but NOT images ![text][ref]
-static RE_REF_LINK: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r"(?:^|[^!])\[([^\]]*)\]\[([^\]]+)\]").unwrap());
-
-// Collapsed reference links: [text][] - but NOT images ![text][]
-static RE_COLLAPSED_REF_LINK: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r"(?:^|[^!])\[([^\]]+)\]\[\]").unwrap());
-
 /// MD039 - Spaces inside link text
 ///
 /// This rule checks for unnecessary spaces at the beginning or end of link text.
 pub(crate) struct MD039Linter {
-    context: Rc<Context>,
+    context: Arc<Context>,
     violations: Vec<RuleViolation>,
+    /// Normalized labels of the link reference definitions in the document
+    definitions: HashSet<String>,
 }
 
 impl MD039Linter {
-    pub fn new(context: Rc<Context>) -> Self {
+    pub fn new(context: Arc<Context>) -> Self {
+        let definitions = context.link_reference_labels();
         Self {
             context,
             violations: Vec::new(),
+            definitions,
         }
     }
 }
 
 impl RuleLinter for MD039Linter {
     fn feed(&mut self, node: &Node) {
-        // Process different possible link node types
-        if node.kind() == "link" {
-            self.check_link_for_spaces(node);
-        } else if node.kind() == "inline" {
-            // Check if this inline node contains links
-            self.check_inline_for_links(node);
+        match node.kind() {
+            "inline_link" | "full_reference_link" | "collapsed_reference_link" => {
+                self.check_link_for_spaces(node);
+            }
+            // Any bracketed text parses as a shortcut link, only those with a
+            // matching reference definition are actual links
+            "shortcut_link" => {
+                let label = {
+                    let document_content = &self.context.document_content;
+                    normalize_link_label(node.utf8_text(document_content.as_bytes()).unwrap_or(""))
+                };
+                if self.definitions.contains(&label) {
+                    self.check_link_for_spaces(node);
+                }
+            }
+            _ => {}
         }
     }
 
@@ -56,91 +55,31 @@ impl RuleLinter for MD039Linter {
 }
 
 impl MD039Linter {
-    fn check_inline_for_links(&mut self, inline_node: &Node) {
-        // Look for links within inline content using the text
-        let link_text = {
-            let document_content = self.context.document_content.borrow();
-            inline_node
-                .utf8_text(document_content.as_bytes())
-                .unwrap_or("")
-                .to_string()
-        };
-
-        // Parse the inline content for markdown links
-        // Look for patterns like [text](url), [text][ref], [ref][], [ref]
-        self.check_text_for_link_patterns(&link_text, inline_node);
-    }
-
-    fn check_text_for_link_patterns(&mut self, text: &str, node: &Node) {
-        for caps in RE_INLINE_LINK.captures_iter(text) {
-            if let Some(label_match) = caps.get(1) {
-                let label_text = label_match.as_str();
-                self.check_label_for_spaces(label_text, node);
-            }
-        }
-
-        for caps in RE_REF_LINK.captures_iter(text) {
-            if let Some(label_match) = caps.get(1) {
-                let label_text = label_match.as_str();
-                self.check_label_for_spaces(label_text, node);
-            }
-        }
-
-        for caps in RE_COLLAPSED_REF_LINK.captures_iter(text) {
-            if let Some(label_match) = caps.get(1) {
-                let label_text = label_match.as_str();
-                self.check_label_for_spaces(label_text, node);
-            }
-        }
-
-        // Shortcut reference links: [text] - but only if there's a matching reference definition
-        // We need to be careful here to not match arbitrary brackets
-        // For now, let's only process shortcut links in specific contexts or skip them
-        // since they require document-level analysis to verify the reference exists
-    }
-
-    fn check_label_for_spaces(&mut self, label_text: &str, node: &Node) {
-        // Check for leading spaces
-        if label_text.len() != label_text.trim_start().len() {
-            self.create_space_violation(node, true);
-        }
-
-        // Check for trailing spaces
-        if label_text.len() != label_text.trim_end().len() {
-            self.create_space_violation(node, false);
-        }
-    }
-
     fn check_link_for_spaces(&mut self, link_node: &Node) {
-        // Look for the link text within the link node
-        // In tree-sitter markdown, links have different structures
-        // We need to find the text content and check for leading/trailing spaces
+        let mut cursor = link_node.walk();
+        let Some(link_text_node) = link_node
+            .children(&mut cursor)
+            .find(|child| child.kind() == "link_text")
+        else {
+            return;
+        };
 
-        let link_text = {
-            let document_content = self.context.document_content.borrow();
-            link_node
+        let (has_leading, has_trailing) = {
+            let document_content = &self.context.document_content;
+            let label_text = link_text_node
                 .utf8_text(document_content.as_bytes())
-                .unwrap_or("")
-                .to_string()
+                .unwrap_or("");
+            (
+                label_text.len() != label_text.trim_start().len(),
+                label_text.len() != label_text.trim_end().len(),
+            )
         };
 
-        // Find the bracket part [text] in the link
-        if let Some(bracket_start) = link_text.find('[') {
-            if let Some(bracket_end) = link_text.find(']') {
-                if bracket_end > bracket_start {
-                    let label_text = &link_text[bracket_start + 1..bracket_end];
-
-                    // Check for leading spaces
-                    if label_text.len() != label_text.trim_start().len() {
-                        self.create_space_violation(link_node, true);
-                    }
-
-                    // Check for trailing spaces
-                    if label_text.len() != label_text.trim_end().len() {
-                        self.create_space_violation(link_node, false);
-                    }
-                }
-            }
+        if has_leading {
+            self.create_space_violation(link_node, true);
+        }
+        if has_trailing {
+            self.create_space_violation(link_node, false);
         }
     }
 
@@ -163,7 +102,13 @@ pub const MD039: Rule = Rule {
     tags: &["whitespace", "links"],
     description: "Spaces inside link text",
     rule_type: RuleType::Token,
-    required_nodes: &["link", "inline"], // We need link nodes to check for spaces in link text
+    required_nodes: &[
+        "inline_link",
+        "full_reference_link",
+        "collapsed_reference_link",
+        "shortcut_link",
+        "link_label",
+    ],
     new_linter: |context| Box::new(MD039Linter::new(context)),
 };
 
@@ -310,4 +255,69 @@ mod test {
             assert_eq!("MD039", violation.rule().id);
         }
     }
+
+    #[test]
+    fn test_violation_range_covers_link() {
+        let input = "Some [ link](url) text";
+
+        let config = test_config();
+        let mut linter = MultiRuleLinter::new_for_document(PathBuf::from("test.md"), config, input);
+        let violations = linter.analyze();
+
+        assert_eq!(1, violations.len());
+        let range = &violations[0].location().range;
+        assert_eq!(5, range.start.character);
+        assert_eq!(17, range.end.character);
+    }
+
+    #[test]
+    fn test_shortcut_link_with_spaces() {
+        let input = "[ defined ] and [ undefined ]\n\n[defined]: https://example.com";
+
+        let config = test_config();
+        let mut linter = MultiRuleLinter::new_for_document(PathBuf::from("test.md"), config, input);
+        let violations = linter.analyze();
+
+        // Brackets without a definition are plain text
+        assert_eq!(2, violations.len());
+        for violation in &violations {
+            assert_eq!(0, violation.location().range.start.character);
+        }
+    }
+
+    #[test]
+    fn test_escaped_brackets_in_link_text() {
+        let input = "[a \\] b ](url) and [\\[ok\\]](url)";
+
+        let config = test_config();
+        let mut linter = MultiRuleLinter::new_for_document(PathBuf::from("test.md"), config, input);
+        let violations = linter.analyze();
+
+        assert_eq!(1, violations.len());
+        assert!(violations[0].message().contains("trailing"));
+    }
+
+    #[test]
+    fn test_links_in_code_spans_ignored() {
+        let input = "Use `[ text ](url)` for links, also in\n\n```\n[ text ](url)\n```";
+
+        let config = test_config();
+        let mut linter = MultiRuleLinter::new_for_document(PathBuf::from("test.md"), config, input);
+        let violations = linter.analyze();
+
+        assert_eq!(0, violations.len());
+    }
+
+    #[test]
+    fn test_links_in_block_quotes_and_tables() {
+        let input = "> A [ quoted](url) link\n\n| Column |\n| - |\n| [cell ](url) |\n";
+
+        let config = test_config();
+        let mut linter = MultiRuleLinter::new_for_document(PathBuf::from("test.md"), config, input);
+        let violations = linter.analyze();
+
+        assert_eq!(2, violations.len());
+        assert_eq!(0, violations[0].location().range.start.line);
+        assert_eq!(4, violations[1].location().range.start.line);
+    }
 }
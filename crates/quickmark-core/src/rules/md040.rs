@@ -1,6 +1,6 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
-use std::rc::Rc;
+use std::sync::Arc;
 use tree_sitter::Node;
 
 use crate::{
@@ -9,21 +9,20 @@ use crate::{
 };
 
 // MD040-specific configuration types
-#[derive(Debug, PartialEq, Clone, Deserialize, Default)]
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize, Default)]
+#[serde(default)]
 pub struct MD040FencedCodeLanguageTable {
-    #[serde(default)]
     pub allowed_languages: Vec<String>,
-    #[serde(default)]
     pub language_only: bool,
 }
 
 pub(crate) struct MD040Linter {
-    context: Rc<Context>,
+    context: Arc<Context>,
     violations: Vec<RuleViolation>,
 }
 
 impl MD040Linter {
-    pub fn new(context: Rc<Context>) -> Self {
+    pub fn new(context: Arc<Context>) -> Self {
         Self {
             context,
             violations: Vec::new(),
@@ -74,8 +73,8 @@ impl RuleLinter for MD040Linter {
 
     fn finalize(&mut self) -> Vec<RuleViolation> {
         let config = &self.context.config.linters.settings.fenced_code_language;
-        let node_cache = self.context.node_cache.borrow();
-        let lines = self.context.lines.borrow();
+        let node_cache = &self.context.node_cache;
+        let lines = &self.context.lines;
 
         // For performance, convert allowed_languages to a HashSet if it's not empty.
         let allowed_languages_set: Option<HashSet<&str>> = if !config.allowed_languages.is_empty() {
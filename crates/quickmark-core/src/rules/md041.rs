@@ -1,22 +1,20 @@
-use serde::Deserialize;
-use std::rc::Rc;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 
-use regex::Regex;
 use tree_sitter::Node;
 
 use crate::{
+    front_matter::DEFAULT_FRONT_MATTER_TITLE,
     linter::{range_from_tree_sitter, Context, RuleLinter, RuleViolation},
     rules::{Rule, RuleType},
 };
 
 // MD041-specific configuration types
-#[derive(Debug, PartialEq, Clone, Deserialize)]
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(default)]
 pub struct MD041FirstLineHeadingTable {
-    #[serde(default)]
     pub allow_preamble: bool,
-    #[serde(default)]
     pub front_matter_title: String,
-    #[serde(default)]
     pub level: u8,
 }
 
@@ -24,7 +22,7 @@ impl Default for MD041FirstLineHeadingTable {
     fn default() -> Self {
         Self {
             allow_preamble: false,
-            front_matter_title: r"^\s*title\s*[:=]".to_string(),
+            front_matter_title: DEFAULT_FRONT_MATTER_TITLE.to_string(),
             level: 1,
         }
     }
@@ -38,71 +36,20 @@ enum FirstElement {
 }
 
 pub(crate) struct MD041Linter {
-    context: Rc<Context>,
+    context: Arc<Context>,
     violations: Vec<RuleViolation>,
     first_element: FirstElement,
-    front_matter_end_byte: Option<usize>,
-    title_regex: Option<Regex>,
 }
 
 impl MD041Linter {
-    pub fn new(context: Rc<Context>) -> Self {
-        let content = context.get_document_content();
-        let front_matter_end_byte = Self::calculate_front_matter_end_byte(&content);
-
-        let config = &context.config.linters.settings.first_line_heading;
-        let title_regex = if !config.front_matter_title.is_empty() {
-            Some(
-                Regex::new(&config.front_matter_title)
-                    .unwrap_or_else(|_| Regex::new(r"^\s*title\s*[:=]").unwrap()),
-            )
-        } else {
-            None
-        };
-
+    pub fn new(context: Arc<Context>) -> Self {
         Self {
-            context: context.clone(),
+            context,
             violations: Vec::new(),
             first_element: FirstElement::None,
-            front_matter_end_byte,
-            title_regex,
         }
     }
 
-    /// Calculates the end byte of the front matter, including the final newline.
-    /// This is done by iterating through the lines of the content.
-    fn calculate_front_matter_end_byte(content: &str) -> Option<usize> {
-        if !content.starts_with("---") {
-            return None;
-        }
-
-        let mut byte_pos = 0;
-        let mut found_start = false;
-
-        let mut remaining = content;
-        while let Some(newline_pos) = remaining.find('\n') {
-            let line = &remaining[..newline_pos];
-            let line_to_check = line.trim_end_matches('\r');
-
-            if line_to_check.trim() == "---" {
-                if !found_start {
-                    found_start = true;
-                } else {
-                    return Some(byte_pos + newline_pos + 1);
-                }
-            }
-            byte_pos += newline_pos + 1;
-            remaining = &remaining[newline_pos + 1..];
-        }
-
-        // Check last line if no newline at end
-        if !remaining.is_empty() && remaining.trim() == "---" && found_start {
-            return Some(content.len());
-        }
-
-        None
-    }
-
     fn extract_heading_level(&self, node: &Node) -> u8 {
         match node.kind() {
             "atx_heading" => {
@@ -131,25 +78,6 @@ impl MD041Linter {
         }
     }
 
-    fn check_front_matter_has_title(&self) -> bool {
-        let Some(title_regex) = &self.title_regex else {
-            return false; // Front matter title checking disabled
-        };
-
-        let Some(fm_end) = self.front_matter_end_byte else {
-            return false; // No front matter found
-        };
-
-        let content = self.context.get_document_content();
-        let front_matter_content = &content[..fm_end];
-
-        front_matter_content
-            .lines()
-            .skip(1) // Skip the initial "---"
-            .take_while(|line| line.trim() != "---")
-            .any(|line| title_regex.is_match(line))
-    }
-
     fn is_html_comment(&self, node: &Node) -> bool {
         if node.kind() == "html_flow" {
             let source = self.context.get_document_content();
@@ -161,11 +89,10 @@ impl MD041Linter {
     }
 
     fn is_in_front_matter(&self, node: &Node) -> bool {
-        if let Some(fm_end) = self.front_matter_end_byte {
-            node.start_byte() < fm_end
-        } else {
-            false
-        }
+        self.context
+            .front_matter
+            .as_ref()
+            .is_some_and(|front_matter| node.start_byte() < front_matter.end_byte)
     }
 
     fn should_ignore_node(&self, node: &Node) -> bool {
@@ -224,12 +151,14 @@ impl RuleLinter for MD041Linter {
 
     fn finalize(&mut self) -> Vec<RuleViolation> {
         // Check if front matter has title - if so, no violation
-        if self.check_front_matter_has_title() {
+        let config = &self.context.config.linters.settings.first_line_heading;
+        if self
+            .context
+            .front_matter_has_title(&config.front_matter_title)
+        {
             return Vec::new();
         }
 
-        let config = &self.context.config.linters.settings.first_line_heading;
-
         match &self.first_element {
             FirstElement::Heading(level, range) => {
                 // First element is a heading - check if it has the correct level
@@ -541,4 +470,33 @@ Content";
         let violations = linter.analyze();
         assert_eq!(violations.len(), 0);
     }
+
+    #[test]
+    fn test_toml_front_matter_with_title() {
+        let config = test_config(1, r"^\s*title\s*[:=]", false);
+        let input = "+++
+title = \"Welcome\"
++++
+
+This is content without a heading";
+
+        let mut linter = MultiRuleLinter::new_for_document(PathBuf::from("test.md"), config, input);
+        let violations = linter.analyze();
+        assert_eq!(violations.len(), 0);
+    }
+
+    #[test]
+    fn test_json_front_matter_is_skipped() {
+        let config = test_config(1, r"^\s*title\s*[:=]", false);
+        let input = "{
+  \"layout\": \"post\"
+}
+# Heading
+
+Content";
+
+        let mut linter = MultiRuleLinter::new_for_document(PathBuf::from("test.md"), config, input);
+        let violations = linter.analyze();
+        assert_eq!(violations.len(), 0);
+    }
 }
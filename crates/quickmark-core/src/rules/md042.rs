@@ -1,7 +1,5 @@
-use std::rc::Rc;
+use std::sync::Arc;
 
-use once_cell::sync::Lazy;
-use regex::Regex;
 use tree_sitter::Node;
 
 use crate::{
@@ -9,25 +7,19 @@ use crate::{
     rules::{Context, Rule, RuleLinter, RuleType},
 };
 
-// Regular inline links: [text](url) - but NOT images ![text](url)
-static RE_INLINE_LINK: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r"(?:^|[^!])\[([^\]]*)\]\(([^)]*)\)").unwrap());
-
 /// MD042 - No empty links
 ///
 /// This rule checks for links that have no destination or only a fragment identifier.
 pub(crate) struct MD042Linter {
-    context: Rc<Context>,
+    context: Arc<Context>,
     violations: Vec<RuleViolation>,
 }
 
 impl RuleLinter for MD042Linter {
     fn feed(&mut self, node: &Node) {
-        // Process different possible link node types
-        match node.kind() {
-            "link" => self.check_link_for_empty_destination(node),
-            "inline" => self.check_inline_for_links(node),
-            _ => {}
+        // Images are separate `image` nodes and are not checked
+        if node.kind() == "inline_link" {
+            self.check_link_for_empty_destination(node);
         }
     }
 
@@ -37,49 +29,46 @@ impl RuleLinter for MD042Linter {
 }
 
 impl MD042Linter {
-    pub fn new(context: Rc<Context>) -> Self {
+    pub fn new(context: Arc<Context>) -> Self {
         Self {
             context,
             violations: Vec::new(),
         }
     }
 
-    fn check_inline_for_links(&mut self, inline_node: &Node) {
-        let link_text = {
-            let document_content = self.context.document_content.borrow();
-            inline_node
-                .utf8_text(document_content.as_bytes())
-                .unwrap_or_default()
-                .to_string()
+    fn check_link_for_empty_destination(&mut self, link_node: &Node) {
+        let mut cursor = link_node.walk();
+        let children: Vec<Node> = link_node.children(&mut cursor).collect();
+        // Like markdownlint, a link with a title but no destination is not empty
+        if children.iter().any(|child| child.kind() == "link_title") {
+            return;
+        }
+        let is_empty = match children
+            .iter()
+            .find(|child| child.kind() == "link_destination")
+        {
+            Some(destination) => {
+                let document_content = &self.context.document_content;
+                let url = destination
+                    .utf8_text(document_content.as_bytes())
+                    .unwrap_or_default();
+                self.is_empty_link_destination(url)
+            }
+            None => true,
         };
-        self.check_text_for_link_patterns(&link_text, inline_node);
-    }
 
-    fn check_text_for_link_patterns(&mut self, text: &str, node: &Node) {
-        // Check inline links: [text](url)
-        for caps in RE_INLINE_LINK.captures_iter(text) {
-            if let Some(url_match) = caps.get(2) {
-                if self.is_empty_link_destination(url_match.as_str()) {
-                    self.create_empty_link_violation(node);
-                }
-            }
+        if is_empty {
+            self.create_empty_link_violation(link_node);
         }
     }
 
-    fn check_link_for_empty_destination(&mut self, link_node: &Node) {
-        let link_text = {
-            let document_content = self.context.document_content.borrow();
-            link_node
-                .utf8_text(document_content.as_bytes())
-                .unwrap_or_default()
-                .to_string()
-        };
-        // Use the same regex-based checker for robustness and consistency
-        self.check_text_for_link_patterns(&link_text, link_node);
-    }
-
     fn is_empty_link_destination(&self, url: &str) -> bool {
         let trimmed = url.trim();
+        let trimmed = trimmed
+            .strip_prefix('<')
+            .and_then(|url| url.strip_suffix('>'))
+            .unwrap_or(trimmed)
+            .trim();
         trimmed.is_empty() || trimmed == "#"
     }
 
@@ -99,7 +88,7 @@ pub const MD042: Rule = Rule {
     tags: &["links"],
     description: "No empty links",
     rule_type: RuleType::Token,
-    required_nodes: &["link", "inline"], // We need link nodes and inline nodes that might contain links
+    required_nodes: &["inline_link"],
     new_linter: |context| Box::new(MD042Linter::new(context)),
 };
 
@@ -271,6 +260,17 @@ mod test {
         assert_eq!(0, violations.len());
     }
 
+    #[test]
+    fn test_empty_link_with_multi_word_title() {
+        let input = "[link text]( \"multi word title\")\n\n[other]( 'single quoted title')\n\n[paren](  (parenthesized title))";
+
+        let config = test_config();
+        let mut linter = MultiRuleLinter::new_for_document(PathBuf::from("test.md"), config, input);
+        let violations = linter.analyze();
+
+        assert_eq!(0, violations.len());
+    }
+
     #[test]
     fn test_fragment_with_content() {
         // Test that fragments with actual content are not flagged
@@ -299,4 +299,17 @@ mod test {
             assert_eq!("MD042", violation.rule().id);
         }
     }
+
+    #[test]
+    fn test_escaped_brackets_and_code_spans() {
+        let input = "[a \\] b]() and `[code]()` and [<>](<>)";
+
+        let config = test_config();
+        let mut linter = MultiRuleLinter::new_for_document(PathBuf::from("test.md"), config, input);
+        let violations = linter.analyze();
+
+        assert_eq!(2, violations.len());
+        assert_eq!(0, violations[0].location().range.start.character);
+        assert_eq!(30, violations[1].location().range.start.character);
+    }
 }
@@ -1,5 +1,5 @@
-use serde::Deserialize;
-use std::rc::Rc;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 
 use tree_sitter::Node;
 
@@ -9,11 +9,10 @@ use crate::{
 };
 
 // MD043-specific configuration types
-#[derive(Debug, PartialEq, Clone, Deserialize, Default)]
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize, Default)]
+#[serde(default)]
 pub struct MD043RequiredHeadingsTable {
-    #[serde(default)]
     pub headings: Vec<String>,
-    #[serde(default)]
     pub match_case: bool,
 }
 
@@ -25,13 +24,13 @@ struct HeadingInfo {
 }
 
 pub(crate) struct MD043Linter {
-    context: Rc<Context>,
+    context: Arc<Context>,
     violations: Vec<RuleViolation>,
     headings: Vec<HeadingInfo>,
 }
 
 impl MD043Linter {
-    pub fn new(context: Rc<Context>) -> Self {
+    pub fn new(context: Arc<Context>) -> Self {
         Self {
             context,
             violations: Vec::new(),
@@ -1,21 +1,20 @@
 use regex::Regex;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
-use std::rc::Rc;
+use std::sync::Arc;
 
 use crate::{
     linter::{range_from_tree_sitter, Context, RuleLinter, RuleViolation},
+    regex_cache::cached_regex,
     rules::{Rule, RuleType},
 };
 
 // MD044-specific configuration types
-#[derive(Debug, PartialEq, Clone, Deserialize)]
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(default)]
 pub struct MD044ProperNamesTable {
-    #[serde(default)]
     pub names: Vec<String>,
-    #[serde(default)]
     pub code_blocks: bool,
-    #[serde(default)]
     pub html_elements: bool,
 }
 
@@ -30,14 +29,14 @@ impl Default for MD044ProperNamesTable {
 }
 
 pub(crate) struct MD044Linter {
-    context: Rc<Context>,
+    context: Arc<Context>,
     violations: Vec<RuleViolation>,
     name_regexes: Vec<(String, Regex)>, // (original_name, compiled_regex)
     all_names: HashSet<String>,         // Added for performance
 }
 
 impl MD044Linter {
-    pub fn new(context: Rc<Context>) -> Self {
+    pub fn new(context: Arc<Context>) -> Self {
         let config = &context.config.linters.settings.proper_names;
         let mut name_regexes = Vec::new();
 
@@ -69,8 +68,8 @@ impl MD044Linter {
         let config = &self.context.config.linters.settings.proper_names;
 
         match node_kind {
-            // Code blocks and inline code
-            "fenced_code_block" | "indented_code_block" | "code_span" => config.code_blocks,
+            // Code blocks; code spans are checked as part of their paragraph
+            "fenced_code_block" | "indented_code_block" => config.code_blocks,
             // HTML elements
             "html_block" | "html_inline" => config.html_elements,
             // Regular text content
@@ -86,13 +85,13 @@ impl MD044Linter {
         text: &str,
         start_line: usize,
         start_column: usize,
+        mut exclusion_ranges: Vec<(usize, usize)>, // (start, end) byte ranges
     ) -> Vec<RuleViolation> {
         if self.name_regexes.is_empty() {
             return Vec::new();
         }
 
         let mut violations = Vec::new();
-        let mut exclusion_ranges: Vec<(usize, usize)> = Vec::new(); // (start, end) byte ranges
 
         for (expected_name, regex) in &self.name_regexes {
             for match_result in regex.find_iter(text) {
@@ -163,7 +162,28 @@ impl RuleLinter for MD044Linter {
             let start_line = node.start_position().row;
             let start_column = node.start_position().column;
 
-            let new_violations = self.check_text_content(text_slice, start_line, start_column);
+            // Code spans inside paragraphs are only checked along with code blocks
+            let exclusion_ranges = if node.kind() == "paragraph"
+                && !self
+                    .context
+                    .config
+                    .linters
+                    .settings
+                    .proper_names
+                    .code_blocks
+            {
+                self.context
+                    .get_inline_nodes(&["code_span"])
+                    .into_iter()
+                    .filter(|span| span.start_byte >= start_byte && span.end_byte <= end_byte)
+                    .map(|span| (span.start_byte - start_byte, span.end_byte - start_byte))
+                    .collect()
+            } else {
+                Vec::new()
+            };
+
+            let new_violations =
+                self.check_text_content(text_slice, start_line, start_column, exclusion_ranges);
             self.violations.extend(new_violations);
         }
     }
@@ -187,7 +207,7 @@ fn create_name_regex(name: &str) -> Result<Regex, regex::Error> {
 
     // Performance: Use non-capturing groups (?:...) as we only need the full match.
     let pattern = format!("(?i){start_boundary}{escaped_name}{end_boundary}");
-    Regex::new(&pattern)
+    cached_regex(&pattern)
 }
 
 // Helper function to check if a character is a word character (equivalent to \w in regex)
@@ -206,9 +226,9 @@ pub const MD044: Rule = Rule {
         "paragraph",
         "fenced_code_block",
         "indented_code_block",
-        "code_span",
         "html_block",
         "html_inline",
+        "code_span",
     ],
     new_linter: |context| Box::new(MD044Linter::new(context)),
 };
@@ -308,6 +328,21 @@ mod test {
         assert_eq!(violations.len(), 0);
     }
 
+    #[test]
+    fn test_code_spans() {
+        let input = "Run `javascript` before javascript.";
+
+        let config = test_config(vec!["JavaScript".to_string()], true, true);
+        let mut linter = MultiRuleLinter::new_for_document(PathBuf::from("test.md"), config, input);
+        assert_eq!(linter.analyze().len(), 2);
+
+        let config = test_config(vec!["JavaScript".to_string()], false, true);
+        let mut linter = MultiRuleLinter::new_for_document(PathBuf::from("test.md"), config, input);
+        let violations = linter.analyze();
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].location().range.start.character, 24);
+    }
+
     #[test]
     fn test_html_elements_enabled() {
         let config = test_config(vec!["JavaScript".to_string()], true, true);
@@ -1,6 +1,6 @@
 use once_cell::sync::Lazy;
 use regex::Regex;
-use std::rc::Rc;
+use std::sync::Arc;
 use tree_sitter::Node;
 
 use crate::{
@@ -37,19 +37,18 @@ static MARKDOWN_REFERENCE_IMAGE_SHORTCUT_REGEX: Lazy<Regex> = Lazy::new(|| {
 });
 
 pub(crate) struct MD045Linter {
-    context: Rc<Context>,
+    context: Arc<Context>,
     violations: Vec<RuleViolation>,
     line_starts: Vec<usize>,
 }
 
 impl MD045Linter {
-    pub fn new(context: Rc<Context>) -> Self {
+    pub fn new(context: Arc<Context>) -> Self {
         // Pre-calculate line starts for efficient line/col lookup
         let line_starts: Vec<usize> = std::iter::once(0)
             .chain(
                 context
                     .document_content
-                    .borrow()
                     .match_indices('\n')
                     .map(|(i, _)| i + 1),
             )
@@ -209,7 +208,7 @@ impl RuleLinter for MD045Linter {
                 }
 
                 let (markdown_ranges, html_ranges) = {
-                    let document_content = self.context.document_content.borrow();
+                    let document_content = &self.context.document_content;
                     let content = &document_content[node.start_byte()..node.end_byte()];
 
                     if self.contains_inline_code_with_images(content) {
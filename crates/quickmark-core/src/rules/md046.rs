@@ -1,5 +1,5 @@
-use serde::Deserialize;
-use std::rc::Rc;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 use tree_sitter::Node;
 
 use crate::linter::{CharPosition, Context, Range, RuleLinter, RuleViolation};
@@ -7,9 +7,10 @@ use crate::linter::{CharPosition, Context, Range, RuleLinter, RuleViolation};
 use super::{Rule, RuleType};
 
 // MD046-specific configuration types
-#[derive(Debug, PartialEq, Clone, Deserialize)]
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize, Default)]
 pub enum CodeBlockStyle {
     #[serde(rename = "consistent")]
+    #[default]
     Consistent,
     #[serde(rename = "fenced")]
     Fenced,
@@ -17,15 +18,9 @@ pub enum CodeBlockStyle {
     Indented,
 }
 
-impl Default for CodeBlockStyle {
-    fn default() -> Self {
-        Self::Consistent
-    }
-}
-
-#[derive(Debug, PartialEq, Clone, Deserialize)]
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(default)]
 pub struct MD046CodeBlockStyleTable {
-    #[serde(default)]
     pub style: CodeBlockStyle,
 }
 
@@ -40,13 +35,13 @@ impl Default for MD046CodeBlockStyleTable {
 const VIOLATION_MESSAGE: &str = "Code block style";
 
 pub(crate) struct MD046Linter {
-    context: Rc<Context>,
+    context: Arc<Context>,
     violations: Vec<RuleViolation>,
     expected_style: Option<CodeBlockStyle>,
 }
 
 impl MD046Linter {
-    pub fn new(context: Rc<Context>) -> Self {
+    pub fn new(context: Arc<Context>) -> Self {
         Self {
             context,
             violations: Vec::new(),
@@ -65,22 +60,18 @@ impl MD046Linter {
             .clone();
 
         let all_code_blocks = {
-            let node_cache = self.context.node_cache.borrow();
+            let node_cache = &self.context.node_cache;
             let mut all_code_blocks = Vec::new();
 
             if let Some(fenced_blocks) = node_cache.get("fenced_code_block") {
-                all_code_blocks.extend(
-                    fenced_blocks
-                        .iter()
-                        .map(|n| (n.clone(), CodeBlockStyle::Fenced)),
-                );
+                all_code_blocks.extend(fenced_blocks.iter().map(|n| (*n, CodeBlockStyle::Fenced)));
             }
 
             if let Some(indented_blocks) = node_cache.get("indented_code_block") {
                 all_code_blocks.extend(
                     indented_blocks
                         .iter()
-                        .map(|n| (n.clone(), CodeBlockStyle::Indented)),
+                        .map(|n| (*n, CodeBlockStyle::Indented)),
                 );
             }
 
@@ -1,9 +1,9 @@
-use std::rc::Rc;
+use std::sync::Arc;
 
 use tree_sitter::Node;
 
 use crate::{
-    linter::{range_from_tree_sitter, RuleViolation},
+    linter::{range_from_tree_sitter, CharPosition, Edit, RuleViolation},
     rules::{Context, Rule, RuleLinter, RuleType},
 };
 
@@ -13,12 +13,12 @@ use crate::{
 /// After processing a document (via feed() calls and finalize()), the linter
 /// should be discarded. The violations state is not cleared between uses.
 pub(crate) struct MD047Linter {
-    context: Rc<Context>,
+    context: Arc<Context>,
     violations: Vec<RuleViolation>,
 }
 
 impl MD047Linter {
-    pub fn new(context: Rc<Context>) -> Self {
+    pub fn new(context: Arc<Context>) -> Self {
         Self {
             context,
             violations: Vec::new(),
@@ -27,7 +27,7 @@ impl MD047Linter {
 
     /// Analyze the last line to check if file ends with newline
     fn analyze_last_line(&mut self) {
-        let lines = self.context.lines.borrow();
+        let lines = &self.context.lines;
 
         if lines.is_empty() {
             return;
@@ -92,6 +92,13 @@ impl MD047Linter {
                 },
             }),
         )
+        .with_fix(vec![Edit::insert(
+            CharPosition {
+                line: line_index,
+                character: line.len(),
+            },
+            "\n",
+        )])
     }
 }
 
@@ -266,4 +273,13 @@ mod test {
         // Should point to the end of the last line
         assert_eq!(2, violation.location().range.start.line); // 0-indexed, so line 2 = third line
     }
+
+    #[test]
+    fn test_fix_appends_trailing_newline() {
+        let input = "# Title\n\nNo newline at the end";
+
+        let result = MultiRuleLinter::fix_document(PathBuf::from("test.md"), test_config(), input);
+        assert_eq!("# Title\n\nNo newline at the end\n", result.content);
+        assert!(result.remaining.is_empty());
+    }
 }
@@ -1,5 +1,5 @@
-use serde::Deserialize;
-use std::rc::Rc;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 use tree_sitter::Node;
 
 use crate::linter::{CharPosition, Context, Range, RuleLinter, RuleViolation};
@@ -7,9 +7,10 @@ use crate::linter::{CharPosition, Context, Range, RuleLinter, RuleViolation};
 use super::{Rule, RuleType};
 
 // MD048-specific configuration types
-#[derive(Debug, PartialEq, Clone, Deserialize)]
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize, Default)]
 pub enum CodeFenceStyle {
     #[serde(rename = "consistent")]
+    #[default]
     Consistent,
     #[serde(rename = "backtick")]
     Backtick,
@@ -17,15 +18,9 @@ pub enum CodeFenceStyle {
     Tilde,
 }
 
-impl Default for CodeFenceStyle {
-    fn default() -> Self {
-        Self::Consistent
-    }
-}
-
-#[derive(Debug, PartialEq, Clone, Deserialize)]
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(default)]
 pub struct MD048CodeFenceStyleTable {
-    #[serde(default)]
     pub style: CodeFenceStyle,
 }
 
@@ -40,13 +35,13 @@ impl Default for MD048CodeFenceStyleTable {
 const VIOLATION_MESSAGE: &str = "Code fence style";
 
 pub(crate) struct MD048Linter {
-    context: Rc<Context>,
+    context: Arc<Context>,
     violations: Vec<RuleViolation>,
     expected_style: Option<CodeFenceStyle>,
 }
 
 impl MD048Linter {
-    pub fn new(context: Rc<Context>) -> Self {
+    pub fn new(context: Arc<Context>) -> Self {
         Self {
             context,
             violations: Vec::new(),
@@ -64,20 +59,8 @@ impl MD048Linter {
             .style
             .clone();
 
-        self.context
-            .node_cache
-            .borrow_mut()
-            .entry("fenced_code_block".to_string())
-            .or_default()
-            .sort_by_key(|node_info| node_info.line_start);
-
-        let fenced_blocks = self
-            .context
-            .node_cache
-            .borrow()
-            .get("fenced_code_block")
-            .cloned()
-            .unwrap_or_default();
+        let mut fenced_blocks = self.context.get_nodes(&["fenced_code_block"]);
+        fenced_blocks.sort_by_key(|node_info| node_info.line_start);
 
         for node_info in &fenced_blocks {
             self.check_fenced_code_block(node_info, &configured_style);
@@ -91,7 +74,7 @@ impl MD048Linter {
     ) {
         // Get the fence marker from the first line of the fenced code block
         let line_start = node_info.line_start;
-        if let Some(line) = self.context.lines.borrow().get(line_start) {
+        if let Some(line) = self.context.lines.get(line_start) {
             let trimmed_line = line.trim_start();
             let fence_marker = if trimmed_line.starts_with("```") {
                 CodeFenceStyle::Backtick
@@ -1,19 +1,20 @@
-use serde::Deserialize;
-use std::rc::Rc;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 
 use once_cell::sync::Lazy;
 use regex::Regex;
 use tree_sitter::Node;
 
 use crate::{
-    linter::{range_from_tree_sitter, Context, RuleViolation},
+    linter::{range_from_tree_sitter, CharPosition, Context, Edit, Range, RuleViolation},
     rules::{Rule, RuleLinter, RuleType},
 };
 
 // MD049-specific configuration types
-#[derive(Debug, PartialEq, Clone, Deserialize)]
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize, Default)]
 pub enum EmphasisStyle {
     #[serde(rename = "consistent")]
+    #[default]
     Consistent,
     #[serde(rename = "asterisk")]
     Asterisk,
@@ -21,15 +22,9 @@ pub enum EmphasisStyle {
     Underscore,
 }
 
-impl Default for EmphasisStyle {
-    fn default() -> Self {
-        Self::Consistent
-    }
-}
-
-#[derive(Debug, PartialEq, Clone, Deserialize)]
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(default)]
 pub struct MD049EmphasisStyleTable {
-    #[serde(default)]
     pub style: EmphasisStyle,
 }
 
@@ -59,13 +54,13 @@ enum DetectedEmphasisStyle {
 }
 
 pub(crate) struct MD049Linter {
-    context: Rc<Context>,
+    context: Arc<Context>,
     violations: Vec<RuleViolation>,
     document_style: Option<DetectedEmphasisStyle>,
 }
 
 impl MD049Linter {
-    pub fn new(context: Rc<Context>) -> Self {
+    pub fn new(context: Arc<Context>) -> Self {
         Self {
             context,
             violations: Vec::new(),
@@ -209,12 +204,24 @@ impl MD049Linter {
                     end_point: self.byte_to_point(global_end),
                 };
 
-                self.violations.push(RuleViolation::new(
-                    &MD049,
-                    format!("Expected: {expected_style}; Actual: {actual_style}"),
-                    self.context.file_path.clone(),
-                    range_from_tree_sitter(&range),
-                ));
+                let expected_marker = match expected_style {
+                    "underscore" => "_",
+                    _ => "*",
+                };
+                let fix = vec![
+                    Edit::replace(self.marker_range(global_start), expected_marker),
+                    Edit::replace(self.marker_range(global_end - 1), expected_marker),
+                ];
+
+                self.violations.push(
+                    RuleViolation::new(
+                        &MD049,
+                        format!("Expected: {expected_style}; Actual: {actual_style}"),
+                        self.context.file_path.clone(),
+                        range_from_tree_sitter(&range),
+                    )
+                    .with_fix(fix),
+                );
             }
         }
     }
@@ -249,6 +256,22 @@ impl MD049Linter {
         );
     }
 
+    /// Range of the single-byte emphasis marker at `byte_pos`, with byte columns as used by fixes
+    fn marker_range(&self, byte_pos: usize) -> Range {
+        let source = self.context.get_document_content();
+        let line_start = source[..byte_pos].rfind('\n').map_or(0, |i| i + 1);
+        let line = source[..line_start].matches('\n').count();
+        let character = byte_pos - line_start;
+
+        Range {
+            start: CharPosition { line, character },
+            end: CharPosition {
+                line,
+                character: character + 1,
+            },
+        }
+    }
+
     fn byte_to_point(&self, byte_pos: usize) -> tree_sitter::Point {
         let source = self.context.get_document_content();
         let mut line = 0;
@@ -380,4 +403,16 @@ mod test {
         // Should find violations for the inconsistent nested emphasis
         assert!(!md049_violations.is_empty());
     }
+
+    #[test]
+    fn test_fix_replaces_emphasis_markers() {
+        let input = "Some *emphasis* and _more emphasis_ here.\n";
+
+        let result = MultiRuleLinter::fix_document(PathBuf::from("test.md"), test_config(), input);
+        assert_eq!(
+            "Some *emphasis* and *more emphasis* here.\n",
+            result.content
+        );
+        assert!(result.remaining.is_empty());
+    }
 }
@@ -1,17 +1,18 @@
-use serde::Deserialize;
-use std::rc::Rc;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 
 use tree_sitter::Node;
 
 use crate::{
-    linter::{range_from_tree_sitter, Context, RuleLinter, RuleViolation},
+    linter::{range_from_tree_sitter, Context, Edit, RuleLinter, RuleViolation},
     rules::{Rule, RuleType},
 };
 
 // MD050-specific configuration types
-#[derive(Debug, PartialEq, Clone, Deserialize)]
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize, Default)]
 pub enum StrongStyle {
     #[serde(rename = "consistent")]
+    #[default]
     Consistent,
     #[serde(rename = "asterisk")]
     Asterisk,
@@ -19,15 +20,9 @@ pub enum StrongStyle {
     Underscore,
 }
 
-impl Default for StrongStyle {
-    fn default() -> Self {
-        Self::Consistent
-    }
-}
-
-#[derive(Debug, PartialEq, Clone, Deserialize)]
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(default)]
 pub struct MD050StrongStyleTable {
-    #[serde(default)]
     pub style: StrongStyle,
 }
 
@@ -46,14 +41,14 @@ enum StrongMarkerType {
 }
 
 pub(crate) struct MD050Linter {
-    context: Rc<Context>,
+    context: Arc<Context>,
     violations: Vec<RuleViolation>,
     first_strong_marker: Option<StrongMarkerType>,
     line_start_bytes: Vec<usize>,
 }
 
 impl MD050Linter {
-    pub fn new(context: Rc<Context>) -> Self {
+    pub fn new(context: Arc<Context>) -> Self {
         let line_start_bytes = {
             let content = context.get_document_content();
             std::iter::once(0)
@@ -194,12 +189,41 @@ impl MD050Linter {
                             end_point: self.byte_to_point(char_end_byte),
                         };
 
-                        self.violations.push(RuleViolation::new(
-                            &MD050,
-                            format!("Expected: {expected_style}; Actual: {actual_style}"),
-                            self.context.file_path.clone(),
-                            range_from_tree_sitter(&range),
-                        ));
+                        // Markers combined with emphasis (*** or ___) are left alone, since
+                        // swapping only the strong half would change how the text is parsed
+                        let is_part_of_triple_marker = (i + 2 < chars.len()
+                            && chars[i + 2] == current_char)
+                            || (i > 0 && chars[i - 1] == current_char);
+                        let fix = if is_part_of_triple_marker {
+                            Vec::new()
+                        } else {
+                            let marker_start_byte = text_start_byte
+                                + text.chars().take(i).map(|c| c.len_utf8()).sum::<usize>();
+                            let marker_range = tree_sitter::Range {
+                                start_byte: marker_start_byte,
+                                end_byte: marker_start_byte + 2,
+                                start_point: self.byte_to_point(marker_start_byte),
+                                end_point: self.byte_to_point(marker_start_byte + 2),
+                            };
+                            let expected_marker = match expected_style {
+                                "underscore" => "__",
+                                _ => "**",
+                            };
+                            vec![Edit::replace(
+                                range_from_tree_sitter(&marker_range),
+                                expected_marker,
+                            )]
+                        };
+
+                        self.violations.push(
+                            RuleViolation::new(
+                                &MD050,
+                                format!("Expected: {expected_style}; Actual: {actual_style}"),
+                                self.context.file_path.clone(),
+                                range_from_tree_sitter(&range),
+                            )
+                            .with_fix(fix),
+                        );
                     }
 
                     // Move past this marker pair
@@ -411,4 +435,14 @@ mod test {
         // Should find 2 violations for the inconsistent strong emphasis (opening and closing)
         assert_eq!(md050_violations.len(), 2);
     }
+
+    #[test]
+    fn test_fix_replaces_strong_markers() {
+        let config = test_config_with_style(StrongStyle::Asterisk);
+        let input = "Some __strong__ text and **more**.\n";
+
+        let result = MultiRuleLinter::fix_document(PathBuf::from("test.md"), config, input);
+        assert_eq!("Some **strong** text and **more**.\n", result.content);
+        assert!(result.remaining.is_empty());
+    }
 }
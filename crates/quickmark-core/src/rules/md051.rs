@@ -1,22 +1,20 @@
-use once_cell::sync::Lazy;
-use regex::Regex;
-use serde::Deserialize;
-use std::collections::HashSet;
-use std::rc::Rc;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 
 use tree_sitter::Node;
 
 use crate::{
     linter::{range_from_tree_sitter, RuleViolation},
+    project_index::DocumentAnchors,
+    regex_cache::cached_regex,
     rules::{Context, Rule, RuleLinter, RuleType},
 };
 
 // MD051-specific configuration types
-#[derive(Debug, PartialEq, Clone, Deserialize, Default)]
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize, Default)]
+#[serde(default)]
 pub struct MD051LinkFragmentsTable {
-    #[serde(default)]
     pub ignore_case: bool,
-    #[serde(default)]
     pub ignored_pattern: String,
 }
 
@@ -26,259 +24,56 @@ struct LinkFragment {
     range: tree_sitter::Range,
 }
 
-// GitHub line fragment regex matching:
-// ^#(?:L\d+(?:C\d+)?-L\d+(?:C\d+)?|L\d+)$
-// This allows: L123, L12C5-L34C10. It also matches L12-L34.
-static LINE_FRAGMENT_PATTERN: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r"^L\d+(?:C\d+)?-L\d+(?:C\d+)?$|^L\d+$").unwrap());
-
-static ID_PATTERN: Lazy<Regex> = Lazy::new(|| Regex::new(r#"id\s*=\s*["']([^"']+)["']"#).unwrap());
-
-static NAME_PATTERN: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r#"name\s*=\s*["']([^"']+)["']"#).unwrap());
-
-// Shared regex pattern for extracting markdown links: [text](url)
-static MARKDOWN_LINK_PATTERN: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r"\[([^\]]*)\]\(([^)]*)\)").unwrap());
-
 pub(crate) struct MD051Linter {
-    context: Rc<Context>,
-    valid_fragments: HashSet<String>,
-    valid_fragments_lowercase: HashSet<String>, // Pre-computed lowercase for case-insensitive lookups
+    context: Arc<Context>,
+    anchors: DocumentAnchors,
     link_fragments: Vec<LinkFragment>,
 }
 
 impl MD051Linter {
-    pub fn new(context: Rc<Context>) -> Self {
+    pub fn new(context: Arc<Context>) -> Self {
         Self {
             context,
-            valid_fragments: HashSet::new(),
-            valid_fragments_lowercase: HashSet::new(),
+            anchors: DocumentAnchors::default(),
             link_fragments: Vec::new(),
         }
     }
 
-    fn extract_heading_text(&self, node: &Node) -> Option<String> {
-        let inline_node = if node.kind() == "atx_heading" {
-            node.children(&mut node.walk())
-                .find(|c| c.kind() == "inline")
-        } else if node.kind() == "setext_heading" {
-            node.children(&mut node.walk())
-                .find(|c| c.kind() == "paragraph")
-                .and_then(|p| p.children(&mut p.walk()).find(|gc| gc.kind() == "inline"))
-        } else {
-            None
-        };
-
-        inline_node.map(|n| {
-            let document_content = self.context.document_content.borrow();
-            document_content[n.start_byte()..n.end_byte()]
-                .trim()
-                .to_string()
-        })
-    }
-
-    fn generate_github_fragment(&self, heading_text: &str) -> String {
-        // GitHub fragment generation rules based on reverse engineering:
-        // 1. Convert to lowercase
-        // 2. Replace spaces with hyphens
-        // 3. Keep only alphanumeric, hyphens, and underscores
-        // 4. Remove leading/trailing hyphens
-        let lower = heading_text.trim().to_lowercase().replace(' ', "-");
-        let mut fragment = String::with_capacity(lower.len());
-
-        for c in lower.chars() {
-            if c.is_alphanumeric() || c == '-' || c == '_' {
-                fragment.push(c);
-            }
+    fn extract_link_fragment(&self, node: &Node) -> Option<LinkFragment> {
+        // Only inline links carry their destination: [text](#fragment)
+        let mut cursor = node.walk();
+        let destination = node
+            .children(&mut cursor)
+            .find(|child| child.kind() == "link_destination")?;
+        let document_content = &self.context.document_content;
+        let url = &document_content[destination.start_byte()..destination.end_byte()];
+        let url = url
+            .strip_prefix('<')
+            .and_then(|url| url.strip_suffix('>'))
+            .unwrap_or(url);
+
+        // Only process internal fragments (URLs starting with #)
+        let fragment = url.strip_prefix('#')?;
+        // Only process non-empty fragments that don't contain spaces
+        if fragment.is_empty() || fragment.contains(' ') {
+            return None;
         }
-
-        // Remove leading and trailing hyphens
-        fragment.trim_matches('-').to_string()
-    }
-
-    fn extract_custom_anchor(&self, heading_text: &str) -> Option<String> {
-        // Look for {#custom-anchor} syntax
-        if let Some(start) = heading_text.rfind("{#") {
-            if let Some(end) = heading_text[start..].find('}') {
-                let anchor = &heading_text[start + 2..start + end];
-                return Some(anchor.to_string());
-            }
-        }
-        None
-    }
-
-    fn extract_link_fragment_from_link_node(&self, node: &Node) -> Option<LinkFragment> {
-        // Extract fragment from a tree-sitter "link" node
-        let start_byte = node.start_byte();
-        let end_byte = node.end_byte();
-        let document_content = self.context.document_content.borrow();
-        let link_text = &document_content[start_byte..end_byte];
-
-        // Use regex to extract URL from inline link: [text](url)
-        for cap in MARKDOWN_LINK_PATTERN.captures_iter(link_text) {
-            if let Some(url_match) = cap.get(2) {
-                let url = url_match.as_str();
-                // Only process internal fragments (URLs starting with #)
-                if url.starts_with('#') {
-                    if let Some(hash_pos) = url.rfind('#') {
-                        let fragment = &url[hash_pos + 1..];
-                        // Only process non-empty fragments that don't contain spaces
-                        if !fragment.is_empty() && !fragment.contains(' ') {
-                            return Some(LinkFragment {
-                                fragment: fragment.to_string(),
-                                range: node.range(),
-                            });
-                        }
-                    }
-                }
-            }
-        }
-
-        None
-    }
-
-    fn extract_link_fragments_from_inline(&self, node: &Node) -> Vec<LinkFragment> {
-        // Extract fragments from inline text using regex - more robust than tree parsing
-        let start_byte = node.start_byte();
-        let end_byte = node.end_byte();
-        let document_content = self.context.document_content.borrow();
-        let text = &document_content[start_byte..end_byte];
-        let mut fragments = Vec::new();
-
-        // Use regex to match proper markdown links: [text](url) with no text between ] and (
-        for cap in MARKDOWN_LINK_PATTERN.captures_iter(text) {
-            if let Some(url_match) = cap.get(2) {
-                let url = url_match.as_str();
-                // Only process internal fragments (URLs starting with #)
-                if url.starts_with('#') {
-                    if let Some(hash_pos) = url.rfind('#') {
-                        let fragment = &url[hash_pos + 1..];
-                        // Only process non-empty fragments that don't contain spaces
-                        if !fragment.is_empty() && !fragment.contains(' ') {
-                            // Calculate the actual byte position of this match in the document
-                            let match_start = start_byte + cap.get(0).unwrap().start();
-                            let match_end = start_byte + cap.get(0).unwrap().end();
-                            fragments.push(LinkFragment {
-                                fragment: fragment.to_string(),
-                                range: tree_sitter::Range {
-                                    start_byte: match_start,
-                                    end_byte: match_end,
-                                    start_point: tree_sitter::Point::new(0, match_start),
-                                    end_point: tree_sitter::Point::new(0, match_end),
-                                },
-                            });
-                        }
-                    }
-                }
-            }
-        }
-
-        fragments
-    }
-
-    fn is_github_special_fragment(&self, fragment: &str) -> bool {
-        // GitHub special fragments according to GitHub specification
-        // Reference: https://docs.github.com/en/get-started/writing-on-github/working-with-advanced-formatting/creating-a-permanent-link-to-a-code-snippet
-
-        if fragment == "top" {
-            return true;
-        }
-
-        if LINE_FRAGMENT_PATTERN.is_match(fragment) {
-            return true;
-        }
-
-        false
-    }
-
-    fn extract_html_id_or_name(&self, node: &Node) -> Vec<String> {
-        // Extract id and name attributes from HTML elements
-        let mut ids = Vec::new();
-        let start_byte = node.start_byte();
-        let end_byte = node.end_byte();
-        let document_content = self.context.document_content.borrow();
-        let html_content = &document_content[start_byte..end_byte];
-
-        for cap in ID_PATTERN.captures_iter(html_content) {
-            if let Some(id) = cap.get(1) {
-                ids.push(id.as_str().to_string());
-            }
-        }
-
-        for cap in NAME_PATTERN.captures_iter(html_content) {
-            if let Some(name) = cap.get(1) {
-                ids.push(name.as_str().to_string());
-            }
-        }
-
-        ids
+        Some(LinkFragment {
+            fragment: fragment.to_string(),
+            range: node.range(),
+        })
     }
 }
 
 impl RuleLinter for MD051Linter {
     fn feed(&mut self, node: &Node) {
         match node.kind() {
-            "atx_heading" | "setext_heading" => {
-                if let Some(heading_text) = self.extract_heading_text(node) {
-                    // Check for custom anchor first
-                    if let Some(custom_anchor) = self.extract_custom_anchor(&heading_text) {
-                        self.valid_fragments.insert(custom_anchor.clone());
-                        self.valid_fragments_lowercase
-                            .insert(custom_anchor.to_lowercase());
-                        // Also generate the default fragment from the heading text without the anchor
-                        let clean_text = heading_text
-                            .replace(&format!("{{#{custom_anchor}}}"), "")
-                            .trim()
-                            .to_string();
-                        if !clean_text.is_empty() {
-                            let fragment = self.generate_github_fragment(&clean_text);
-                            if !fragment.is_empty() {
-                                self.valid_fragments.insert(fragment.clone());
-                                self.valid_fragments_lowercase
-                                    .insert(fragment.to_lowercase());
-                            }
-                        }
-                    } else {
-                        // Generate GitHub-style fragment
-                        let fragment = self.generate_github_fragment(&heading_text);
-                        if !fragment.is_empty() {
-                            // Handle duplicate headings by checking if fragment already exists
-                            let mut unique_fragment = fragment.clone();
-                            let mut counter = 1;
-                            while self.valid_fragments.contains(&unique_fragment) {
-                                unique_fragment = format!("{fragment}-{counter}");
-                                counter += 1;
-                            }
-                            self.valid_fragments.insert(unique_fragment.clone());
-                            self.valid_fragments_lowercase
-                                .insert(unique_fragment.to_lowercase());
-                        }
-                    }
-                }
+            "atx_heading" | "setext_heading" | "inline" | "html_block" => {
+                let document_content = self.context.get_document_content();
+                self.anchors.add_node(node, document_content);
             }
-            "link" => {
-                // Extract link fragments from actual tree-sitter link nodes
-                if let Some(link_fragment) = self.extract_link_fragment_from_link_node(node) {
-                    self.link_fragments.push(link_fragment);
-                }
-            }
-            "inline" | "html_block" => {
-                // Extract HTML id and name attributes
-                let ids = self.extract_html_id_or_name(node);
-                for id in ids {
-                    self.valid_fragments.insert(id.clone());
-                    self.valid_fragments_lowercase.insert(id.to_lowercase());
-                }
-
-                // We can't fully rely on tree-sitter "link" nodes for link extraction because:
-                // 1. Tree-sitter may not always parse all link structures as "link" nodes in complex inline contexts
-                // 2. Some markdown parsers have different interpretations of what constitutes a valid link structure
-                // 3. Tree-sitter's parsing can vary based on surrounding context and nesting
-                // 4. We need to ensure we catch all valid [text](#fragment) patterns regardless of how they're categorized
-                // Therefore, we supplement tree-sitter link nodes with regex-based extraction from inline content.
-                let link_fragments = self.extract_link_fragments_from_inline(node);
-                for link_fragment in link_fragments {
+            "inline_link" => {
+                if let Some(link_fragment) = self.extract_link_fragment(node) {
                     self.link_fragments.push(link_fragment);
                 }
             }
@@ -294,38 +89,19 @@ impl RuleLinter for MD051Linter {
 
         // Compile ignored pattern regex if provided
         let ignored_regex = if !config.ignored_pattern.is_empty() {
-            Regex::new(&config.ignored_pattern).ok()
+            cached_regex(&config.ignored_pattern).ok()
         } else {
             None
         };
 
         for link_fragment in &self.link_fragments {
             let fragment = &link_fragment.fragment;
-            let mut is_valid = false;
-
-            // Check if it's a GitHub special fragment
-            if self.is_github_special_fragment(fragment) {
-                is_valid = true;
-            }
-
-            // Check if it matches ignored pattern
-            if !is_valid {
-                if let Some(ref regex) = ignored_regex {
-                    if regex.is_match(fragment) {
-                        is_valid = true;
-                    }
-                }
-            }
 
-            // Check if it matches any valid fragment
-            if !is_valid {
-                if config.ignore_case {
-                    let fragment_lower = fragment.to_lowercase();
-                    is_valid = self.valid_fragments_lowercase.contains(&fragment_lower);
-                } else {
-                    is_valid = self.valid_fragments.contains(fragment);
-                }
-            }
+            // Check if it matches the ignored pattern or any valid fragment
+            let is_valid = ignored_regex
+                .as_ref()
+                .is_some_and(|regex| regex.is_match(fragment))
+                || self.anchors.contains(fragment, config.ignore_case);
 
             if !is_valid {
                 violations.push(RuleViolation::new(
@@ -347,7 +123,7 @@ pub const MD051: Rule = Rule {
     tags: &["links"],
     description: "Link fragments should be valid",
     rule_type: RuleType::Document,
-    required_nodes: &["link", "atx_heading", "setext_heading"],
+    required_nodes: &["inline_link", "atx_heading", "setext_heading"],
     new_linter: |context| Box::new(MD051Linter::new(context)),
 };
 
@@ -822,4 +598,25 @@ This paragraph has [valid link](#test-heading-one) and [invalid link](#nonexiste
         // Should have no violations - colons should be removed per GitHub spec
         assert_eq!(0, violations.len());
     }
+
+    #[test]
+    fn test_fragment_links_in_code_and_block_quotes() {
+        let input = "# Heading
+
+`[code](#missing)`
+
+> Quoted [link](#heading) and a
+> [broken one](#nowhere)
+";
+
+        let config = test_config();
+        let mut multi_linter =
+            MultiRuleLinter::new_for_document(PathBuf::from("test.md"), config, input);
+        let violations = multi_linter.analyze();
+
+        assert_eq!(1, violations.len());
+        assert!(violations[0].message().contains("Link fragment 'nowhere'"));
+        assert_eq!(5, violations[0].location().range.start.line);
+        assert_eq!(2, violations[0].location().range.start.character);
+    }
 }
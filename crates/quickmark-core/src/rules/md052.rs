@@ -1,8 +1,8 @@
 use once_cell::sync::Lazy;
 use regex::Regex;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
-use std::rc::Rc;
+use std::sync::Arc;
 use tree_sitter::Node;
 
 use crate::{
@@ -11,11 +11,10 @@ use crate::{
 };
 
 // MD052-specific configuration types
-#[derive(Debug, PartialEq, Clone, Deserialize)]
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(default)]
 pub struct MD052ReferenceLinksImagesTable {
-    #[serde(default)]
     pub shortcut_syntax: bool,
-    #[serde(default)]
     pub ignored_labels: Vec<String>,
 }
 
@@ -48,13 +47,13 @@ struct ReferenceLink {
 }
 
 pub(crate) struct MD052Linter {
-    context: Rc<Context>,
+    context: Arc<Context>,
     definitions: HashSet<String>,
     references: Vec<ReferenceLink>,
 }
 
 impl MD052Linter {
-    pub fn new(context: Rc<Context>) -> Self {
+    pub fn new(context: Arc<Context>) -> Self {
         Self {
             context,
             definitions: HashSet::new(),
@@ -79,7 +78,7 @@ impl MD052Linter {
         // [label]: url "title"
         let start_byte = node.start_byte();
         let end_byte = node.end_byte();
-        let document_content = self.context.document_content.borrow();
+        let document_content = &self.context.document_content;
         let content = &document_content[start_byte..end_byte];
 
         let mut definitions = Vec::new();
@@ -98,7 +97,7 @@ impl MD052Linter {
         // Shortcut: [label]
         let start_byte = node.start_byte();
         let end_byte = node.end_byte();
-        let document_content = self.context.document_content.borrow();
+        let document_content = &self.context.document_content;
         let content = &document_content[start_byte..end_byte];
 
         let mut links = Vec::new();
@@ -164,17 +163,6 @@ impl RuleLinter for MD052Linter {
                     });
                 }
             }
-            // Handle reference links [text][label], [label][], [label]
-            "link" | "image" => {
-                let links = self.extract_reference_links(node);
-                for (label, is_shortcut) in links {
-                    self.references.push(ReferenceLink {
-                        label,
-                        range: node.range(),
-                        is_shortcut,
-                    });
-                }
-            }
             _ => {
                 // Check all other node types for reference definitions
                 let definitions = self.extract_reference_definition(node);
@@ -231,7 +219,7 @@ pub const MD052: Rule = Rule {
     tags: &["links", "images"],
     description: "Reference links and images should use a label that is defined",
     rule_type: RuleType::Document,
-    required_nodes: &["link", "image", "paragraph"],
+    required_nodes: &["paragraph"],
     new_linter: |context| Box::new(MD052Linter::new(context)),
 };
 
@@ -1,8 +1,8 @@
 use once_cell::sync::Lazy;
 use regex::Regex;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
-use std::rc::Rc;
+use std::sync::Arc;
 use tree_sitter::Node;
 
 use crate::{
@@ -11,9 +11,9 @@ use crate::{
 };
 
 // MD053-specific configuration types
-#[derive(Debug, PartialEq, Clone, Deserialize)]
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(default)]
 pub struct MD053LinkImageReferenceDefinitionsTable {
-    #[serde(default)]
     pub ignored_definitions: Vec<String>,
 }
 
@@ -44,13 +44,13 @@ struct ReferenceDefinition {
 }
 
 pub(crate) struct MD053Linter {
-    context: Rc<Context>,
+    context: Arc<Context>,
     definitions: HashMap<String, Vec<ReferenceDefinition>>, // Track multiple definitions per label
     references: HashSet<String>,                            // All referenced labels
 }
 
 impl MD053Linter {
-    pub fn new(context: Rc<Context>) -> Self {
+    pub fn new(context: Arc<Context>) -> Self {
         Self {
             context,
             definitions: HashMap::new(),
@@ -91,7 +91,7 @@ impl MD053Linter {
         // [label]: url "title"
         let start_byte = node.start_byte();
         let end_byte = node.end_byte();
-        let document_content = self.context.document_content.borrow();
+        let document_content = &self.context.document_content;
         let content = &document_content[start_byte..end_byte];
 
         REFERENCE_DEFINITION_PATTERN
@@ -115,7 +115,7 @@ impl MD053Linter {
         // Shortcut: [label]
         let start_byte = node.start_byte();
         let end_byte = node.end_byte();
-        let document_content = self.context.document_content.borrow();
+        let document_content = &self.context.document_content;
         let content = &document_content[start_byte..end_byte];
 
         let mut links = Vec::new();
@@ -202,13 +202,6 @@ impl RuleLinter for MD053Linter {
                     self.references.insert(link);
                 }
             }
-            // Handle reference links [text][label], [label][], [label]
-            "link" | "image" => {
-                let links = self.extract_reference_links(node);
-                for link in links {
-                    self.references.insert(link);
-                }
-            }
             _ => {
                 // Ignore other node types
             }
@@ -291,7 +284,7 @@ pub const MD053: Rule = Rule {
     tags: &["links", "images"],
     description: "Link and image reference definitions should be needed",
     rule_type: RuleType::Document,
-    required_nodes: &["link", "image", "paragraph", "link_reference_definition"],
+    required_nodes: &["paragraph", "link_reference_definition"],
     new_linter: |context| Box::new(MD053Linter::new(context)),
 };
 
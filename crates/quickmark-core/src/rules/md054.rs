@@ -1,30 +1,23 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
-use std::rc::Rc;
+use std::sync::Arc;
 
-use once_cell::sync::Lazy;
-use regex::Regex;
 use tree_sitter::Node;
 
 use crate::{
-    linter::{range_from_tree_sitter, RuleViolation},
+    linter::{normalize_link_label, range_from_tree_sitter, RuleViolation},
     rules::{Context, Rule, RuleLinter, RuleType},
 };
 
 // MD054-specific configuration types
-#[derive(Debug, PartialEq, Clone, Deserialize)]
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(default)]
 pub struct MD054LinkImageStyleTable {
-    #[serde(default)]
     pub autolink: bool,
-    #[serde(default)]
     pub inline: bool,
-    #[serde(default)]
     pub full: bool,
-    #[serde(default)]
     pub collapsed: bool,
-    #[serde(default)]
     pub shortcut: bool,
-    #[serde(default)]
     pub url_inline: bool,
 }
 
@@ -41,53 +34,57 @@ impl Default for MD054LinkImageStyleTable {
     }
 }
 
-// Combined regular expressions for detecting different link and image styles.
-// This improves performance by reducing the number of passes over the text.
-// Groups are used to differentiate between image and link matches.
-
-// Style: [text](url)
-static RE_INLINE: Lazy<Regex> = Lazy::new(|| {
-    Regex::new(r"(!\[([^\]]*)\]\(([^)]*)\))|((?:^|[^!])\[([^\]]*)\]\(([^)]*)\))").unwrap()
-});
-
-// Style: [text][ref]
-static RE_FULL_REFERENCE: Lazy<Regex> = Lazy::new(|| {
-    Regex::new(r"(!\[([^\]]*)\]\[([^\]]+)\])|((?:^|[^!])\[([^\]]*)\]\[([^\]]+)\])").unwrap()
-});
-
-// Style: [ref][]
-static RE_COLLAPSED_REFERENCE: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r"(!\[([^\]]+)\]\[\])|((?:^|[^!])\[([^\]]+)\]\[\])").unwrap());
-
-// Style: [ref]
-static RE_SHORTCUT_REFERENCE: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r"(!\[([^\]]+)\])|((?:^|[^!])\[([^\]]+)\])").unwrap());
-
-// Style: <url>
-static RE_AUTOLINK: Lazy<Regex> = Lazy::new(|| Regex::new(r"<(https?://[^>]+)>").unwrap());
-
 /// MD054 - Link and image style
 ///
 /// This rule controls which styles of links and images are allowed in the document.
 pub(crate) struct MD054Linter {
-    context: Rc<Context>,
+    context: Arc<Context>,
     violations: Vec<RuleViolation>,
+    /// Normalized labels of the link reference definitions in the document
+    definitions: HashSet<String>,
 }
 
 impl MD054Linter {
-    pub fn new(context: Rc<Context>) -> Self {
+    pub fn new(context: Arc<Context>) -> Self {
+        let definitions = context.link_reference_labels();
         Self {
             context,
             violations: Vec::new(),
+            definitions,
         }
     }
 }
 
 impl RuleLinter for MD054Linter {
     fn feed(&mut self, node: &Node) {
-        if node.kind() == "inline" {
-            self.check_inline_content(node);
-        }
+        let config = &self.context.config.linters.settings.link_image_style;
+        let message = match node.kind() {
+            "uri_autolink" | "email_autolink" if !config.autolink => "Autolinks are not allowed",
+            "inline_link" if !config.inline => "Inline links are not allowed",
+            "inline_link" if !config.url_inline && self.has_url_text(node) => {
+                "Inline links with matching URL text are not allowed"
+            }
+            "full_reference_link" if !config.full => "Full reference links are not allowed",
+            "collapsed_reference_link" if !config.collapsed => {
+                "Collapsed reference links are not allowed"
+            }
+            "shortcut_link" if !config.shortcut && self.is_defined(node) => {
+                "Shortcut reference links are not allowed"
+            }
+            "image" => match self.image_style(node) {
+                ImageStyle::Inline if !config.inline => "Inline images are not allowed",
+                ImageStyle::Full if !config.full => "Full reference images are not allowed",
+                ImageStyle::Collapsed if !config.collapsed => {
+                    "Collapsed reference images are not allowed"
+                }
+                ImageStyle::Shortcut if !config.shortcut && self.is_defined(node) => {
+                    "Shortcut reference images are not allowed"
+                }
+                _ => return,
+            },
+            _ => return,
+        };
+        self.create_violation(node, message.to_string());
     }
 
     fn finalize(&mut self) -> Vec<RuleViolation> {
@@ -95,230 +92,70 @@ impl RuleLinter for MD054Linter {
     }
 }
 
-impl MD054Linter {
-    fn check_inline_content(&mut self, node: &Node) {
-        let content = {
-            let document_content = self.context.document_content.borrow();
-            node.utf8_text(document_content.as_bytes())
-                .unwrap_or("")
-                .to_string()
-        };
+enum ImageStyle {
+    Inline,
+    Full,
+    Collapsed,
+    Shortcut,
+}
 
-        if !content.is_empty() {
-            self.check_content_for_violations(&content, node);
+impl MD054Linter {
+    fn node_text(&self, node: &Node) -> String {
+        let document_content = &self.context.document_content;
+        node.utf8_text(document_content.as_bytes())
+            .unwrap_or("")
+            .to_string()
+    }
+
+    fn child_text(&self, node: &Node, kind: &str) -> Option<String> {
+        let mut cursor = node.walk();
+        let child = node
+            .children(&mut cursor)
+            .find(|child| child.kind() == kind)?;
+        Some(self.node_text(&child))
+    }
+
+    /// Whether an inline link uses its URL as the link text: `[https://...](https://...)`
+    fn has_url_text(&self, node: &Node) -> bool {
+        match (
+            self.child_text(node, "link_text"),
+            self.child_text(node, "link_destination"),
+        ) {
+            (Some(text), Some(destination)) => text == destination,
+            _ => false,
         }
     }
 
-    fn check_content_for_violations(&mut self, content: &str, node: &Node) {
-        let config = self
-            .context
-            .config
-            .linters
-            .settings
-            .link_image_style
-            .clone();
-        let mut found_violations = HashSet::new();
-
-        // Check for autolinks
-        if !config.autolink {
-            for caps in RE_AUTOLINK.captures_iter(content) {
-                let start = caps.get(0).unwrap().start();
-                if found_violations.insert(("autolink", start)) {
-                    self.create_violation_at_offset(
-                        node,
-                        content,
-                        start,
-                        "Autolinks are not allowed".to_string(),
-                    );
-                }
-            }
-        }
-
-        // Check for inline style
-        for caps in RE_INLINE.captures_iter(content) {
-            // Group 1: Image match `![]()`
-            if let Some(image_match) = caps.get(1) {
-                if !config.inline && found_violations.insert(("inline_image", image_match.start()))
-                {
-                    self.create_violation_at_offset(
-                        node,
-                        content,
-                        image_match.start(),
-                        "Inline images are not allowed".to_string(),
-                    );
-                }
-            }
-            // Group 4: Link match `[]()`
-            else if let Some(link_match) = caps.get(4) {
-                let mut start = link_match.start();
-                if !link_match.as_str().starts_with('[') {
-                    start += 1; // Adjust for `(?:^|[^!])`
-                }
-
-                if !config.inline {
-                    if found_violations.insert(("inline_link", start)) {
-                        self.create_violation_at_offset(
-                            node,
-                            content,
-                            start,
-                            "Inline links are not allowed".to_string(),
-                        );
-                    }
-                    continue; // If disallowed, no need for further checks
-                }
-
-                // Check for url_inline: [https://...](https://...)
-                if !config.url_inline {
-                    if let (Some(text), Some(url)) = (caps.get(5), caps.get(6)) {
-                        if text.as_str() == url.as_str()
-                            && found_violations.insert(("url_inline", start))
-                        {
-                            self.create_violation_at_offset(
-                                node,
-                                content,
-                                start,
-                                "Inline links with matching URL text are not allowed".to_string(),
-                            );
-                        }
-                    }
-                }
-            }
-        }
-
-        // Check for full reference style
-        if !config.full {
-            for caps in RE_FULL_REFERENCE.captures_iter(content) {
-                if let Some(image_match) = caps.get(1) {
-                    if found_violations.insert(("full_image", image_match.start())) {
-                        self.create_violation_at_offset(
-                            node,
-                            content,
-                            image_match.start(),
-                            "Full reference images are not allowed".to_string(),
-                        );
-                    }
-                } else if let Some(link_match) = caps.get(4) {
-                    let mut start = link_match.start();
-                    if !link_match.as_str().starts_with('[') {
-                        start += 1;
-                    }
-                    if found_violations.insert(("full_link", start)) {
-                        self.create_violation_at_offset(
-                            node,
-                            content,
-                            start,
-                            "Full reference links are not allowed".to_string(),
-                        );
-                    }
-                }
-            }
-        }
-
-        // Check for collapsed reference style
-        if !config.collapsed {
-            for caps in RE_COLLAPSED_REFERENCE.captures_iter(content) {
-                if let Some(image_match) = caps.get(1) {
-                    if found_violations.insert(("collapsed_image", image_match.start())) {
-                        self.create_violation_at_offset(
-                            node,
-                            content,
-                            image_match.start(),
-                            "Collapsed reference images are not allowed".to_string(),
-                        );
-                    }
-                } else if let Some(link_match) = caps.get(3) {
-                    let mut start = link_match.start();
-                    if !link_match.as_str().starts_with('[') {
-                        start += 1;
-                    }
-                    if found_violations.insert(("collapsed_link", start)) {
-                        self.create_violation_at_offset(
-                            node,
-                            content,
-                            start,
-                            "Collapsed reference links are not allowed".to_string(),
-                        );
-                    }
-                }
-            }
-        }
-
-        // Check for shortcut reference style
-        if !config.shortcut {
-            for caps in RE_SHORTCUT_REFERENCE.captures_iter(content) {
-                let whole_match = caps.get(0).unwrap();
-                let end_offset = whole_match.end();
-
-                // Check character after match to avoid false positives for other link types
-                if end_offset < content.len() {
-                    if let Some(next_char) = content[end_offset..].chars().next() {
-                        if next_char == '(' || next_char == '[' {
-                            continue;
-                        }
-                    }
-                }
+    /// Whether a shortcut link or image refers to a link reference definition
+    fn is_defined(&self, node: &Node) -> bool {
+        let text = self.node_text(node);
+        let label = normalize_link_label(text.trim_start_matches('!'));
+        self.definitions.contains(&label)
+    }
 
-                if let Some(image_match) = caps.get(1) {
-                    if found_violations.insert(("shortcut_image", image_match.start())) {
-                        self.create_violation_at_offset(
-                            node,
-                            content,
-                            image_match.start(),
-                            "Shortcut reference images are not allowed".to_string(),
-                        );
-                    }
-                } else if let Some(link_match) = caps.get(3) {
-                    let mut start = link_match.start();
-                    if !link_match.as_str().starts_with('[') {
-                        start += 1;
-                    }
-                    if found_violations.insert(("shortcut_link", start)) {
-                        self.create_violation_at_offset(
-                            node,
-                            content,
-                            start,
-                            "Shortcut reference links are not allowed".to_string(),
-                        );
-                    }
-                }
-            }
+    /// Collapsed and shortcut images share the same shape and differ in the trailing `[]`
+    fn image_style(&self, node: &Node) -> ImageStyle {
+        if self.child_text(node, "link_destination").is_some() {
+            ImageStyle::Inline
+        } else if self.child_text(node, "link_label").is_some() {
+            ImageStyle::Full
+        } else if self.node_text(node).ends_with("[]") {
+            ImageStyle::Collapsed
+        } else {
+            ImageStyle::Shortcut
         }
     }
 
-    fn create_violation_at_offset(
-        &mut self,
-        node: &Node,
-        content: &str,
-        offset: usize,
-        message: String,
-    ) {
-        // Calculate the line number within the content where the violation occurs
-        let lines_before_offset = content[..offset].matches('\n').count();
-        let node_start_row = node.start_position().row;
-        let violation_row = node_start_row + lines_before_offset;
-
-        // Create a custom range for this specific violation
+    fn create_violation(&mut self, node: &Node, message: String) {
+        // Just mark the start of the violation
+        let start = node.start_position();
         let violation_range = tree_sitter::Range {
-            start_byte: node.start_byte() + offset,
-            end_byte: node.start_byte() + offset + 1, // Just mark the start of the violation
-            start_point: tree_sitter::Point {
-                row: violation_row,
-                column: if lines_before_offset == 0 {
-                    node.start_position().column + offset
-                } else {
-                    // Calculate column position for this line
-                    let line_start = content[..offset].rfind('\n').map(|i| i + 1).unwrap_or(0);
-                    offset - line_start
-                },
-            },
+            start_byte: node.start_byte(),
+            end_byte: node.start_byte() + 1,
+            start_point: start,
             end_point: tree_sitter::Point {
-                row: violation_row,
-                column: if lines_before_offset == 0 {
-                    node.start_position().column + offset + 1
-                } else {
-                    let line_start = content[..offset].rfind('\n').map(|i| i + 1).unwrap_or(0);
-                    offset - line_start + 1
-                },
+                row: start.row,
+                column: start.column + 1,
             },
         };
 
@@ -337,7 +174,16 @@ pub const MD054: Rule = Rule {
     tags: &["links", "images"],
     description: "Link and image style",
     rule_type: RuleType::Token,
-    required_nodes: &["inline"],
+    required_nodes: &[
+        "inline_link",
+        "full_reference_link",
+        "collapsed_reference_link",
+        "shortcut_link",
+        "image",
+        "uri_autolink",
+        "email_autolink",
+        "link_label",
+    ],
     new_linter: |context| Box::new(MD054Linter::new(context)),
 };
 
@@ -880,4 +726,45 @@ mod test {
         assert!(violations[0].message().contains("Inline images"));
         assert_eq!(0, violations[0].location().range.start.line);
     }
+
+    #[test]
+    fn test_undefined_brackets_are_not_shortcut_links() {
+        let input = "Array [index] and ![not an image]\n\n[defined]: https://example.com";
+        let config = test_config_with_settings(MD054LinkImageStyleTable {
+            shortcut: false,
+            ..Default::default()
+        });
+        let mut linter = MultiRuleLinter::new_for_document(PathBuf::from("test.md"), config, input);
+        let violations = linter.analyze();
+        assert_eq!(0, violations.len());
+    }
+
+    #[test]
+    fn test_links_in_code_spans_ignored() {
+        let input = "Write `[text](url)` or `<https://example.com>`";
+        let config = test_config_with_settings(MD054LinkImageStyleTable {
+            autolink: false,
+            inline: false,
+            ..Default::default()
+        });
+        let mut linter = MultiRuleLinter::new_for_document(PathBuf::from("test.md"), config, input);
+        let violations = linter.analyze();
+        assert_eq!(0, violations.len());
+    }
+
+    #[test]
+    fn test_image_inside_link() {
+        let input = "[![badge](badge.svg)](https://example.com)";
+        let config = test_config_with_settings(MD054LinkImageStyleTable {
+            inline: false,
+            ..Default::default()
+        });
+        let mut linter = MultiRuleLinter::new_for_document(PathBuf::from("test.md"), config, input);
+        let violations = linter.analyze();
+        assert_eq!(2, violations.len());
+        assert!(violations[0].message().contains("Inline links"));
+        assert_eq!(0, violations[0].location().range.start.character);
+        assert!(violations[1].message().contains("Inline images"));
+        assert_eq!(1, violations[1].location().range.start.character);
+    }
 }
@@ -1,17 +1,18 @@
-use serde::Deserialize;
-use std::rc::Rc;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 
 use tree_sitter::Node;
 
 use crate::{
-    linter::{range_from_tree_sitter, RuleViolation},
+    linter::{range_from_tree_sitter, CharPosition, Edit, Range, RuleViolation},
     rules::{Context, Rule, RuleLinter, RuleType},
 };
 
 // MD055-specific configuration types
-#[derive(Debug, PartialEq, Clone, Deserialize)]
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize, Default)]
 pub enum TablePipeStyle {
     #[serde(rename = "consistent")]
+    #[default]
     Consistent,
     #[serde(rename = "leading_and_trailing")]
     LeadingAndTrailing,
@@ -23,15 +24,9 @@ pub enum TablePipeStyle {
     NoLeadingOrTrailing,
 }
 
-impl Default for TablePipeStyle {
-    fn default() -> Self {
-        Self::Consistent
-    }
-}
-
-#[derive(Debug, PartialEq, Clone, Deserialize)]
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(default)]
 pub struct MD055TablePipeStyleTable {
-    #[serde(default)]
     pub style: TablePipeStyle,
 }
 
@@ -47,7 +42,7 @@ impl Default for MD055TablePipeStyleTable {
 ///
 /// This rule enforces consistent use of leading and trailing pipes in tables.
 pub(crate) struct MD055Linter {
-    context: Rc<Context>,
+    context: Arc<Context>,
     violations: Vec<RuleViolation>,
     first_table_style: Option<(bool, bool)>, // (has_leading, has_trailing)
 }
@@ -55,10 +50,12 @@ pub(crate) struct MD055Linter {
 struct ViolationInfo {
     message: String,
     column_offset: usize,
+    /// Replacement for the row text between two column offsets that fixes the violation
+    fix: (usize, usize, &'static str),
 }
 
 impl MD055Linter {
-    pub fn new(context: Rc<Context>) -> Self {
+    pub fn new(context: Arc<Context>) -> Self {
         Self {
             context,
             violations: Vec::new(),
@@ -99,7 +96,7 @@ impl MD055Linter {
         let mut all_violation_infos = Vec::new();
         {
             // This scope limits the lifetime of `document_content`'s borrow
-            let document_content = self.context.document_content.borrow();
+            let document_content = &self.context.document_content;
             let config_style = &self.context.config.linters.settings.table_pipe_style.style;
 
             let expected_style = match config_style {
@@ -126,7 +123,7 @@ impl MD055Linter {
             };
 
             for row in &table_rows {
-                let infos = self.check_row_pipe_style(row, expected_style, &document_content);
+                let infos = self.check_row_pipe_style(row, expected_style, document_content);
                 if !infos.is_empty() {
                     all_violation_infos.push((*row, infos));
                 }
@@ -135,7 +132,7 @@ impl MD055Linter {
 
         for (row, infos) in all_violation_infos {
             for info in infos {
-                self.create_violation_at_position(&row, info.message, info.column_offset);
+                self.create_violation_at_position(&row, info);
             }
         }
     }
@@ -165,9 +162,22 @@ impl MD055Linter {
             } else {
                 "Unexpected leading pipe"
             };
+            let fix = if expected_leading {
+                (leading_whitespace_len, leading_whitespace_len, "| ")
+            } else {
+                // Drop the pipe together with the padding that follows it
+                let after_pipe = &trimmed_text[1..];
+                let padding = after_pipe.len() - after_pipe.trim_start().len();
+                (
+                    leading_whitespace_len,
+                    leading_whitespace_len + 1 + padding,
+                    "",
+                )
+            };
             infos.push(ViolationInfo {
                 message: message.to_string(),
                 column_offset: leading_whitespace_len,
+                fix,
             });
         }
 
@@ -183,25 +193,48 @@ impl MD055Linter {
             } else {
                 leading_whitespace_len + trimmed_text.len()
             };
+            let fix = if expected_trailing {
+                (pos, pos, " |")
+            } else {
+                // Drop the pipe together with the padding that precedes it
+                let before_pipe = &trimmed_text[..trimmed_text.len() - 1];
+                (
+                    leading_whitespace_len + before_pipe.trim_end().len(),
+                    pos + 1,
+                    "",
+                )
+            };
             infos.push(ViolationInfo {
                 message: message.to_string(),
                 column_offset: pos,
+                fix,
             });
         }
         infos
     }
 
-    fn create_violation_at_position(&mut self, node: &Node, message: String, column_offset: usize) {
+    fn create_violation_at_position(&mut self, node: &Node, info: ViolationInfo) {
+        let row_start = node.start_position();
         let mut range = range_from_tree_sitter(&node.range());
-        range.start.character += column_offset;
+        range.start.character += info.column_offset;
         range.end.character = range.start.character + 1;
 
-        self.violations.push(RuleViolation::new(
-            &MD055,
-            message,
-            self.context.file_path.clone(),
-            range,
-        ));
+        let (fix_start, fix_end, replacement) = info.fix;
+        let fix_range = Range {
+            start: CharPosition {
+                line: row_start.row,
+                character: row_start.column + fix_start,
+            },
+            end: CharPosition {
+                line: row_start.row,
+                character: row_start.column + fix_end,
+            },
+        };
+
+        self.violations.push(
+            RuleViolation::new(&MD055, info.message, self.context.file_path.clone(), range)
+                .with_fix(vec![Edit::replace(fix_range, replacement)]),
+        );
     }
 }
 
@@ -580,4 +613,25 @@ Cell 1    | Cell 2"#; // Missing both leading and trailing pipes
         let violations = linter.analyze();
         assert_eq!(0, violations.len()); // Escaped pipes shouldn't affect style detection
     }
+
+    #[test]
+    fn test_fix_adds_and_removes_pipes() {
+        let config = test_config_with_style(TablePipeStyle::LeadingAndTrailing);
+        let input = "Header 1 | Header 2\n-------- | --------\n| Cell 1 | Cell 2 |\n";
+
+        let result = MultiRuleLinter::fix_document(PathBuf::from("test.md"), config, input);
+        assert_eq!(
+            "| Header 1 | Header 2 |\n| -------- | -------- |\n| Cell 1 | Cell 2 |\n",
+            result.content
+        );
+        assert!(result.remaining.is_empty());
+
+        let config = test_config_with_style(TablePipeStyle::NoLeadingOrTrailing);
+        let result = MultiRuleLinter::fix_document(PathBuf::from("test.md"), config, input);
+        assert_eq!(
+            "Header 1 | Header 2\n-------- | --------\nCell 1 | Cell 2\n",
+            result.content
+        );
+        assert!(result.remaining.is_empty());
+    }
 }
@@ -1,4 +1,4 @@
-use std::rc::Rc;
+use std::sync::Arc;
 
 use tree_sitter::Node;
 
@@ -11,12 +11,12 @@ use crate::{
 ///
 /// This rule checks that all rows in a table have the same number of columns.
 pub(crate) struct MD056Linter {
-    context: Rc<Context>,
+    context: Arc<Context>,
     violations: Vec<RuleViolation>,
 }
 
 impl MD056Linter {
-    pub fn new(context: Rc<Context>) -> Self {
+    pub fn new(context: Arc<Context>) -> Self {
         Self {
             context,
             violations: Vec::new(),
@@ -88,7 +88,7 @@ impl MD056Linter {
     }
 
     fn get_row_end_position(&self, row_node: &Node) -> usize {
-        let document_content = self.context.document_content.borrow();
+        let document_content = &self.context.document_content;
         let row_text = row_node
             .utf8_text(document_content.as_bytes())
             .unwrap_or("");
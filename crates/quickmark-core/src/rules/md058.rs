@@ -1,4 +1,4 @@
-use std::rc::Rc;
+use std::sync::Arc;
 
 use tree_sitter::Node;
 
@@ -12,12 +12,12 @@ use crate::{
 /// This rule checks that tables have blank lines before and after them,
 /// except when the table is at the very beginning or end of the document.
 pub(crate) struct MD058Linter {
-    context: Rc<Context>,
+    context: Arc<Context>,
     violations: Vec<RuleViolation>,
 }
 
 impl MD058Linter {
-    pub fn new(context: Rc<Context>) -> Self {
+    pub fn new(context: Arc<Context>) -> Self {
         Self {
             context,
             violations: Vec::new(),
@@ -26,7 +26,7 @@ impl MD058Linter {
 
     fn check_table_blanks(&mut self, table_node: &Node) {
         let start_line = table_node.start_position().row;
-        let lines = self.context.lines.borrow();
+        let lines = &self.context.lines;
 
         // Find the actual last row of the table.
         // tree-sitter can sometimes identify nodes as table rows even if they are not
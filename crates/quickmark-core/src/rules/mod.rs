@@ -1,7 +1,13 @@
-use std::rc::Rc;
+use std::sync::Arc;
 
-use crate::linter::{Context, RuleLinter};
+use tree_sitter::Node;
 
+use crate::config::QuickmarkConfig;
+use crate::linter::{CharPosition, Context, Edit, RuleLinter, RuleViolation};
+use crate::plugin;
+use crate::workspace::DocumentSummary;
+
+pub mod custom;
 pub mod md001;
 pub mod md003;
 pub mod md004;
@@ -54,6 +60,9 @@ pub mod md055;
 pub mod md056;
 pub mod md058;
 pub mod md059;
+pub mod qm001;
+pub mod qm002;
+pub mod qm003;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum RuleType {
@@ -65,6 +74,9 @@ pub enum RuleType {
     Document,
     /// Rules that need both AST nodes and line context (blank line spacing around elements)
     Hybrid,
+    /// Rules that check all documents of a workspace together after the per-document
+    /// pass (e.g., duplicate titles across files), see [`crate::workspace`]
+    Workspace,
 }
 
 #[derive(Debug)]
@@ -74,8 +86,43 @@ pub struct Rule {
     pub tags: &'static [&'static str],
     pub description: &'static str,
     pub rule_type: RuleType,
-    pub required_nodes: &'static [&'static str], // For caching optimization
-    pub new_linter: fn(Rc<Context>) -> Box<dyn RuleLinter>,
+    /// Node kinds the rule looks up in the [`Context`] node caches; only these are cached
+    pub required_nodes: &'static [&'static str],
+    pub new_linter: fn(Arc<Context>) -> Box<dyn RuleLinter>,
+}
+
+/// Check of a [`RuleType::Workspace`] rule, run once over the summaries of all documents
+pub struct WorkspaceRule {
+    pub rule: &'static Rule,
+    pub check: fn(&[DocumentSummary]) -> Vec<RuleViolation>,
+}
+
+/// Per-document linter of rules that don't lint through [`Rule::new_linter`]: workspace
+/// rules report from the workspace phase, plugin rules from their own linters
+pub(crate) struct NoDocumentLinter;
+
+impl RuleLinter for NoDocumentLinter {
+    fn feed(&mut self, _node: &Node) {}
+
+    fn finalize(&mut self) -> Vec<RuleViolation> {
+        Vec::new()
+    }
+}
+
+/// Builds an edit inserting `count` blank lines at the start of `line`.
+///
+/// Blockquote markers found at the start of `container_line` are repeated on each
+/// inserted line, so the blank lines stay inside the same blockquote.
+pub(crate) fn insert_blank_lines(line: usize, count: usize, container_line: &str) -> Edit {
+    let prefix_len = container_line
+        .find(|c: char| !matches!(c, ' ' | '\t' | '>'))
+        .unwrap_or(container_line.len());
+    let prefix = container_line[..prefix_len].trim_end();
+
+    Edit::insert(
+        CharPosition { line, character: 0 },
+        format!("{prefix}\n").repeat(count),
+    )
 }
 
 pub const ALL_RULES: &[Rule] = &[
@@ -131,4 +178,47 @@ pub const ALL_RULES: &[Rule] = &[
     md056::MD056,
     md058::MD058,
     md059::MD059,
+    qm001::QM001,
+    qm002::QM002,
+    qm003::QM003,
 ];
+
+/// Rules that stay off unless the configuration sets their severity by name, since they
+/// would report many problems in documentation that was never checked for them. A
+/// `default` severity doesn't turn them on.
+pub const OPT_IN_RULES: &[&str] = &[qm002::QM002.alias, qm003::QM003.alias];
+
+/// Built-in rules followed by the rules of loaded plugins, see [`crate::plugin`], and
+/// custom rules compiled from `[[custom_rules]]` tables
+pub fn all_rules() -> Vec<&'static Rule> {
+    ALL_RULES
+        .iter()
+        .chain(plugin::rules())
+        .chain(custom::rules())
+        .collect()
+}
+
+/// Find the rule with `alias` as seen by documents linted with `config`. Custom rules
+/// of the configuration take precedence, since other configurations may define custom
+/// rules with the same alias, and only the plugins of the configuration are searched.
+pub fn find_rule_for_config(alias: &str, config: &QuickmarkConfig) -> Option<&'static Rule> {
+    let custom_rule = config
+        .compiled_custom_rules
+        .iter()
+        .map(|compiled| compiled.rule())
+        .find(|rule| rule.alias == alias);
+    let plugin_rule = || {
+        plugin::rules()
+            .into_iter()
+            .filter(|rule| config.plugin_rules.iter().any(|a| a == rule.alias))
+            .find(|rule| rule.alias == alias)
+    };
+    custom_rule
+        .or_else(|| ALL_RULES.iter().find(|rule| rule.alias == alias))
+        .or_else(plugin_rule)
+}
+
+pub const WORKSPACE_RULES: &[WorkspaceRule] = &[WorkspaceRule {
+    rule: &qm003::QM003,
+    check: qm003::check,
+}];
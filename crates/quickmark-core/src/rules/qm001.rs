@@ -0,0 +1,889 @@
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::SystemTime;
+
+use tree_sitter::Node;
+
+use crate::{
+    linter::{CharPosition, Context, Range, RuleLinter, RuleViolation},
+    regex_cache::cached_regex,
+    rules::{Rule, RuleType},
+};
+
+/// Value types a front matter property can be restricted to, named after JSON Schema types
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FrontMatterValueType {
+    String,
+    Number,
+    Integer,
+    Boolean,
+    Array,
+    Object,
+    Null,
+}
+
+impl FrontMatterValueType {
+    fn name(self) -> &'static str {
+        match self {
+            Self::String => "string",
+            Self::Number => "number",
+            Self::Integer => "integer",
+            Self::Boolean => "boolean",
+            Self::Array => "array",
+            Self::Object => "object",
+            Self::Null => "null",
+        }
+    }
+
+    fn parse(name: &str) -> Option<Self> {
+        serde_json::from_value(Value::String(name.to_string())).ok()
+    }
+
+    fn matches(self, value: &Value) -> bool {
+        match self {
+            Self::String => value.is_string(),
+            Self::Number => value.is_number(),
+            Self::Integer => {
+                value.is_i64()
+                    || value.is_u64()
+                    || value.as_f64().is_some_and(|number| number.fract() == 0.0)
+            }
+            Self::Boolean => value.is_boolean(),
+            Self::Array => value.is_array(),
+            Self::Object => value.is_object(),
+            Self::Null => value.is_null(),
+        }
+    }
+}
+
+impl Display for FrontMatterValueType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Constraints on a single front matter property
+#[derive(Debug, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct FrontMatterProperty {
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub value_type: Option<FrontMatterValueType>,
+    #[serde(rename = "enum", skip_serializing_if = "Vec::is_empty")]
+    pub allowed_values: Vec<Value>,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub pattern: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub items: Option<FrontMatterValueType>,
+}
+
+// QM001-specific configuration types
+#[derive(Debug, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct QM001FrontMatterSchemaTable {
+    pub required: Vec<String>,
+    pub properties: BTreeMap<String, FrontMatterProperty>,
+    pub schema: String,
+}
+
+impl QM001FrontMatterSchemaTable {
+    fn is_empty(&self) -> bool {
+        self.required.is_empty() && self.properties.is_empty() && self.schema.is_empty()
+    }
+}
+
+/// A front matter value that doesn't satisfy the schema
+#[derive(Debug, PartialEq)]
+struct SchemaError {
+    /// Dotted path of the offending value, empty for the front matter itself
+    path: String,
+    message: String,
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(number) if number.is_f64() => "number",
+        Value::Number(_) => "integer",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn join_path(parent: &str, segment: &str) -> String {
+    if parent.is_empty() {
+        segment.to_string()
+    } else {
+        format!("{parent}.{segment}")
+    }
+}
+
+fn describe(path: &str) -> String {
+    if path.is_empty() {
+        "Front matter".to_string()
+    } else {
+        format!("Front matter key `{path}`")
+    }
+}
+
+fn list_values(values: &[Value]) -> String {
+    values
+        .iter()
+        .map(Value::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Validate front matter against the `required` and `properties` settings
+fn check_properties(
+    settings: &QM001FrontMatterSchemaTable,
+    front_matter: &Value,
+) -> Vec<SchemaError> {
+    let mut errors = Vec::new();
+    let get = |path: &str| {
+        path.split('.')
+            .try_fold(front_matter, |value, segment| match value {
+                Value::Object(map) => map.get(segment),
+                Value::Array(items) => segment.parse::<usize>().ok().and_then(|i| items.get(i)),
+                _ => None,
+            })
+    };
+
+    for key in &settings.required {
+        if get(key).is_none() {
+            let parent = key.rfind('.').map_or("", |index| &key[..index]);
+            errors.push(SchemaError {
+                path: parent.to_string(),
+                message: format!("Missing required front matter key `{key}`"),
+            });
+        }
+    }
+
+    for (key, property) in &settings.properties {
+        let Some(value) = get(key) else {
+            continue;
+        };
+        if let Some(value_type) = property.value_type {
+            if !value_type.matches(value) {
+                errors.push(SchemaError {
+                    path: key.clone(),
+                    message: format!(
+                        "{} must be of type {value_type}, found {}",
+                        describe(key),
+                        type_name(value)
+                    ),
+                });
+                continue;
+            }
+        }
+
+        let elements: Vec<(String, &Value)> = match value {
+            Value::Array(items) => items
+                .iter()
+                .enumerate()
+                .map(|(index, item)| (join_path(key, &index.to_string()), item))
+                .collect(),
+            _ => vec![(key.clone(), value)],
+        };
+        for (path, element) in elements {
+            if let (Some(items), Value::Array(_)) = (property.items, value) {
+                if !items.matches(element) {
+                    errors.push(SchemaError {
+                        message: format!(
+                            "Items of front matter key `{key}` must be of type {items}, found {}",
+                            type_name(element)
+                        ),
+                        path,
+                    });
+                    continue;
+                }
+            }
+            if !property.allowed_values.is_empty() && !property.allowed_values.contains(element) {
+                errors.push(SchemaError {
+                    message: format!(
+                        "{} must be one of {}, found {element}",
+                        describe(key),
+                        list_values(&property.allowed_values)
+                    ),
+                    path,
+                });
+                continue;
+            }
+            if let (false, Value::String(text)) = (property.pattern.is_empty(), element) {
+                // Patterns are validated with the configuration, invalid ones never match
+                if !cached_regex(&property.pattern).is_ok_and(|pattern| pattern.is_match(text)) {
+                    errors.push(SchemaError {
+                        message: format!(
+                            "{} must match `{}`, found {element}",
+                            describe(key),
+                            property.pattern
+                        ),
+                        path,
+                    });
+                }
+            }
+        }
+    }
+
+    errors
+}
+
+/// Validator for the subset of JSON Schema that is useful for front matter: `type`,
+/// `enum`, `const`, `required`, `properties`, `additionalProperties`, `items`,
+/// `minItems`, `maxItems`, `uniqueItems`, `minLength`, `maxLength`, `pattern`,
+/// `minimum`, `maximum`, `allOf`, `anyOf`, `oneOf`, `not` and local `$ref`s.
+struct JsonSchema<'a> {
+    root: &'a Value,
+}
+
+impl<'a> JsonSchema<'a> {
+    fn validate(
+        &self,
+        schema: &'a Value,
+        value: &Value,
+        path: &str,
+        errors: &mut Vec<SchemaError>,
+    ) {
+        let Value::Object(schema) = schema else {
+            if schema == &Value::Bool(false) {
+                errors.push(SchemaError {
+                    path: path.to_string(),
+                    message: format!("{} is not allowed", describe(path)),
+                });
+            }
+            return;
+        };
+        let mut error = |message: String| {
+            errors.push(SchemaError {
+                path: path.to_string(),
+                message,
+            })
+        };
+
+        if let Some(target) = schema.get("$ref").and_then(Value::as_str) {
+            match self.resolve(target) {
+                Some(resolved) => self.validate(resolved, value, path, errors),
+                None => error(format!("Can't resolve schema reference `{target}`")),
+            }
+            return;
+        }
+
+        if let Some(types) = schema.get("type") {
+            let names: Vec<&str> = match types {
+                Value::String(name) => vec![name.as_str()],
+                Value::Array(names) => names.iter().filter_map(Value::as_str).collect(),
+                _ => Vec::new(),
+            };
+            let matches = names.iter().any(|name| {
+                FrontMatterValueType::parse(name)
+                    .is_some_and(|value_type| value_type.matches(value))
+            });
+            if !names.is_empty() && !matches {
+                error(format!(
+                    "{} must be of type {}, found {}",
+                    describe(path),
+                    names.join(" or "),
+                    type_name(value)
+                ));
+                return;
+            }
+        }
+        if let Some(Value::Array(allowed)) = schema.get("enum") {
+            if !allowed.contains(value) {
+                error(format!(
+                    "{} must be one of {}, found {value}",
+                    describe(path),
+                    list_values(allowed)
+                ));
+            }
+        }
+        if let Some(expected) = schema.get("const") {
+            if expected != value {
+                error(format!(
+                    "{} must be {expected}, found {value}",
+                    describe(path)
+                ));
+            }
+        }
+
+        match value {
+            Value::String(text) => self.validate_string(schema, text, path, &mut error),
+            Value::Number(number) => {
+                let number = number.as_f64().unwrap_or_default();
+                if let Some(minimum) = schema.get("minimum").and_then(Value::as_f64) {
+                    if number < minimum {
+                        error(format!("{} must be at least {minimum}", describe(path)));
+                    }
+                }
+                if let Some(maximum) = schema.get("maximum").and_then(Value::as_f64) {
+                    if number > maximum {
+                        error(format!("{} must be at most {maximum}", describe(path)));
+                    }
+                }
+            }
+            Value::Array(items) => {
+                self.validate_array_size(schema, items, path, &mut error);
+                if let Some(item_schema) = schema.get("items") {
+                    for (index, item) in items.iter().enumerate() {
+                        self.validate(
+                            item_schema,
+                            item,
+                            &join_path(path, &index.to_string()),
+                            errors,
+                        );
+                    }
+                }
+            }
+            Value::Object(map) => self.validate_object(schema, map, path, errors),
+            _ => {}
+        }
+
+        self.validate_combinators(schema, value, path, errors);
+    }
+
+    fn validate_string(
+        &self,
+        schema: &Map<String, Value>,
+        text: &str,
+        path: &str,
+        error: &mut impl FnMut(String),
+    ) {
+        let length = text.chars().count() as u64;
+        if let Some(min_length) = schema.get("minLength").and_then(Value::as_u64) {
+            if length < min_length {
+                error(format!(
+                    "{} must be at least {min_length} characters long",
+                    describe(path)
+                ));
+            }
+        }
+        if let Some(max_length) = schema.get("maxLength").and_then(Value::as_u64) {
+            if length > max_length {
+                error(format!(
+                    "{} must be at most {max_length} characters long",
+                    describe(path)
+                ));
+            }
+        }
+        if let Some(pattern) = schema.get("pattern").and_then(Value::as_str) {
+            match cached_regex(pattern) {
+                Ok(regex) if !regex.is_match(text) => error(format!(
+                    "{} must match `{pattern}`, found \"{text}\"",
+                    describe(path)
+                )),
+                Ok(_) => {}
+                Err(_) => error(format!(
+                    "Invalid pattern `{pattern}` in front matter schema"
+                )),
+            }
+        }
+    }
+
+    fn validate_array_size(
+        &self,
+        schema: &Map<String, Value>,
+        items: &[Value],
+        path: &str,
+        error: &mut impl FnMut(String),
+    ) {
+        let count = items.len() as u64;
+        if let Some(min_items) = schema.get("minItems").and_then(Value::as_u64) {
+            if count < min_items {
+                error(format!(
+                    "{} must have at least {min_items} items",
+                    describe(path)
+                ));
+            }
+        }
+        if let Some(max_items) = schema.get("maxItems").and_then(Value::as_u64) {
+            if count > max_items {
+                error(format!(
+                    "{} must have at most {max_items} items",
+                    describe(path)
+                ));
+            }
+        }
+        if schema.get("uniqueItems") == Some(&Value::Bool(true)) {
+            let duplicate = items
+                .iter()
+                .enumerate()
+                .find(|(index, item)| items[..*index].contains(item));
+            if let Some((_, item)) = duplicate {
+                error(format!("{} contains {item} more than once", describe(path)));
+            }
+        }
+    }
+
+    fn validate_object(
+        &self,
+        schema: &'a Map<String, Value>,
+        map: &Map<String, Value>,
+        path: &str,
+        errors: &mut Vec<SchemaError>,
+    ) {
+        if let Some(Value::Array(required)) = schema.get("required") {
+            for key in required.iter().filter_map(Value::as_str) {
+                if !map.contains_key(key) {
+                    errors.push(SchemaError {
+                        path: path.to_string(),
+                        message: format!(
+                            "Missing required front matter key `{}`",
+                            join_path(path, key)
+                        ),
+                    });
+                }
+            }
+        }
+
+        let properties = schema.get("properties").and_then(Value::as_object);
+        for (key, value) in map {
+            let key_path = join_path(path, key);
+            match properties.and_then(|properties| properties.get(key)) {
+                Some(property_schema) => self.validate(property_schema, value, &key_path, errors),
+                None => match schema.get("additionalProperties") {
+                    Some(Value::Bool(false)) => errors.push(SchemaError {
+                        message: format!("Unknown front matter key `{key_path}`"),
+                        path: key_path,
+                    }),
+                    Some(additional) => self.validate(additional, value, &key_path, errors),
+                    None => {}
+                },
+            }
+        }
+    }
+
+    fn validate_combinators(
+        &self,
+        schema: &'a Map<String, Value>,
+        value: &Value,
+        path: &str,
+        errors: &mut Vec<SchemaError>,
+    ) {
+        let subschemas = |keyword: &str| {
+            schema
+                .get(keyword)
+                .and_then(Value::as_array)
+                .map(|schemas| schemas.as_slice())
+                .unwrap_or_default()
+        };
+        let is_valid = |subschema: &'a Value| {
+            let mut errors = Vec::new();
+            self.validate(subschema, value, path, &mut errors);
+            errors.is_empty()
+        };
+
+        for subschema in subschemas("allOf") {
+            self.validate(subschema, value, path, errors);
+        }
+        let any_of = subschemas("anyOf");
+        if !any_of.is_empty() && !any_of.iter().any(is_valid) {
+            errors.push(SchemaError {
+                path: path.to_string(),
+                message: format!(
+                    "{} doesn't match any of the allowed schemas",
+                    describe(path)
+                ),
+            });
+        }
+        let one_of = subschemas("oneOf");
+        if !one_of.is_empty() && one_of.iter().filter(|s| is_valid(s)).count() != 1 {
+            errors.push(SchemaError {
+                path: path.to_string(),
+                message: format!(
+                    "{} must match exactly one of the allowed schemas",
+                    describe(path)
+                ),
+            });
+        }
+        if let Some(not) = schema.get("not") {
+            if is_valid(not) {
+                errors.push(SchemaError {
+                    path: path.to_string(),
+                    message: format!("{} matches a disallowed schema", describe(path)),
+                });
+            }
+        }
+    }
+
+    /// Resolve a reference within the schema document, e.g. `#/$defs/author`
+    fn resolve(&self, reference: &str) -> Option<&'a Value> {
+        let pointer = reference.strip_prefix('#')?;
+        self.root.pointer(pointer)
+    }
+}
+
+/// A schema file as last read, with the modification time and size it was read at
+struct CachedSchema {
+    stamp: Option<(SystemTime, u64)>,
+    schema: Arc<Result<Value, String>>,
+}
+
+/// Schema files by path. Every document of a run shares one read and parse of its
+/// schema, until the file changes.
+static SCHEMAS: Lazy<Mutex<HashMap<PathBuf, CachedSchema>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+fn file_stamp(path: &Path) -> Option<(SystemTime, u64)> {
+    let metadata = std::fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+pub(crate) fn read_schema(path: &Path) -> Result<Value, String> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("Can't read front matter schema {}: {e}", path.display()))?;
+    serde_json::from_str(&content)
+        .map_err(|e| format!("Invalid front matter schema {}: {e}", path.display()))
+}
+
+/// The schema at `path`, read again only when the file has changed since
+pub(crate) fn load_schema(path: &Path) -> Arc<Result<Value, String>> {
+    let stamp = file_stamp(path);
+    let mut schemas = SCHEMAS.lock().unwrap_or_else(PoisonError::into_inner);
+    if let Some(cached) = schemas.get(path).filter(|cached| cached.stamp == stamp) {
+        return Arc::clone(&cached.schema);
+    }
+
+    let schema = Arc::new(read_schema(path));
+    if let Err(message) = schema.as_ref() {
+        // Reported once per change of the file rather than for every document
+        eprintln!("{message}. Front matter is not checked against it.");
+    }
+    schemas.insert(
+        path.to_path_buf(),
+        CachedSchema {
+            stamp,
+            schema: Arc::clone(&schema),
+        },
+    );
+    schema
+}
+
+pub(crate) struct QM001Linter {
+    context: Arc<Context>,
+    violations: Vec<RuleViolation>,
+}
+
+impl QM001Linter {
+    pub fn new(context: Arc<Context>) -> Self {
+        Self {
+            context,
+            violations: Vec::new(),
+        }
+    }
+
+    fn report(&mut self, line: usize, message: String) {
+        let length = self.context.lines.get(line).map_or(0, |line| line.len());
+        self.violations.push(RuleViolation::new(
+            &QM001,
+            message,
+            self.context.file_path.clone(),
+            Range {
+                start: CharPosition { line, character: 0 },
+                end: CharPosition {
+                    line,
+                    character: length,
+                },
+            },
+        ));
+    }
+
+    fn check_front_matter(&mut self) {
+        let context = Arc::clone(&self.context);
+        let settings = &context.config.linters.settings.front_matter_schema;
+        if settings.is_empty() {
+            return;
+        }
+
+        // Documents without front matter are validated as an empty one, so that
+        // required keys are still reported
+        let front_matter = context.front_matter.as_ref();
+        if let Some(error) = front_matter.and_then(|front_matter| front_matter.error.as_ref()) {
+            let message = format!("Invalid front matter: {}", error.message);
+            self.report(error.line, message);
+            return;
+        }
+        let empty = Value::Object(Map::new());
+        let value = front_matter
+            .and_then(|front_matter| front_matter.value.as_ref())
+            .unwrap_or(&empty);
+
+        let mut errors = check_properties(settings, value);
+        if !settings.schema.is_empty() {
+            if let Ok(schema) = load_schema(Path::new(&settings.schema)).as_ref() {
+                JsonSchema { root: schema }.validate(schema, value, "", &mut errors)
+            }
+        }
+
+        for error in errors {
+            let line = front_matter.map_or(0, |front_matter| front_matter.line_of(&error.path));
+            self.report(line, error.message);
+        }
+    }
+}
+
+impl RuleLinter for QM001Linter {
+    fn feed(&mut self, _node: &Node) {}
+
+    fn finalize(&mut self) -> Vec<RuleViolation> {
+        self.check_front_matter();
+        std::mem::take(&mut self.violations)
+    }
+}
+
+pub const QM001: Rule = Rule {
+    id: "QM001",
+    alias: "front-matter-schema",
+    tags: &["front_matter"],
+    description: "Front matter should match the schema",
+    rule_type: RuleType::Document,
+    required_nodes: &[],
+    new_linter: |context| Box::new(QM001Linter::new(context)),
+};
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+    use std::io::Write;
+    use std::path::PathBuf;
+
+    use serde_json::json;
+
+    use super::{FrontMatterProperty, FrontMatterValueType};
+    use crate::config::{LintersSettingsTable, QM001FrontMatterSchemaTable, RuleSeverity};
+    use crate::linter::MultiRuleLinter;
+    use crate::test_utils::test_helpers::test_config_with_settings;
+
+    fn test_config(settings: QM001FrontMatterSchemaTable) -> crate::config::QuickmarkConfig {
+        test_config_with_settings(
+            vec![("front-matter-schema", RuleSeverity::Error)],
+            LintersSettingsTable {
+                front_matter_schema: settings,
+                ..Default::default()
+            },
+        )
+    }
+
+    fn docs_site_settings() -> QM001FrontMatterSchemaTable {
+        QM001FrontMatterSchemaTable {
+            required: vec![
+                "title".to_string(),
+                "description".to_string(),
+                "owner".to_string(),
+                "tags".to_string(),
+            ],
+            properties: BTreeMap::from([
+                (
+                    "tags".to_string(),
+                    FrontMatterProperty {
+                        value_type: Some(FrontMatterValueType::Array),
+                        items: Some(FrontMatterValueType::String),
+                        ..Default::default()
+                    },
+                ),
+                (
+                    "owner".to_string(),
+                    FrontMatterProperty {
+                        allowed_values: vec![json!("docs"), json!("platform")],
+                        ..Default::default()
+                    },
+                ),
+                (
+                    "slug".to_string(),
+                    FrontMatterProperty {
+                        pattern: "^[a-z-]+$".to_string(),
+                        ..Default::default()
+                    },
+                ),
+            ]),
+            ..Default::default()
+        }
+    }
+
+    fn lint(settings: QM001FrontMatterSchemaTable, input: &str) -> Vec<(usize, String)> {
+        let mut linter = MultiRuleLinter::new_for_document(
+            PathBuf::from("test.md"),
+            test_config(settings),
+            input,
+        );
+        linter
+            .analyze()
+            .iter()
+            .map(|violation| {
+                (
+                    violation.location().range.start.line,
+                    violation.message().to_string(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_no_settings() {
+        let violations = lint(QM001FrontMatterSchemaTable::default(), "# Title\n");
+        assert!(violations.is_empty());
+    }
+
+    #[test]
+    fn test_valid_front_matter() {
+        let input = "---\ntitle: Guide\ndescription: How to\nowner: docs\ntags: [a, b]\nslug: the-guide\n---\n# Guide\n";
+        assert!(lint(docs_site_settings(), input).is_empty());
+    }
+
+    #[test]
+    fn test_missing_front_matter() {
+        let violations = lint(docs_site_settings(), "# Guide\n");
+        assert_eq!(4, violations.len());
+        assert_eq!(
+            (0, "Missing required front matter key `title`".to_string()),
+            violations[0]
+        );
+    }
+
+    #[test]
+    fn test_missing_required_keys() {
+        let input = "---\ntitle: Guide\ndescription: How to\n---\n";
+        let violations = lint(docs_site_settings(), input);
+        assert_eq!(
+            vec![
+                (0, "Missing required front matter key `owner`".to_string()),
+                (0, "Missing required front matter key `tags`".to_string()),
+            ],
+            violations
+        );
+    }
+
+    #[test]
+    fn test_violations_point_at_front_matter_line() {
+        let input = "---\ntitle: Guide\ndescription: How to\nowner: marketing\ntags:\n  - a\n  - 2\nslug: The Guide\n---\n";
+        let violations = lint(docs_site_settings(), input);
+        assert_eq!(
+            vec![
+                (
+                    3,
+                    "Front matter key `owner` must be one of \"docs\", \"platform\", found \"marketing\""
+                        .to_string()
+                ),
+                (
+                    7,
+                    "Front matter key `slug` must match `^[a-z-]+$`, found \"The Guide\"".to_string()
+                ),
+                (
+                    6,
+                    "Items of front matter key `tags` must be of type string, found integer"
+                        .to_string()
+                ),
+            ],
+            violations
+        );
+    }
+
+    #[test]
+    fn test_wrong_type() {
+        let input = "+++\ntitle = \"Guide\"\ndescription = \"How to\"\nowner = \"docs\"\ntags = \"a\"\n+++\n";
+        let violations = lint(docs_site_settings(), input);
+        assert_eq!(
+            vec![(
+                4,
+                "Front matter key `tags` must be of type array, found string".to_string()
+            )],
+            violations
+        );
+    }
+
+    #[test]
+    fn test_invalid_front_matter() {
+        let input = "---\ntitle: Guide\ntags: [a\n---\n";
+        let violations = lint(docs_site_settings(), input);
+        assert_eq!(1, violations.len());
+        assert!(violations[0].1.starts_with("Invalid front matter: "));
+    }
+
+    #[test]
+    fn test_json_schema_file() {
+        let mut schema = tempfile::NamedTempFile::new().unwrap();
+        write!(
+            schema,
+            "{}",
+            json!({
+                "type": "object",
+                "required": ["title", "author"],
+                "additionalProperties": false,
+                "properties": {
+                    "title": {"type": "string", "maxLength": 10},
+                    "author": {"$ref": "#/$defs/author"},
+                    "tags": {"type": "array", "items": {"type": "string"}, "uniqueItems": true}
+                },
+                "$defs": {
+                    "author": {"type": "object", "required": ["name"]}
+                }
+            })
+        )
+        .unwrap();
+        let settings = QM001FrontMatterSchemaTable {
+            schema: schema.path().to_string_lossy().to_string(),
+            ..Default::default()
+        };
+
+        let input =
+            "---\ntitle: A very long title\nauthor:\n  email: x\ntags: [a, a]\ndraft: true\n---\n";
+        let violations = lint(settings, input);
+        assert_eq!(
+            vec![
+                (
+                    1,
+                    "Front matter key `title` must be at most 10 characters long".to_string()
+                ),
+                (
+                    2,
+                    "Missing required front matter key `author.name`".to_string()
+                ),
+                (
+                    4,
+                    "Front matter key `tags` contains \"a\" more than once".to_string()
+                ),
+                (5, "Unknown front matter key `draft`".to_string()),
+            ],
+            violations
+        );
+    }
+
+    #[test]
+    fn test_missing_schema_file() {
+        let settings = QM001FrontMatterSchemaTable {
+            schema: "/does/not/exist.json".to_string(),
+            ..Default::default()
+        };
+        // Reported once on stderr and by `qmark config check`, not for every document
+        assert!(lint(settings, "---\ntitle: x\n---\n").is_empty());
+        assert!(
+            super::load_schema(std::path::Path::new("/does/not/exist.json"))
+                .as_ref()
+                .as_ref()
+                .unwrap_err()
+                .starts_with("Can't read front matter schema /does/not/exist.json")
+        );
+    }
+
+    #[test]
+    fn test_schema_is_read_again_when_changed() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("schema.json");
+        std::fs::write(&path, r#"{"required": ["title"]}"#).unwrap();
+        let first = super::load_schema(&path);
+        assert!(std::sync::Arc::ptr_eq(&first, &super::load_schema(&path)));
+
+        std::fs::write(&path, r#"{"required": ["title", "author"]}"#).unwrap();
+        let changed = super::load_schema(&path);
+        assert_eq!(
+            Some(&json!(["title", "author"])),
+            changed.as_ref().as_ref().unwrap().get("required")
+        );
+    }
+}
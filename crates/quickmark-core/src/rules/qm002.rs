@@ -0,0 +1,293 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use tree_sitter::Node;
+
+use crate::{
+    linter::{range_from_tree_sitter, RuleViolation},
+    project_index::{is_markdown_path, normalize_path, RelativeTarget},
+    regex_cache::cached_regex,
+    rules::{Context, Rule, RuleLinter, RuleType},
+};
+
+// QM002-specific configuration types
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize, Default)]
+#[serde(default)]
+pub struct QM002RelativeLinksTable {
+    pub ignore_case: bool,
+    pub ignored_pattern: String,
+}
+
+/// QM002 - Relative links should point to existing files and headings
+///
+/// Checks the relative targets of inline links, images and link reference
+/// definitions. Fragments into other Markdown documents must match one of
+/// their headings or anchors, which are looked up in the project index.
+pub(crate) struct QM002Linter {
+    context: Arc<Context>,
+    violations: Vec<RuleViolation>,
+    ignored_regex: Option<Regex>,
+    /// Directory relative links are resolved against
+    base_dir: PathBuf,
+}
+
+impl QM002Linter {
+    pub fn new(context: Arc<Context>) -> Self {
+        let config = &context.config.linters.settings.relative_links;
+        let ignored_regex = if config.ignored_pattern.is_empty() {
+            None
+        } else {
+            cached_regex(&config.ignored_pattern).ok()
+        };
+        let base_dir = normalize_path(&context.file_path)
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+
+        Self {
+            context,
+            violations: Vec::new(),
+            ignored_regex,
+            base_dir,
+        }
+    }
+
+    fn check_destination(&mut self, destination_node: &Node) {
+        let destination = {
+            let document_content = &self.context.document_content;
+            document_content[destination_node.start_byte()..destination_node.end_byte()].to_string()
+        };
+        if self
+            .ignored_regex
+            .as_ref()
+            .is_some_and(|regex| regex.is_match(&destination))
+        {
+            return;
+        }
+        let Some(target) = RelativeTarget::parse(&destination) else {
+            return;
+        };
+
+        let project_index = &self.context.project_index;
+        let path = self.base_dir.join(&target.path);
+        let message = if !project_index.exists(&path) {
+            format!("Link target '{}' does not exist", target.path)
+        } else {
+            let Some(fragment) = target.fragment else {
+                return;
+            };
+            if !is_markdown_path(&path) {
+                return;
+            }
+            let ignore_case = self
+                .context
+                .config
+                .linters
+                .settings
+                .relative_links
+                .ignore_case;
+            match project_index.anchors(&path) {
+                Some(anchors) if !anchors.contains(&fragment, ignore_case) => format!(
+                    "Link fragment '{fragment}' does not match any heading or anchor in '{}'",
+                    target.path
+                ),
+                _ => return,
+            }
+        };
+
+        self.violations.push(RuleViolation::new(
+            &QM002,
+            message,
+            self.context.file_path.clone(),
+            range_from_tree_sitter(&destination_node.range()),
+        ));
+    }
+}
+
+impl RuleLinter for QM002Linter {
+    fn feed(&mut self, node: &Node) {
+        // Reference links are covered by checking their definitions
+        if matches!(
+            node.kind(),
+            "inline_link" | "image" | "link_reference_definition"
+        ) {
+            let mut cursor = node.walk();
+            let destination = node
+                .children(&mut cursor)
+                .find(|child| child.kind() == "link_destination");
+            if let Some(destination) = destination {
+                self.check_destination(&destination);
+            }
+        }
+    }
+
+    fn finalize(&mut self) -> Vec<RuleViolation> {
+        std::mem::take(&mut self.violations)
+    }
+}
+
+pub const QM002: Rule = Rule {
+    id: "QM002",
+    alias: "relative-links",
+    tags: &["links"],
+    description: "Relative links should point to existing files and headings",
+    rule_type: RuleType::Document,
+    required_nodes: &["inline_link", "image", "link_reference_definition"],
+    new_linter: |context| Box::new(QM002Linter::new(context)),
+};
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+    use std::path::Path;
+    use std::sync::Arc;
+
+    use crate::config::{LintersSettingsTable, QM002RelativeLinksTable, RuleSeverity};
+    use crate::linter::MultiRuleLinter;
+    use crate::project_index::ProjectIndex;
+    use crate::test_utils::test_helpers::test_config_with_settings;
+
+    fn test_config(settings: QM002RelativeLinksTable) -> crate::config::QuickmarkConfig {
+        test_config_with_settings(
+            vec![("relative-links", RuleSeverity::Error)],
+            LintersSettingsTable {
+                relative_links: settings,
+                ..Default::default()
+            },
+        )
+    }
+
+    /// A project with `README.md`, `guide/setup.md` and `images/logo.png`
+    fn project() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("guide")).unwrap();
+        fs::create_dir_all(dir.path().join("images")).unwrap();
+        fs::write(
+            dir.path().join("guide/setup.md"),
+            "# Setup\n\n## Install {#install}\n\n## Configure the Server\n",
+        )
+        .unwrap();
+        fs::write(dir.path().join("images/logo.png"), "").unwrap();
+        dir
+    }
+
+    fn lint(
+        dir: &Path,
+        settings: QM002RelativeLinksTable,
+        input: &str,
+    ) -> Vec<(usize, usize, String)> {
+        let mut linter =
+            MultiRuleLinter::new_for_document(dir.join("README.md"), test_config(settings), input);
+        linter
+            .analyze()
+            .iter()
+            .map(|violation| {
+                let start = &violation.location().range.start;
+                (start.line, start.character, violation.message().to_string())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_valid_links() {
+        let dir = project();
+        let input = "[Setup](guide/setup.md) and [install](./guide/setup.md#install)
+[configure](guide/../guide/setup.md#configure-the-server) ![logo](images/logo.png)
+[top](guide/setup.md#top) [folder](guide/) [web](https://example.com/missing.md)
+";
+        assert!(lint(dir.path(), Default::default(), input).is_empty());
+    }
+
+    #[test]
+    fn test_missing_files() {
+        let dir = project();
+        let input = "See [setup](guide/install.md) and ![logo](images/logo.svg).\n";
+        let violations = lint(dir.path(), Default::default(), input);
+        assert_eq!(
+            vec![
+                (
+                    0,
+                    12,
+                    "Link target 'guide/install.md' does not exist".to_string()
+                ),
+                (
+                    0,
+                    42,
+                    "Link target 'images/logo.svg' does not exist".to_string()
+                ),
+            ],
+            violations
+        );
+    }
+
+    #[test]
+    fn test_missing_fragments() {
+        let dir = project();
+        let input = "[a](guide/setup.md#installation) [b](guide/setup.md#Install)\n";
+        let violations = lint(dir.path(), Default::default(), input);
+        assert_eq!(2, violations.len());
+        assert_eq!(
+            "Link fragment 'installation' does not match any heading or anchor in 'guide/setup.md'",
+            violations[0].2
+        );
+
+        let settings = QM002RelativeLinksTable {
+            ignore_case: true,
+            ..Default::default()
+        };
+        assert_eq!(1, lint(dir.path(), settings, input).len());
+    }
+
+    #[test]
+    fn test_reference_definitions() {
+        let dir = project();
+        let input = "Read the [setup guide][setup].
+
+[setup]: guide/setup.md#missing
+[unused]: <guide/missing.md> \"Title\"
+";
+        let violations = lint(dir.path(), Default::default(), input);
+        assert_eq!(2, violations.len());
+        assert_eq!((2, 9), (violations[0].0, violations[0].1));
+        assert!(violations[0].2.contains("fragment 'missing'"));
+        assert_eq!(3, violations[1].0);
+        assert!(violations[1]
+            .2
+            .contains("'guide/missing.md' does not exist"));
+    }
+
+    #[test]
+    fn test_ignored_pattern_and_code() {
+        let dir = project();
+        let settings = QM002RelativeLinksTable {
+            ignored_pattern: r"^generated/".to_string(),
+            ..Default::default()
+        };
+        let input = "[api](generated/api.md) `[code](missing.md)`\n\n    [block](missing.md)\n";
+        assert!(lint(dir.path(), settings, input).is_empty());
+    }
+
+    #[test]
+    fn test_shared_project_index() {
+        let dir = project();
+        let index = Arc::new(ProjectIndex::new());
+        index.update(
+            &dir.path().join("guide/setup.md"),
+            "# Setup\n\n## Renamed\n",
+        );
+        index.update(&dir.path().join("guide/new.md"), "# Unsaved\n");
+
+        let input = "[a](guide/setup.md#install) [b](guide/setup.md#renamed) [c](guide/new.md)\n";
+        let mut linter = MultiRuleLinter::new_for_document_in_project(
+            dir.path().join("README.md"),
+            test_config(Default::default()),
+            input,
+            index,
+        );
+        let violations = linter.analyze();
+        assert_eq!(1, violations.len());
+        assert!(violations[0].message().contains("'install'"));
+    }
+}
@@ -0,0 +1,161 @@
+use std::collections::HashMap;
+
+use crate::{
+    linter::RuleViolation,
+    rules::{NoDocumentLinter, Rule, RuleType},
+    workspace::DocumentSummary,
+};
+
+/// Titles are compared case-insensitively and with collapsed whitespace
+fn title_key(title: &str) -> String {
+    title
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// QM003 - Documents should have unique titles
+///
+/// Every document whose title is already used by another document is reported,
+/// with documents ordered by path so that results don't depend on the order
+/// they were linted in. Documents that turn the rule off take no part.
+pub(crate) fn check(documents: &[DocumentSummary]) -> Vec<RuleViolation> {
+    let mut documents: Vec<&DocumentSummary> = documents
+        .iter()
+        .filter(|document| document.title.is_some() && document.is_enabled(&QM003))
+        .collect();
+    documents.sort_by(|a, b| a.file_path.cmp(&b.file_path));
+
+    let mut first_with_title: HashMap<String, &DocumentSummary> = HashMap::new();
+    let mut violations = Vec::new();
+    for document in documents {
+        let Some(title) = &document.title else {
+            continue;
+        };
+        let key = title_key(&title.text);
+        if key.is_empty() {
+            continue;
+        }
+        match first_with_title.get(&key) {
+            Some(first) => violations.push(RuleViolation::new(
+                &QM003,
+                format!(
+                    "Title '{}' is already used by '{}'",
+                    title.text,
+                    first.file_path.to_string_lossy()
+                ),
+                document.file_path.clone(),
+                title.range.clone(),
+            )),
+            None => {
+                first_with_title.insert(key, document);
+            }
+        }
+    }
+    violations
+}
+
+pub const QM003: Rule = Rule {
+    id: "QM003",
+    alias: "duplicate-titles",
+    tags: &["headings", "workspace"],
+    description: "Documents should have unique titles",
+    rule_type: RuleType::Workspace,
+    required_nodes: &[],
+    new_linter: |_| Box::new(NoDocumentLinter),
+};
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use crate::config::RuleSeverity;
+    use crate::linter::MultiRuleLinter;
+    use crate::test_utils::test_helpers::test_config_with_rules;
+    use crate::workspace::{check_workspace, DocumentSummary};
+
+    fn summary(path: &str, severity: RuleSeverity, input: &str) -> DocumentSummary {
+        // Documents with every rule turned off have no summary
+        let config = test_config_with_rules(vec![
+            ("duplicate-titles", severity),
+            ("heading-increment", RuleSeverity::Error),
+        ]);
+        let mut linter = MultiRuleLinter::new_for_document(PathBuf::from(path), config, input);
+        assert!(linter.analyze().is_empty());
+        linter.into_summary().unwrap()
+    }
+
+    fn lint(documents: &[DocumentSummary]) -> Vec<(String, usize, String)> {
+        check_workspace(documents)
+            .iter()
+            .map(|violation| {
+                (
+                    violation.location().file_path.to_string_lossy().to_string(),
+                    violation.location().range.start.line,
+                    violation.message().to_string(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_unique_titles() {
+        let documents = [
+            summary("a.md", RuleSeverity::Error, "# Install\n"),
+            summary("b.md", RuleSeverity::Error, "# Configure\n\n# Install\n"),
+            summary("c.md", RuleSeverity::Error, "## Install\n"),
+            summary("d.md", RuleSeverity::Error, "No title\n"),
+        ];
+        assert!(lint(&documents).is_empty());
+    }
+
+    #[test]
+    fn test_duplicate_titles() {
+        let documents = [
+            summary(
+                "guide/setup.md",
+                RuleSeverity::Error,
+                "Install  guide\n===\n",
+            ),
+            summary("b.md", RuleSeverity::Warning, "\n# Install Guide\n"),
+            summary(
+                "a.md",
+                RuleSeverity::Error,
+                "---\ntitle: install guide\n---\n# Overview\n",
+            ),
+        ];
+        let violations = check_workspace(&documents);
+        assert_eq!(2, violations.len());
+        assert_eq!(RuleSeverity::Warning, *violations[0].severity());
+        assert_eq!(
+            vec![
+                (
+                    "b.md".to_string(),
+                    1,
+                    "Title 'Install Guide' is already used by 'a.md'".to_string()
+                ),
+                (
+                    "guide/setup.md".to_string(),
+                    0,
+                    "Title 'Install  guide' is already used by 'a.md'".to_string()
+                ),
+            ],
+            lint(&documents)
+        );
+    }
+
+    #[test]
+    fn test_disabled_documents() {
+        let documents = [
+            summary("a.md", RuleSeverity::Off, "# Install\n"),
+            summary("b.md", RuleSeverity::Error, "# Install\n"),
+            summary(
+                "c.md",
+                RuleSeverity::Error,
+                "<!-- quickmark-disable-next-line duplicate-titles -->\n# Install\n",
+            ),
+        ];
+        assert!(lint(&documents).is_empty());
+    }
+}
@@ -34,6 +34,7 @@ pub mod test_helpers {
                 severity,
                 settings: LintersSettingsTable::default(),
             },
+            ..Default::default()
         }
     }
 
@@ -67,6 +68,7 @@ pub mod test_helpers {
 
         QuickmarkConfig {
             linters: LintersTable { severity, settings },
+            ..Default::default()
         }
     }
 }
@@ -1,4 +1,6 @@
-use tree_sitter::{Node, Tree};
+use tree_sitter::{Node, Tree, TreeCursor};
+
+use crate::markdown_tree::MarkdownTree;
 
 #[derive(Copy, Clone, Debug)]
 pub enum TraversalOrder {
@@ -10,6 +12,9 @@ pub enum TraversalOrder {
 pub struct TreeSitterWalker<'a> {
     pub order: TraversalOrder,
     pub tree: &'a Tree,
+    /// When set, the nodes of each inline tree are visited right after the
+    /// `inline` or `pipe_table_cell` block node they were parsed from
+    pub markdown_tree: Option<&'a MarkdownTree>,
 }
 
 impl<'a> TreeSitterWalker<'a> {
@@ -17,11 +22,25 @@ impl<'a> TreeSitterWalker<'a> {
         Self {
             tree,
             order: TraversalOrder::PreOrder,
+            markdown_tree: None,
         }
     }
 
     pub fn with_order(tree: &'a Tree, order: TraversalOrder) -> Self {
-        Self { tree, order }
+        Self {
+            tree,
+            order,
+            markdown_tree: None,
+        }
+    }
+
+    /// Walk the block tree of a document together with its inline trees
+    pub fn for_markdown(markdown_tree: &'a MarkdownTree) -> Self {
+        Self {
+            tree: markdown_tree.block_tree(),
+            order: TraversalOrder::PreOrder,
+            markdown_tree: Some(markdown_tree),
+        }
     }
 
     pub fn walk(&self, mut callback: impl FnMut(Node)) {
@@ -32,14 +51,10 @@ impl<'a> TreeSitterWalker<'a> {
         }
     }
 
-    #[allow(clippy::only_used_in_recursion)]
-    fn walk_pre_order(
-        &self,
-        cursor: &mut tree_sitter::TreeCursor,
-        callback: &mut impl FnMut(Node),
-    ) {
+    fn walk_pre_order(&self, cursor: &mut TreeCursor, callback: &mut impl FnMut(Node)) {
         let node = cursor.node();
         callback(node);
+        self.walk_inline_tree(&node, callback);
 
         if cursor.goto_first_child() {
             loop {
@@ -52,12 +67,10 @@ impl<'a> TreeSitterWalker<'a> {
         }
     }
 
-    #[allow(clippy::only_used_in_recursion)]
-    fn walk_post_order(
-        &self,
-        cursor: &mut tree_sitter::TreeCursor,
-        callback: &mut impl FnMut(Node),
-    ) {
+    fn walk_post_order(&self, cursor: &mut TreeCursor, callback: &mut impl FnMut(Node)) {
+        let node = cursor.node();
+        self.walk_inline_tree(&node, callback);
+
         if cursor.goto_first_child() {
             loop {
                 self.walk_post_order(cursor, callback);
@@ -68,7 +81,32 @@ impl<'a> TreeSitterWalker<'a> {
             cursor.goto_parent();
         }
 
-        let node = cursor.node();
         callback(node);
     }
+
+    /// Visit the descendants of the inline tree parsed from a block node, if any.
+    /// The root of an inline tree spans the same text as the block node and is skipped.
+    fn walk_inline_tree(&self, node: &Node, callback: &mut impl FnMut(Node)) {
+        let Some(inline_tree) = self
+            .markdown_tree
+            .and_then(|markdown_tree| markdown_tree.inline_tree(node))
+        else {
+            return;
+        };
+        let inline_walker = TreeSitterWalker::with_order(inline_tree, self.order);
+        let mut cursor = inline_tree.walk();
+        if cursor.goto_first_child() {
+            loop {
+                match self.order {
+                    TraversalOrder::PreOrder => inline_walker.walk_pre_order(&mut cursor, callback),
+                    TraversalOrder::PostOrder => {
+                        inline_walker.walk_post_order(&mut cursor, callback)
+                    }
+                }
+                if !cursor.goto_next_sibling() {
+                    break;
+                }
+            }
+        }
+    }
 }
@@ -0,0 +1,313 @@
+//! Workspace rules, which check the documents of a lint run together.
+//!
+//! Regular rules see a single document through [`RuleLinter`](crate::linter::RuleLinter).
+//! Rules of type [`RuleType::Workspace`](crate::rules::RuleType::Workspace) run after
+//! that per-document pass instead. They receive a [`DocumentSummary`] of every document
+//! and may report violations against any of them, which makes checks such as duplicate
+//! page titles across a documentation tree possible.
+//!
+//! ```rust,no_run
+//! use quickmark_core::linter::MultiRuleLinter;
+//! use quickmark_core::config::QuickmarkConfig;
+//! use quickmark_core::workspace::check_workspace;
+//! use std::path::PathBuf;
+//!
+//! # let documents: Vec<(PathBuf, String)> = Vec::new();
+//! # let config: QuickmarkConfig = unimplemented!();
+//! let mut violations = Vec::new();
+//! let mut summaries = Vec::new();
+//! for (path, source) in &documents {
+//!     let mut linter = MultiRuleLinter::new_for_document(path.clone(), config.clone(), source);
+//!     violations.extend(linter.analyze());
+//!     summaries.extend(linter.into_summary());
+//! }
+//! violations.extend(check_workspace(&summaries));
+//! ```
+
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tree_sitter::Node;
+
+use crate::{
+    config::{QuickmarkConfig, RuleSeverity},
+    inline_config::InlineConfig,
+    linter::{range_from_tree_sitter, CharPosition, Context, Range, RuleViolation},
+    markdown_tree::MarkdownTree,
+    project_index::{heading_text, normalize_path, RelativeTarget},
+    rules::{Rule, WORKSPACE_RULES},
+    tree_sitter_walker::TreeSitterWalker,
+};
+
+/// Title of a document: the `title` key of its front matter or its first level 1 heading
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DocumentTitle {
+    pub text: String,
+    pub range: Range,
+}
+
+/// A relative link from a document to another file of the workspace
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DocumentLink {
+    /// Normalized absolute path of the link target
+    pub target: PathBuf,
+    pub fragment: Option<String>,
+    /// Range of the link destination
+    pub range: Range,
+}
+
+/// What workspace rules know about a single document after the per-document pass.
+///
+/// Summaries can be serialized to cache them between runs. The configuration is left
+/// out and restored with [`DocumentSummary::with_config`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocumentSummary {
+    /// Path of the document, as given to the linter
+    pub file_path: PathBuf,
+    /// Configuration of the document, including inline `configure-file` directives
+    #[serde(skip)]
+    pub config: QuickmarkConfig,
+    pub title: Option<DocumentTitle>,
+    /// Relative links, images and link reference definitions, in document order
+    pub links: Vec<DocumentLink>,
+    inline_config: InlineConfig,
+}
+
+impl DocumentSummary {
+    pub(crate) fn new(
+        context: &Context,
+        markdown_tree: &MarkdownTree,
+        inline_config: InlineConfig,
+    ) -> Self {
+        let source = context.get_document_content();
+        let base_dir = normalize_path(&context.file_path)
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+
+        let mut title = context.front_matter.as_ref().and_then(|front_matter| {
+            match front_matter.get("title") {
+                Some(Value::String(text)) => {
+                    let line = front_matter.line_of("title");
+                    Some(DocumentTitle {
+                        text: text.trim().to_string(),
+                        range: line_range(line, context),
+                    })
+                }
+                _ => None,
+            }
+        });
+        let mut links = Vec::new();
+        TreeSitterWalker::for_markdown(markdown_tree).walk(|node| match node.kind() {
+            "atx_heading" | "setext_heading" if title.is_none() && is_top_level_heading(&node) => {
+                title = heading_text(&node, source).map(|text| DocumentTitle {
+                    text,
+                    range: range_from_tree_sitter(&node.range()),
+                });
+            }
+            "inline_link" | "image" | "link_reference_definition" => {
+                let mut cursor = node.walk();
+                let destination = node
+                    .children(&mut cursor)
+                    .find(|child| child.kind() == "link_destination");
+                let Some(destination) = destination else {
+                    return;
+                };
+                if let Some(target) =
+                    RelativeTarget::parse(&source[destination.start_byte()..destination.end_byte()])
+                {
+                    links.push(DocumentLink {
+                        target: normalize_path(&base_dir.join(&target.path)),
+                        fragment: target.fragment,
+                        range: range_from_tree_sitter(&destination.range()),
+                    });
+                }
+            }
+            _ => {}
+        });
+
+        Self {
+            file_path: context.file_path.clone(),
+            config: context.config.clone(),
+            title,
+            links,
+            inline_config,
+        }
+    }
+
+    /// Restore the configuration of a deserialized summary from `config`, the
+    /// configuration the document was linted with
+    pub fn with_config(mut self, mut config: QuickmarkConfig) -> Self {
+        self.inline_config.configure(&mut config);
+        self.config = config;
+        self
+    }
+
+    /// Severity of a rule for this document, `Off` when it is not configured
+    pub fn severity(&self, rule: &Rule) -> RuleSeverity {
+        self.config
+            .linters
+            .severity
+            .get(rule.alias)
+            .cloned()
+            .unwrap_or(RuleSeverity::Off)
+    }
+
+    /// Whether violations of `rule` are reported for this document
+    pub fn is_enabled(&self, rule: &Rule) -> bool {
+        self.severity(rule) != RuleSeverity::Off
+    }
+}
+
+fn is_top_level_heading(node: &Node) -> bool {
+    let mut cursor = node.walk();
+    let is_h1 = node
+        .children(&mut cursor)
+        .any(|child| matches!(child.kind(), "atx_h1_marker" | "setext_h1_underline"));
+    // Headings nested in block quotes or lists don't title the document
+    let mut parent = node.parent();
+    while let Some(container) = parent {
+        if matches!(container.kind(), "block_quote" | "list_item") {
+            return false;
+        }
+        parent = container.parent();
+    }
+    is_h1
+}
+
+fn line_range(line: usize, context: &Context) -> Range {
+    let length = context.lines.get(line).map_or(0, String::len);
+    Range {
+        start: CharPosition { line, character: 0 },
+        end: CharPosition {
+            line,
+            character: length,
+        },
+    }
+}
+
+/// Run the workspace rules over the summaries of all documents of a lint run.
+///
+/// Each violation takes the severity configured by the document it is reported
+/// against, and is dropped when that document turns the rule off, either in its
+/// configuration or with an inline directive.
+pub fn check_workspace(documents: &[DocumentSummary]) -> Vec<RuleViolation> {
+    let by_path: HashMap<&Path, &DocumentSummary> = documents
+        .iter()
+        .map(|document| (document.file_path.as_path(), document))
+        .collect();
+
+    let mut violations = Vec::new();
+    for workspace_rule in WORKSPACE_RULES {
+        let rule = workspace_rule.rule;
+        if !documents.iter().any(|document| document.is_enabled(rule)) {
+            continue;
+        }
+        for mut violation in (workspace_rule.check)(documents) {
+            let location = violation.location();
+            let severity = match by_path.get(location.file_path.as_path()) {
+                Some(document)
+                    if document
+                        .inline_config
+                        .is_disabled(rule, location.range.start.line) =>
+                {
+                    continue
+                }
+                Some(document) => document.severity(rule),
+                None => RuleSeverity::Error,
+            };
+            if severity == RuleSeverity::Off {
+                continue;
+            }
+            violation.severity = severity;
+            violations.push(violation);
+        }
+    }
+    violations
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::{Path, PathBuf};
+
+    use crate::config::RuleSeverity;
+    use crate::linter::MultiRuleLinter;
+    use crate::project_index::normalize_path;
+    use crate::rules::qm003::QM003;
+    use crate::test_utils::test_helpers::test_config_with_rules;
+
+    use super::DocumentSummary;
+
+    fn summary(severity: RuleSeverity, input: &str) -> Option<DocumentSummary> {
+        let config = test_config_with_rules(vec![("duplicate-titles", severity)]);
+        let mut linter =
+            MultiRuleLinter::new_for_document(PathBuf::from("docs/guide.md"), config, input);
+        assert!(linter.analyze().is_empty());
+        linter.into_summary()
+    }
+
+    #[test]
+    fn test_summary_title_and_links() {
+        let input = "> # Quoted
+## Setup
+
+# Getting Started
+
+See [setup](../setup.md#install), ![logo](img/logo.png) and [site](https://example.com).
+
+[ref]: <other guide.md>
+";
+        let summary = summary(RuleSeverity::Error, input).unwrap();
+        let title = summary.title.unwrap();
+        assert_eq!("Getting Started", title.text);
+        assert_eq!(3, title.range.start.line);
+
+        let links: Vec<_> = summary
+            .links
+            .iter()
+            .map(|link| (link.target.clone(), link.fragment.as_deref()))
+            .collect();
+        assert_eq!(
+            vec![
+                (normalize_path(Path::new("setup.md")), Some("install")),
+                (normalize_path(Path::new("docs/img/logo.png")), None),
+                (normalize_path(Path::new("docs/other guide.md")), None),
+            ],
+            links
+        );
+    }
+
+    #[test]
+    fn test_summary_front_matter_title() {
+        let summary =
+            summary(RuleSeverity::Error, "---\ntitle: Guide\n---\n\n# Heading\n").unwrap();
+        let title = summary.title.unwrap();
+        assert_eq!("Guide", title.text);
+        assert_eq!(1, title.range.start.line);
+    }
+
+    #[test]
+    fn test_summary_round_trip() {
+        let input =
+            "<!-- quickmark-disable-next-line duplicate-titles -->\n# Guide\n\n[setup](setup.md)\n";
+        let summary = summary(RuleSeverity::Warning, input).unwrap();
+        let json = serde_json::to_string(&summary).unwrap();
+        let config = test_config_with_rules(vec![("duplicate-titles", RuleSeverity::Warning)]);
+        let restored: DocumentSummary = serde_json::from_str(&json).unwrap();
+        let restored = restored.with_config(config);
+
+        assert_eq!(summary.title, restored.title);
+        assert_eq!(summary.links, restored.links);
+        assert_eq!(summary.config, restored.config);
+        assert!(restored.inline_config.is_disabled(&QM003, 1));
+    }
+
+    #[test]
+    fn test_no_summary_without_active_rules() {
+        assert!(summary(RuleSeverity::Off, "# Heading\n").is_none());
+    }
+}
@@ -0,0 +1,186 @@
+//! Plugin rules registered in-process. These live in their own test binary because
+//! registered plugins stay in a process-wide registry.
+
+use std::ffi::c_void;
+use std::path::{Path, PathBuf};
+
+use quickmark_core::config::{parse_toml_config, validate_config, QuickmarkConfig, RuleSeverity};
+use quickmark_core::linter::MultiRuleLinter;
+use quickmark_core::plugin::{
+    allow_plugins, register_plugin, QmDocument, QmNode, QmPlugin, QmPoint, QmReportFn, QmRule,
+    QmStr, QmViolation, PLUGIN_ABI_VERSION,
+};
+
+/// Linter state: the number of headings and the `max` setting
+struct HeadingCounter {
+    headings: usize,
+    max: usize,
+}
+
+unsafe extern "C" fn new_linter(document: *const QmDocument) -> *mut c_void {
+    let document = &*document;
+    let settings: serde_json::Value =
+        serde_json::from_str(document.settings_json.as_str().unwrap()).unwrap();
+    let max = settings
+        .get("max")
+        .and_then(|max| max.as_u64())
+        .unwrap_or(1);
+    Box::into_raw(Box::new(HeadingCounter {
+        headings: 0,
+        max: max as usize,
+    })) as *mut c_void
+}
+
+unsafe extern "C" fn feed(linter: *mut c_void, node: *const QmNode) {
+    let linter = &mut *(linter as *mut HeadingCounter);
+    if (*node).kind.as_str() == Some("atx_heading") {
+        linter.headings += 1;
+    }
+}
+
+unsafe extern "C" fn finalize(linter: *mut c_void, sink: *mut c_void, report: QmReportFn) {
+    let linter = &*(linter as *mut HeadingCounter);
+    if linter.headings > linter.max {
+        let message = format!("{} headings, at most {}", linter.headings, linter.max);
+        let violation = QmViolation {
+            message: QmStr::new(&message),
+            start: QmPoint { row: 0, column: 0 },
+            end: QmPoint { row: 0, column: 1 },
+        };
+        report(sink, &violation);
+    }
+}
+
+unsafe extern "C" fn free_linter(linter: *mut c_void) {
+    drop(Box::from_raw(linter as *mut HeadingCounter));
+}
+
+const fn rule(id: &'static str, alias: &'static str) -> QmRule {
+    QmRule {
+        id: QmStr::from_static(id),
+        alias: QmStr::from_static(alias),
+        description: QmStr::from_static("Documents should have few headings"),
+        tags: std::ptr::null(),
+        tag_count: 0,
+        new_linter: Some(new_linter),
+        feed: Some(feed),
+        finalize: Some(finalize),
+        free_linter: Some(free_linter),
+    }
+}
+
+static RULES: [QmRule; 1] = [rule("TEST001", "few-headings")];
+
+static PLUGIN: QmPlugin = QmPlugin {
+    abi_version: PLUGIN_ABI_VERSION,
+    name: QmStr::from_static("test"),
+    rules: RULES.as_ptr(),
+    rule_count: RULES.len(),
+};
+
+/// Register the plugin under the path that `plugins = ["test-plugin"]` resolves to
+fn register() {
+    allow_plugins(true);
+    let origin = std::env::current_dir().unwrap().join("test-plugin");
+    unsafe { register_plugin(&PLUGIN, &origin) }.unwrap();
+}
+
+#[test]
+fn test_plugin_rule_severity_and_settings() {
+    register();
+    let config = parse_toml_config(
+        "plugins = ['test-plugin']\n\n[linters.severity]\ndefault = 'off'\nfew-headings = 'warn'\n\n[linters.settings.few-headings]\nmax = 2\n",
+    )
+    .unwrap();
+    assert_eq!(
+        Some(&RuleSeverity::Warning),
+        config.linters.severity.get("few-headings")
+    );
+
+    let lint = |input: &str| {
+        MultiRuleLinter::new_for_document(PathBuf::from("doc.md"), config.clone(), input).analyze()
+    };
+    assert!(lint("# One\n\n## Two\n").is_empty());
+    let violations = lint("# One\n\n## Two\n\n## Three\n");
+    assert_eq!(1, violations.len());
+    assert_eq!("TEST001", violations[0].rule().id);
+    assert_eq!("3 headings, at most 2", violations[0].message());
+    assert_eq!(RuleSeverity::Warning, *violations[0].severity());
+
+    // Inline configuration applies to plugin rules like to built-in ones
+    assert!(lint("<!-- quickmark-disable few-headings -->\n# One\n## Two\n## Three\n").is_empty());
+
+    // The default severity covers plugin rules
+    let config =
+        parse_toml_config("plugins = ['test-plugin']\n\n[linters.severity]\ndefault = 'off'\n")
+            .unwrap();
+    assert_eq!(
+        Some(&RuleSeverity::Off),
+        config.linters.severity.get("few-headings")
+    );
+}
+
+#[test]
+fn test_plugin_rules_only_apply_to_their_configuration() {
+    register();
+    let with_plugin = parse_toml_config("plugins = ['test-plugin']\n").unwrap();
+    let without_plugin = parse_toml_config("[linters.severity]\ndefault = 'err'\n").unwrap();
+    assert_eq!(vec!["few-headings"], with_plugin.plugin_rules);
+    assert!(without_plugin.plugin_rules.is_empty());
+    assert_eq!(None, without_plugin.linters.severity.get("few-headings"));
+
+    let lint = |config: &QuickmarkConfig, input: &str| {
+        MultiRuleLinter::new_for_document(PathBuf::from("doc.md"), config.clone(), input)
+            .analyze()
+            .into_iter()
+            .filter(|violation| violation.rule().id == "TEST001")
+            .count()
+    };
+    let input = "# One\n\n## Two\n";
+    assert_eq!(1, lint(&with_plugin, input));
+    assert_eq!(0, lint(&without_plugin, input));
+
+    // Inline configuration can't turn on a rule of another configuration's plugin
+    let enabled = "<!-- quickmark-configure-file { \"few-headings\": true } -->\n# One\n\n## Two\n";
+    assert_eq!(0, lint(&without_plugin, enabled));
+}
+
+#[test]
+fn test_plugin_rules_in_validation() {
+    register();
+    let content = "plugins = ['test-plugin']\n\n[linters.severity]\nfew-headings = 'err'\n\n[linters.settings.few-headings]\nmax = 3\n";
+    assert!(validate_config(content).is_empty());
+
+    let problems = validate_config("[linters.settings]\nfew-headings = 3\n");
+    assert_eq!(1, problems.len());
+    assert_eq!(
+        "Settings of `few-headings` must be a table",
+        problems[0].message
+    );
+}
+
+#[test]
+fn test_invalid_plugins() {
+    register();
+    // Registering the same origin twice is a no-op
+    register();
+
+    static OLD: QmPlugin = QmPlugin {
+        abi_version: PLUGIN_ABI_VERSION + 1,
+        name: QmStr::from_static("old"),
+        rules: std::ptr::null(),
+        rule_count: 0,
+    };
+    let error = unsafe { register_plugin(&OLD, Path::new("old")) }.unwrap_err();
+    assert!(error.to_string().contains("interface version"), "{error}");
+
+    static CLASHING_RULES: [QmRule; 1] = [rule("TEST002", "line-length")];
+    static CLASHING: QmPlugin = QmPlugin {
+        abi_version: PLUGIN_ABI_VERSION,
+        name: QmStr::from_static("clashing"),
+        rules: CLASHING_RULES.as_ptr(),
+        rule_count: CLASHING_RULES.len(),
+    };
+    let error = unsafe { register_plugin(&CLASHING, Path::new("clashing")) }.unwrap_err();
+    assert_eq!("Rule `line-length` is already defined", error.to_string());
+}
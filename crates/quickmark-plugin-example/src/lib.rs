@@ -0,0 +1,152 @@
+//! An example quickmark plugin with a single rule, `todo-markers`, reporting markers
+//! such as `TODO` left in the text of a document.
+//!
+//! Build it with `cargo build -p quickmark-plugin-example` and list the library in
+//! `quickmark.toml`:
+//!
+//! ```toml
+//! plugins = ["target/debug/libquickmark_plugin_example.so"]
+//!
+//! [linters.settings.todo-markers]
+//! markers = ["TODO", "FIXME", "XXX"]
+//! ```
+
+use std::ffi::c_void;
+
+use quickmark_core::plugin::{
+    QmDocument, QmNode, QmPlugin, QmPoint, QmReportFn, QmRule, QmStr, QmViolation,
+    PLUGIN_ABI_VERSION,
+};
+use serde_json::Value;
+
+const DEFAULT_MARKERS: &[&str] = &["TODO", "FIXME"];
+
+struct TodoMarkersLinter {
+    source: String,
+    markers: Vec<String>,
+    violations: Vec<(String, QmPoint, QmPoint)>,
+}
+
+impl TodoMarkersLinter {
+    fn new(source: &str, settings_json: &str) -> Self {
+        let markers = serde_json::from_str::<Value>(settings_json)
+            .ok()
+            .and_then(|settings| {
+                let markers = settings.get("markers")?.as_array()?;
+                Some(
+                    markers
+                        .iter()
+                        .filter_map(Value::as_str)
+                        .filter(|marker| !marker.is_empty())
+                        .map(str::to_string)
+                        .collect(),
+                )
+            })
+            .unwrap_or_else(|| DEFAULT_MARKERS.iter().map(|m| m.to_string()).collect());
+        Self {
+            source: source.to_string(),
+            markers,
+            violations: Vec::new(),
+        }
+    }
+
+    /// Look for markers in the text of a paragraph or heading
+    fn check_inline(&mut self, node: &QmNode) {
+        let Some(text) = self.source.get(node.start_byte..node.end_byte) else {
+            return;
+        };
+        let mut found: Vec<(usize, &str)> = self
+            .markers
+            .iter()
+            .flat_map(|marker| text.match_indices(marker.as_str()))
+            .collect();
+        found.sort();
+        for (offset, marker) in found {
+            let start = position(text, offset, node.start);
+            let end = position(text, offset + marker.len(), node.start);
+            self.violations
+                .push((format!("Unresolved '{marker}' marker"), start, end));
+        }
+    }
+}
+
+/// Position of `offset` in `text`, which starts at `start`
+fn position(text: &str, offset: usize, start: QmPoint) -> QmPoint {
+    let before = &text[..offset];
+    match before.rfind('\n') {
+        Some(newline) => QmPoint {
+            row: start.row + before.matches('\n').count(),
+            column: offset - newline - 1,
+        },
+        None => QmPoint {
+            row: start.row,
+            column: start.column + offset,
+        },
+    }
+}
+
+unsafe extern "C" fn new_linter(document: *const QmDocument) -> *mut c_void {
+    let Some(document) = document.as_ref() else {
+        return std::ptr::null_mut();
+    };
+    let source = document.source.as_str().unwrap_or_default();
+    let settings_json = document.settings_json.as_str().unwrap_or("{}");
+    Box::into_raw(Box::new(TodoMarkersLinter::new(source, settings_json))) as *mut c_void
+}
+
+unsafe extern "C" fn feed(linter: *mut c_void, node: *const QmNode) {
+    let (Some(linter), Some(node)) = ((linter as *mut TodoMarkersLinter).as_mut(), node.as_ref())
+    else {
+        return;
+    };
+    if node.kind.as_str() == Some("inline") {
+        linter.check_inline(node);
+    }
+}
+
+unsafe extern "C" fn finalize(linter: *mut c_void, sink: *mut c_void, report: QmReportFn) {
+    let Some(linter) = (linter as *mut TodoMarkersLinter).as_mut() else {
+        return;
+    };
+    for (message, start, end) in &linter.violations {
+        let violation = QmViolation {
+            message: QmStr::new(message),
+            start: *start,
+            end: *end,
+        };
+        report(sink, &violation);
+    }
+}
+
+unsafe extern "C" fn free_linter(linter: *mut c_void) {
+    if !linter.is_null() {
+        drop(Box::from_raw(linter as *mut TodoMarkersLinter));
+    }
+}
+
+static TAGS: [QmStr; 1] = [QmStr::from_static("text")];
+
+static RULES: [QmRule; 1] = [QmRule {
+    id: QmStr::from_static("EX001"),
+    alias: QmStr::from_static("todo-markers"),
+    description: QmStr::from_static("Documents should not contain TODO markers"),
+    tags: TAGS.as_ptr(),
+    tag_count: TAGS.len(),
+    new_linter: Some(new_linter),
+    feed: Some(feed),
+    finalize: Some(finalize),
+    free_linter: Some(free_linter),
+}];
+
+static PLUGIN: QmPlugin = QmPlugin {
+    abi_version: PLUGIN_ABI_VERSION,
+    name: QmStr::from_static("quickmark-plugin-example"),
+    rules: RULES.as_ptr(),
+    rule_count: RULES.len(),
+};
+
+/// Entry point looked up by quickmark, see `quickmark_core::plugin::PLUGIN_ENTRY_SYMBOL`
+#[no_mangle]
+pub extern "C" fn quickmark_plugin_v1() -> *const QmPlugin {
+    &PLUGIN
+}
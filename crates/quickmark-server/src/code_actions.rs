@@ -0,0 +1,362 @@
+use quickmark_core::config::QuickmarkConfig;
+use quickmark_core::front_matter::FrontMatter;
+use quickmark_core::inline_config::disable_line_edit;
+use quickmark_core::linter::{self, Edit, RuleViolation};
+use quickmark_core::rules::find_rule_for_config;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use tower_lsp::lsp_types::{
+    CodeAction, CodeActionKind, CodeActionOrCommand, Diagnostic, Position, Range, TextEdit, Url,
+    WorkspaceEdit,
+};
+
+use crate::document::TextDocument;
+
+/// Kind of the source action fixing every fixable problem in a document
+pub const SOURCE_FIX_ALL_QUICKMARK: &str = "source.fixAll.quickmark";
+
+/// Payload stored in `Diagnostic::data`, enough to rebuild the originating violation.
+///
+/// The rule is stored by alias, which unlike ids is unique within a configuration.
+/// Ranges use the linter's coordinates (zero-based lines and byte columns).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DiagnosticData {
+    pub alias: String,
+    pub range: linter::Range,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fix: Vec<Edit>,
+}
+
+impl DiagnosticData {
+    pub fn from_violation(violation: &RuleViolation) -> Self {
+        Self {
+            alias: violation.rule().alias.to_string(),
+            range: violation.location().range.clone(),
+            fix: violation.fix().to_vec(),
+        }
+    }
+
+    pub fn from_diagnostic(diagnostic: &Diagnostic) -> Option<Self> {
+        serde_json::from_value(diagnostic.data.clone()?).ok()
+    }
+
+    /// Rebuild the violation a diagnostic was created from, resolving its rule with
+    /// the configuration of the diagnostic's document
+    pub fn into_violation(
+        self,
+        message: String,
+        file_path: PathBuf,
+        config: &QuickmarkConfig,
+    ) -> Option<RuleViolation> {
+        let rule = find_rule_for_config(&self.alias, config)?;
+        Some(RuleViolation::new(rule, message, file_path, self.range).with_fix(self.fix))
+    }
+}
+
+fn lsp_range(document: &TextDocument, range: &linter::Range) -> Range {
+    Range {
+        start: document.position_at(range.start.line, range.start.character),
+        end: document.position_at(range.end.line, range.end.character),
+    }
+}
+
+fn workspace_edit(uri: &Url, edits: Vec<TextEdit>) -> WorkspaceEdit {
+    WorkspaceEdit {
+        changes: Some(HashMap::from([(uri.clone(), edits)])),
+        ..Default::default()
+    }
+}
+
+fn action(
+    title: String,
+    kind: CodeActionKind,
+    diagnostic: Option<&Diagnostic>,
+    edit: WorkspaceEdit,
+) -> CodeActionOrCommand {
+    CodeActionOrCommand::CodeAction(CodeAction {
+        title,
+        kind: Some(kind),
+        diagnostics: diagnostic.map(|d| vec![d.clone()]),
+        edit: Some(edit),
+        ..Default::default()
+    })
+}
+
+/// First line after the front matter, if the document starts with one
+fn first_line_after_front_matter(document: &TextDocument) -> usize {
+    FrontMatter::parse(document.text())
+        .map(|front_matter| front_matter.end_line + 1)
+        .unwrap_or(0)
+}
+
+/// Quick fixes for a single quickmark diagnostic
+pub fn quick_fixes(
+    uri: &Url,
+    document: &TextDocument,
+    diagnostic: &Diagnostic,
+    violation: &RuleViolation,
+) -> Vec<CodeActionOrCommand> {
+    let rule = violation.rule();
+    let mut actions = Vec::new();
+
+    if violation.is_fixable() {
+        let edits = violation
+            .fix()
+            .iter()
+            .map(|edit| TextEdit {
+                range: lsp_range(document, &edit.range),
+                new_text: edit.replacement.clone(),
+            })
+            .collect();
+        let mut fix = action(
+            format!("Fix {}/{}", rule.id, rule.alias),
+            CodeActionKind::QUICKFIX,
+            Some(diagnostic),
+            workspace_edit(uri, edits),
+        );
+        if let CodeActionOrCommand::CodeAction(fix) = &mut fix {
+            fix.is_preferred = Some(true);
+        }
+        actions.push(fix);
+    }
+
+    let line = violation.location().range.start.line;
+    if let Some(edit) = disable_line_edit(document.text(), line, rule.alias) {
+        actions.push(action(
+            format!("Disable {} for this line", rule.alias),
+            CodeActionKind::QUICKFIX,
+            Some(diagnostic),
+            workspace_edit(
+                uri,
+                vec![TextEdit {
+                    range: lsp_range(document, &edit.range),
+                    new_text: edit.replacement,
+                }],
+            ),
+        ));
+    }
+
+    let file_line = first_line_after_front_matter(document) as u32;
+    actions.push(action(
+        format!("Disable {} for this file", rule.alias),
+        CodeActionKind::QUICKFIX,
+        Some(diagnostic),
+        workspace_edit(
+            uri,
+            vec![TextEdit {
+                range: Range::new(Position::new(file_line, 0), Position::new(file_line, 0)),
+                new_text: format!("<!-- quickmark-disable-file {} -->\n", rule.alias),
+            }],
+        ),
+    ));
+
+    actions
+}
+
+/// Source action replacing the document with its fully fixed content
+pub fn fix_all(uri: &Url, document: &TextDocument, fixed_content: String) -> CodeActionOrCommand {
+    let edit = TextEdit {
+        range: Range::new(Position::new(0, 0), document.end_position()),
+        new_text: fixed_content,
+    };
+    action(
+        "Fix all quickmark problems in file".to_string(),
+        CodeActionKind::new(SOURCE_FIX_ALL_QUICKMARK),
+        None,
+        workspace_edit(uri, vec![edit]),
+    )
+}
+
+/// Whether a code action request restricted to `only` kinds accepts actions of `kind`
+pub fn is_requested(only: Option<&Vec<CodeActionKind>>, kind: &str) -> bool {
+    match only {
+        None => true,
+        Some(kinds) => kinds.iter().any(|requested| {
+            let requested = requested.as_str();
+            kind == requested || kind.starts_with(&format!("{requested}."))
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quickmark_core::config::parse_toml_config;
+    use quickmark_core::linter::CharPosition;
+    use quickmark_core::rules::md009::MD009;
+
+    fn position(line: usize, character: usize) -> CharPosition {
+        CharPosition { line, character }
+    }
+
+    fn trailing_space_violation() -> RuleViolation {
+        let range = linter::Range {
+            start: position(1, 6),
+            end: position(1, 7),
+        };
+        RuleViolation::new(
+            &MD009,
+            "Trailing spaces".to_string(),
+            PathBuf::from("/tmp/a.md"),
+            range.clone(),
+        )
+        .with_fix(vec![Edit::delete(range)])
+    }
+
+    fn edits(action: &CodeActionOrCommand, uri: &Url) -> Vec<TextEdit> {
+        match action {
+            CodeActionOrCommand::CodeAction(action) => {
+                action.edit.as_ref().unwrap().changes.as_ref().unwrap()[uri].clone()
+            }
+            CodeActionOrCommand::Command(_) => panic!("Expected a code action"),
+        }
+    }
+
+    #[test]
+    fn test_diagnostic_data_round_trip() {
+        let violation = trailing_space_violation();
+        let diagnostic = Diagnostic {
+            message: violation.message().to_string(),
+            data: Some(serde_json::to_value(DiagnosticData::from_violation(&violation)).unwrap()),
+            ..Default::default()
+        };
+
+        let restored = DiagnosticData::from_diagnostic(&diagnostic)
+            .unwrap()
+            .into_violation(
+                diagnostic.message.clone(),
+                PathBuf::from("/tmp/a.md"),
+                &parse_toml_config("").unwrap(),
+            )
+            .unwrap();
+        assert_eq!(violation.rule().id, restored.rule().id);
+        assert_eq!(violation.location().range, restored.location().range);
+        assert_eq!(violation.fix(), restored.fix());
+    }
+
+    #[test]
+    fn test_diagnostic_data_resolves_rule_in_document_config() {
+        let config = |alias: &str| {
+            parse_toml_config(&format!(
+                "[[custom_rules]]\nid = \"CUSTOM001\"\nalias = \"{alias}\"\nmessage = \"M\"\nnodes = [\"inline\"]\n"
+            ))
+            .unwrap()
+        };
+        let (first, second) = (config("first-rule"), config("second-rule"));
+        let data = DiagnosticData {
+            alias: "second-rule".to_string(),
+            range: trailing_space_violation().location().range.clone(),
+            fix: Vec::new(),
+        };
+
+        let restored = data
+            .clone()
+            .into_violation("M".to_string(), PathBuf::from("/tmp/a.md"), &second)
+            .unwrap();
+        assert_eq!("second-rule", restored.rule().alias);
+        assert!(data
+            .into_violation("M".to_string(), PathBuf::from("/tmp/a.md"), &first)
+            .is_none());
+    }
+
+    #[test]
+    fn test_quick_fixes() {
+        let uri = Url::parse("file:///tmp/a.md").unwrap();
+        let document = TextDocument::new("---\ntitle: x\n---\n> é text \n".to_string(), 1);
+        let violation = RuleViolation::new(
+            &MD009,
+            "Trailing spaces".to_string(),
+            PathBuf::from("/tmp/a.md"),
+            linter::Range {
+                start: position(3, 9),
+                end: position(3, 10),
+            },
+        )
+        .with_fix(vec![Edit::delete(linter::Range {
+            start: position(3, 9),
+            end: position(3, 10),
+        })]);
+
+        let actions = quick_fixes(&uri, &document, &Diagnostic::default(), &violation);
+        assert_eq!(3, actions.len());
+
+        // Byte columns are converted to UTF-16 positions
+        let fix = edits(&actions[0], &uri);
+        assert_eq!(Position::new(3, 8), fix[0].range.start);
+        assert_eq!("", fix[0].new_text);
+
+        // Line-level comments go at the end of the line
+        let disable_line = edits(&actions[1], &uri);
+        assert_eq!(Position::new(3, 9), disable_line[0].range.start);
+        assert_eq!(
+            " <!-- quickmark-disable-line no-trailing-spaces -->",
+            disable_line[0].new_text
+        );
+
+        // File-level comments go after the front matter
+        let disable_file = edits(&actions[2], &uri);
+        assert_eq!(Position::new(3, 0), disable_file[0].range.start);
+        assert_eq!(
+            "<!-- quickmark-disable-file no-trailing-spaces -->\n",
+            disable_file[0].new_text
+        );
+    }
+
+    #[test]
+    fn test_unfixable_violation_only_offers_suppression() {
+        let uri = Url::parse("file:///tmp/a.md").unwrap();
+        let document = TextDocument::new("# Title\ntext \n".to_string(), 1);
+        let violation = RuleViolation::new(
+            &MD009,
+            "Trailing spaces".to_string(),
+            PathBuf::from("/tmp/a.md"),
+            linter::Range {
+                start: position(1, 4),
+                end: position(1, 5),
+            },
+        );
+
+        let actions = quick_fixes(&uri, &document, &Diagnostic::default(), &violation);
+        assert_eq!(2, actions.len());
+        assert_eq!(Position::new(0, 0), edits(&actions[1], &uri)[0].range.start);
+    }
+
+    #[test]
+    fn test_is_requested() {
+        assert!(is_requested(None, SOURCE_FIX_ALL_QUICKMARK));
+        let only = vec![CodeActionKind::SOURCE_FIX_ALL];
+        assert!(is_requested(Some(&only), SOURCE_FIX_ALL_QUICKMARK));
+        assert!(!is_requested(
+            Some(&only),
+            CodeActionKind::QUICKFIX.as_str()
+        ));
+        let only = vec![CodeActionKind::SOURCE];
+        assert!(is_requested(Some(&only), SOURCE_FIX_ALL_QUICKMARK));
+        let only = vec![CodeActionKind::new("source.fix")];
+        assert!(!is_requested(Some(&only), SOURCE_FIX_ALL_QUICKMARK));
+    }
+
+    #[test]
+    fn test_suppression_placement() {
+        let uri = Url::parse("file:///tmp/a.md").unwrap();
+        let violation = |line| {
+            RuleViolation::new(
+                &MD009,
+                "Trailing spaces".to_string(),
+                PathBuf::from("/tmp/a.md"),
+                linter::Range {
+                    start: position(line, 0),
+                    end: position(line, 1),
+                },
+            )
+        };
+
+        // No line-level comment inside code, where it would change the code
+        let document = TextDocument::new("+++\ntitle = 'x'\n+++\n```\ncode \n```\n".to_string(), 1);
+        let actions = quick_fixes(&uri, &document, &Diagnostic::default(), &violation(4));
+        assert_eq!(1, actions.len());
+        // TOML front matter is skipped like YAML
+        assert_eq!(Position::new(3, 0), edits(&actions[0], &uri)[0].range.start);
+    }
+}
@@ -0,0 +1,71 @@
+use quickmark_core::config::{is_markdownlint_config, validate_config_content, ConfigProblem};
+use std::path::Path;
+use tower_lsp::lsp_types::{Diagnostic, DiagnosticSeverity, Range};
+
+use crate::document::TextDocument;
+
+/// Whether `path` is a configuration file rather than a Markdown document
+pub fn is_config_file(path: &Path) -> bool {
+    path.file_name()
+        .is_some_and(|name| name == "quickmark.toml")
+        || is_markdownlint_config(path)
+}
+
+fn problem_to_diagnostic(problem: ConfigProblem, document: &TextDocument) -> Diagnostic {
+    let range = &problem.range;
+    Diagnostic {
+        range: Range {
+            start: document.position_at(range.start.line, range.start.character),
+            end: document.position_at(range.end.line, range.end.character),
+        },
+        severity: Some(DiagnosticSeverity::ERROR),
+        source: Some("quickmark".to_string()),
+        message: problem.message,
+        ..Default::default()
+    }
+}
+
+/// Validation problems of an open configuration file
+pub fn config_diagnostics(path: &Path, document: &TextDocument) -> Vec<Diagnostic> {
+    validate_config_content(document.text(), path)
+        .into_iter()
+        .map(|problem| problem_to_diagnostic(problem, document))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tower_lsp::lsp_types::Position;
+
+    #[test]
+    fn test_is_config_file() {
+        assert!(is_config_file(Path::new("/project/quickmark.toml")));
+        assert!(is_config_file(Path::new("/project/.markdownlint.json")));
+        assert!(!is_config_file(Path::new("/project/Cargo.toml")));
+        assert!(!is_config_file(Path::new("/project/README.md")));
+    }
+
+    #[test]
+    fn test_config_diagnostics() {
+        let document = TextDocument::new(
+            "[linters.severity]\nline-lenght = 'off'\nheading-style = 'error'\n".to_string(),
+            1,
+        );
+        let diagnostics = config_diagnostics(Path::new("/project/quickmark.toml"), &document);
+        assert_eq!(2, diagnostics.len());
+        assert_eq!(
+            Range::new(Position::new(1, 0), Position::new(1, 11)),
+            diagnostics[0].range
+        );
+        assert_eq!(
+            "Unknown rule `line-lenght`, did you mean `line-length`?",
+            diagnostics[0].message
+        );
+        assert_eq!(Some(DiagnosticSeverity::ERROR), diagnostics[0].severity);
+        assert_eq!(
+            Range::new(Position::new(2, 16), Position::new(2, 23)),
+            diagnostics[1].range
+        );
+    }
+}
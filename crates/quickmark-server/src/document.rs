@@ -0,0 +1,180 @@
+use tower_lsp::lsp_types::{Position, TextDocumentContentChangeEvent};
+
+/// In-memory copy of an open text document, kept in sync with the client.
+///
+/// LSP positions use UTF-16 code units (the negotiated position encoding), while the
+/// linter reports byte columns, so the document converts between the two.
+#[derive(Debug, Clone)]
+pub struct TextDocument {
+    text: String,
+    version: i32,
+    /// Byte offset of the start of each line
+    line_starts: Vec<usize>,
+}
+
+impl TextDocument {
+    pub fn new(text: String, version: i32) -> Self {
+        let line_starts = Self::compute_line_starts(&text);
+        Self {
+            text,
+            version,
+            line_starts,
+        }
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn version(&self) -> i32 {
+        self.version
+    }
+
+    fn compute_line_starts(text: &str) -> Vec<usize> {
+        std::iter::once(0)
+            .chain(text.match_indices('\n').map(|(i, _)| i + 1))
+            .collect()
+    }
+
+    /// Byte range of `line`, excluding the line terminator
+    fn line_bounds(&self, line: usize) -> (usize, usize) {
+        let start = self.line_starts[line];
+        let end = self
+            .line_starts
+            .get(line + 1)
+            .map(|next| next - 1)
+            .unwrap_or(self.text.len());
+        let end = if end > start && self.text.as_bytes()[end - 1] == b'\r' {
+            end - 1
+        } else {
+            end
+        };
+        (start, end)
+    }
+
+    /// Position just past the last character of the document
+    pub fn end_position(&self) -> Position {
+        let last_line = self.line_starts.len() - 1;
+        self.position_at(last_line, self.text.len() - self.line_starts[last_line])
+    }
+
+    /// Convert an LSP position to a byte offset, clamping it to the document
+    pub fn offset_at(&self, position: Position) -> usize {
+        let line = position.line as usize;
+        if line >= self.line_starts.len() {
+            return self.text.len();
+        }
+        let (start, end) = self.line_bounds(line);
+        let mut utf16_units = 0;
+        for (offset, c) in self.text[start..end].char_indices() {
+            if utf16_units >= position.character as usize {
+                return start + offset;
+            }
+            utf16_units += c.len_utf16();
+        }
+        end
+    }
+
+    /// Convert a zero-based line and byte column reported by the linter to an LSP position
+    pub fn position_at(&self, line: usize, byte_column: usize) -> Position {
+        if line >= self.line_starts.len() {
+            return Position::new(line as u32, byte_column as u32);
+        }
+        let (start, end) = self.line_bounds(line);
+        let column_end = (start + byte_column).min(end);
+        let prefix = self.text.get(start..column_end).unwrap_or_default();
+        let mut character: usize = prefix.chars().map(char::len_utf16).sum();
+        // Columns past the end of the line (e.g. covering the line break) are kept as-is
+        character += (start + byte_column).saturating_sub(end);
+        Position::new(line as u32, character as u32)
+    }
+
+    /// Apply content changes sent with `textDocument/didChange`
+    pub fn apply_changes(&mut self, changes: Vec<TextDocumentContentChangeEvent>, version: i32) {
+        for change in changes {
+            match change.range {
+                Some(range) => {
+                    let start = self.offset_at(range.start);
+                    let end = self.offset_at(range.end).max(start);
+                    self.text.replace_range(start..end, &change.text);
+                }
+                None => self.text = change.text,
+            }
+            self.line_starts = Self::compute_line_starts(&self.text);
+        }
+        self.version = version;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tower_lsp::lsp_types::Range;
+
+    fn change(
+        range: Option<((u32, u32), (u32, u32))>,
+        text: &str,
+    ) -> TextDocumentContentChangeEvent {
+        TextDocumentContentChangeEvent {
+            range: range.map(|(start, end)| {
+                Range::new(Position::new(start.0, start.1), Position::new(end.0, end.1))
+            }),
+            range_length: None,
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_incremental_changes() {
+        let mut doc = TextDocument::new("# Title\n\nSome text\n".to_string(), 1);
+        doc.apply_changes(
+            vec![
+                change(Some(((0, 2), (0, 7))), "Heading"),
+                change(Some(((2, 9), (2, 9))), " here"),
+            ],
+            2,
+        );
+        assert_eq!("# Heading\n\nSome text here\n", doc.text());
+        assert_eq!(2, doc.version());
+    }
+
+    #[test]
+    fn test_multiline_change() {
+        let mut doc = TextDocument::new("a\nb\nc\n".to_string(), 1);
+        doc.apply_changes(vec![change(Some(((0, 1), (2, 0))), "\nx\n")], 2);
+        assert_eq!("a\nx\nc\n", doc.text());
+    }
+
+    #[test]
+    fn test_full_change() {
+        let mut doc = TextDocument::new("old".to_string(), 1);
+        doc.apply_changes(vec![change(None, "new\ntext")], 5);
+        assert_eq!("new\ntext", doc.text());
+        assert_eq!(Position::new(1, 4), doc.position_at(1, 4));
+    }
+
+    #[test]
+    fn test_utf16_positions() {
+        // "é" is 2 bytes and 1 UTF-16 unit, "😀" is 4 bytes and 2 UTF-16 units
+        let mut doc = TextDocument::new("é😀x\n".to_string(), 1);
+        assert_eq!(Position::new(0, 3), doc.position_at(0, 6));
+        assert_eq!(6, doc.offset_at(Position::new(0, 3)));
+
+        doc.apply_changes(vec![change(Some(((0, 3), (0, 4))), "y")], 2);
+        assert_eq!("é😀y\n", doc.text());
+    }
+
+    #[test]
+    fn test_end_position() {
+        let doc = TextDocument::new("# Title\r\n\ntext é".to_string(), 1);
+        assert_eq!(Position::new(2, 6), doc.end_position());
+    }
+
+    #[test]
+    fn test_positions_are_clamped() {
+        let doc = TextDocument::new("ab\r\ncd".to_string(), 1);
+        assert_eq!(2, doc.offset_at(Position::new(0, 10)));
+        assert_eq!(6, doc.offset_at(Position::new(5, 0)));
+        assert_eq!(Position::new(0, 3), doc.position_at(0, 3));
+    }
+}
@@ -1,84 +1,323 @@
 use anyhow::Result;
+use ignore::{types::TypesBuilder, WalkBuilder};
+use quickmark_core::baseline::{Baseline, DEFAULT_BASELINE_FILE};
 use quickmark_core::config::{
-    config_in_path_or_default, discover_config_with_workspace_or_default, RuleSeverity,
+    config_in_path_or_default, discover_config_with_workspace_or_default, QuickmarkConfig,
+    RuleSeverity,
 };
 use quickmark_core::linter::{MultiRuleLinter, RuleViolation};
+use quickmark_core::plugin;
+use quickmark_core::project_index::ProjectIndex;
+use quickmark_core::workspace::{check_workspace, DocumentSummary};
+use std::collections::HashMap;
 use std::env;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use tokio::io::{stdin, stdout};
 use tower_lsp::jsonrpc;
 use tower_lsp::lsp_types::*;
 use tower_lsp::{Client, LanguageServer, LspService, Server};
 
-#[derive(Debug)]
+mod code_actions;
+mod config_diagnostics;
+mod document;
+
+use code_actions::{DiagnosticData, SOURCE_FIX_ALL_QUICKMARK};
+use config_diagnostics::{config_diagnostics, is_config_file};
+use document::TextDocument;
+
+/// Delay between the last change of a document and re-linting it, unless the client
+/// sets `lintDebounceMs` in its initialization options
+const DEFAULT_LINT_DEBOUNCE_MS: u64 = 300;
+
+#[derive(Debug, Clone)]
 struct Backend {
     client: Client,
-    workspace_roots: std::sync::Mutex<Vec<PathBuf>>,
+    workspace_roots: Arc<Mutex<Vec<PathBuf>>>,
+    /// Open documents keyed by URI
+    documents: Arc<Mutex<HashMap<Url, TextDocument>>>,
+    /// Anchors of the project's Markdown documents, with open documents indexed from
+    /// their in-memory text so that links into unsaved changes resolve
+    project_index: Arc<ProjectIndex>,
+    /// Diagnostics of the workspace rules from the last workspace diagnostic request,
+    /// keyed by file path. They are added to the diagnostics of single documents.
+    workspace_diagnostics: Arc<Mutex<HashMap<PathBuf, Vec<Diagnostic>>>>,
+    lint_debounce_ms: Arc<AtomicU64>,
+    /// Baseline files of the workspace, the `baseline` initialization option or a
+    /// `quickmark-baseline.json` in each workspace root
+    baseline_files: Arc<Mutex<Vec<PathBuf>>>,
+    /// Loaded baselines; violations they record are not reported
+    baselines: Arc<Mutex<Vec<Baseline>>>,
 }
 
 impl Backend {
     fn new(client: Client) -> Self {
         Self {
             client,
-            workspace_roots: std::sync::Mutex::new(Vec::new()),
+            workspace_roots: Arc::new(Mutex::new(Vec::new())),
+            documents: Arc::new(Mutex::new(HashMap::new())),
+            project_index: Arc::new(ProjectIndex::new()),
+            workspace_diagnostics: Arc::new(Mutex::new(HashMap::new())),
+            lint_debounce_ms: Arc::new(AtomicU64::new(DEFAULT_LINT_DEBOUNCE_MS)),
+            baseline_files: Arc::new(Mutex::new(Vec::new())),
+            baselines: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// (Re)load the baseline files. Missing files are skipped.
+    fn load_baselines(&self) {
+        let baselines = self
+            .baseline_files
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|path| path.is_file())
+            .filter_map(|path| match Baseline::load(path) {
+                Ok(baseline) => Some(baseline),
+                Err(err) => {
+                    eprintln!("Failed to load baseline: {err:#}");
+                    None
+                }
+            })
+            .collect();
+        *self.baselines.lock().unwrap() = baselines;
+    }
+
+    fn is_baseline_file(&self, path: &Path) -> bool {
+        self.baseline_files
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|file| file == path)
+    }
+
+    /// Drop the violations of a document that are recorded in the baseline covering it
+    fn remove_baselined(&self, violations: &mut Vec<RuleViolation>, source: &str) {
+        let Some(file_path) = violations.first().map(|v| v.location().file_path.clone()) else {
+            return;
+        };
+        let baselines = self.baselines.lock().unwrap();
+        if let Some(baseline) = baselines.iter().find(|b| b.covers(&file_path)) {
+            let mut matcher = baseline.matcher();
+            violations.retain(|violation| !matcher.matches(violation, source));
+        }
+    }
+
+    /// Resolve the configuration that applies to `file_path`
+    fn config_for(&self, file_path: &std::path::Path) -> Result<QuickmarkConfig> {
+        // Use hierarchical config discovery with workspace roots or fallback to old behavior
+        let workspace_roots = self.workspace_roots.lock().unwrap();
+        if workspace_roots.is_empty() {
+            // Fallback to old behavior if no workspace roots
+            let pwd = env::current_dir()?;
+            config_in_path_or_default(&pwd)?.for_file(file_path)
+        } else {
+            discover_config_with_workspace_or_default(file_path, workspace_roots.clone())
         }
     }
 
-    fn lint_document(&self, uri: &Url, content: &str) -> Result<Vec<Diagnostic>> {
+    fn lint_document(&self, uri: &Url, document: &TextDocument) -> Result<Vec<Diagnostic>> {
         let file_path = uri
             .to_file_path()
             .map_err(|_| anyhow::anyhow!("Invalid file path"))?;
+        if is_config_file(&file_path) {
+            return Ok(config_diagnostics(&file_path, document));
+        }
+        let (violations, _) = self.analyze(&file_path, document)?;
 
-        // Use hierarchical config discovery with workspace roots or fallback to old behavior
-        let config = {
-            let workspace_roots = self.workspace_roots.lock().unwrap();
-            if workspace_roots.is_empty() {
-                // Fallback to old behavior if no workspace roots
-                let pwd = env::current_dir()?;
-                config_in_path_or_default(&pwd)?
-            } else {
-                discover_config_with_workspace_or_default(&file_path, workspace_roots.clone())?
+        let mut diagnostics: Vec<Diagnostic> = violations
+            .into_iter()
+            .map(|violation| self.violation_to_diagnostic(violation, document))
+            .collect();
+        if let Some(workspace) = self.workspace_diagnostics.lock().unwrap().get(&file_path) {
+            diagnostics.extend(workspace.iter().cloned());
+        }
+        Ok(diagnostics)
+    }
+
+    /// [`Backend::lint_document`] on the blocking thread pool, keeping the async
+    /// runtime responsive while a large document is linted
+    async fn lint_document_in_background(
+        &self,
+        uri: &Url,
+        document: &TextDocument,
+    ) -> Result<Vec<Diagnostic>> {
+        let backend = self.clone();
+        let uri = uri.clone();
+        let document = document.clone();
+        tokio::task::spawn_blocking(move || backend.lint_document(&uri, &document)).await?
+    }
+
+    /// Lint a Markdown document and summarize it for the workspace rules
+    fn analyze(
+        &self,
+        file_path: &Path,
+        document: &TextDocument,
+    ) -> Result<(Vec<RuleViolation>, Option<DocumentSummary>)> {
+        let config = self.config_for(file_path)?;
+        let mut linter = MultiRuleLinter::new_for_document_in_project(
+            file_path.to_path_buf(),
+            config,
+            document.text(),
+            Arc::clone(&self.project_index),
+        );
+        let mut violations = linter.analyze();
+        self.remove_baselined(&mut violations, document.text());
+        Ok((violations, linter.into_summary()))
+    }
+
+    /// Markdown files under the workspace roots, honoring `.gitignore`
+    fn workspace_markdown_files(&self) -> Result<Vec<PathBuf>> {
+        let roots = self.workspace_roots.lock().unwrap().clone();
+        let mut types_builder = TypesBuilder::new();
+        types_builder.add_defaults();
+        types_builder.select("markdown");
+        let types = types_builder.build()?;
+
+        let mut files = Vec::new();
+        for root in roots {
+            let walker = WalkBuilder::new(root)
+                .hidden(false)
+                .types(types.clone())
+                .build();
+            files.extend(
+                walker
+                    .filter_map(|entry| entry.ok())
+                    .filter(|entry| entry.file_type().is_some_and(|t| t.is_file()))
+                    .map(|entry| entry.into_path()),
+            );
+        }
+        files.sort();
+        files.dedup();
+        Ok(files)
+    }
+
+    /// Lint every Markdown file of the workspace, open documents from their in-memory
+    /// text, and run the workspace rules over all of them
+    async fn lint_workspace(&self) -> Result<Vec<WorkspaceDocumentDiagnosticReport>> {
+        let mut pending = Vec::new();
+        for file_path in self.workspace_markdown_files()? {
+            let Ok(uri) = Url::from_file_path(&file_path) else {
+                continue;
+            };
+            let (document, version) = match self.document(&uri) {
+                Some(document) => {
+                    let version = document.version();
+                    (document, Some(version as i64))
+                }
+                None => match std::fs::read_to_string(&file_path) {
+                    Ok(text) => (TextDocument::new(text, 0), None),
+                    Err(err) => {
+                        eprintln!("Failed to read {}: {err}", file_path.display());
+                        continue;
+                    }
+                },
+            };
+            pending.push((file_path, uri, version, document));
+        }
+
+        // Documents are linted in one blocking task per core, in chunks so that each
+        // task reuses its thread's parsers
+        let workers = std::thread::available_parallelism().map_or(1, |n| n.get());
+        let chunk_size = pending.len().div_ceil(workers).max(1);
+        let mut tasks = Vec::new();
+        while !pending.is_empty() {
+            let chunk: Vec<_> = pending.drain(..chunk_size.min(pending.len())).collect();
+            let backend = self.clone();
+            tasks.push(tokio::task::spawn_blocking(move || {
+                chunk
+                    .into_iter()
+                    .filter_map(|(file_path, uri, version, document)| {
+                        match backend.analyze(&file_path, &document) {
+                            Ok((violations, summary)) => {
+                                let diagnostics: Vec<Diagnostic> = violations
+                                    .into_iter()
+                                    .map(|violation| {
+                                        backend.violation_to_diagnostic(violation, &document)
+                                    })
+                                    .collect();
+                                Some((file_path, uri, version, document, diagnostics, summary))
+                            }
+                            Err(err) => {
+                                eprintln!("Failed to lint {}: {err}", file_path.display());
+                                None
+                            }
+                        }
+                    })
+                    .collect::<Vec<_>>()
+            }));
+        }
+
+        let mut documents = Vec::new();
+        let mut summaries = Vec::new();
+        for task in tasks {
+            for (file_path, uri, version, document, diagnostics, summary) in task.await? {
+                summaries.extend(summary);
+                documents.push((file_path, uri, version, document, diagnostics));
             }
-        };
+        }
 
-        let mut linter = MultiRuleLinter::new_for_document(file_path, config.clone(), content);
-        let violations = linter.analyze();
+        let mut workspace_violations: HashMap<PathBuf, Vec<RuleViolation>> = HashMap::new();
+        for violation in check_workspace(&summaries) {
+            workspace_violations
+                .entry(violation.location().file_path.clone())
+                .or_default()
+                .push(violation);
+        }
+        let mut workspace_diagnostics: HashMap<PathBuf, Vec<Diagnostic>> = HashMap::new();
+        for (file_path, mut violations) in workspace_violations {
+            let Some((.., document, _)) = documents.iter().find(|(path, ..)| *path == file_path)
+            else {
+                continue;
+            };
+            self.remove_baselined(&mut violations, document.text());
+            let diagnostics = violations
+                .into_iter()
+                .map(|violation| self.violation_to_diagnostic(violation, document))
+                .collect();
+            workspace_diagnostics.insert(file_path, diagnostics);
+        }
 
-        Ok(violations
+        let reports = documents
             .into_iter()
-            .map(|violation| self.violation_to_diagnostic(violation, &config))
-            .collect())
+            .map(|(file_path, uri, version, _, mut items)| {
+                if let Some(workspace) = workspace_diagnostics.get(&file_path) {
+                    items.extend(workspace.iter().cloned());
+                }
+                WorkspaceDocumentDiagnosticReport::Full(WorkspaceFullDocumentDiagnosticReport {
+                    uri,
+                    version,
+                    full_document_diagnostic_report: FullDocumentDiagnosticReport {
+                        result_id: None,
+                        items,
+                    },
+                })
+            })
+            .collect();
+        *self.workspace_diagnostics.lock().unwrap() = workspace_diagnostics;
+        Ok(reports)
     }
 
     fn violation_to_diagnostic(
         &self,
         violation: RuleViolation,
-        config: &quickmark_core::config::QuickmarkConfig,
+        document: &TextDocument,
     ) -> Diagnostic {
-        // Get severity from configuration
-        let rule_severity = config
-            .linters
-            .severity
-            .get(violation.rule().alias)
-            .unwrap_or(&RuleSeverity::Warning);
-
-        let severity = match rule_severity {
+        // Severity is resolved by the linter, including inline configuration overrides
+        let severity = match violation.severity() {
             RuleSeverity::Error => DiagnosticSeverity::ERROR,
             RuleSeverity::Warning => DiagnosticSeverity::WARNING,
             RuleSeverity::Off => DiagnosticSeverity::HINT, // Shouldn't happen since off rules are filtered
         };
 
-        let range = violation.location();
+        let range = &violation.location().range;
+        let data = serde_json::to_value(DiagnosticData::from_violation(&violation)).ok();
         Diagnostic {
             range: Range {
-                start: Position {
-                    line: range.range.start.line as u32,
-                    character: range.range.start.character as u32,
-                },
-                end: Position {
-                    line: range.range.end.line as u32,
-                    character: range.range.end.character as u32,
-                },
+                start: document.position_at(range.start.line, range.start.character),
+                end: document.position_at(range.end.line, range.end.character),
             },
             severity: Some(severity),
             code: Some(NumberOrString::String(violation.rule().alias.to_string())),
@@ -87,21 +326,73 @@ impl Backend {
             related_information: None,
             tags: None,
             code_description: None,
-            data: None,
+            data,
         }
     }
 
-    async fn publish_diagnostics(&self, uri: Url, content: &str) {
-        match self.lint_document(&uri, content) {
+    /// Snapshot of an open document
+    fn document(&self, uri: &Url) -> Option<TextDocument> {
+        self.documents.lock().unwrap().get(uri).cloned()
+    }
+
+    /// Index the in-memory text of a Markdown document.
+    /// Returns whether its anchors changed, which affects links from other documents.
+    fn index_document(&self, uri: &Url, document: &TextDocument) -> bool {
+        match uri.to_file_path() {
+            Ok(path) if !is_config_file(&path) => self.project_index.update(&path, document.text()),
+            _ => false,
+        }
+    }
+
+    async fn publish_diagnostics(&self, uri: Url, document: &TextDocument) {
+        let anchors_changed = self.index_document(&uri, document);
+        match self.lint_document_in_background(&uri, document).await {
             Ok(diagnostics) => {
                 self.client
-                    .publish_diagnostics(uri, diagnostics, None)
+                    .publish_diagnostics(uri.clone(), diagnostics, Some(document.version()))
                     .await;
             }
             Err(err) => {
                 eprintln!("Failed to lint document: {err}");
             }
         }
+        // Links from other open documents into this one may have become (in)valid
+        if anchors_changed {
+            Box::pin(self.relint_markdown_documents(Some(&uri))).await;
+        }
+    }
+
+    /// Re-lint every open Markdown document except `skip`, e.g. after a configuration
+    /// file was saved or the headings of a linked document changed
+    async fn relint_markdown_documents(&self, skip: Option<&Url>) {
+        let documents: Vec<(Url, TextDocument)> = self
+            .documents
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(uri, _)| Some(*uri) != skip)
+            .filter(|(uri, _)| !uri.to_file_path().is_ok_and(|path| is_config_file(&path)))
+            .map(|(uri, document)| (uri.clone(), document.clone()))
+            .collect();
+        for (uri, document) in documents {
+            self.publish_diagnostics(uri, &document).await;
+        }
+    }
+
+    /// Re-lint a document once it has not changed for the debounce period
+    fn schedule_lint(&self, uri: Url, version: i32) {
+        let backend = self.clone();
+        let debounce = Duration::from_millis(self.lint_debounce_ms.load(Ordering::Relaxed));
+        tokio::spawn(async move {
+            tokio::time::sleep(debounce).await;
+            // A newer change has scheduled its own lint
+            match backend.document(&uri) {
+                Some(document) if document.version() == version => {
+                    backend.publish_diagnostics(uri, &document).await;
+                }
+                _ => {}
+            }
+        });
     }
 }
 
@@ -139,13 +430,54 @@ impl LanguageServer for Backend {
 
         eprintln!("Workspace roots configured: {:?}", workspace_roots);
 
+        if let Some(debounce) = params
+            .initialization_options
+            .as_ref()
+            .and_then(|options| options.get("lintDebounceMs"))
+            .and_then(|value| value.as_u64())
+        {
+            self.lint_debounce_ms.store(debounce, Ordering::Relaxed);
+        }
+
+        // Workspace configuration can't load plugins unless the client opts in, since
+        // opening a repository shouldn't run its code
+        let allow_plugins = params
+            .initialization_options
+            .as_ref()
+            .and_then(|options| options.get("allowPlugins"))
+            .and_then(|value| value.as_bool())
+            .unwrap_or(false);
+        plugin::allow_plugins(allow_plugins);
+
+        // A relative `baseline` option is resolved against the first workspace root
+        let baseline_files = match params
+            .initialization_options
+            .as_ref()
+            .and_then(|options| options.get("baseline"))
+            .and_then(|value| value.as_str())
+        {
+            Some(path) => {
+                let path = PathBuf::from(path);
+                match workspace_roots.first() {
+                    Some(root) if path.is_relative() => vec![root.join(path)],
+                    _ => vec![path],
+                }
+            }
+            None => workspace_roots
+                .iter()
+                .map(|root| root.join(DEFAULT_BASELINE_FILE))
+                .collect(),
+        };
+        *self.baseline_files.lock().unwrap() = baseline_files;
+        self.load_baselines();
+
         Ok(InitializeResult {
             capabilities: ServerCapabilities {
-                // Explicitly enable full text document synchronization
+                // Incremental sync keeps the in-memory documents up to date while typing
                 text_document_sync: Some(TextDocumentSyncCapability::Options(
                     TextDocumentSyncOptions {
                         open_close: Some(true),
-                        change: Some(TextDocumentSyncKind::FULL),
+                        change: Some(TextDocumentSyncKind::INCREMENTAL),
                         will_save: Some(false),
                         will_save_wait_until: Some(false),
                         save: Some(TextDocumentSyncSaveOptions::SaveOptions(SaveOptions {
@@ -153,12 +485,12 @@ impl LanguageServer for Backend {
                         })),
                     },
                 )),
-                // Explicitly enable diagnostics with push model only
+                // Diagnostics are pushed after changes and can also be pulled
                 diagnostic_provider: Some(DiagnosticServerCapabilities::Options(
                     DiagnosticOptions {
                         identifier: Some("quickmark".to_string()),
-                        inter_file_dependencies: false,
-                        workspace_diagnostics: false,
+                        inter_file_dependencies: true,
+                        workspace_diagnostics: true,
                         work_done_progress_options: WorkDoneProgressOptions::default(),
                     },
                 )),
@@ -175,7 +507,16 @@ impl LanguageServer for Backend {
                 document_highlight_provider: Some(OneOf::Left(false)),
                 document_symbol_provider: Some(OneOf::Left(false)),
                 workspace_symbol_provider: Some(OneOf::Left(false)),
-                code_action_provider: Some(CodeActionProviderCapability::Simple(false)),
+                code_action_provider: Some(CodeActionProviderCapability::Options(
+                    CodeActionOptions {
+                        code_action_kinds: Some(vec![
+                            CodeActionKind::QUICKFIX,
+                            CodeActionKind::new(SOURCE_FIX_ALL_QUICKMARK),
+                        ]),
+                        resolve_provider: Some(false),
+                        work_done_progress_options: WorkDoneProgressOptions::default(),
+                    },
+                )),
                 code_lens_provider: None,
                 document_formatting_provider: Some(OneOf::Left(false)),
                 document_range_formatting_provider: Some(OneOf::Left(false)),
@@ -214,7 +555,12 @@ impl LanguageServer for Backend {
             params.text_document.uri,
             params.text_document.text.len()
         );
-        self.publish_diagnostics(params.text_document.uri, &params.text_document.text)
+        let document = TextDocument::new(params.text_document.text, params.text_document.version);
+        self.documents
+            .lock()
+            .unwrap()
+            .insert(params.text_document.uri.clone(), document.clone());
+        self.publish_diagnostics(params.text_document.uri, &document)
             .await;
     }
 
@@ -224,22 +570,48 @@ impl LanguageServer for Backend {
             params.text_document.uri,
             params.content_changes.len()
         );
-        //do nothing on changes, only lint on save
+        let uri = params.text_document.uri;
+        let version = params.text_document.version;
+        {
+            let mut documents = self.documents.lock().unwrap();
+            let Some(document) = documents.get_mut(&uri) else {
+                eprintln!("[QUICKMARK] change for unknown document: {uri}");
+                return;
+            };
+            document.apply_changes(params.content_changes, version);
+        }
+        self.schedule_lint(uri, version);
     }
 
     async fn did_save(&self, params: DidSaveTextDocumentParams) {
         eprintln!("[QUICKMARK] did_save: {}", params.text_document.uri);
         eprintln!("[QUICKMARK] text provided: {}", params.text.is_some());
 
-        let content = if let Some(text) = params.text {
+        let uri = params.text_document.uri;
+        if uri
+            .to_file_path()
+            .is_ok_and(|path| self.is_baseline_file(&path))
+        {
+            self.load_baselines();
+            self.relint_markdown_documents(None).await;
+            return;
+        }
+        let document = if let Some(text) = params.text {
             eprintln!("[QUICKMARK] using provided text (len: {})", text.len());
             // Use text content provided by the LSP client
-            text
+            let mut documents = self.documents.lock().unwrap();
+            let version = documents.get(&uri).map(|d| d.version()).unwrap_or(0);
+            let document = TextDocument::new(text, version);
+            documents.insert(uri.clone(), document.clone());
+            document
+        } else if let Some(document) = self.document(&uri) {
+            eprintln!("[QUICKMARK] using in-memory document");
+            document
         } else {
             eprintln!("[QUICKMARK] no text provided, reading from disk...");
             // Fallback: read file from disk if client doesn't send text content
             // Some LSP clients don't send text despite server requesting include_text: true
-            match params.text_document.uri.to_file_path() {
+            match uri.to_file_path() {
                 Ok(path) => {
                     eprintln!("[QUICKMARK] reading file: {:?}", path);
                     match std::fs::read_to_string(&path) {
@@ -248,7 +620,7 @@ impl LanguageServer for Backend {
                                 "[QUICKMARK] successfully read {} chars from disk",
                                 content.len()
                             );
-                            content
+                            TextDocument::new(content, 0)
                         }
                         Err(err) => {
                             eprintln!("[QUICKMARK] failed to read file {:?}: {}", path, err);
@@ -257,10 +629,7 @@ impl LanguageServer for Backend {
                     }
                 }
                 Err(_) => {
-                    eprintln!(
-                        "[QUICKMARK] invalid file path: {}",
-                        params.text_document.uri
-                    );
+                    eprintln!("[QUICKMARK] invalid file path: {}", uri);
                     return;
                 }
             }
@@ -268,37 +637,127 @@ impl LanguageServer for Backend {
 
         eprintln!(
             "[QUICKMARK] publishing diagnostics for {} chars",
-            content.len()
+            document.text().len()
         );
-        self.publish_diagnostics(params.text_document.uri, &content)
-            .await;
+        let is_config = uri.to_file_path().is_ok_and(|path| is_config_file(&path));
+        self.publish_diagnostics(uri, &document).await;
         eprintln!("[QUICKMARK] diagnostics published");
+        // Configuration is read from disk, so saving it changes the results of other documents
+        if is_config {
+            self.relint_markdown_documents(None).await;
+        }
     }
 
     async fn did_close(&self, params: DidCloseTextDocumentParams) {
+        let uri = params.text_document.uri;
+        self.documents.lock().unwrap().remove(&uri);
         // Clear diagnostics
         self.client
-            .publish_diagnostics(params.text_document.uri, vec![], None)
+            .publish_diagnostics(uri.clone(), vec![], None)
             .await;
+        // Unsaved changes are discarded, so links resolve against the file on disk again
+        if let Ok(path) = uri.to_file_path() {
+            if !is_config_file(&path) {
+                self.project_index.invalidate(&path);
+                self.relint_markdown_documents(None).await;
+            }
+        }
     }
 
     async fn diagnostic(
         &self,
-        _params: DocumentDiagnosticParams,
+        params: DocumentDiagnosticParams,
     ) -> jsonrpc::Result<DocumentDiagnosticReportResult> {
-        // Per Phase 1 requirements: Don't send anything on textDocument/diagnostic event
-        // as it doesn't contain the full document's content (caching required for future phases)
+        // Lint the in-memory text; unknown documents have no diagnostics
+        let items = match self.document(&params.text_document.uri) {
+            Some(document) => {
+                self.index_document(&params.text_document.uri, &document);
+                self.lint_document_in_background(&params.text_document.uri, &document)
+                    .await
+                    .unwrap_or_else(|err| {
+                        eprintln!("Failed to lint document: {err}");
+                        Vec::new()
+                    })
+            }
+            None => Vec::new(),
+        };
+
         Ok(DocumentDiagnosticReportResult::Report(
             DocumentDiagnosticReport::Full(RelatedFullDocumentDiagnosticReport {
                 related_documents: None,
                 full_document_diagnostic_report: FullDocumentDiagnosticReport {
                     result_id: None,
-                    items: vec![], // Empty - we only provide diagnostics via push model
+                    items,
                 },
             }),
         ))
     }
 
+    async fn workspace_diagnostic(
+        &self,
+        _params: WorkspaceDiagnosticParams,
+    ) -> jsonrpc::Result<WorkspaceDiagnosticReportResult> {
+        let items = self.lint_workspace().await.unwrap_or_else(|err| {
+            eprintln!("Failed to lint workspace: {err}");
+            Vec::new()
+        });
+        Ok(WorkspaceDiagnosticReportResult::Report(
+            WorkspaceDiagnosticReport { items },
+        ))
+    }
+
+    async fn code_action(
+        &self,
+        params: CodeActionParams,
+    ) -> jsonrpc::Result<Option<CodeActionResponse>> {
+        let uri = params.text_document.uri;
+        let (Some(document), Ok(file_path)) = (self.document(&uri), uri.to_file_path()) else {
+            return Ok(None);
+        };
+        if is_config_file(&file_path) {
+            return Ok(None);
+        }
+        let config = match self.config_for(&file_path) {
+            Ok(config) => config,
+            Err(err) => {
+                eprintln!("Failed to load config: {err}");
+                return Ok(None);
+            }
+        };
+        let only = params.context.only.as_ref();
+        let mut actions = Vec::new();
+
+        if code_actions::is_requested(only, CodeActionKind::QUICKFIX.as_str()) {
+            for diagnostic in &params.context.diagnostics {
+                if diagnostic.source.as_deref() != Some("quickmark") {
+                    continue;
+                }
+                let violation = DiagnosticData::from_diagnostic(diagnostic).and_then(|data| {
+                    data.into_violation(diagnostic.message.clone(), file_path.clone(), &config)
+                });
+                if let Some(violation) = violation {
+                    actions.extend(code_actions::quick_fixes(
+                        &uri, &document, diagnostic, &violation,
+                    ));
+                }
+            }
+        }
+
+        if code_actions::is_requested(only, SOURCE_FIX_ALL_QUICKMARK) {
+            let result = MultiRuleLinter::fix_document_in_project(
+                file_path,
+                config,
+                document.text(),
+                Arc::clone(&self.project_index),
+            );
+            if result.fixed > 0 && result.content != document.text() {
+                actions.push(code_actions::fix_all(&uri, &document, result.content));
+            }
+        }
+
+        Ok(Some(actions))
+    }
+
     async fn shutdown(&self) -> jsonrpc::Result<()> {
         Ok(())
     }
@@ -333,6 +792,7 @@ mod tests {
                 severity: severity_map,
                 ..Default::default()
             },
+            ..Default::default()
         }
     }
 
@@ -283,3 +283,351 @@ fn test_lsp_server_complete_workflow() -> anyhow::Result<()> {
     println!("✅ LSP server integration test completed successfully");
     Ok(())
 }
+
+/// Read messages until a `publishDiagnostics` notification for the given document version arrives
+fn wait_for_published_version(client: &mut LspTestClient, version: u64) -> anyhow::Result<Value> {
+    loop {
+        let message = client
+            .read_message()?
+            .ok_or_else(|| anyhow::anyhow!("Server closed the connection"))?;
+        if message["method"] == "textDocument/publishDiagnostics"
+            && message["params"]["version"].as_u64() == Some(version)
+        {
+            return Ok(message);
+        }
+    }
+}
+
+fn has_diagnostic(diagnostics: &Value, code: &str) -> bool {
+    diagnostics
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|d| d["code"] == code)
+}
+
+#[test]
+fn test_lsp_server_lints_incremental_changes() -> anyhow::Result<()> {
+    let mut client = LspTestClient::new()?;
+
+    let init_id = client.send_request(
+        "initialize",
+        json!({
+            "processId": 12345,
+            "rootUri": "file:///tmp/test",
+            "capabilities": {},
+            "initializationOptions": { "lintDebounceMs": 50 }
+        }),
+    )?;
+    let init_response = client.wait_for_response(init_id)?;
+    assert_eq!(
+        2,
+        init_response["result"]["capabilities"]["textDocumentSync"]["change"]
+    );
+    client.send_notification("initialized", json!({}))?;
+
+    let uri = "file:///tmp/test/live.md";
+    client.send_notification(
+        "textDocument/didOpen",
+        json!({
+            "textDocument": {
+                "uri": uri,
+                "languageId": "markdown",
+                "version": 1,
+                "text": "# Title\n\n## Section\n"
+            }
+        }),
+    )?;
+    let opened = wait_for_published_version(&mut client, 1)?;
+    assert!(!has_diagnostic(
+        &opened["params"]["diagnostics"],
+        "heading-increment"
+    ));
+
+    // Turn "## Section" into "### Section", which skips a heading level
+    client.send_notification(
+        "textDocument/didChange",
+        json!({
+            "textDocument": { "uri": uri, "version": 2 },
+            "contentChanges": [{
+                "range": {
+                    "start": { "line": 2, "character": 0 },
+                    "end": { "line": 2, "character": 0 }
+                },
+                "text": "#"
+            }]
+        }),
+    )?;
+    let changed = wait_for_published_version(&mut client, 2)?;
+    assert!(has_diagnostic(
+        &changed["params"]["diagnostics"],
+        "heading-increment"
+    ));
+
+    // Pull diagnostics are computed from the in-memory text as well
+    let diag_id = client.send_request(
+        "textDocument/diagnostic",
+        json!({ "textDocument": { "uri": uri } }),
+    )?;
+    let pulled = client.wait_for_response(diag_id)?;
+    assert_eq!("full", pulled["result"]["kind"]);
+    assert!(has_diagnostic(
+        &pulled["result"]["items"],
+        "heading-increment"
+    ));
+
+    let shutdown_id = client.send_request("shutdown", json!(null))?;
+    client.wait_for_response(shutdown_id)?;
+    client.send_notification("exit", json!({}))?;
+    Ok(())
+}
+
+#[test]
+fn test_lsp_server_code_actions() -> anyhow::Result<()> {
+    let mut client = LspTestClient::new()?;
+
+    let init_id = client.send_request(
+        "initialize",
+        json!({ "processId": 12345, "rootUri": "file:///tmp/test", "capabilities": {} }),
+    )?;
+    let init_response = client.wait_for_response(init_id)?;
+    let kinds = &init_response["result"]["capabilities"]["codeActionProvider"]["codeActionKinds"];
+    assert_eq!(json!(["quickfix", "source.fixAll.quickmark"]), *kinds);
+    client.send_notification("initialized", json!({}))?;
+
+    let uri = "file:///tmp/test/actions.md";
+    client.send_notification(
+        "textDocument/didOpen",
+        json!({
+            "textDocument": {
+                "uri": uri,
+                "languageId": "markdown",
+                "version": 1,
+                "text": "# Title\n\nTrailing spaces \n"
+            }
+        }),
+    )?;
+    let published = wait_for_published_version(&mut client, 1)?;
+    let diagnostics = published["params"]["diagnostics"].as_array().unwrap();
+    let trailing = diagnostics
+        .iter()
+        .find(|d| d["code"] == "no-trailing-spaces")
+        .expect("no-trailing-spaces diagnostic")
+        .clone();
+    assert_eq!("no-trailing-spaces", trailing["data"]["alias"]);
+
+    let action_id = client.send_request(
+        "textDocument/codeAction",
+        json!({
+            "textDocument": { "uri": uri },
+            "range": trailing["range"],
+            "context": { "diagnostics": [trailing] }
+        }),
+    )?;
+    let response = client.wait_for_response(action_id)?;
+    let actions = response["result"].as_array().unwrap();
+    let titles: Vec<&str> = actions
+        .iter()
+        .map(|a| a["title"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        vec![
+            "Fix MD009/no-trailing-spaces",
+            "Disable no-trailing-spaces for this line",
+            "Disable no-trailing-spaces for this file",
+            "Fix all quickmark problems in file",
+        ],
+        titles
+    );
+
+    let fix_edit = &actions[0]["edit"]["changes"][uri][0];
+    assert_eq!(
+        json!({"line": 2, "character": 15}),
+        fix_edit["range"]["start"]
+    );
+    assert_eq!("", fix_edit["newText"]);
+
+    let fix_all_edit = &actions[3]["edit"]["changes"][uri][0];
+    assert_eq!("source.fixAll.quickmark", actions[3]["kind"]);
+    assert_eq!("# Title\n\nTrailing spaces\n", fix_all_edit["newText"]);
+
+    let shutdown_id = client.send_request("shutdown", json!(null))?;
+    client.wait_for_response(shutdown_id)?;
+    client.send_notification("exit", json!({}))?;
+    Ok(())
+}
+
+#[test]
+fn test_lsp_server_validates_config_documents() -> anyhow::Result<()> {
+    let mut client = LspTestClient::new()?;
+
+    let init_id = client.send_request(
+        "initialize",
+        json!({ "processId": 12345, "rootUri": "file:///tmp/test", "capabilities": {} }),
+    )?;
+    client.wait_for_response(init_id)?;
+    client.send_notification("initialized", json!({}))?;
+
+    client.send_notification(
+        "textDocument/didOpen",
+        json!({
+            "textDocument": {
+                "uri": "file:///tmp/test/quickmark.toml",
+                "languageId": "toml",
+                "version": 1,
+                "text": "[linters.severity]\nline-lenght = 'off'\n"
+            }
+        }),
+    )?;
+    let published = wait_for_published_version(&mut client, 1)?;
+    let diagnostics = published["params"]["diagnostics"].as_array().unwrap();
+    assert_eq!(1, diagnostics.len());
+    assert_eq!(
+        "Unknown rule `line-lenght`, did you mean `line-length`?",
+        diagnostics[0]["message"]
+    );
+    assert_eq!(1, diagnostics[0]["range"]["start"]["line"]);
+    assert_eq!(1, diagnostics[0]["severity"]);
+
+    let shutdown_id = client.send_request("shutdown", json!(null))?;
+    client.wait_for_response(shutdown_id)?;
+    client.send_notification("exit", json!({}))?;
+    Ok(())
+}
+
+/// Diagnostics of a workspace diagnostic report, keyed by file name
+fn workspace_report_items(response: &Value) -> Vec<(String, Vec<String>)> {
+    let mut items: Vec<(String, Vec<String>)> = response["result"]["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|item| {
+            let uri = item["uri"].as_str().unwrap();
+            let name = uri.rsplit('/').next().unwrap().to_string();
+            let messages = item["items"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|diagnostic| diagnostic["message"].as_str().unwrap().to_string())
+                .collect();
+            (name, messages)
+        })
+        .collect();
+    items.sort();
+    items
+}
+
+#[test]
+fn test_lsp_server_workspace_diagnostics() -> anyhow::Result<()> {
+    let root = std::env::temp_dir().join(format!("quickmark-workspace-{}", std::process::id()));
+    std::fs::create_dir_all(root.join("docs"))?;
+    std::fs::write(
+        root.join("quickmark.toml"),
+        "[linters.severity]\ndefault = 'off'\nduplicate-titles = 'err'\n",
+    )?;
+    std::fs::write(root.join("a.md"), "# Setup\n")?;
+    std::fs::write(root.join("docs/b.md"), "# Setup\n")?;
+    let root_uri = format!("file://{}", root.display());
+
+    let mut client = LspTestClient::new()?;
+    let init_id = client.send_request(
+        "initialize",
+        json!({ "processId": 12345, "rootUri": root_uri, "capabilities": {} }),
+    )?;
+    let init = client.wait_for_response(init_id)?;
+    assert_eq!(
+        true,
+        init["result"]["capabilities"]["diagnosticProvider"]["workspaceDiagnostics"]
+    );
+    client.send_notification("initialized", json!({}))?;
+
+    let request_id =
+        client.send_request("workspace/diagnostic", json!({ "previousResultIds": [] }))?;
+    let response = client.wait_for_response(request_id)?;
+    let expected_message = format!(
+        "Title 'Setup' is already used by '{}'",
+        root.join("a.md").display()
+    );
+    assert_eq!(
+        vec![
+            ("a.md".to_string(), vec![]),
+            ("b.md".to_string(), vec![expected_message]),
+        ],
+        workspace_report_items(&response)
+    );
+
+    // Open documents are checked with their unsaved content
+    client.send_notification(
+        "textDocument/didOpen",
+        json!({
+            "textDocument": {
+                "uri": format!("{root_uri}/docs/b.md"),
+                "languageId": "markdown",
+                "version": 2,
+                "text": "# Setup Guide\n"
+            }
+        }),
+    )?;
+    let request_id =
+        client.send_request("workspace/diagnostic", json!({ "previousResultIds": [] }))?;
+    let response = client.wait_for_response(request_id)?;
+    assert_eq!(
+        vec![("a.md".to_string(), vec![]), ("b.md".to_string(), vec![])],
+        workspace_report_items(&response)
+    );
+
+    let shutdown_id = client.send_request("shutdown", json!(null))?;
+    client.wait_for_response(shutdown_id)?;
+    client.send_notification("exit", json!({}))?;
+    std::fs::remove_dir_all(&root)?;
+    Ok(())
+}
+
+#[test]
+fn test_lsp_server_honours_baseline() -> anyhow::Result<()> {
+    use quickmark_core::baseline::Baseline;
+    use quickmark_core::config::parse_toml_config;
+    use quickmark_core::linter::MultiRuleLinter;
+
+    let root = std::env::temp_dir().join(format!("quickmark-baseline-{}", std::process::id()));
+    std::fs::create_dir_all(&root)?;
+    let config = "[linters.severity]\ndefault = 'off'\nno-trailing-spaces = 'err'\n";
+    std::fs::write(root.join("quickmark.toml"), config)?;
+
+    // Record the trailing space of the first paragraph
+    let old = "# Title\n\nOld text \n\nEnd\n";
+    let path = root.join("a.md");
+    std::fs::write(&path, old)?;
+    let mut baseline = Baseline::for_file(&root.join("quickmark-baseline.json"));
+    let mut linter = MultiRuleLinter::new_for_document(path, parse_toml_config(config)?, old);
+    for violation in linter.analyze() {
+        baseline.add(&violation, old);
+    }
+    baseline.save(&root.join("quickmark-baseline.json"))?;
+    std::fs::write(
+        root.join("a.md"),
+        "# Title\n\nOld text \n\nEnd\n\nNew text \n",
+    )?;
+    let root_uri = format!("file://{}", root.display());
+
+    let mut client = LspTestClient::new()?;
+    let init_id = client.send_request(
+        "initialize",
+        json!({ "processId": 12345, "rootUri": root_uri, "capabilities": {} }),
+    )?;
+    client.wait_for_response(init_id)?;
+    client.send_notification("initialized", json!({}))?;
+
+    let request_id =
+        client.send_request("workspace/diagnostic", json!({ "previousResultIds": [] }))?;
+    let response = client.wait_for_response(request_id)?;
+    let items = &response["result"]["items"][0]["items"];
+    assert_eq!(1, items.as_array().unwrap().len());
+    assert_eq!(6, items[0]["range"]["start"]["line"]);
+
+    let shutdown_id = client.send_request("shutdown", json!(null))?;
+    client.wait_for_response(shutdown_id)?;
+    client.send_notification("exit", json!({}))?;
+    std::fs::remove_dir_all(&root)?;
+    Ok(())
+}